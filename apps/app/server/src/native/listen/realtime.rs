@@ -29,7 +29,18 @@ async fn websocket(socket: WebSocket, state: STTState, params: ListenParams) {
 
     let (mut ws_sender, ws_receiver) = socket.split();
 
-    let mut stt = state.realtime_stt.for_language(params.language).await;
+    let mut stt = match state
+        .realtime_stt
+        .for_language(params.language, &params.vocabulary, params.interim_results)
+        .await
+    {
+        Ok(stt) => stt,
+        Err(e) => {
+            tracing::error!("stt_client_error: {:?}", e);
+            let _ = ws_sender.close().await;
+            return;
+        }
+    };
 
     let input_stream =
         futures_util::stream::try_unfold(ws_receiver, |mut ws_receiver| async move {
@@ -44,6 +55,7 @@ async fn websocket(socket: WebSocket, state: STTState, params: ListenParams) {
         });
 
     let input_stream = Box::pin(input_stream);
+    let interim_results = params.interim_results;
 
     let _handle = tokio::spawn(async move {
         match stt.transcribe(input_stream).await {
@@ -51,7 +63,14 @@ async fn websocket(socket: WebSocket, state: STTState, params: ListenParams) {
             Ok(mut transcript_stream) => {
                 while let Some(result) = transcript_stream.next().await {
                     match result {
-                        Ok(data) => {
+                        Ok(mut data) => {
+                            if !interim_results {
+                                data.transcripts.retain(|t| t.is_final);
+                                if data.transcripts.is_empty() && data.diarizations.is_empty() {
+                                    continue;
+                                }
+                            }
+
                             let out: ListenOutputChunk = data.into();
                             let msg = Message::Text(serde_json::to_string(&out).unwrap().into());
 
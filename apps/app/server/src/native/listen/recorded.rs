@@ -1,5 +1,6 @@
 use axum::{
     extract::{Query, State},
+    http::StatusCode,
     response::IntoResponse,
     Json,
 };
@@ -13,10 +14,17 @@ pub async fn handler(
     Query(params): Query<ListenParams>,
     State(state): State<STTState>,
 ) -> impl IntoResponse {
-    let stt = state.recorded_stt.for_language(params.language).await;
+    let stt = match state
+        .recorded_stt
+        .for_language(params.language, &params.vocabulary)
+        .await
+    {
+        Ok(stt) => stt,
+        Err(e) => return (StatusCode::BAD_REQUEST, e.to_string()).into_response(),
+    };
 
     let input = RecordedSpeech::File("TODO".into());
     let result = stt.transcribe(input).await.unwrap();
 
-    Json(result)
+    Json(result).into_response()
 }
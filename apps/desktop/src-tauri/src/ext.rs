@@ -184,12 +184,7 @@ impl<R: tauri::Runtime, T: tauri::Manager<R>> AppExt<R> for T {
                     if cfg!(debug_assertions) {
                         hypr_db_core::DatabaseBuilder::default().memory()
                     } else {
-                        let db_name = format_db_name(account_id);
-                        let db_url = format_db_url(&db_name, DEFAULT_ORG_SLUG);
-
-                        hypr_db_core::DatabaseBuilder::default()
-                            .local(local_db_path)
-                            .remote(db_url, database_token.unwrap())
+                        hypr_db_core::DatabaseBuilder::default().local(local_db_path)
                     }
                 }
                 .build()
@@ -198,6 +193,24 @@ impl<R: tauri::Runtime, T: tauri::Manager<R>> AppExt<R> for T {
 
                 app.db_attach(db).await.unwrap();
 
+                // The remote is only connected to by the sync task, so starting offline works.
+                if !cfg!(debug_assertions) {
+                    match database_token {
+                        Some(database_token) => {
+                            let db_name = format_db_name(account_id);
+                            let db_url = format_db_url(&db_name, DEFAULT_ORG_SLUG);
+
+                            app.db_set_remote(db_url, database_token).await;
+                            if let Err(e) =
+                                app.db_start_sync(std::time::Duration::from_secs(300)).await
+                            {
+                                tracing::error!("failed_to_start_db_sync: {}", e);
+                            }
+                        }
+                        None => tracing::warn!("missing_database_token"),
+                    }
+                }
+
                 #[cfg(debug_assertions)]
                 {
                     let state = app.state::<tauri_plugin_db::ManagedState>();
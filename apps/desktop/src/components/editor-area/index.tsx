@@ -6,9 +6,11 @@ import { AnimatePresence } from "motion/react";
 import { useCallback, useEffect, useMemo, useRef } from "react";
 
 import { useHypr } from "@/contexts";
+import { indexSession } from "@/utils/semantic-search";
 import { commands as analyticsCommands } from "@hypr/plugin-analytics";
-import { commands as connectorCommands } from "@hypr/plugin-connector";
-import { commands as dbCommands } from "@hypr/plugin-db";
+import { commands as connectorCommands, type ConnectionLLM } from "@hypr/plugin-connector";
+import { commands as dbCommands, type TimelineView } from "@hypr/plugin-db";
+import { commands as localLlmCommands } from "@hypr/plugin-local-llm";
 import { commands as miscCommands } from "@hypr/plugin-misc";
 import { commands as templateCommands } from "@hypr/plugin-template";
 import Editor, { type TiptapEditor } from "@hypr/tiptap/editor";
@@ -21,6 +23,14 @@ import { enhanceFailedToast } from "../toast/shared";
 import { FloatingButton } from "./floating-button";
 import { NoteHeader } from "./note-header";
 
+// For models whose context window isn't known: a local one that can't be read, a custom endpoint without
+// the setting, or the cloud. Matches what the local server assumes for such models.
+const DEFAULT_CONTEXT_TOKENS = 4 * 1024;
+// Kept free for the instructions, the raw note and the output.
+const RESERVED_TOKENS = 3 * 1024;
+// Transcripts average about this many characters per token, across languages.
+const CHARS_PER_TOKEN = 3;
+
 export default function EditorArea({
   editable,
   sessionId,
@@ -186,38 +196,84 @@ export function useEnhanceMutation({
 
       const config = await dbCommands.getConfig();
       const participants = await dbCommands.sessionListParticipants(sessionId);
+      const speakers = await dbCommands.sessionResolveSpeakers(sessionId);
+
+      // Transcripts too long for the model's context are summarized window by window first.
+      const contextTokens = await contextLength(type);
+      const windowChars = Math.max(contextTokens - RESERVED_TOKENS, 1024) * CHARS_PER_TOKEN;
+      const windows = sessionId === onboardingSessionId
+        ? [timeline]
+        : await dbCommands.getTimelineViewWindows(sessionId, windowChars);
+
+      const templateId = config.general.selected_template_id;
+      const template = templateId
+        ? (await dbCommands.listTemplates()).find((t) => t.id === templateId) ?? null
+        : null;
 
       const systemMessage = await templateCommands.render(
         "enhance.system",
         { config, type },
       );
 
+      const abortController = new AbortController();
+      // Every model call gets a minute. Merging the notes of n windows takes fewer than n calls.
+      const abortSignal = AbortSignal.any([
+        abortController.signal,
+        AbortSignal.timeout((windows.length > 1 ? windows.length * 2 : 1) * 60 * 1000),
+      ]);
+      setEnhanceController(abortController);
+
+      const provider = await modelProvider();
+      const model = sessionId === onboardingSessionId
+        ? provider.languageModel("onboardingModel")
+        : provider.languageModel("defaultModel");
+
+      const onProgress = async (markdown: string) => {
+        setEnhancedContent(await miscCommands.opinionatedMdToHtml(markdown));
+      };
+      const context = { config, type, participants, speakers };
+
+      const summaries = windows.length > 1
+        ? await mergeSummaries({
+          summaries: await summarizeWindows({ windows, model, abortSignal, context, onProgress }),
+          maxChars: windowChars,
+          model,
+          abortSignal,
+          context,
+          onProgress,
+        })
+        : null;
+
       const userMessage = await templateCommands.render(
         "enhance.user",
         {
           type,
           editor: rawContent,
           timeline,
+          summaries,
           participants,
+          speakers,
+          template,
         },
       );
 
-      const abortController = new AbortController();
-      const abortSignal = AbortSignal.any([abortController.signal, AbortSignal.timeout(60 * 1000)]);
-      setEnhanceController(abortController);
-
-      const provider = await modelProvider();
-      const model = sessionId === onboardingSessionId
-        ? provider.languageModel("onboardingModel")
-        : provider.languageModel("defaultModel");
-
-      const { text, textStream } = streamText({
+      const { text, textStream, response } = streamText({
         abortSignal,
         model,
         messages: [
           { role: "system", content: systemMessage },
           { role: "user", content: userMessage },
         ],
+        // Only the local server constrains the output with a grammar. Other endpoints may reject unknown fields.
+        // With a template, the grammar forces its section titles as the headings.
+        providerOptions: type === "HyprLocal"
+          ? {
+            "hypr-llm": {
+              grammar: "enhance-template",
+              sections: template?.sections.map((section) => section.title) ?? [],
+            },
+          }
+          : undefined,
         experimental_transform: [
           markdownTransform(),
           smoothStream({ delayInMs: 80, chunking: "line" }),
@@ -231,6 +287,18 @@ export function useEnhanceMutation({
         setEnhancedContent(html);
       }
 
+      // The local server cuts the middle of prompts that don't fit the model's context window.
+      const { headers } = await response;
+      if (headers?.["x-hypr-truncated-tokens"]) {
+        toast({
+          id: "enhance-truncated",
+          title: "Long Meeting",
+          content: "Part of the transcript didn't fit the model's context and was left out of the note",
+          dismissible: true,
+          duration: 15000,
+        });
+      }
+
       return text.then(miscCommands.opinionatedMdToHtml);
     },
     onSuccess: () => {
@@ -242,7 +310,12 @@ export function useEnhanceMutation({
         session_id: sessionId,
       });
 
-      persistSession();
+      // Indexed for semantic search once saved. A failure only leaves the previous index in place.
+      persistSession().then(() => {
+        if (sessionId !== onboardingSessionId) {
+          indexSession(sessionId).catch(console.error);
+        }
+      });
     },
     onError: (error) => {
       console.error(error);
@@ -256,6 +329,134 @@ export function useEnhanceMutation({
   return enhance;
 }
 
+// The context window of the model behind the configured connection, in tokens.
+async function contextLength(type: ConnectionLLM["type"]) {
+  switch (type) {
+    case "HyprLocal":
+      return localLlmCommands.contextLength().catch(() => DEFAULT_CONTEXT_TOKENS);
+    case "Custom":
+      return (await connectorCommands.getCustomLlmContextLength().catch(() => null)) ?? DEFAULT_CONTEXT_TOKENS;
+    default:
+      return DEFAULT_CONTEXT_TOKENS;
+  }
+}
+
+// The map step of map-reduce enhancing: notes for each window, streamed to `onProgress` as they are written.
+async function summarizeWindows({
+  windows,
+  model,
+  abortSignal,
+  context,
+  onProgress,
+}: {
+  windows: TimelineView[];
+  model: Parameters<typeof streamText>[0]["model"];
+  abortSignal: AbortSignal;
+  context: Parameters<typeof templateCommands.render>[1];
+  onProgress: (markdown: string) => Promise<void>;
+}) {
+  const systemMessage = await templateCommands.render("enhance_chunk.system", context);
+
+  const summaries: string[] = [];
+  for (const [index, timeline] of windows.entries()) {
+    const userMessage = await templateCommands.render(
+      "enhance_chunk.user",
+      { ...context, timeline, index, total: windows.length },
+    );
+
+    const { textStream } = streamText({
+      abortSignal,
+      model,
+      messages: [
+        { role: "system", content: systemMessage },
+        { role: "user", content: userMessage },
+      ],
+    });
+
+    const progress = `Summarizing part ${index + 1} of ${windows.length} of the transcript...\n\n`;
+    let acc = "";
+    for await (const chunk of textStream) {
+      acc += chunk;
+      await onProgress(progress + acc);
+    }
+
+    summaries.push(acc.trim());
+  }
+
+  return summaries;
+}
+
+// The reduce step of map-reduce enhancing. While the notes are too long for one prompt, consecutive ones
+// are merged in groups that fit. Every group holds at least two, so each round at least halves them.
+async function mergeSummaries({
+  summaries,
+  maxChars,
+  model,
+  abortSignal,
+  context,
+  onProgress,
+}: {
+  summaries: string[];
+  maxChars: number;
+  model: Parameters<typeof streamText>[0]["model"];
+  abortSignal: AbortSignal;
+  context: Parameters<typeof templateCommands.render>[1];
+  onProgress: (markdown: string) => Promise<void>;
+}) {
+  const systemMessage = await templateCommands.render("enhance_chunk.system", context);
+  const length = (group: string[]) => group.reduce((sum, summary) => sum + summary.length, 0);
+
+  let notes = summaries;
+  while (notes.length > 1 && length(notes) > maxChars) {
+    const groups: string[][] = [];
+    for (const summary of notes) {
+      const last = groups[groups.length - 1];
+      if (last && (last.length < 2 || length(last) + summary.length <= maxChars)) {
+        last.push(summary);
+      } else {
+        groups.push([summary]);
+      }
+    }
+
+    // A lone trailing note is merged into the group before it.
+    if (groups.length > 1 && groups[groups.length - 1].length < 2) {
+      groups[groups.length - 2].push(...groups.pop()!);
+    }
+
+    const merged: string[] = [];
+    let first = 0;
+    for (const [index, group] of groups.entries()) {
+      const userMessage = await templateCommands.render(
+        "enhance_merge.user",
+        { ...context, summaries: group, first, total: notes.length },
+      );
+      first += group.length;
+
+      const { textStream } = streamText({
+        abortSignal,
+        model,
+        messages: [
+          { role: "system", content: systemMessage },
+          { role: "user", content: userMessage },
+        ],
+      });
+
+      const progress = `Merging notes ${index + 1} of ${groups.length}...\n\n`;
+      let acc = "";
+      for await (const chunk of textStream) {
+        acc += chunk;
+        await onProgress(progress + acc);
+      }
+
+      merged.push(acc.trim());
+    }
+
+    notes = merged;
+  }
+
+  return notes;
+}
+
 export function useAutoEnhance({
   sessionId,
   enhanceStatus,
@@ -318,7 +318,7 @@ function ParticipantCandidates(
   const participants = useQuery({
     queryKey: ["search-participants", query],
     queryFn: async () => {
-      const humans = await dbCommands.listHumans({ search: [4, query] });
+      const { items: humans } = await dbCommands.listHumans({ type: "search", query, limit: 4, cursor: null });
       const participants = await dbCommands.sessionListParticipants(sessionId);
       return humans.filter((human) => !participants.some((participant) => participant.id === human.id));
     },
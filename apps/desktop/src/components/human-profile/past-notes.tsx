@@ -15,9 +15,10 @@ export function PastNotes({ human }: { human: Human }) {
   const { data: sessions = [], isLoading } = useQuery({
     queryKey: ["sessions", "human", human.id],
     queryFn: async () => {
-      const allSessions = await dbCommands.listSessions({
+      const { items: allSessions } = await dbCommands.listSessions({
         user_id: human.id,
         limit: 10,
+        cursor: null,
         type: "recentlyVisited",
       });
 
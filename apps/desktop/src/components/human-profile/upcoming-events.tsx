@@ -17,9 +17,10 @@ export function UpcomingEvents({ human }: { human: Human }) {
       const endDate = new Date(now);
       endDate.setMonth(now.getMonth() + 3);
 
-      const events = await dbCommands.listEvents({
+      const { items: events } = await dbCommands.listEvents({
         user_id: human.id,
         limit: 5,
+        cursor: null,
         type: "dateRange",
         start: startDate,
         end: endDate.toISOString(),
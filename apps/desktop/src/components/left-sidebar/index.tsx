@@ -43,10 +43,11 @@ export default function LeftSidebar() {
     refetchInterval: 5000,
     queryKey: ["events", ongoingSessionId],
     queryFn: async () => {
-      const events = await dbCommands.listEvents({
+      const { items: events } = await dbCommands.listEvents({
         type: "dateRange",
         user_id: userId,
         limit: 3,
+        cursor: null,
         start: new Date().toISOString(),
         end: addDays(new Date(), 28).toISOString(),
       });
@@ -53,12 +53,13 @@ export default function NotesList({ ongoingSessionId, filter }: NotesListProps)
         .map((d) => subMonths(d, monthOffset))
         .map((d) => d.toISOString());
 
-      const sessions = await dbCommands.listSessions({
+      const { items: sessions } = await dbCommands.listSessions({
         type: "dateRange",
         user_id: userId,
         start: from,
         end: to,
         limit: 100,
+        cursor: null,
       });
       sessions.forEach(insertSession);
 
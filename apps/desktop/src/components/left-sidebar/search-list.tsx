@@ -92,7 +92,7 @@ export default function SearchList({ matches }: { matches: SearchMatch[] }) {
   );
 }
 
-export function SessionMatch({ match: { item: session } }: { match: SearchMatch & { type: "session" } }) {
+export function SessionMatch({ match: { item: session, passage } }: { match: SearchMatch & { type: "session" } }) {
   const navigate = useNavigate();
 
   const match = useMatch({ from: "/app/note/$id", shouldThrow: false });
@@ -118,6 +118,7 @@ export function SessionMatch({ match: { item: session } }: { match: SearchMatch
         <div className="flex items-center gap-2 text-xs text-neutral-500 line-clamp-1">
           {new Date(session.created_at).toLocaleDateString()}
         </div>
+        {passage && <div className="text-xs text-neutral-400 line-clamp-2">{passage}</div>}
       </div>
     </button>
   );
@@ -16,9 +16,10 @@ export function RecentNotes({ organizationId, members }: RecentNotesProps) {
     queryFn: async () => {
       const memberSessions = await Promise.all(
         members.map(async (member) => {
-          const sessions = await dbCommands.listSessions({
+          const { items: sessions } = await dbCommands.listSessions({
             user_id: member.id,
             limit: 5,
+            cursor: null,
             type: "recentlyVisited",
           });
           return sessions;
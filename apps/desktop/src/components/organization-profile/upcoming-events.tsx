@@ -23,9 +23,10 @@ export function UpcomingEvents({
 
       const memberEvents = await Promise.all(
         members.map(async (member) => {
-          const events = await dbCommands.listEvents({
+          const { items: events } = await dbCommands.listEvents({
             user_id: member.id,
             limit: 5,
+            cursor: null,
             type: "dateRange",
             start: startDate,
             end: endDate.toISOString(),
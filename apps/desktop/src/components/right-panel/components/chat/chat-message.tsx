@@ -1,5 +1,6 @@
 import { cn } from "@hypr/ui/lib/utils";
 import { Trans } from "@lingui/react/macro";
+import { Link } from "@tanstack/react-router";
 import { Message } from "./types";
 
 interface ChatMessageProps {
@@ -18,6 +19,28 @@ export function ChatMessage({ message }: ChatMessageProps) {
         {message.isUser ? <Trans>User:</Trans> : <Trans>Assistant:</Trans>}
       </div>
       <div className="text-sm whitespace-pre-wrap break-words overflow-wrap-anywhere max-w-full">{message.content}</div>
+      {!!message.citations?.length && (
+        <div className="flex flex-wrap gap-1 mt-2">
+          {message.citations.map((citation) => (
+            <Link
+              key={citation.index}
+              to="/app/note/$id"
+              params={{ id: citation.session_id }}
+              title={citation.text}
+              className="text-xs px-1.5 py-0.5 rounded bg-neutral-100 text-neutral-600 hover:bg-neutral-200"
+            >
+              [{citation.index}] {citation.start_ms === null ? <Trans>Note</Trans> : formatTime(citation.start_ms)}
+            </Link>
+          ))}
+        </div>
+      )}
     </div>
   );
 }
+
+function formatTime(ms: number) {
+  const seconds = Math.floor(ms / 1000);
+  const m = Math.floor(seconds / 60);
+  const s = seconds % 60;
+  return `${m.toString().padStart(2, "0")}:${s.toString().padStart(2, "0")}`;
+}
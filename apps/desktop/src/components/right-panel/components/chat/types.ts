@@ -1,8 +1,11 @@
+import type { ChatCitation } from "@hypr/plugin-db";
+
 export type Message = {
   id: string;
   content: string;
   isUser: boolean;
   timestamp: Date;
+  citations?: ChatCitation[];
 };
 
 export type ChatSession = {
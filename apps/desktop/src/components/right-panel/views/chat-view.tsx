@@ -1,6 +1,8 @@
+import { Channel } from "@tauri-apps/api/core";
 import { useEffect, useState } from "react";
 
-import { useRightPanel } from "@/contexts";
+import { useHypr, useRightPanel } from "@/contexts";
+import { commands as dbCommands } from "@hypr/plugin-db";
 import { useMatch, useNavigate } from "@tanstack/react-router";
 import {
   ChatHistoryView,
@@ -21,8 +23,10 @@ export type BadgeType = "note" | "human" | "organization";
 
 export function ChatView() {
   const navigate = useNavigate();
+  const { userId } = useHypr();
   const { isExpanded, chatInputRef } = useRightPanel();
 
+  const [groupId, setGroupId] = useState<string | null>(null);
   const [messages, setMessages] = useState<Message[]>([]);
   const [inputValue, setInputValue] = useState("");
   const [showHistory, setShowHistory] = useState(false);
@@ -79,6 +83,46 @@ export function ChatView() {
     setInputValue(e.target.value);
   };
 
+  // Chats started from a note only draw on that meeting.
+  const sendMessage = async (content: string) => {
+    const userMessage: Message = {
+      id: crypto.randomUUID(),
+      content,
+      isUser: true,
+      timestamp: new Date(),
+    };
+    const pending: Message = {
+      id: crypto.randomUUID(),
+      content: "",
+      isUser: false,
+      timestamp: new Date(),
+    };
+    const updatePending = (update: (message: Message) => Message) =>
+      setMessages((prev) => prev.map((message) => (message.id === pending.id ? update(message) : message)));
+
+    setMessages((prev) => [...prev, userMessage, pending]);
+    setInputValue("");
+
+    try {
+      const group = groupId ?? (await dbCommands.createChatGroup({
+        id: crypto.randomUUID(),
+        user_id: userId,
+        name: content.slice(0, 50),
+        created_at: new Date().toISOString(),
+        session_id: activeEntity?.type === "note" ? activeEntity.id : null,
+      })).id;
+      setGroupId(group);
+
+      const onDelta = new Channel<string>();
+      onDelta.onmessage = (delta) => updatePending((message) => ({ ...message, content: message.content + delta }));
+
+      const answer = await dbCommands.sendChatMessage(group, content, onDelta);
+      updatePending((message) => ({ ...message, content: answer.content, citations: answer.citations }));
+    } catch (error) {
+      updatePending((message) => ({ ...message, content: `Failed to answer: ${error}` }));
+    }
+  };
+
   const handleSubmit = () => {
     if (!inputValue.trim()) {
       return;
@@ -88,25 +132,7 @@ export function ChatView() {
       setHasChatStarted(true);
     }
 
-    const userMessage: Message = {
-      id: Date.now().toString(),
-      content: inputValue,
-      isUser: true,
-      timestamp: new Date(),
-    };
-
-    setMessages((prev) => [...prev, userMessage]);
-    setInputValue("");
-
-    setTimeout(() => {
-      const aiMessage: Message = {
-        id: (Date.now() + 1).toString(),
-        content: "This is a sample response from the AI assistant.",
-        isUser: false,
-        timestamp: new Date(),
-      };
-      setMessages((prev) => [...prev, aiMessage]);
-    }, 1000);
+    sendMessage(inputValue);
   };
 
   const handleKeyDown = (e: React.KeyboardEvent<HTMLTextAreaElement>) => {
@@ -117,25 +143,7 @@ export function ChatView() {
   };
 
   const handleQuickAction = (prompt: string) => {
-    const userMessage: Message = {
-      id: Date.now().toString(),
-      content: prompt,
-      isUser: true,
-      timestamp: new Date(),
-    };
-
-    setMessages((prev) => [...prev, userMessage]);
-    setInputValue("");
-
-    setTimeout(() => {
-      const aiMessage: Message = {
-        id: (Date.now() + 1).toString(),
-        content: "This is a sample response from the AI assistant.",
-        isUser: false,
-        timestamp: new Date(),
-      };
-      setMessages((prev) => [...prev, aiMessage]);
-    }, 1000);
+    sendMessage(prompt);
 
     if (chatInputRef.current) {
       chatInputRef.current.focus();
@@ -149,6 +157,7 @@ export function ChatView() {
   };
 
   const handleNewChat = () => {
+    setGroupId(null);
     setMessages([]);
     setInputValue("");
     setShowHistory(false);
@@ -43,6 +43,7 @@ const endpointSchema = z.object({
     { message: "`/chat/completions` will be appended automatically" },
   ),
   api_key: z.string().optional(),
+  context_length: z.string().regex(/^\d*$/, { message: "Please enter a number of tokens" }).optional(),
 });
 type FormValues = z.infer<typeof endpointSchema>;
 
@@ -199,6 +200,18 @@ export default function LocalAI() {
     },
   });
 
+  const customLLMContextLength = useQuery({
+    queryKey: ["custom-llm-context-length"],
+    queryFn: () => connectorCommands.getCustomLlmContextLength(),
+  });
+
+  const setCustomLLMContextLength = useMutation({
+    mutationFn: (tokens: number) => connectorCommands.setCustomLlmContextLength(tokens),
+    onSuccess: () => {
+      customLLMContextLength.refetch();
+    },
+  });
+
   const setCustomLLMConnection = useMutation({
     mutationFn: (connection: Connection) => connectorCommands.setCustomLlmConnection(connection),
     onError: console.error,
@@ -234,8 +247,9 @@ export default function LocalAI() {
       model: getCustomLLMModel.data || "",
       api_base: customLLMConnection.data?.api_base || "",
       api_key: customLLMConnection.data?.api_key || "",
+      context_length: customLLMContextLength.data?.toString() ?? "",
     });
-  }, [getCustomLLMModel.data, customLLMConnection.data]);
+  }, [getCustomLLMModel.data, customLLMConnection.data, customLLMContextLength.data]);
 
   useEffect(() => {
     const subscription = form.watch((value, { name }) => {
@@ -249,6 +263,10 @@ export default function LocalAI() {
           api_key: value.api_key || null,
         });
       }
+
+      if (name === "context_length" && !form.formState.errors.context_length && value.context_length) {
+        setCustomLLMContextLength.mutate(Number(value.context_length));
+      }
     });
 
     return () => subscription.unsubscribe();
@@ -545,6 +563,33 @@ export default function LocalAI() {
                         </FormItem>
                       )}
                     />
+
+                    <FormField
+                      control={form.control}
+                      name="context_length"
+                      render={({ field }) => (
+                        <FormItem>
+                          <FormLabel className="text-sm font-medium">
+                            <Trans>Context Window</Trans>
+                          </FormLabel>
+                          <FormDescription className="text-xs">
+                            <Trans>
+                              How many tokens the model takes at once. Long transcripts are split to fit it.
+                            </Trans>
+                          </FormDescription>
+                          <FormControl>
+                            <Input
+                              {...field}
+                              inputMode="numeric"
+                              placeholder="4096"
+                              disabled={!customLLMEnabled.data}
+                              className="focus-visible:ring-1 focus-visible:ring-offset-0"
+                            />
+                          </FormControl>
+                          <FormMessage />
+                        </FormItem>
+                      )}
+                    />
                   </form>
                 </Form>
               </div>
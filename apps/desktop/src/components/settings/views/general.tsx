@@ -31,6 +31,9 @@ const schema = z.object({
   telemetryConsent: z.boolean().optional(),
   jargons: z.string(),
   saveRecordings: z.boolean().optional(),
+  detectLanguage: z.boolean().optional(),
+  translationLanguage: z.enum(["none", ...SUPPORTED_LANGUAGES] as [string, ...string[]]),
+  selectedTemplateId: z.string(),
 });
 
 type Schema = z.infer<typeof schema>;
@@ -47,6 +50,11 @@ export default function General() {
     },
   });
 
+  const templates = useQuery({
+    queryKey: ["templates"],
+    queryFn: () => dbCommands.listTemplates(),
+  });
+
   const form = useForm<Schema>({
     resolver: zodResolver(schema),
     defaultValues: {
@@ -55,6 +63,9 @@ export default function General() {
       telemetryConsent: true,
       jargons: "",
       saveRecordings: true,
+      detectLanguage: false,
+      translationLanguage: "none",
+      selectedTemplateId: "none",
     },
   });
 
@@ -66,6 +77,9 @@ export default function General() {
         telemetryConsent: config.data.general.telemetry_consent ?? true,
         jargons: (config.data.general.jargons ?? []).join(", "),
         saveRecordings: config.data.general.save_recordings ?? true,
+        detectLanguage: config.data.general.detect_language ?? false,
+        translationLanguage: config.data.general.translation_language ?? "none",
+        selectedTemplateId: config.data.general.selected_template_id ?? "none",
       });
     }
   }, [config.data, form]);
@@ -83,6 +97,9 @@ export default function General() {
         telemetry_consent: v.telemetryConsent ?? true,
         jargons: v.jargons.split(",").map((jargon) => jargon.trim()).filter(Boolean),
         save_recordings: v.saveRecordings ?? true,
+        detect_language: v.detectLanguage ?? false,
+        translation_language: v.translationLanguage === "none" ? null : v.translationLanguage,
+        selected_template_id: v.selectedTemplateId === "none" ? null : v.selectedTemplateId,
       };
 
       await dbCommands.setConfig({
@@ -206,6 +223,104 @@ export default function General() {
             )}
           />
 
+          <FormField
+            control={form.control}
+            name="detectLanguage"
+            render={({ field }) => (
+              <FormItem className="flex flex-row items-center justify-between">
+                <div>
+                  <FormLabel>
+                    <Trans>Detect spoken language</Trans>
+                  </FormLabel>
+                  <FormDescription>
+                    <Trans>
+                      Transcribe in whatever language is spoken, instead of the language above
+                    </Trans>
+                  </FormDescription>
+                </div>
+                <FormControl>
+                  <Switch
+                    checked={field.value}
+                    onCheckedChange={field.onChange}
+                    color="gray"
+                  />
+                </FormControl>
+              </FormItem>
+            )}
+          />
+
+          <FormField
+            control={form.control}
+            name="translationLanguage"
+            render={({ field }) => (
+              <FormItem>
+                <FormLabel>
+                  <Trans>Live translation</Trans>
+                </FormLabel>
+                <FormDescription>
+                  <Trans>Translate the transcript into this language while recording</Trans>
+                </FormDescription>
+                <FormControl>
+                  <Select
+                    onValueChange={field.onChange}
+                    value={field.value}
+                  >
+                    <SelectTrigger>
+                      <SelectValue placeholder="Select language" />
+                    </SelectTrigger>
+                    <SelectContent>
+                      <SelectItem value="none">
+                        <Trans>Off</Trans>
+                      </SelectItem>
+                      {SUPPORTED_LANGUAGES.map((lang) => (
+                        <SelectItem key={lang} value={lang}>
+                          {LANGUAGES_ISO_639_1[lang].name}
+                        </SelectItem>
+                      ))}
+                    </SelectContent>
+                  </Select>
+                </FormControl>
+                <FormMessage />
+              </FormItem>
+            )}
+          />
+
+          <FormField
+            control={form.control}
+            name="selectedTemplateId"
+            render={({ field }) => (
+              <FormItem>
+                <FormLabel>
+                  <Trans>Note template</Trans>
+                </FormLabel>
+                <FormDescription>
+                  <Trans>Enhanced notes use the sections of this template</Trans>
+                </FormDescription>
+                <FormControl>
+                  <Select
+                    onValueChange={field.onChange}
+                    value={field.value}
+                  >
+                    <SelectTrigger>
+                      <SelectValue placeholder="Select template" />
+                    </SelectTrigger>
+                    <SelectContent>
+                      <SelectItem value="none">
+                        <Trans>None</Trans>
+                      </SelectItem>
+                      {(templates.data ?? []).map((template) => (
+                        <SelectItem key={template.id} value={template.id}>
+                          {template.title}
+                        </SelectItem>
+                      ))}
+                    </SelectContent>
+                  </Select>
+                </FormControl>
+                <FormMessage />
+              </FormItem>
+            )}
+          />
+
           <FormField
             control={form.control}
             name="jargons"
@@ -1,10 +1,11 @@
 import { useQuery } from "@tanstack/react-query";
 import { useEffect } from "react";
 
+import { commands as listenerCommands } from "@hypr/plugin-listener";
 import { commands as localLlmCommands } from "@hypr/plugin-local-llm";
 import { commands as localSttCommands } from "@hypr/plugin-local-stt";
 import { sonnerToast, toast } from "@hypr/ui/components/ui/toast";
-import { showLlmModelDownloadToast, showSttModelDownloadToast } from "./shared";
+import { showLlmModelDownloadToast, showSttModelDownloadToast, showVoiceModelDownloadToast } from "./shared";
 
 export default function ModelDownloadNotification() {
   const currentSttModel = useQuery({
@@ -16,15 +17,17 @@ export default function ModelDownloadNotification() {
     enabled: !!currentSttModel.data,
     queryKey: ["check-model-downloaded"],
     queryFn: async () => {
-      const [stt, llm] = await Promise.all([
+      const [stt, llm, voice] = await Promise.all([
         localSttCommands.isModelDownloaded(currentSttModel.data!),
         localLlmCommands.isModelDownloaded(),
+        listenerCommands.isVoiceModelDownloaded(),
       ]);
 
       return {
         currentSttModel,
         sttModelDownloaded: stt,
         llmModelDownloaded: llm,
+        voiceModelDownloaded: voice,
       };
     },
     refetchInterval: 5000,
@@ -48,16 +51,29 @@ export default function ModelDownloadNotification() {
     refetchInterval: 3000,
   });
 
+  const voiceModelDownloading = useQuery({
+    enabled: !checkForModelDownload.data?.voiceModelDownloaded,
+    queryKey: ["voice-model-downloading"],
+    queryFn: async () => {
+      return listenerCommands.isVoiceModelDownloading();
+    },
+    refetchInterval: 3000,
+  });
+
   useEffect(() => {
     if (!checkForModelDownload.data) {
       return;
     }
 
-    if (checkForModelDownload.data?.sttModelDownloaded && checkForModelDownload.data?.llmModelDownloaded) {
+    if (
+      checkForModelDownload.data?.sttModelDownloaded
+      && checkForModelDownload.data?.llmModelDownloaded
+      && checkForModelDownload.data?.voiceModelDownloaded
+    ) {
       return;
     }
 
-    if (sttModelDownloading.data || llmModelDownloading.data) {
+    if (sttModelDownloading.data || llmModelDownloading.data || voiceModelDownloading.data) {
       return;
     }
 
@@ -78,13 +94,17 @@ export default function ModelDownloadNotification() {
             if (!checkForModelDownload.data?.llmModelDownloaded && !llmModelDownloading.data) {
               showLlmModelDownloadToast();
             }
+
+            if (!checkForModelDownload.data?.voiceModelDownloaded && !voiceModelDownloading.data) {
+              showVoiceModelDownloadToast();
+            }
           },
           primary: true,
         },
       ],
       dismissible: false,
     });
-  }, [checkForModelDownload.data, sttModelDownloading.data, llmModelDownloading.data]);
+  }, [checkForModelDownload.data, sttModelDownloading.data, llmModelDownloading.data, voiceModelDownloading.data]);
 
   return null;
 }
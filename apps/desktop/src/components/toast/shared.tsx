@@ -1,6 +1,7 @@
 import { Channel } from "@tauri-apps/api/core";
 import { useEffect, useState } from "react";
 
+import { commands as listenerCommands } from "@hypr/plugin-listener";
 import { commands as localLlmCommands } from "@hypr/plugin-local-llm";
 import { commands as localSttCommands, SupportedModel } from "@hypr/plugin-local-stt";
 import { commands as windowsCommands } from "@hypr/plugin-windows";
@@ -108,6 +109,32 @@ export function showLlmModelDownloadToast() {
   );
 }
 
+export function showVoiceModelDownloadToast() {
+  const voiceChannel = new Channel();
+  listenerCommands.downloadVoiceModel(voiceChannel);
+
+  const id = "voice-model-download";
+
+  toast(
+    {
+      id,
+      title: "Voice Recognition Model",
+      content: (
+        <div className="space-y-1">
+          <div>Downloading the voice recognition model...</div>
+          <DownloadProgress
+            channel={voiceChannel}
+            onComplete={() => {
+              sonnerToast.dismiss(id);
+            }}
+          />
+        </div>
+      ),
+      dismissible: false,
+    },
+  );
+}
+
 export function enhanceFailedToast() {
   const id = "no-llm-connection";
 
@@ -42,7 +42,8 @@ export const Route = createFileRoute("/app/calendar")({
           start,
           end,
           limit: 100,
-        }),
+          cursor: null,
+        }).then((page) => page.items),
     });
 
     const eventsPromise = queryClient.fetchQuery({
@@ -54,7 +55,8 @@ export const Route = createFileRoute("/app/calendar")({
           start,
           end,
           limit: 100,
-        }),
+          cursor: null,
+        }).then((page) => page.items),
     });
 
     const [sessions, events] = await Promise.all([sessionsPromise, eventsPromise]);
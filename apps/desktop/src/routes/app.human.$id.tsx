@@ -435,11 +435,10 @@ function OrganizationControl({
 
   const { data: organizations = [] } = useQuery({
     queryKey: ["organizations", searchTerm],
-    queryFn: () => {
-      if (!searchTerm) {
-        return dbCommands.listOrganizations(null);
-      }
-      return dbCommands.listOrganizations({ search: [5, searchTerm] });
+    queryFn: async () => {
+      return searchTerm
+        ? (await dbCommands.listOrganizations({ type: "search", query: searchTerm, limit: 5, cursor: null })).items
+        : await dbCommands.listAllOrganizations();
     },
   });
 
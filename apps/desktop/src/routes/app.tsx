@@ -1,6 +1,6 @@
 import { createFileRoute, Outlet, useRouter } from "@tanstack/react-router";
 import { getCurrentWebviewWindow } from "@tauri-apps/api/webviewWindow";
-import { useEffect } from "react";
+import { useEffect, useRef } from "react";
 
 import LeftSidebar from "@/components/left-sidebar";
 import { LoginModal } from "@/components/login-modal";
@@ -15,13 +15,15 @@ import {
   RightPanelProvider,
   SearchProvider,
   SettingsProvider,
+  useHypr,
   useLeftSidebar,
   useRightPanel,
 } from "@/contexts";
 import { commands } from "@/types";
+import { indexSession } from "@/utils/semantic-search";
 import { commands as listenerCommands } from "@hypr/plugin-listener";
 import { events as windowsEvents, getCurrentWebviewWindowLabel } from "@hypr/plugin-windows";
-import { OngoingSessionProvider, SessionsProvider } from "@hypr/utils/contexts";
+import { OngoingSessionProvider, SessionsProvider, useOngoingSession } from "@hypr/utils/contexts";
 
 export const Route = createFileRoute("/app")({
   component: Component,
@@ -47,6 +49,7 @@ function Component() {
               <RightPanelProvider>
                 <AudioPermissions />
                 <MainWindowStateEventSupport />
+                <IndexStoppedSessions />
                 <SettingsProvider>
                   <NewNoteProvider>
                     <SearchProvider>
@@ -121,3 +124,28 @@ function MainWindowStateEventSupport() {
 
   return null;
 }
+
+// Recordings are indexed for semantic search as soon as they stop, not only once they are enhanced.
+function IndexStoppedSessions() {
+  const { onboardingSessionId } = useHypr();
+  const { status, sessionId } = useOngoingSession((s) => ({ status: s.status, sessionId: s.sessionId }));
+
+  // The session is cleared along with the status when recording stops, so it is kept here.
+  const recording = useRef<string | null>(null);
+
+  useEffect(() => {
+    if (status !== "inactive") {
+      recording.current = sessionId ?? recording.current;
+      return;
+    }
+
+    const stopped = recording.current;
+    recording.current = null;
+
+    if (stopped && stopped !== onboardingSessionId) {
+      indexSession(stopped).catch(console.error);
+    }
+  }, [status, sessionId, onboardingSessionId]);
+
+  return null;
+}
@@ -1,9 +1,16 @@
 import { commands as dbCommands, type Event, type Human, type Organization, type Session } from "@hypr/plugin-db";
 import { createStore } from "zustand";
 
+import { findSessions } from "@/utils/semantic-search";
+
+// Semantic search waits for typing to pause, since every query is embedded by the local model.
+const SEMANTIC_SEARCH_DELAY_MS = 300;
+
 export type SearchMatch = {
   type: "session";
   item: Session;
+  // For matches by meaning rather than keyword, the part of the meeting that matched.
+  passage?: string;
 } | {
   type: "event";
   item: Event;
@@ -32,16 +39,52 @@ type Actions = {
 export type SearchStore = ReturnType<typeof createSearchStore>;
 
 export const createSearchStore = (userId: string) => {
+  // The pending or running semantic search. A newer query cancels it.
+  let semantic: { timer: ReturnType<typeof setTimeout>; controller: AbortController } | null = null;
+
+  const cancelSemantic = () => {
+    if (semantic) {
+      clearTimeout(semantic.timer);
+      semantic.controller.abort();
+      semantic = null;
+    }
+  };
+
   return createStore<State & Actions>((set, get) => ({
     query: "",
     matches: [],
     searchInputRef: null,
     setQuery: async (query: string) => {
-      const [sessions, events, humans, organizations] = await Promise.all([
-        dbCommands.listSessions({ type: "search", query, limit: 10, user_id: userId }),
-        dbCommands.listEvents({ type: "search", query, limit: 5, user_id: userId }),
-        dbCommands.listHumans({ search: [3, query] }),
-        dbCommands.listOrganizations({ search: [3, query] }),
+      cancelSemantic();
+
+      // Added when they arrive, since embedding the query can take a while.
+      const controller = new AbortController();
+      const timer = setTimeout(() => {
+        findSessions(query, 5, controller.signal).then((related) => {
+          if (controller.signal.aborted || get().query !== query) {
+            return;
+          }
+
+          const current = get().matches;
+          const known = new Set(current.filter((m) => m.type === "session").map((m) => m.item.id));
+          const extra: SearchMatch[] = related
+            .filter(({ session }) => !known.has(session.id))
+            .map(({ session, passages }) => ({
+              type: "session" as const,
+              item: session,
+              passage: passages[0]?.text,
+            }));
+
+          set({ matches: [...current, ...extra] });
+        }).catch(() => {});
+      }, SEMANTIC_SEARCH_DELAY_MS);
+      semantic = { timer, controller };
+
+      const [{ items: sessions }, { items: events }, { items: humans }, { items: organizations }] = await Promise.all([
+        dbCommands.listSessions({ type: "search", query, limit: 10, cursor: null, user_id: userId }),
+        dbCommands.listEvents({ type: "search", query, limit: 5, cursor: null, user_id: userId }),
+        dbCommands.listHumans({ type: "search", query, limit: 3, cursor: null }),
+        dbCommands.listOrganizations({ type: "search", query, limit: 3, cursor: null }),
       ]);
 
       const matches: SearchMatch[] = [
@@ -75,6 +118,8 @@ export const createSearchStore = (userId: string) => {
       set({ query, matches });
     },
     clearSearch: () => {
+      cancelSemantic();
+
       const { searchInputRef } = get();
       searchInputRef?.current?.blur();
 
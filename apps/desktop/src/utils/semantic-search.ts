@@ -0,0 +1,97 @@
+import { commands as dbCommands, type SessionSearchResult } from "@hypr/plugin-db";
+import { commands as localLlmCommands } from "@hypr/plugin-local-llm";
+import { embed, embeddingModel, embedMany } from "@hypr/utils/ai";
+
+import { extractTextFromHtml } from "./parse";
+
+// Short enough that a passage is mostly about one thing.
+const PASSAGE_CHARS = 1000;
+// Embeddings share the local server's queue with generation, which answers 429 while it is full, e.g. during an
+// enhance. The SDK retries that with exponential backoff from 2s, so this waits out about two minutes of it.
+const QUEUE_FULL_RETRIES = 6;
+
+// Embeddings from different models are not comparable, so stored ones are keyed by the model the local server loads.
+async function localEmbeddingModel() {
+  const id = await localLlmCommands.getCurrentModel();
+  return { id, model: await embeddingModel(id) };
+}
+
+// Replaces the session's passages with its current transcript and enhanced note.
+export async function indexSession(sessionId: string) {
+  const session = await dbCommands.getSession({ id: sessionId });
+  if (!session) {
+    return;
+  }
+
+  const windows = await dbCommands.getTimelineViewWindows(sessionId, PASSAGE_CHARS);
+  const passages = [
+    ...windows.map((window) => ({
+      kind: "transcript" as const,
+      text: window.items.map((item) => item.text).join(" "),
+      start_ms: window.items[0]?.start ?? null,
+      end_ms: window.items[window.items.length - 1]?.end ?? null,
+    })),
+    ...splitNote(extractTextFromHtml(session.enhanced_memo_html ?? session.raw_memo_html)).map((text) => ({
+      kind: "note" as const,
+      text,
+      start_ms: null,
+      end_ms: null,
+    })),
+  ]
+    .filter((passage) => passage.text.trim().length > 0);
+
+  const { id, model } = await localEmbeddingModel();
+  const { embeddings } = passages.length
+    ? await embedMany({ model, values: passages.map((passage) => passage.text), maxRetries: QUEUE_FULL_RETRIES })
+    : { embeddings: [] };
+
+  await dbCommands.replaceSessionPassages(
+    sessionId,
+    id,
+    passages.map((passage, i) => ({
+      ...passage,
+      id: crypto.randomUUID(),
+      session_id: sessionId,
+      model: id,
+      created_at: new Date().toISOString(),
+      embedding: embeddings[i],
+    })),
+  );
+}
+
+// "Find meetings about X": sessions ranked by how close their passages are to `query`, with the passages that matched.
+// Empty unless the local server is already running, since starting it loads the model.
+export async function findSessions(
+  query: string,
+  limit = 10,
+  abortSignal?: AbortSignal,
+): Promise<SessionSearchResult[]> {
+  if (!query.trim() || !(await localLlmCommands.isServerRunning())) {
+    return [];
+  }
+
+  const { id, model } = await localEmbeddingModel();
+  const { embedding } = await embed({ model, value: query, abortSignal });
+  abortSignal?.throwIfAborted();
+
+  return dbCommands.searchSessionsByEmbedding(id, embedding, limit);
+}
+
+// Paragraphs, packed into passages of up to `PASSAGE_CHARS`.
+function splitNote(text: string) {
+  const passages: string[] = [];
+  let current = "";
+
+  for (const paragraph of text.split("\n")) {
+    if (current && current.length + paragraph.length > PASSAGE_CHARS) {
+      passages.push(current);
+      current = "";
+    }
+    current = current ? `${current}\n${paragraph}` : paragraph;
+  }
+  if (current) {
+    passages.push(current);
+  }
+
+  return passages;
+}
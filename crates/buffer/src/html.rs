@@ -0,0 +1,621 @@
+use markdown::mdast::{self, Node};
+
+use crate::Error;
+
+pub fn html_to_md(html: impl AsRef<str>) -> Result<String, Error> {
+    let dom = tl::parse(html.as_ref(), tl::ParserOptions::default())
+        .map_err(|e| Error::HTMLParseError(e.to_string()))?;
+    let parser = dom.parser();
+
+    let root = Node::Root(mdast::Root {
+        children: flow(dom.children(), parser),
+        position: None,
+    });
+
+    mdast_util_to_markdown::to_markdown_with_options(
+        &root,
+        &mdast_util_to_markdown::Options {
+            bullet: '-',
+            ..Default::default()
+        },
+    )
+    .map_err(|e| Error::MarkdownRenderError(e.to_string()))
+}
+
+fn child_handles(tag: &tl::HTMLTag) -> Vec<tl::NodeHandle> {
+    tag.children().top().iter().copied().collect()
+}
+
+fn is_block(node: &Node) -> bool {
+    matches!(
+        node,
+        Node::Paragraph(_)
+            | Node::Heading(_)
+            | Node::List(_)
+            | Node::Blockquote(_)
+            | Node::Code(_)
+            | Node::ThematicBreak(_)
+    )
+}
+
+// Converts children of a block container, wrapping runs of inline content into paragraphs.
+fn flow(handles: &[tl::NodeHandle], parser: &tl::Parser) -> Vec<Node> {
+    let mut blocks = Vec::new();
+    let mut inline = Vec::new();
+
+    for handle in handles {
+        for node in convert(handle, parser) {
+            if is_block(&node) {
+                flush_paragraph(&mut inline, &mut blocks);
+                blocks.push(node);
+            } else {
+                inline.push(node);
+            }
+        }
+    }
+
+    flush_paragraph(&mut inline, &mut blocks);
+    blocks
+}
+
+fn flush_paragraph(inline: &mut Vec<Node>, blocks: &mut Vec<Node>) {
+    let mut children = std::mem::take(inline);
+    trim_inline(&mut children);
+
+    if !children.is_empty() {
+        blocks.push(Node::Paragraph(mdast::Paragraph {
+            children,
+            position: None,
+        }));
+    }
+}
+
+// Converts children of an inline container. Block content found here is flattened.
+fn phrasing(handles: &[tl::NodeHandle], parser: &tl::Parser) -> Vec<Node> {
+    let mut nodes = Vec::new();
+
+    for handle in handles {
+        for node in convert(handle, parser) {
+            if is_block(&node) {
+                if let Some(children) = node.children() {
+                    nodes.extend(children.iter().cloned());
+                }
+            } else {
+                nodes.push(node);
+            }
+        }
+    }
+
+    nodes
+}
+
+fn trim_inline(nodes: &mut Vec<Node>) {
+    if let Some(Node::Text(text)) = nodes.first_mut() {
+        text.value = text.value.trim_start().to_string();
+    }
+    if let Some(Node::Text(text)) = nodes.last_mut() {
+        text.value = text.value.trim_end().to_string();
+    }
+    nodes.retain(|n| !matches!(n, Node::Text(t) if t.value.is_empty()));
+}
+
+fn convert(handle: &tl::NodeHandle, parser: &tl::Parser) -> Vec<Node> {
+    let tag = match handle.get(parser) {
+        Some(tl::Node::Tag(tag)) => tag,
+        Some(tl::Node::Raw(raw)) => {
+            let value = collapse_whitespace(&decode_entities(&raw.as_utf8_str()));
+            return vec![Node::Text(mdast::Text {
+                value,
+                position: None,
+            })];
+        }
+        _ => return vec![],
+    };
+
+    let name = tag.name().as_utf8_str().to_lowercase();
+    let children = child_handles(tag);
+
+    let node = match name.as_str() {
+        "p" => Node::Paragraph(mdast::Paragraph {
+            children: {
+                let mut c = phrasing(&children, parser);
+                trim_inline(&mut c);
+                c
+            },
+            position: None,
+        }),
+        "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => Node::Heading(mdast::Heading {
+            depth: name[1..].parse().unwrap_or(1),
+            children: {
+                let mut c = phrasing(&children, parser);
+                trim_inline(&mut c);
+                c
+            },
+            position: None,
+        }),
+        "ul" | "ol" => Node::List(mdast::List {
+            ordered: name == "ol",
+            start: if name == "ol" {
+                Some(attr(tag, "start").and_then(|s| s.parse().ok()).unwrap_or(1))
+            } else {
+                None
+            },
+            spread: false,
+            children: children
+                .iter()
+                .flat_map(|h| convert(h, parser))
+                .filter(|n| matches!(n, Node::ListItem(_)))
+                .collect(),
+            position: None,
+        }),
+        "li" => Node::ListItem(mdast::ListItem {
+            checked: None,
+            spread: false,
+            children: {
+                let mut c = flow(&children, parser);
+                if let Some(checked) = task_state(tag, &children, parser) {
+                    prepend_task_marker(&mut c, checked);
+                }
+                c
+            },
+            position: None,
+        }),
+        "blockquote" => Node::Blockquote(mdast::Blockquote {
+            children: flow(&children, parser),
+            position: None,
+        }),
+        "pre" => Node::Code(mdast::Code {
+            value: decode_entities(&tag.inner_text(parser))
+                .trim_end_matches('\n')
+                .to_string(),
+            lang: code_language(&children, parser),
+            meta: None,
+            position: None,
+        }),
+        "code" => Node::InlineCode(mdast::InlineCode {
+            value: decode_entities(&tag.inner_text(parser)),
+            position: None,
+        }),
+        "strong" | "b" => Node::Strong(mdast::Strong {
+            children: phrasing(&children, parser),
+            position: None,
+        }),
+        "em" | "i" => Node::Emphasis(mdast::Emphasis {
+            children: phrasing(&children, parser),
+            position: None,
+        }),
+        "s" | "del" | "strike" => {
+            let mut nodes = vec![raw("~~")];
+            nodes.extend(phrasing(&children, parser));
+            nodes.push(raw("~~"));
+            return nodes;
+        }
+        _ if is_mention(tag) => Node::Text(mdast::Text {
+            value: mention_text(tag, parser),
+            position: None,
+        }),
+        "a" => Node::Link(mdast::Link {
+            url: attr(tag, "href").unwrap_or_default(),
+            title: attr(tag, "title"),
+            children: phrasing(&children, parser),
+            position: None,
+        }),
+        "img" => Node::Image(mdast::Image {
+            url: attr(tag, "src").unwrap_or_default(),
+            alt: attr(tag, "alt").unwrap_or_default(),
+            title: attr(tag, "title"),
+            position: None,
+        }),
+        "br" => Node::Break(mdast::Break { position: None }),
+        "hr" => Node::ThematicBreak(mdast::ThematicBreak { position: None }),
+        "table" => match table(tag, parser) {
+            Some(node) => node,
+            None => return vec![],
+        },
+        "script" | "style" | "head" | "input" => return vec![],
+        // `div`, `span`, `html`, `body` and anything unknown are transparent.
+        _ => return children.iter().flat_map(|h| convert(h, parser)).collect(),
+    };
+
+    vec![node]
+}
+
+// Markdown emitted verbatim. `mdast_util_to_markdown` has no GFM extensions, so task markers,
+// strikethrough and tables are written out as raw text instead of their mdast nodes.
+fn raw(value: impl Into<String>) -> Node {
+    Node::Html(mdast::Html {
+        value: value.into(),
+        position: None,
+    })
+}
+
+// Tiptap marks task items with `data-checked`, GFM renders a checkbox as the first child.
+fn task_state(tag: &tl::HTMLTag, children: &[tl::NodeHandle], parser: &tl::Parser) -> Option<bool> {
+    if let Some(checked) = attr(tag, "data-checked") {
+        return Some(checked == "true");
+    }
+
+    children.iter().find_map(|h| match h.get(parser)? {
+        tl::Node::Tag(child) => match &*child.name().as_utf8_str() {
+            "input" if attr(child, "type").as_deref() == Some("checkbox") => {
+                Some(child.attributes().get("checked").is_some())
+            }
+            "label" | "p" => task_state(child, &child_handles(child), parser),
+            _ => None,
+        },
+        _ => None,
+    })
+}
+
+fn prepend_task_marker(blocks: &mut Vec<Node>, checked: bool) {
+    let marker = raw(if checked { "[x] " } else { "[ ] " });
+
+    match blocks.first_mut() {
+        Some(Node::Paragraph(p)) => p.children.insert(0, marker),
+        _ => blocks.insert(
+            0,
+            Node::Paragraph(mdast::Paragraph {
+                children: vec![marker],
+                position: None,
+            }),
+        ),
+    }
+}
+
+fn is_mention(tag: &tl::HTMLTag) -> bool {
+    attr(tag, "data-type").as_deref() == Some("mention")
+        || tag.attributes().get("data-mention").is_some()
+}
+
+fn mention_text(tag: &tl::HTMLTag, parser: &tl::Parser) -> String {
+    let label = attr(tag, "data-label")
+        .unwrap_or_else(|| decode_entities(&tag.inner_text(parser)).trim().to_string());
+
+    format!("@{}", label.trim_start_matches('@'))
+}
+
+// Renders a GFM table. The first row becomes the header, whether or not it sits in `thead`.
+fn table(tag: &tl::HTMLTag, parser: &tl::Parser) -> Option<Node> {
+    let mut rows = Vec::new();
+    collect_rows(&child_handles(tag), parser, &mut rows);
+
+    let columns = rows.iter().map(|r| r.len()).max()?;
+    if columns == 0 {
+        return None;
+    }
+
+    let align = rows[0]
+        .iter()
+        .map(|(_, align)| align.as_str())
+        .chain(std::iter::repeat(""))
+        .take(columns)
+        .map(|align| match align {
+            "left" => ":---",
+            "center" => ":---:",
+            "right" => "---:",
+            _ => "---",
+        })
+        .collect::<Vec<_>>();
+
+    let line = |cells: Vec<&str>| format!("| {} |", cells.join(" | "));
+
+    let mut lines = Vec::new();
+    for (i, row) in rows.iter().enumerate() {
+        let mut cells = row
+            .iter()
+            .map(|(cell, _)| cell.as_str())
+            .collect::<Vec<_>>();
+        cells.resize(columns, "");
+        lines.push(line(cells));
+
+        if i == 0 {
+            lines.push(line(align.clone()));
+        }
+    }
+
+    Some(Node::Paragraph(mdast::Paragraph {
+        children: vec![raw(lines.join("\n"))],
+        position: None,
+    }))
+}
+
+fn collect_rows(
+    handles: &[tl::NodeHandle],
+    parser: &tl::Parser,
+    rows: &mut Vec<Vec<(String, String)>>,
+) {
+    for handle in handles {
+        let Some(tl::Node::Tag(tag)) = handle.get(parser) else {
+            continue;
+        };
+
+        match tag.name().as_utf8_str().to_lowercase().as_str() {
+            "tr" => rows.push(
+                child_handles(tag)
+                    .iter()
+                    .filter_map(|h| match h.get(parser)? {
+                        tl::Node::Tag(cell)
+                            if matches!(&*cell.name().as_utf8_str(), "th" | "td") =>
+                        {
+                            Some((table_cell(cell, parser), cell_align(cell)))
+                        }
+                        _ => None,
+                    })
+                    .collect(),
+            ),
+            "thead" | "tbody" | "tfoot" => collect_rows(&child_handles(tag), parser, rows),
+            _ => {}
+        }
+    }
+}
+
+fn table_cell(cell: &tl::HTMLTag, parser: &tl::Parser) -> String {
+    let mut children = phrasing(&child_handles(cell), parser);
+    trim_inline(&mut children);
+
+    let paragraph = Node::Paragraph(mdast::Paragraph {
+        children,
+        position: None,
+    });
+
+    mdast_util_to_markdown::to_markdown(&paragraph)
+        .unwrap_or_default()
+        .trim()
+        .replace('\n', " ")
+        .replace('|', "\\|")
+}
+
+fn cell_align(cell: &tl::HTMLTag) -> String {
+    attr(cell, "align")
+        .or_else(|| {
+            attr(cell, "style")?.split(';').find_map(|decl| {
+                let (key, value) = decl.split_once(':')?;
+                (key.trim() == "text-align").then(|| value.trim().to_string())
+            })
+        })
+        .unwrap_or_default()
+}
+
+fn attr(tag: &tl::HTMLTag, name: &str) -> Option<String> {
+    tag.attributes()
+        .get(name)
+        .flatten()
+        .map(|v| decode_entities(&v.as_utf8_str()))
+}
+
+fn code_language(children: &[tl::NodeHandle], parser: &tl::Parser) -> Option<String> {
+    children.iter().find_map(|h| match h.get(parser) {
+        Some(tl::Node::Tag(tag)) if tag.name().as_utf8_str() == "code" => attr(tag, "class")?
+            .split_whitespace()
+            .find_map(|c| c.strip_prefix("language-").map(String::from)),
+        _ => None,
+    })
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut last_was_space = false;
+
+    for c in text.chars() {
+        if c.is_whitespace() && c != '\u{00A0}' {
+            if !last_was_space {
+                out.push(' ');
+            }
+            last_was_space = true;
+        } else {
+            out.push(c);
+            last_was_space = false;
+        }
+    }
+
+    out
+}
+
+fn decode_entities(text: &str) -> String {
+    if !text.contains('&') {
+        return text.to_string();
+    }
+
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find('&') {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let decoded = rest.find(';').and_then(|end| {
+            let entity = &rest[1..end];
+            let c = match entity {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                "nbsp" => Some('\u{00A0}'),
+                _ => entity
+                    .strip_prefix("#x")
+                    .or_else(|| entity.strip_prefix("#X"))
+                    .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                    .or_else(|| entity.strip_prefix('#').and_then(|d| d.parse().ok()))
+                    .and_then(char::from_u32),
+            };
+            c.map(|c| (c, end))
+        });
+
+        match decoded {
+            Some((c, end)) => {
+                out.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_html_to_md() {
+        let input = r#"<h1>Meeting</h1><p>Hello <strong>world</strong> &amp; <a href="https://hyprnote.com">friends</a></p><ul><li><p>one</p><ul><li><p>nested</p></li></ul></li><li><p>two</p></li></ul><pre><code class="language-rust">let a = 1;</code></pre>"#;
+
+        assert_eq!(
+            html_to_md(input).unwrap(),
+            "# Meeting\n\nHello **world** & [friends](https://hyprnote.com)\n\n- one\n  - nested\n- two\n\n```rust\nlet a = 1;\n```\n"
+        );
+    }
+
+    #[test]
+    fn test_html_to_md_empty() {
+        assert!(html_to_md("").unwrap().trim().is_empty());
+        assert!(html_to_md("<p>  </p>").unwrap().trim().is_empty());
+    }
+
+    #[test]
+    fn test_html_to_md_task_list() {
+        let tiptap = r#"<ul data-type="taskList"><li data-checked="true" data-type="taskItem"><label><input type="checkbox" checked="checked"><span></span></label><div><p>done</p></div></li><li data-checked="false" data-type="taskItem"><label><input type="checkbox"><span></span></label><div><p>todo</p></div></li></ul>"#;
+        assert_eq!(html_to_md(tiptap).unwrap(), "- [x] done\n- [ ] todo\n");
+
+        let gfm = "<ul>\n<li><input type=\"checkbox\" disabled=\"\" checked=\"\" /> done</li>\n<li><input type=\"checkbox\" disabled=\"\" /> todo</li>\n</ul>";
+        assert_eq!(html_to_md(gfm).unwrap(), "- [x] done\n- [ ] todo\n");
+    }
+
+    #[test]
+    fn test_html_to_md_table() {
+        let input = r#"<table><tbody><tr><th><p>Name</p></th><th style="text-align: right"><p>Count</p></th></tr><tr><td><p><strong>a</strong></p></td><td><p>1 | 2</p></td></tr><tr><td><p>b</p></td></tr></tbody></table>"#;
+
+        assert_eq!(
+            html_to_md(input).unwrap(),
+            "| Name | Count |\n| --- | ---: |\n| **a** | 1 \\| 2 |\n| b |  |\n"
+        );
+    }
+
+    #[test]
+    fn test_html_to_md_mention_and_strikethrough() {
+        let input = r#"<p>ping <span data-type="mention" data-id="1" data-label="John Doe">@John Doe</span> about <s>old</s> new</p>"#;
+
+        assert_eq!(
+            html_to_md(input).unwrap(),
+            "ping @John Doe about ~~old~~ new\n"
+        );
+    }
+
+    #[test]
+    fn test_decode_entities() {
+        assert_eq!(
+            decode_entities("a &lt;b&gt; &#39;c&#x27; &unknown; &"),
+            "a <b> 'c' &unknown; &"
+        );
+    }
+}
+
+#[cfg(test)]
+mod roundtrip {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn md_to_html(md: &str) -> String {
+        markdown::to_html_with_options(md, &markdown::Options::gfm()).unwrap()
+    }
+
+    fn word() -> impl Strategy<Value = String> {
+        "[a-z]{1,8}"
+    }
+
+    fn inline() -> impl Strategy<Value = String> {
+        prop_oneof![
+            word(),
+            word().prop_map(|w| format!("**{}**", w)),
+            word().prop_map(|w| format!("*{}*", w)),
+            word().prop_map(|w| format!("`{}`", w)),
+            word().prop_map(|w| format!("~~{}~~", w)),
+            (word(), word())
+                .prop_map(|(text, path)| format!("[{}](https://example.com/{})", text, path)),
+            word().prop_map(|w| format!("@{}", w)),
+        ]
+    }
+
+    fn line() -> impl Strategy<Value = String> {
+        prop::collection::vec(inline(), 1..6).prop_map(|words| words.join(" "))
+    }
+
+    fn block() -> impl Strategy<Value = String> {
+        prop_oneof![
+            line(),
+            (1..=6usize, line()).prop_map(|(depth, l)| format!("{} {}", "#".repeat(depth), l)),
+            prop::collection::vec((line(), prop::collection::vec(line(), 0..3)), 1..4).prop_map(
+                |items| {
+                    items
+                        .into_iter()
+                        .map(|(item, nested)| {
+                            std::iter::once(format!("- {}", item))
+                                .chain(nested.into_iter().map(|n| format!("  - {}", n)))
+                                .collect::<Vec<_>>()
+                                .join("\n")
+                        })
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                }
+            ),
+            prop::collection::vec((any::<bool>(), line()), 1..4).prop_map(|items| {
+                items
+                    .into_iter()
+                    .map(|(checked, l)| format!("- [{}] {}", if checked { "x" } else { " " }, l))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            }),
+            (word(), prop::collection::vec(word(), 1..4)).prop_map(|(lang, lines)| format!(
+                "```{}\n{}\n```",
+                lang,
+                lines.join("\n")
+            )),
+            (1..4usize)
+                .prop_flat_map(|columns| {
+                    prop::collection::vec(prop::collection::vec(word(), columns), 2..5)
+                })
+                .prop_map(|rows| {
+                    let line = |cells: Vec<String>| format!("| {} |", cells.join(" | "));
+                    let columns = rows[0].len();
+
+                    let mut lines = rows.into_iter().map(line).collect::<Vec<_>>();
+                    lines.insert(1, line(vec!["---".to_string(); columns]));
+                    lines.join("\n")
+                }),
+        ]
+    }
+
+    proptest! {
+        // The first pass normalizes the Markdown, after which md -> html -> md must be a fixed point.
+        #[test]
+        fn md_html_md_is_stable(blocks in prop::collection::vec(block(), 1..6)) {
+            let md = blocks.join("\n\n");
+
+            let once = html_to_md(md_to_html(&md)).unwrap();
+            let twice = html_to_md(md_to_html(&once)).unwrap();
+
+            prop_assert_eq!(once, twice);
+        }
+
+        #[test]
+        fn md_html_md_keeps_text(blocks in prop::collection::vec(block(), 1..6)) {
+            let md = blocks.join("\n\n");
+            let out = html_to_md(md_to_html(&md)).unwrap();
+
+            let words = |s: &str| {
+                s.split(|c: char| !c.is_ascii_lowercase())
+                    .filter(|w| !w.is_empty() && !["https", "example", "com", "x"].contains(w))
+                    .map(String::from)
+                    .collect::<Vec<_>>()
+            };
+
+            prop_assert_eq!(words(&md), words(&out));
+        }
+    }
+}
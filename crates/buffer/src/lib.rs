@@ -1,3 +1,6 @@
+mod html;
+pub use html::*;
+
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
     #[error("failed to parse markdown")]
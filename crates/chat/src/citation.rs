@@ -0,0 +1,36 @@
+// The source ids an answer cites, as in "[1]" or "[2, 3]", in the order they are first cited. Ids
+// outside `1..=max` are made up by the model and left out.
+pub(crate) fn cited(answer: &str, max: usize) -> Vec<usize> {
+    let mut ids = vec![];
+
+    for part in answer.split('[').skip(1) {
+        let Some(end) = part.find(']') else {
+            continue;
+        };
+
+        for id in part[..end].split(',') {
+            match id.trim().parse::<usize>() {
+                Ok(id) if (1..=max).contains(&id) && !ids.contains(&id) => ids.push(id),
+                _ => {}
+            }
+        }
+    }
+
+    ids
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cited() {
+        assert_eq!(cited("No sources.", 3), Vec::<usize>::new());
+        assert_eq!(cited("Two engineers [2]. Budget [1][2].", 3), vec![2, 1]);
+        assert_eq!(cited("Both [3, 1] and [ 2 ].", 3), vec![3, 1, 2]);
+        assert_eq!(
+            cited("Made up [4], [0], [a] and [link](x).", 3),
+            Vec::<usize>::new()
+        );
+    }
+}
@@ -0,0 +1,19 @@
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error(transparent)]
+    DatabaseError(#[from] hypr_db_core::Error),
+    #[error(transparent)]
+    TemplateError(#[from] hypr_template::Error),
+    #[error(transparent)]
+    RequestError(#[from] reqwest_middleware::Error),
+    #[error(transparent)]
+    ReqwestError(#[from] reqwest::Error),
+    #[error(transparent)]
+    SerdeJsonError(#[from] serde_json::Error),
+    #[error("chat group not found: {0}")]
+    ChatGroupNotFound(String),
+    #[error("model failed to answer: {0}")]
+    CompletionFailed(String),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
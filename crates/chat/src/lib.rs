@@ -0,0 +1,404 @@
+use std::collections::HashMap;
+
+use futures_util::StreamExt;
+
+use hypr_db_user::{
+    ChatCitation, ChatGroup, ChatMessage, ChatMessageRole, GetSessionFilter, PassageMatch, Session,
+    SessionPassageKind, UserDatabase,
+};
+use hypr_openai::{
+    ChatCompletionRequestAssistantMessage, ChatCompletionRequestAssistantMessageContent,
+    ChatCompletionRequestMessage, ChatCompletionRequestSystemMessage,
+    ChatCompletionRequestSystemMessageContent, ChatCompletionRequestUserMessage,
+    ChatCompletionRequestUserMessageContent, CreateChatCompletionRequest,
+    CreateChatCompletionStreamResponse, CreateEmbeddingRequest, CreateEmbeddingResponse,
+    EmbeddingInput,
+};
+
+mod citation;
+mod error;
+mod sse;
+
+pub use error::*;
+
+// Passages put in front of the model for each question.
+const MAX_SOURCES: usize = 8;
+// Earlier messages sent along, so follow-up questions make sense.
+const MAX_HISTORY: usize = 10;
+// Same as `PASSAGE_CHARS` in `semantic-search.ts`.
+const WINDOW_CHARS: usize = 1000;
+
+// Answers questions about the user's meetings from the passages that match them best, and keeps
+// the conversation in the chat group.
+pub struct ChatOrchestrator {
+    db: UserDatabase,
+    llm: hypr_openai::OpenAIClient,
+    model: String,
+    // Unset when the local server isn't running. Passages are then matched by their words.
+    embedder: Option<hypr_openai::OpenAIClient>,
+    env: hypr_template::minijinja::Environment<'static>,
+}
+
+// A passage as the prompt shows it, numbered so the answer can cite it.
+struct Source {
+    index: u32,
+    session: Option<Session>,
+    passage: PassageMatch,
+}
+
+impl ChatOrchestrator {
+    pub fn new(
+        db: UserDatabase,
+        llm: hypr_openai::OpenAIClient,
+        model: impl Into<String>,
+        embedder: Option<hypr_openai::OpenAIClient>,
+    ) -> Self {
+        let mut env = hypr_template::minijinja::Environment::new();
+        hypr_template::init(&mut env);
+
+        Self {
+            db,
+            llm,
+            model: model.into(),
+            embedder,
+            env,
+        }
+    }
+
+    // Streams the answer to `on_delta` as it's written, and returns it once saved. The question is
+    // saved along with the answer, so a turn that fails leaves nothing behind.
+    pub async fn send(
+        &self,
+        group_id: impl Into<String>,
+        content: impl Into<String>,
+        mut on_delta: impl FnMut(&str),
+    ) -> Result<ChatMessage> {
+        let group_id = group_id.into();
+        let content = content.into();
+
+        let group = self
+            .db
+            .get_chat_group(&group_id)
+            .await?
+            .ok_or_else(|| Error::ChatGroupNotFound(group_id.clone()))?;
+        let history = self.db.list_chat_messages(&group_id).await?;
+        let asked_at = chrono::Utc::now();
+
+        let sources = self.sources(&group, &content).await?;
+        let messages = self.messages(&group, &history, &sources, &content).await?;
+
+        let answer = self.complete(messages, &mut on_delta).await?;
+
+        let citations = citation::cited(&answer, sources.len())
+            .into_iter()
+            .map(|id| {
+                let source = &sources[id - 1];
+                ChatCitation {
+                    index: source.index,
+                    session_id: source.passage.session_id.clone(),
+                    start_ms: source.passage.start_ms,
+                    end_ms: source.passage.end_ms,
+                    text: source.passage.text.clone(),
+                }
+            })
+            .collect();
+
+        self.db
+            .upsert_chat_message(ChatMessage {
+                id: uuid::Uuid::new_v4().to_string(),
+                group_id: group_id.clone(),
+                created_at: asked_at,
+                role: ChatMessageRole::User,
+                content,
+                citations: vec![],
+            })
+            .await?;
+
+        let message = self
+            .db
+            .upsert_chat_message(ChatMessage {
+                id: uuid::Uuid::new_v4().to_string(),
+                group_id,
+                created_at: chrono::Utc::now(),
+                role: ChatMessageRole::Assistant,
+                content: answer,
+                citations,
+            })
+            .await?;
+
+        Ok(message)
+    }
+
+    async fn sources(&self, group: &ChatGroup, question: &str) -> Result<Vec<Source>> {
+        let passages = self.retrieve(group, question).await?;
+
+        let mut sessions: HashMap<String, Option<Session>> = HashMap::new();
+        let mut sources = vec![];
+
+        for (i, passage) in passages.into_iter().enumerate() {
+            if !sessions.contains_key(&passage.session_id) {
+                let session = self
+                    .db
+                    .get_session(GetSessionFilter::Id(passage.session_id.clone()))
+                    .await?;
+                sessions.insert(passage.session_id.clone(), session);
+            }
+
+            sources.push(Source {
+                index: i as u32 + 1,
+                session: sessions[&passage.session_id].clone(),
+                passage,
+            });
+        }
+
+        Ok(sources)
+    }
+
+    // Embedding search first, then word search for passages indexed by another model or when the
+    // question can't be embedded. A chat about one session that isn't indexed yet reads its transcript.
+    async fn retrieve(&self, group: &ChatGroup, question: &str) -> Result<Vec<PassageMatch>> {
+        if let Some((model, query)) = self.embed(question).await {
+            let passages = self
+                .db
+                .search_passages_by_embedding(
+                    &group.user_id,
+                    group.session_id.clone(),
+                    model,
+                    query,
+                    MAX_SOURCES,
+                )
+                .await?;
+            if !passages.is_empty() {
+                return Ok(passages);
+            }
+        }
+
+        let passages = self
+            .db
+            .search_passages_by_text(
+                &group.user_id,
+                group.session_id.clone(),
+                question,
+                MAX_SOURCES,
+            )
+            .await?;
+        if !passages.is_empty() {
+            return Ok(passages);
+        }
+
+        let Some(session_id) = &group.session_id else {
+            return Ok(vec![]);
+        };
+        let Some(timeline) = self.db.get_timeline_view(session_id).await? else {
+            return Ok(vec![]);
+        };
+
+        Ok(timeline
+            .windows(WINDOW_CHARS)
+            .into_iter()
+            .take(MAX_SOURCES)
+            .map(|window| PassageMatch {
+                session_id: session_id.clone(),
+                kind: SessionPassageKind::Transcript,
+                text: window
+                    .items
+                    .iter()
+                    .map(|item| item.text.trim())
+                    .collect::<Vec<_>>()
+                    .join(" "),
+                start_ms: window.items.first().map(|item| item.start),
+                end_ms: window.items.last().map(|item| item.end),
+                score: 0.0,
+            })
+            .collect())
+    }
+
+    // The vector, and the model that produced it, which is what passages are stored under.
+    async fn embed(&self, text: &str) -> Option<(String, Vec<f32>)> {
+        let embedder = self.embedder.as_ref()?;
+
+        // The local server embeds with whichever model it has loaded, and says which in the response.
+        let request = CreateEmbeddingRequest {
+            model: self.model.clone(),
+            input: EmbeddingInput::String(text.to_string()),
+            ..Default::default()
+        };
+
+        let response = async {
+            embedder
+                .embeddings(&request)
+                .await?
+                .error_for_status()?
+                .json::<CreateEmbeddingResponse>()
+                .await
+                .map_err(Error::from)
+        }
+        .await;
+
+        match response {
+            Ok(response) => {
+                let model = response.model;
+                response
+                    .data
+                    .into_iter()
+                    .next()
+                    .map(|e| (model, e.embedding))
+            }
+            Err(e) => {
+                tracing::warn!("failed to embed question: {}", e);
+                None
+            }
+        }
+    }
+
+    async fn messages(
+        &self,
+        group: &ChatGroup,
+        history: &[ChatMessage],
+        sources: &[Source],
+        question: &str,
+    ) -> Result<Vec<ChatCompletionRequestMessage>> {
+        let session = match &group.session_id {
+            Some(id) => {
+                self.db
+                    .get_session(GetSessionFilter::Id(id.clone()))
+                    .await?
+            }
+            None => None,
+        };
+
+        let ctx = serde_json::json!({
+            "session": session,
+            "question": question,
+            "sources": sources.iter().map(|source| serde_json::json!({
+                "index": source.index,
+                "title": source.session.as_ref().map(|s| s.title.clone()).filter(|t| !t.is_empty()),
+                "date": source.session.as_ref().map(|s| s.created_at.format("%Y-%m-%d").to_string()),
+                "kind": source.passage.kind,
+                "time": source.passage.start_ms.map(timestamp),
+                "text": source.passage.text.trim(),
+            })).collect::<Vec<_>>(),
+        });
+        let ctx = ctx.as_object().unwrap();
+
+        let system = hypr_template::render(
+            &self.env,
+            hypr_template::PredefinedTemplate::AiChatSystem.into(),
+            ctx,
+        )?;
+        let user = hypr_template::render(
+            &self.env,
+            hypr_template::PredefinedTemplate::AiChatUser.into(),
+            ctx,
+        )?;
+
+        let mut messages = vec![ChatCompletionRequestMessage::System(
+            ChatCompletionRequestSystemMessage {
+                content: ChatCompletionRequestSystemMessageContent::Text(system),
+                ..Default::default()
+            },
+        )];
+
+        // Earlier turns go without their sources, which are rarely relevant to the next question.
+        let skip = history.len().saturating_sub(MAX_HISTORY);
+        messages.extend(history.iter().skip(skip).map(|message| match message.role {
+            ChatMessageRole::User => {
+                ChatCompletionRequestMessage::User(ChatCompletionRequestUserMessage {
+                    content: ChatCompletionRequestUserMessageContent::Text(message.content.clone()),
+                    ..Default::default()
+                })
+            }
+            ChatMessageRole::Assistant => {
+                ChatCompletionRequestMessage::Assistant(ChatCompletionRequestAssistantMessage {
+                    content: Some(ChatCompletionRequestAssistantMessageContent::Text(
+                        message.content.clone(),
+                    )),
+                    ..Default::default()
+                })
+            }
+        }));
+
+        messages.push(ChatCompletionRequestMessage::User(
+            ChatCompletionRequestUserMessage {
+                content: ChatCompletionRequestUserMessageContent::Text(user),
+                ..Default::default()
+            },
+        ));
+
+        Ok(messages)
+    }
+
+    async fn complete(
+        &self,
+        messages: Vec<ChatCompletionRequestMessage>,
+        on_delta: &mut impl FnMut(&str),
+    ) -> Result<String> {
+        let request = CreateChatCompletionRequest {
+            model: self.model.clone(),
+            messages,
+            stream: Some(true),
+            ..Default::default()
+        };
+
+        let response = self
+            .llm
+            .chat_completion(&request)
+            .await?
+            .error_for_status()?;
+
+        let mut stream = response.bytes_stream();
+        let mut parser = sse::SseParser::default();
+        let mut answer = String::new();
+
+        while let Some(chunk) = stream.next().await {
+            for data in parser.push(&chunk?) {
+                if data == "[DONE]" {
+                    return Ok(answer);
+                }
+
+                let value: serde_json::Value = serde_json::from_str(&data)?;
+                if let Some(error) = value.get("error") {
+                    let message = error
+                        .get("message")
+                        .and_then(|m| m.as_str())
+                        .unwrap_or("unknown error");
+                    return Err(Error::CompletionFailed(message.to_string()));
+                }
+
+                let response: CreateChatCompletionStreamResponse = serde_json::from_value(value)?;
+                for choice in response.choices {
+                    if let Some(delta) = choice.delta.content.filter(|d| !d.is_empty()) {
+                        on_delta(&delta);
+                        answer.push_str(&delta);
+                    }
+                }
+            }
+        }
+
+        Ok(answer)
+    }
+}
+
+// "mm:ss", or "h:mm:ss" past the first hour.
+fn timestamp(ms: u64) -> String {
+    let secs = ms / 1000;
+    let (h, m, s) = (secs / 3600, secs / 60 % 60, secs % 60);
+
+    if h > 0 {
+        format!("{}:{:02}:{:02}", h, m, s)
+    } else {
+        format!("{:02}:{:02}", m, s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_timestamp() {
+        assert_eq!(timestamp(0), "00:00");
+        assert_eq!(timestamp(83_500), "01:23");
+        assert_eq!(timestamp(3_723_000), "1:02:03");
+    }
+}
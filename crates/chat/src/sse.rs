@@ -0,0 +1,53 @@
+// Splits a server-sent event stream, which arrives in arbitrary chunks, into the data of its events.
+#[derive(Default)]
+pub(crate) struct SseParser {
+    buffer: Vec<u8>,
+}
+
+impl SseParser {
+    pub fn push(&mut self, chunk: &[u8]) -> Vec<String> {
+        self.buffer.extend(chunk.iter().filter(|&&b| b != b'\r'));
+
+        let mut events = vec![];
+        while let Some(end) = self.buffer.windows(2).position(|w| w == b"\n\n") {
+            let event = self.buffer.drain(..end + 2).collect::<Vec<_>>();
+            let event = String::from_utf8_lossy(&event);
+
+            let data = event
+                .lines()
+                .filter_map(|line| line.strip_prefix("data:"))
+                .map(|data| data.strip_prefix(' ').unwrap_or(data))
+                .collect::<Vec<_>>()
+                .join("\n");
+            if !data.is_empty() {
+                events.push(data);
+            }
+        }
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sse_parser() {
+        let mut parser = SseParser::default();
+
+        assert!(parser.push(b"data: {\"a\"").is_empty());
+        assert_eq!(
+            parser.push(b":1}\n\ndata: [DONE]\r\n\r\n"),
+            vec!["{\"a\":1}", "[DONE]"]
+        );
+        assert_eq!(
+            parser.push(b": comment\n\ndata: x\ndata: y\n\n"),
+            vec!["x\ny"]
+        );
+
+        // Characters split across chunks come out whole.
+        let text = "data: 회의\n\n".as_bytes();
+        assert!(parser.push(&text[..8]).is_empty());
+        assert_eq!(parser.push(&text[8..]), vec!["회의"]);
+    }
+}
@@ -10,11 +10,27 @@ use rodio::buffer::SamplesBuffer;
 
 use crate::Predictor;
 
+// A chunk cut from the stream. Partial chunks hold the audio of the chunk in progress so far,
+// and are followed by more partials or the final chunk covering the same audio.
+#[derive(Debug, Clone)]
+pub struct AudioChunk {
+    pub samples: Vec<f32>,
+    pub sample_rate: u32,
+    // Position of the first sample in the stream.
+    pub start: Duration,
+    pub is_final: bool,
+}
+
 pub struct ChunkStream<S: AsyncSource + Unpin, P: Predictor + Unpin> {
     source: S,
     predictor: P,
     buffer: Vec<f32>,
     max_duration: Duration,
+    // Samples drained from the buffer so far.
+    offset: usize,
+    partial_interval: Option<Duration>,
+    // Buffer length at the last partial chunk.
+    partial_len: usize,
 }
 
 impl<S: AsyncSource + Unpin, P: Predictor + Unpin> ChunkStream<S, P> {
@@ -24,9 +40,18 @@ impl<S: AsyncSource + Unpin, P: Predictor + Unpin> ChunkStream<S, P> {
             predictor,
             buffer: Vec::new(),
             max_duration,
+            offset: 0,
+            partial_interval: None,
+            partial_len: 0,
         }
     }
 
+    // Also yields the chunk in progress every `interval` while there is speech in it.
+    pub fn with_partials(mut self, interval: Duration) -> PartialChunkStream<S, P> {
+        self.partial_interval = Some(interval);
+        PartialChunkStream { inner: self }
+    }
+
     fn max_samples(&self) -> usize {
         (self.source.sample_rate() as f64 * self.max_duration.as_secs_f64()) as usize
     }
@@ -35,7 +60,8 @@ impl<S: AsyncSource + Unpin, P: Predictor + Unpin> ChunkStream<S, P> {
         (self.source.sample_rate() as f64 * duration.as_secs_f64()) as usize
     }
 
-    fn trim_silence(predictor: &P, data: &mut Vec<f32>) {
+    // Returns the number of samples trimmed.
+    fn trim_silence(predictor: &P, data: &mut Vec<f32>) -> usize {
         const WINDOW_SIZE: usize = 100;
 
         let mut trim_index = 0;
@@ -50,55 +76,133 @@ impl<S: AsyncSource + Unpin, P: Predictor + Unpin> ChunkStream<S, P> {
         }
 
         data.drain(0..trim_index);
+        trim_index
     }
-}
 
-impl<S: AsyncSource + Unpin, P: Predictor + Unpin> Stream for ChunkStream<S, P> {
-    type Item = SamplesBuffer<f32>;
+    fn cut(
+        predictor: &P,
+        mut data: Vec<f32>,
+        offset: usize,
+        sample_rate: u32,
+        is_final: bool,
+    ) -> AudioChunk {
+        let trimmed = Self::trim_silence(predictor, &mut data);
+
+        AudioChunk {
+            samples: data,
+            sample_rate,
+            start: Duration::from_secs_f64((offset + trimmed) as f64 / sample_rate as f64),
+            is_final,
+        }
+    }
 
-    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
-        let this = self.get_mut();
-        let max_samples = this.max_samples();
-        let sample_rate = this.source.sample_rate();
+    fn poll_chunk(&mut self, cx: &mut Context<'_>) -> Poll<Option<AudioChunk>> {
+        let max_samples = self.max_samples();
+        let sample_rate = self.source.sample_rate();
 
-        let min_buffer_samples = this.samples_for_duration(Duration::from_secs(6));
-        let silence_window_samples = this.samples_for_duration(Duration::from_millis(500));
+        let min_buffer_samples = self.samples_for_duration(Duration::from_secs(6));
+        let silence_window_samples = self.samples_for_duration(Duration::from_millis(500));
+        let partial_samples = self.partial_interval.map(|d| self.samples_for_duration(d));
 
-        let stream = this.source.as_stream();
+        let stream = self.source.as_stream();
         let mut stream = std::pin::pin!(stream);
 
-        while this.buffer.len() < max_samples {
+        while self.buffer.len() < max_samples {
             match stream.as_mut().poll_next(cx) {
                 Poll::Ready(Some(sample)) => {
-                    this.buffer.push(sample);
+                    self.buffer.push(sample);
 
-                    if this.buffer.len() >= min_buffer_samples {
-                        let buffer_len = this.buffer.len();
+                    if self.buffer.len() >= min_buffer_samples {
+                        let buffer_len = self.buffer.len();
                         let silence_start = buffer_len.saturating_sub(silence_window_samples);
-                        let last_samples = &this.buffer[silence_start..buffer_len];
-
-                        if let Ok(false) = this.predictor.predict(last_samples) {
-                            let mut data = std::mem::take(&mut this.buffer);
-                            Self::trim_silence(&this.predictor, &mut data);
+                        let last_samples = &self.buffer[silence_start..buffer_len];
+
+                        if let Ok(false) = self.predictor.predict(last_samples) {
+                            let data = std::mem::take(&mut self.buffer);
+                            let offset = self.offset;
+                            self.offset += data.len();
+                            self.partial_len = 0;
+
+                            return Poll::Ready(Some(Self::cut(
+                                &self.predictor,
+                                data,
+                                offset,
+                                sample_rate,
+                                true,
+                            )));
+                        }
+                    }
 
-                            return Poll::Ready(Some(SamplesBuffer::new(1, sample_rate, data)));
+                    if let Some(partial_samples) = partial_samples {
+                        if self.buffer.len() >= self.partial_len + partial_samples {
+                            let new_samples = &self.buffer[self.partial_len..];
+                            self.partial_len = self.buffer.len();
+
+                            if let Ok(true) = self.predictor.predict(new_samples) {
+                                return Poll::Ready(Some(Self::cut(
+                                    &self.predictor,
+                                    self.buffer.clone(),
+                                    self.offset,
+                                    sample_rate,
+                                    false,
+                                )));
+                            }
                         }
                     }
                 }
-                Poll::Ready(None) if !this.buffer.is_empty() => {
-                    let mut data = std::mem::take(&mut this.buffer);
-                    Self::trim_silence(&this.predictor, &mut data);
-
-                    return Poll::Ready(Some(SamplesBuffer::new(1, sample_rate, data)));
+                Poll::Ready(None) if !self.buffer.is_empty() => {
+                    let data = std::mem::take(&mut self.buffer);
+                    let offset = self.offset;
+                    self.offset += data.len();
+                    self.partial_len = 0;
+
+                    return Poll::Ready(Some(Self::cut(
+                        &self.predictor,
+                        data,
+                        offset,
+                        sample_rate,
+                        true,
+                    )));
                 }
                 Poll::Ready(None) => return Poll::Ready(None),
                 Poll::Pending => return Poll::Pending,
             }
         }
 
-        let mut chunk: Vec<_> = this.buffer.drain(0..max_samples).collect();
-        Self::trim_silence(&this.predictor, &mut chunk);
+        let data: Vec<_> = self.buffer.drain(0..max_samples).collect();
+        let offset = self.offset;
+        self.offset += data.len();
+        self.partial_len = 0;
+
+        Poll::Ready(Some(Self::cut(
+            &self.predictor,
+            data,
+            offset,
+            sample_rate,
+            true,
+        )))
+    }
+}
 
-        Poll::Ready(Some(SamplesBuffer::new(1, sample_rate, chunk)))
+impl<S: AsyncSource + Unpin, P: Predictor + Unpin> Stream for ChunkStream<S, P> {
+    type Item = SamplesBuffer<f32>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        // Without a partial interval, every chunk is final.
+        self.get_mut()
+            .poll_chunk(cx)
+            .map(|chunk| chunk.map(|chunk| SamplesBuffer::new(1, chunk.sample_rate, chunk.samples)))
+    }
+}
+
+pub struct PartialChunkStream<S: AsyncSource + Unpin, P: Predictor + Unpin> {
+    inner: ChunkStream<S, P>,
+}
+
+impl<S: AsyncSource + Unpin, P: Predictor + Unpin> Stream for PartialChunkStream<S, P> {
+    type Item = AudioChunk;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.get_mut().inner.poll_chunk(cx)
     }
 }
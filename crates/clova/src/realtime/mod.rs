@@ -22,7 +22,7 @@ pub struct Client {
 #[derive(Debug, Default)]
 pub struct ClientBuilder {
     api_key: Option<String>,
-    keywords: Option<Vec<String>>,
+    boostings: Option<Vec<interface::KeywordBoostingItem>>,
 }
 
 impl ClientBuilder {
@@ -31,8 +31,8 @@ impl ClientBuilder {
         self
     }
 
-    pub fn keywords(mut self, keywords: impl Into<Vec<String>>) -> Self {
-        self.keywords = Some(keywords.into());
+    pub fn boostings(mut self, boostings: impl Into<Vec<interface::KeywordBoostingItem>>) -> Self {
+        self.boostings = Some(boostings.into());
         self
     }
 
@@ -52,7 +52,9 @@ impl ClientBuilder {
             transcription: Some(interface::Transcription {
                 language: interface::Language::Korean,
             }),
-            keyword_boosting: Some(self.keywords.unwrap_or_default().into()),
+            keyword_boosting: Some(interface::KeywordBoosting {
+                boostings: self.boostings.unwrap_or_default(),
+            }),
             semantic_epd: Some(interface::SemanticEpd {
                 skip_empty_text: Some(true),
                 use_word_epd: Some(true),
@@ -33,6 +33,9 @@ fn run(name: &str) {
             start: (v["start"].as_f64().unwrap() * 1000.0) as u64,
             end: (v["end"].as_f64().unwrap() * 1000.0) as u64,
             confidence: Some(1.0),
+            is_final: true,
+            revision: None,
+            language: None,
         })
         .collect();
 
@@ -10,6 +10,23 @@ pub enum Error {
     SerdeJsonError(#[from] serde_json::Error),
     #[error("invalid database config: {0}")]
     InvalidDatabaseConfig(String),
+    #[error("failed to decode column '{column}': {reason}")]
+    DecodeError { column: String, reason: String },
+    #[error("sync error: {0}")]
+    SyncError(String),
+    #[error("not found: {0}")]
+    NotFound(String),
+    #[error("invalid input: {0}")]
+    InvalidInput(String),
+}
+
+impl Error {
+    pub fn decode(column: impl Into<String>, reason: impl std::fmt::Display) -> Self {
+        Self::DecodeError {
+            column: column.into(),
+            reason: reason.to_string(),
+        }
+    }
 }
 
 impl Serialize for Error {
@@ -1,7 +1,12 @@
 use std::sync::Arc;
 
 mod errors;
+mod row;
+mod sync;
+
 pub use errors::*;
+pub use row::*;
+pub use sync::*;
 
 pub use libsql;
 
@@ -18,10 +23,6 @@ impl Database {
             Database::DynamicConnection(db) => db.connect().map_err(Into::into),
         }
     }
-
-    pub async fn sync(&self) -> Result<(), crate::Error> {
-        Ok(())
-    }
 }
 
 #[derive(Debug, Default)]
@@ -72,14 +73,9 @@ impl DatabaseBuilder {
                 let db = libsql::Builder::new_remote(url, token).build().await?;
                 Database::DynamicConnection(Arc::new(db))
             }
-            (_, Some(path), Some((url, token))) => {
-                let db = libsql::Builder::new_remote_replica(path, url, token)
-                    .read_your_writes(true)
-                    .sync_interval(std::time::Duration::from_secs(300))
-                    .build()
-                    .await?;
-                Database::DynamicConnection(Arc::new(db))
-            }
+            (_, Some(_), Some(_)) => Err(crate::Error::InvalidDatabaseConfig(
+                "'.local()' and '.remote()' can not be combined".to_string(),
+            ))?,
             (_, None, None) => Err(crate::Error::InvalidDatabaseConfig(
                 "either '.memory()' or '.local()' or '.remote()' must be called".to_string(),
             ))?,
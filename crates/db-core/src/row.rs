@@ -0,0 +1,86 @@
+use chrono::{DateTime, Utc};
+
+// Fallible, column-named accessors for hand-written `from_row` implementations.
+pub trait RowExt {
+    fn get_string(&self, idx: i32, column: &str) -> Result<String, crate::Error>;
+
+    fn get_opt_string(&self, idx: i32, column: &str) -> Result<Option<String>, crate::Error>;
+
+    // `NULL` decodes to `T::default()`, anything else must be valid JSON text.
+    fn get_json<T: serde::de::DeserializeOwned + Default>(
+        &self,
+        idx: i32,
+        column: &str,
+    ) -> Result<T, crate::Error>;
+
+    fn get_datetime(&self, idx: i32, column: &str) -> Result<DateTime<Utc>, crate::Error>;
+}
+
+impl RowExt for libsql::Row {
+    fn get_string(&self, idx: i32, column: &str) -> Result<String, crate::Error> {
+        self.get_opt_string(idx, column)?
+            .ok_or_else(|| crate::Error::decode(column, "unexpected NULL"))
+    }
+
+    fn get_opt_string(&self, idx: i32, column: &str) -> Result<Option<String>, crate::Error> {
+        match self
+            .get_value(idx)
+            .map_err(|e| crate::Error::decode(column, e))?
+        {
+            libsql::Value::Null => Ok(None),
+            libsql::Value::Text(s) => Ok(Some(s)),
+            v => Err(crate::Error::decode(
+                column,
+                format!("expected text, got {:?}", v),
+            )),
+        }
+    }
+
+    fn get_json<T: serde::de::DeserializeOwned + Default>(
+        &self,
+        idx: i32,
+        column: &str,
+    ) -> Result<T, crate::Error> {
+        match self
+            .get_value(idx)
+            .map_err(|e| crate::Error::decode(column, e))?
+        {
+            libsql::Value::Null => Ok(T::default()),
+            libsql::Value::Text(s) => {
+                serde_json::from_str(&s).map_err(|e| crate::Error::decode(column, e))
+            }
+            v => Err(crate::Error::decode(
+                column,
+                format!("expected JSON text, got {:?}", v),
+            )),
+        }
+    }
+
+    fn get_datetime(&self, idx: i32, column: &str) -> Result<DateTime<Utc>, crate::Error> {
+        let s = self
+            .get_str(idx)
+            .map_err(|e| crate::Error::decode(column, e))?;
+
+        DateTime::parse_from_rfc3339(s)
+            .map(|dt| dt.with_timezone(&Utc))
+            .map_err(|e| crate::Error::decode(column, e))
+    }
+}
+
+pub fn row_to_json(row: &libsql::Row) -> serde_json::Map<String, serde_json::Value> {
+    let mut map = serde_json::Map::new();
+
+    for idx in 0..row.column_count() {
+        let name = row.column_name(idx).unwrap_or_default().to_string();
+        let value = match row.get_value(idx) {
+            Ok(libsql::Value::Integer(v)) => serde_json::Value::from(v),
+            Ok(libsql::Value::Real(v)) => serde_json::Value::from(v),
+            Ok(libsql::Value::Text(v)) => serde_json::Value::from(v),
+            Ok(libsql::Value::Blob(v)) => serde_json::Value::from(v),
+            Ok(libsql::Value::Null) | Err(_) => serde_json::Value::Null,
+        };
+        map.insert(name, value);
+    }
+
+    map
+}
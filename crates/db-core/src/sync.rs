@@ -0,0 +1,230 @@
+use std::collections::BTreeMap;
+use std::future::Future;
+
+use chrono::{DateTime, Utc};
+
+// A row exchanged with a sync remote, carrying the last-modified time of each synced field.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct SyncRecord {
+    pub table: String,
+    pub id: String,
+    pub fields: serde_json::Map<String, serde_json::Value>,
+    pub field_timestamps: BTreeMap<String, DateTime<Utc>>,
+    // Set on tombstones, which carry no fields.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deleted_at: Option<DateTime<Utc>>,
+}
+
+impl SyncRecord {
+    pub fn tombstone(
+        table: impl Into<String>,
+        id: impl Into<String>,
+        deleted_at: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            table: table.into(),
+            id: id.into(),
+            fields: serde_json::Map::new(),
+            field_timestamps: BTreeMap::new(),
+            deleted_at: Some(deleted_at),
+        }
+    }
+
+    pub fn updated_at(&self) -> Option<DateTime<Utc>> {
+        self.field_timestamps
+            .values()
+            .max()
+            .copied()
+            .max(self.deleted_at)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum SyncSide {
+    #[serde(rename = "local")]
+    Local,
+    #[serde(rename = "remote")]
+    Remote,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldConflict {
+    pub table: String,
+    pub id: String,
+    pub field: String,
+    pub local_value: serde_json::Value,
+    pub remote_value: serde_json::Value,
+    pub local_updated_at: Option<DateTime<Utc>>,
+    pub remote_updated_at: Option<DateTime<Utc>>,
+    pub winner: SyncSide,
+}
+
+#[derive(Debug, Default)]
+pub struct SyncPull {
+    pub records: Vec<SyncRecord>,
+    // Opaque position in the remote change feed. `None` means nothing new.
+    pub cursor: Option<String>,
+}
+
+pub trait SyncRemote {
+    fn pull(
+        &self,
+        cursor: Option<String>,
+    ) -> impl Future<Output = Result<SyncPull, crate::Error>> + Send;
+
+    fn push(
+        &self,
+        records: Vec<SyncRecord>,
+    ) -> impl Future<Output = Result<(), crate::Error>> + Send;
+}
+
+// Last-writer-wins, field by field.
+// Fields without a timestamp lose to fields with one. Equal timestamps fall back to comparing the values,
+// so every device picks the same winner regardless of which side it is on.
+// A differing field is only reported as a conflict when both sides changed it after `synced_at`.
+// Deletes win over any edit, so a row deleted on one device never comes back.
+pub fn merge_records(
+    local: &SyncRecord,
+    remote: &SyncRecord,
+    synced_at: Option<DateTime<Utc>>,
+) -> (SyncRecord, Vec<FieldConflict>) {
+    let deleted_at = match (local.deleted_at, remote.deleted_at) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, b) => a.or(b),
+    };
+    if let Some(deleted_at) = deleted_at {
+        return (
+            SyncRecord::tombstone(&local.table, &local.id, deleted_at),
+            vec![],
+        );
+    }
+
+    let mut merged = local.clone();
+    let mut conflicts = Vec::new();
+
+    for (field, remote_value) in &remote.fields {
+        let local_value = local
+            .fields
+            .get(field)
+            .cloned()
+            .unwrap_or(serde_json::Value::Null);
+
+        let local_ts = local.field_timestamps.get(field).copied();
+        let remote_ts = remote.field_timestamps.get(field).copied();
+
+        let winner = if !local.fields.contains_key(field) {
+            SyncSide::Remote
+        } else {
+            match remote_ts.cmp(&local_ts) {
+                std::cmp::Ordering::Greater => SyncSide::Remote,
+                std::cmp::Ordering::Less => SyncSide::Local,
+                std::cmp::Ordering::Equal => {
+                    let (remote_str, local_str) =
+                        (remote_value.to_string(), local_value.to_string());
+                    if remote_str > local_str {
+                        SyncSide::Remote
+                    } else {
+                        SyncSide::Local
+                    }
+                }
+            }
+        };
+
+        if winner == SyncSide::Remote {
+            merged.fields.insert(field.clone(), remote_value.clone());
+            match remote_ts {
+                Some(ts) => merged.field_timestamps.insert(field.clone(), ts),
+                None => merged.field_timestamps.remove(field),
+            };
+        }
+
+        let changed_since = |ts: Option<DateTime<Utc>>| match (ts, synced_at) {
+            (Some(ts), Some(synced_at)) => ts > synced_at,
+            (Some(_), None) => true,
+            (None, _) => false,
+        };
+
+        if &local_value != remote_value && changed_since(local_ts) && changed_since(remote_ts) {
+            conflicts.push(FieldConflict {
+                table: local.table.clone(),
+                id: local.id.clone(),
+                field: field.clone(),
+                local_value,
+                remote_value: remote_value.clone(),
+                local_updated_at: local_ts,
+                remote_updated_at: remote_ts,
+                winner,
+            });
+        }
+    }
+
+    (merged, conflicts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn record(fields: &[(&str, &str, Option<i64>)]) -> SyncRecord {
+        SyncRecord {
+            table: "sessions".to_string(),
+            id: "1".to_string(),
+            fields: fields
+                .iter()
+                .map(|(k, v, _)| (k.to_string(), serde_json::Value::from(*v)))
+                .collect(),
+            field_timestamps: fields
+                .iter()
+                .filter_map(|(k, _, ts)| {
+                    ts.map(|ts| (k.to_string(), Utc.timestamp_opt(ts, 0).unwrap()))
+                })
+                .collect(),
+            deleted_at: None,
+        }
+    }
+
+    #[test]
+    fn test_merge_records() {
+        let local = record(&[("title", "a", Some(10)), ("memo", "x", Some(30))]);
+        let remote = record(&[("title", "b", Some(20)), ("memo", "y", Some(5))]);
+
+        let (merged, conflicts) = merge_records(&local, &remote, None);
+        assert_eq!(merged.fields["title"], "b");
+        assert_eq!(merged.fields["memo"], "x");
+        assert_eq!(merged.updated_at(), Some(Utc.timestamp_opt(30, 0).unwrap()));
+
+        assert_eq!(conflicts.len(), 2);
+        let winner_of = |field: &str| conflicts.iter().find(|c| c.field == field).unwrap().winner;
+        assert_eq!(winner_of("memo"), SyncSide::Local);
+        assert_eq!(winner_of("title"), SyncSide::Remote);
+
+        // Both sides converge on the same value.
+        let (reversed, _) = merge_records(&remote, &local, None);
+        assert_eq!(reversed.fields, merged.fields);
+
+        // Only `title` changed on both sides since the last sync.
+        let synced_at = Utc.timestamp_opt(8, 0).unwrap();
+        let (_, conflicts) = merge_records(&local, &remote, Some(synced_at));
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].field, "title");
+
+        let tie_a = record(&[("title", "a", Some(10))]);
+        let tie_b = record(&[("title", "b", Some(10))]);
+        assert_eq!(merge_records(&tie_a, &tie_b, None).0.fields["title"], "b");
+        assert_eq!(merge_records(&tie_b, &tie_a, None).0.fields["title"], "b");
+
+        let untouched = record(&[("title", "a", None)]);
+        let (merged, conflicts) = merge_records(&untouched, &tie_b, None);
+        assert_eq!(merged.fields["title"], "b");
+        assert!(conflicts.is_empty());
+
+        // An edit made after the delete still loses to it.
+        let deleted = SyncRecord::tombstone("sessions", "1", Utc.timestamp_opt(5, 0).unwrap());
+        for (a, b) in [(&local, &deleted), (&deleted, &local)] {
+            let (merged, conflicts) = merge_records(a, b, None);
+            assert_eq!(merged, deleted);
+            assert!(conflicts.is_empty());
+        }
+    }
+}
@@ -13,7 +13,10 @@ impl UserDatabase {
                 vec![calendar_id.as_ref()],
             )
             .await?;
-        let row = rows.next().await?.unwrap();
+        let row = rows
+            .next()
+            .await?
+            .ok_or_else(|| crate::Error::NotFound(format!("calendar {}", calendar_id.as_ref())))?;
         let calendar: Calendar = libsql::de::from_row(&row)?;
         Ok(calendar)
     }
@@ -29,11 +32,10 @@ impl UserDatabase {
                 "SELECT * FROM calendars WHERE user_id = ?",
                 vec![user_id.as_ref()],
             )
-            .await
-            .unwrap();
+            .await?;
 
         let mut items = Vec::new();
-        while let Some(row) = rows.next().await.unwrap() {
+        while let Some(row) = rows.next().await? {
             let item: Calendar = libsql::de::from_row(&row)?;
             items.push(item);
         }
@@ -111,7 +113,10 @@ impl UserDatabase {
             )
             .await?;
 
-        let row = rows.next().await?.unwrap();
+        let row = rows
+            .next()
+            .await?
+            .ok_or_else(|| crate::Error::NotFound("calendar".to_string()))?;
         let calendar: Calendar = libsql::de::from_row(&row)?;
         Ok(calendar)
     }
@@ -132,7 +137,10 @@ impl UserDatabase {
             )
             .await?;
 
-        let row = rows.next().await?.unwrap();
+        let row = rows
+            .next()
+            .await?
+            .ok_or_else(|| crate::Error::NotFound(format!("calendar {}", tracking_id.as_ref())))?;
         let calendar: Calendar = libsql::de::from_row(&row)?;
         Ok(calendar)
     }
@@ -0,0 +1 @@
+ALTER TABLE chat_groups ADD COLUMN session_id TEXT DEFAULT NULL;
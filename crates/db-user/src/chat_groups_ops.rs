@@ -10,8 +10,9 @@ impl UserDatabase {
                     id,
                     user_id,
                     name,
-                    created_at
-                ) VALUES (?, ?, ?, ?)
+                    created_at,
+                    session_id
+                ) VALUES (?, ?, ?, ?, ?)
                 RETURNING *",
                 vec![
                     libsql::Value::Text(group.id),
@@ -21,15 +22,36 @@ impl UserDatabase {
                         .map(libsql::Value::Text)
                         .unwrap_or(libsql::Value::Null),
                     libsql::Value::Text(group.created_at.to_rfc3339()),
+                    group
+                        .session_id
+                        .map(libsql::Value::Text)
+                        .unwrap_or(libsql::Value::Null),
                 ],
             )
             .await?;
 
-        let row = rows.next().await?.unwrap();
+        let row = rows
+            .next()
+            .await?
+            .ok_or_else(|| crate::Error::NotFound("chat group".to_string()))?;
         let group: ChatGroup = libsql::de::from_row(&row)?;
         Ok(group)
     }
 
+    pub async fn get_chat_group(
+        &self,
+        id: impl Into<String>,
+    ) -> Result<Option<ChatGroup>, crate::Error> {
+        let conn = self.conn()?;
+
+        let mut rows = conn
+            .query("SELECT * FROM chat_groups WHERE id = ?", vec![id.into()])
+            .await?;
+
+        let row = rows.next().await?;
+        Ok(row.map(|row| libsql::de::from_row(&row)).transpose()?)
+    }
+
     pub async fn list_chat_groups(
         &self,
         user_id: impl Into<String>,
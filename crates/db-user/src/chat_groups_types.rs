@@ -6,5 +6,7 @@ user_common_derives! {
         pub user_id: String,
         pub name: Option<String>,
         pub created_at: chrono::DateTime<chrono::Utc>,
+        // Chats about one session only draw on it. Others draw on every session of the user.
+        pub session_id: Option<String>,
     }
 }
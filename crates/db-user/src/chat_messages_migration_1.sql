@@ -0,0 +1 @@
+ALTER TABLE chat_messages ADD COLUMN citations TEXT NOT NULL DEFAULT '[]';
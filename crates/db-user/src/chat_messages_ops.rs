@@ -14,8 +14,9 @@ impl UserDatabase {
                     group_id,
                     created_at,
                     role,
-                    content
-                ) VALUES (?, ?, ?, ?, ?)
+                    content,
+                    citations
+                ) VALUES (?, ?, ?, ?, ?, ?)
                 RETURNING *",
                 vec![
                     message.id,
@@ -23,13 +24,16 @@ impl UserDatabase {
                     message.created_at.to_rfc3339(),
                     message.role.to_string(),
                     message.content,
+                    serde_json::to_string(&message.citations)?,
                 ],
             )
             .await?;
 
-        let row = rows.next().await?.unwrap();
-        let message: ChatMessage = libsql::de::from_row(&row)?;
-        Ok(message)
+        let row = rows
+            .next()
+            .await?
+            .ok_or_else(|| crate::Error::NotFound("chat message".to_string()))?;
+        ChatMessage::from_row(&row)
     }
 
     pub async fn list_chat_messages(
@@ -49,9 +53,75 @@ impl UserDatabase {
 
         let mut items = Vec::new();
         while let Some(row) = rows.next().await? {
-            let item: ChatMessage = libsql::de::from_row(&row)?;
-            items.push(item);
+            items.push(ChatMessage::from_row(&row)?);
         }
         Ok(items)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        tests::setup_db, ChatCitation, ChatGroup, ChatMessage, ChatMessageRole, Human, Session,
+    };
+
+    #[tokio::test]
+    async fn test_chat_messages() {
+        let db = setup_db().await;
+
+        let user = db.upsert_human(Human::default()).await.unwrap();
+        let session = db
+            .upsert_session(Session {
+                id: uuid::Uuid::new_v4().to_string(),
+                created_at: chrono::Utc::now(),
+                visited_at: chrono::Utc::now(),
+                user_id: user.id.clone(),
+                calendar_event_id: None,
+                title: "test".to_string(),
+                raw_memo_html: "".to_string(),
+                enhanced_memo_html: None,
+                conversations: vec![],
+            })
+            .await
+            .unwrap();
+
+        let group = db
+            .create_chat_group(ChatGroup {
+                id: uuid::Uuid::new_v4().to_string(),
+                user_id: user.id.clone(),
+                name: None,
+                created_at: chrono::Utc::now(),
+                session_id: Some(session.id.clone()),
+            })
+            .await
+            .unwrap();
+        assert_eq!(group.session_id, Some(session.id.clone()));
+        assert_eq!(
+            db.get_chat_group(&group.id).await.unwrap(),
+            Some(group.clone())
+        );
+
+        let citation = ChatCitation {
+            index: 1,
+            session_id: session.id.clone(),
+            start_ms: Some(1000),
+            end_ms: Some(5000),
+            text: "we need two engineers".to_string(),
+        };
+        db.upsert_chat_message(ChatMessage {
+            id: uuid::Uuid::new_v4().to_string(),
+            group_id: group.id.clone(),
+            created_at: chrono::Utc::now(),
+            role: ChatMessageRole::Assistant,
+            content: "Two engineers [1].".to_string(),
+            citations: vec![citation.clone()],
+        })
+        .await
+        .unwrap();
+
+        let messages = db.list_chat_messages(&group.id).await.unwrap();
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].role, ChatMessageRole::Assistant);
+        assert_eq!(messages[0].citations, vec![citation]);
+    }
+}
@@ -1,3 +1,5 @@
+use hypr_db_core::RowExt;
+
 use crate::user_common_derives;
 
 user_common_derives! {
@@ -15,5 +17,35 @@ user_common_derives! {
         pub created_at: chrono::DateTime<chrono::Utc>,
         pub role: ChatMessageRole,
         pub content: String,
+        // The sources an assistant message refers to.
+        pub citations: Vec<ChatCitation>,
+    }
+}
+
+user_common_derives! {
+    pub struct ChatCitation {
+        // What the answer calls it, as in "[1]".
+        pub index: u32,
+        pub session_id: String,
+        // Unset for notes, which have no place in the timeline.
+        pub start_ms: Option<u64>,
+        pub end_ms: Option<u64>,
+        pub text: String,
+    }
+}
+
+impl ChatMessage {
+    pub fn from_row(row: &libsql::Row) -> Result<Self, crate::Error> {
+        Ok(Self {
+            id: row.get_string(0, "id")?,
+            group_id: row.get_string(1, "group_id")?,
+            created_at: row.get_datetime(2, "created_at")?,
+            role: row
+                .get_string(3, "role")?
+                .parse()
+                .map_err(|e| crate::Error::decode("role", e))?,
+            content: row.get_string(4, "content")?,
+            citations: row.get_json(5, "citations")?,
+        })
     }
 }
@@ -1,3 +1,4 @@
+use hypr_db_core::RowExt;
 use serde::Deserialize;
 use std::str::FromStr;
 
@@ -14,22 +15,13 @@ user_common_derives! {
 }
 
 impl Config {
-    pub fn from_row(row: &libsql::Row) -> Result<Self, serde::de::value::Error> {
+    pub fn from_row(row: &libsql::Row) -> Result<Self, crate::Error> {
         Ok(Self {
-            id: row.get(0).expect("id"),
-            user_id: row.get(1).expect("user_id"),
-            general: row
-                .get_str(2)
-                .map(|s| serde_json::from_str(s).unwrap())
-                .unwrap_or_default(),
-            notification: row
-                .get_str(3)
-                .map(|s| serde_json::from_str(s).unwrap())
-                .unwrap_or_default(),
-            ai: row
-                .get_str(4)
-                .map(|s| serde_json::from_str(s).unwrap())
-                .unwrap_or_default(),
+            id: row.get_string(0, "id")?,
+            user_id: row.get_string(1, "user_id")?,
+            general: row.get_json(2, "general")?,
+            notification: row.get_json(3, "notification")?,
+            ai: row.get_json(4, "ai")?,
         })
     }
 }
@@ -44,6 +36,12 @@ user_common_derives! {
         pub jargons: Vec<String>,
         pub telemetry_consent: bool,
         pub save_recordings: Option<bool>,
+        // Detect the spoken language instead of assuming `display_language`.
+        pub detect_language: Option<bool>,
+        // ISO 639-1 code to translate finalized transcripts into. `None` turns translation off.
+        pub translation_language: Option<String>,
+        // Template whose sections structure enhanced notes. `None` lets the model pick its own sections.
+        pub selected_template_id: Option<String>,
     }
 }
 
@@ -55,6 +53,9 @@ impl Default for ConfigGeneral {
             jargons: vec![],
             telemetry_consent: true,
             save_recordings: Some(true),
+            detect_language: Some(false),
+            translation_language: None,
+            selected_template_id: None,
         }
     }
 }
@@ -0,0 +1,206 @@
+use hypr_db_core::SqlTable;
+
+use super::{
+    Calendar, ChatGroup, ChatMessage, Config, DoctorIssue, DoctorReport, Event, ExtensionMapping,
+    Human, Organization, QuarantinedRow, Session, SessionPassage, SessionSpeaker, Tag, Template,
+    UserDatabase, VocabularyEntry, VoiceEnrollment, VoiceSuggestion,
+};
+
+type Decoder = fn(&libsql::Row) -> Result<(), crate::Error>;
+
+fn decode_serde<T: serde::de::DeserializeOwned>(row: &libsql::Row) -> Result<(), crate::Error> {
+    libsql::de::from_row::<T>(row)?;
+    Ok(())
+}
+
+// Every table with a decoder, checked with the same mapping the regular ops use.
+const CHECKS: [(&str, Decoder); 16] = [
+    ("sessions", |row| Session::from_row(row).map(|_| ())),
+    ("configs", |row| Config::from_row(row).map(|_| ())),
+    ("templates", |row| Template::from_row(row).map(|_| ())),
+    ("extension_mappings", |row| {
+        ExtensionMapping::from_row(row).map(|_| ())
+    }),
+    ("session_speakers", |row| {
+        SessionSpeaker::from_row(row).map(|_| ())
+    }),
+    ("voice_enrollments", |row| {
+        VoiceEnrollment::from_row(row).map(|_| ())
+    }),
+    ("vocabulary", |row| {
+        VocabularyEntry::from_row(row).map(|_| ())
+    }),
+    ("chat_messages", |row| {
+        ChatMessage::from_row(row).map(|_| ())
+    }),
+    ("session_passages", |row| {
+        SessionPassage::from_row(row).map(|_| ())
+    }),
+    ("calendars", decode_serde::<Calendar>),
+    ("events", decode_serde::<Event>),
+    ("humans", decode_serde::<Human>),
+    ("organizations", decode_serde::<Organization>),
+    ("tags", decode_serde::<Tag>),
+    ("chat_groups", decode_serde::<ChatGroup>),
+    ("voice_suggestions", decode_serde::<VoiceSuggestion>),
+];
+
+impl UserDatabase {
+    // Scans every table for rows that fail to decode.
+    // With `quarantine`, those rows are moved into the `quarantine` table so the rest of the app keeps working.
+    pub async fn doctor(&self, quarantine: bool) -> Result<DoctorReport, crate::Error> {
+        let conn = self.conn()?;
+
+        let mut report = DoctorReport::default();
+        let mut undecodable = Vec::new();
+
+        for (table, decode) in CHECKS {
+            let mut rows = conn.query(&format!("SELECT * FROM {}", table), ()).await?;
+
+            while let Some(row) = rows.next().await? {
+                report.scanned += 1;
+
+                if let Err(e) = decode(&row) {
+                    let data = hypr_db_core::row_to_json(&row);
+                    let row_id = data.get("id").and_then(|v| v.as_str()).map(String::from);
+
+                    tracing::warn!(table = table, row_id = ?row_id, error = %e, "undecodable_row");
+
+                    report.issues.push(DoctorIssue {
+                        table: table.to_string(),
+                        row_id,
+                        error: e.to_string(),
+                    });
+                    undecodable.push(data);
+                }
+            }
+        }
+
+        if !quarantine || report.issues.is_empty() {
+            return Ok(report);
+        }
+
+        let tx = conn.transaction().await?;
+
+        for (issue, data) in report.issues.iter().zip(undecodable) {
+            // Rows without an `id` can not be addressed, so they are only reported.
+            let Some(row_id) = issue.row_id.clone() else {
+                continue;
+            };
+
+            tx.execute(
+                &format!(
+                    "INSERT INTO {} (id, source_table, row_id, data, error) VALUES (?, ?, ?, ?, ?)",
+                    QuarantinedRow::sql_table()
+                ),
+                vec![
+                    uuid::Uuid::new_v4().to_string(),
+                    issue.table.clone(),
+                    row_id.clone(),
+                    serde_json::to_string(&data)?,
+                    issue.error.clone(),
+                ],
+            )
+            .await?;
+
+            tx.execute(
+                &format!("DELETE FROM {} WHERE id = ?", issue.table),
+                vec![row_id],
+            )
+            .await?;
+
+            report.quarantined += 1;
+        }
+
+        tx.commit().await?;
+        Ok(report)
+    }
+
+    pub async fn list_quarantined_rows(&self) -> Result<Vec<QuarantinedRow>, crate::Error> {
+        let conn = self.conn()?;
+
+        let sql = format!(
+            "SELECT * FROM {} ORDER BY created_at DESC",
+            QuarantinedRow::sql_table()
+        );
+        let mut rows = conn.query(&sql, ()).await?;
+
+        let mut items = Vec::new();
+        while let Some(row) = rows.next().await? {
+            let item: QuarantinedRow = libsql::de::from_row(&row)?;
+            items.push(item);
+        }
+        Ok(items)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{tests::setup_db, GetSessionFilter, Human, Session};
+
+    #[tokio::test]
+    async fn test_doctor() {
+        let db = setup_db().await;
+
+        let user = db.upsert_human(Human::default()).await.unwrap();
+        let session = db
+            .upsert_session(Session {
+                id: uuid::Uuid::new_v4().to_string(),
+                user_id: user.id.clone(),
+                created_at: chrono::Utc::now(),
+                visited_at: chrono::Utc::now(),
+                calendar_event_id: None,
+                title: "ok".to_string(),
+                raw_memo_html: "".to_string(),
+                enhanced_memo_html: None,
+                conversations: vec![],
+            })
+            .await
+            .unwrap();
+
+        let report = db.doctor(false).await.unwrap();
+        assert!(report.issues.is_empty());
+
+        db.conn()
+            .unwrap()
+            .execute(
+                "INSERT INTO sessions (id, user_id, title, raw_memo_html, conversations) VALUES ('broken', ?, '', '', '{\"old\": true}')",
+                vec![user.id.clone()],
+            )
+            .await
+            .unwrap();
+
+        match db
+            .get_session(GetSessionFilter::Id("broken".to_string()))
+            .await
+        {
+            Err(crate::Error::DecodeError { column, .. }) => assert_eq!(column, "conversations"),
+            other => panic!(
+                "expected decode error, got {:?}",
+                other.map(|s| s.map(|s| s.id))
+            ),
+        }
+        assert!(db.list_sessions(None).await.is_err());
+
+        let report = db.doctor(false).await.unwrap();
+        assert_eq!(report.issues.len(), 1);
+        assert_eq!(report.issues[0].table, "sessions");
+        assert_eq!(report.issues[0].row_id, Some("broken".to_string()));
+        assert_eq!(report.quarantined, 0);
+
+        let report = db.doctor(true).await.unwrap();
+        assert_eq!(report.quarantined, 1);
+
+        let sessions = db.list_sessions(None).await.unwrap().items;
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].id, session.id);
+
+        let quarantined = db.list_quarantined_rows().await.unwrap();
+        assert_eq!(quarantined.len(), 1);
+        assert_eq!(quarantined[0].source_table, "sessions");
+        let data: serde_json::Value = serde_json::from_str(&quarantined[0].data).unwrap();
+        assert_eq!(data["conversations"], "{\"old\": true}");
+
+        assert!(db.doctor(false).await.unwrap().issues.is_empty());
+    }
+}
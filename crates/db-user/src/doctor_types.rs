@@ -0,0 +1,32 @@
+use chrono::{DateTime, Utc};
+
+use crate::user_common_derives;
+
+user_common_derives! {
+    #[derive(Default)]
+    pub struct DoctorReport {
+        pub scanned: u32,
+        pub issues: Vec<DoctorIssue>,
+        pub quarantined: u32,
+    }
+}
+
+user_common_derives! {
+    pub struct DoctorIssue {
+        pub table: String,
+        pub row_id: Option<String>,
+        pub error: String,
+    }
+}
+
+user_common_derives! {
+    #[sql_table("quarantine")]
+    pub struct QuarantinedRow {
+        pub id: String,
+        pub source_table: String,
+        pub row_id: String,
+        pub data: String,
+        pub error: String,
+        pub created_at: DateTime<Utc>,
+    }
+}
@@ -1,4 +1,7 @@
-use super::{Event, ListEventFilter, ListEventFilterCommon, ListEventFilterSpecific, UserDatabase};
+use super::{
+    page_size, Cursor, Event, ListEventFilter, ListEventFilterCommon, ListEventFilterSpecific,
+    Page, UserDatabase, DEFAULT_PAGE_SIZE,
+};
 
 impl UserDatabase {
     pub async fn get_event(&self, id: impl Into<String>) -> Result<Option<Event>, crate::Error> {
@@ -72,7 +75,10 @@ impl UserDatabase {
             )
             .await?;
 
-        let row = rows.next().await?.unwrap();
+        let row = rows
+            .next()
+            .await?
+            .ok_or_else(|| crate::Error::NotFound("event".to_string()))?;
         let event: Event = libsql::de::from_row(&row)?;
         Ok(event)
     }
@@ -80,55 +86,123 @@ impl UserDatabase {
     pub async fn list_events(
         &self,
         filter: Option<ListEventFilter>,
-    ) -> Result<Vec<Event>, crate::Error> {
+    ) -> Result<Page<Event>, crate::Error> {
         let conn = self.conn()?;
 
-        let mut rows = match filter {
+        let (limit, rows) = match filter {
             Some(ListEventFilter {
-                common: ListEventFilterCommon { user_id, limit },
+                common:
+                    ListEventFilterCommon {
+                        user_id,
+                        limit,
+                        cursor,
+                    },
                 specific: ListEventFilterSpecific::Simple {},
             }) => {
-                conn.query(
-                    "SELECT * FROM events WHERE user_id = ? ORDER BY start_date DESC LIMIT ?",
-                    vec![user_id, limit.unwrap_or(100).to_string()],
-                )
-                .await?
+                let limit = page_size(limit);
+                let cursor = Cursor::decode(cursor.as_deref())?;
+
+                let rows = conn
+                    .query(
+                        "
+                        SELECT *, start_date AS cursor_key, id AS cursor_id FROM events
+                        WHERE
+                            user_id = :user_id AND
+                            (:cursor_key IS NULL OR (start_date, id) < (:cursor_key, :cursor_id))
+                        ORDER BY start_date DESC, id DESC
+                        LIMIT :limit",
+                        libsql::named_params! {
+                            ":user_id": user_id,
+                            ":cursor_key": cursor.key,
+                            ":cursor_id": cursor.id,
+                            ":limit": limit + 1,
+                        },
+                    )
+                    .await?;
+                (limit, rows)
             }
             Some(ListEventFilter {
-                common: ListEventFilterCommon { user_id, limit },
+                common:
+                    ListEventFilterCommon {
+                        user_id,
+                        limit,
+                        cursor,
+                    },
                 specific: ListEventFilterSpecific::Search { query },
             }) => {
-                conn.query(
-                    "SELECT * FROM events WHERE user_id = ? AND name LIKE ? ORDER BY start_date DESC LIMIT ?",
-                    vec![user_id, format!("%{}%", query), limit.unwrap_or(100).to_string()],
-                )
-                .await?
+                let limit = page_size(limit);
+                let cursor = Cursor::decode(cursor.as_deref())?;
+
+                let rows = conn
+                    .query(
+                        "
+                        SELECT *, start_date AS cursor_key, id AS cursor_id FROM events
+                        WHERE
+                            user_id = :user_id AND
+                            name LIKE :query AND
+                            (:cursor_key IS NULL OR (start_date, id) < (:cursor_key, :cursor_id))
+                        ORDER BY start_date DESC, id DESC
+                        LIMIT :limit",
+                        libsql::named_params! {
+                            ":user_id": user_id,
+                            ":query": format!("%{}%", query),
+                            ":cursor_key": cursor.key,
+                            ":cursor_id": cursor.id,
+                            ":limit": limit + 1,
+                        },
+                    )
+                    .await?;
+                (limit, rows)
             }
             Some(ListEventFilter {
-                common: ListEventFilterCommon { user_id, limit },
+                common:
+                    ListEventFilterCommon {
+                        user_id,
+                        limit,
+                        cursor,
+                    },
                 specific: ListEventFilterSpecific::DateRange { start, end },
             }) => {
-                conn.query(
-                    "SELECT * FROM events WHERE user_id = ? AND start_date BETWEEN ? AND ? ORDER BY start_date ASC LIMIT ?",
-                    vec![user_id, start.to_rfc3339(), end.to_rfc3339(), limit.unwrap_or(100).to_string()],
-                )
-                .await?
+                let limit = page_size(limit);
+                let cursor = Cursor::decode(cursor.as_deref())?;
+
+                let rows = conn
+                    .query(
+                        "
+                        SELECT *, start_date AS cursor_key, id AS cursor_id FROM events
+                        WHERE
+                            user_id = :user_id AND
+                            start_date BETWEEN :start AND :end AND
+                            (:cursor_key IS NULL OR (start_date, id) > (:cursor_key, :cursor_id))
+                        ORDER BY start_date ASC, id ASC
+                        LIMIT :limit",
+                        libsql::named_params! {
+                            ":user_id": user_id,
+                            ":start": start.to_rfc3339(),
+                            ":end": end.to_rfc3339(),
+                            ":cursor_key": cursor.key,
+                            ":cursor_id": cursor.id,
+                            ":limit": limit + 1,
+                        },
+                    )
+                    .await?;
+                (limit, rows)
             }
             None => {
-                conn.query(
-                    "SELECT * FROM events ORDER BY start_date DESC LIMIT 100",
-                    (),
-                )
-                .await?
+                let rows = conn
+                    .query(
+                        "
+                        SELECT *, start_date AS cursor_key, id AS cursor_id FROM events
+                        ORDER BY start_date DESC, id DESC
+                        LIMIT ?",
+                        vec![DEFAULT_PAGE_SIZE + 1],
+                    )
+                    .await?;
+                (DEFAULT_PAGE_SIZE, rows)
             }
         };
 
-        let mut items = Vec::new();
-        while let Some(row) = rows.next().await.unwrap() {
-            let item: Event = libsql::de::from_row(&row)?;
-            items.push(item);
-        }
-        Ok(items)
+        Page::from_rows(rows, limit, |row| Ok(libsql::de::from_row(row)?)).await
     }
 }
 
@@ -149,7 +223,7 @@ mod tests {
             .await
             .unwrap();
 
-        let events = db.list_events(None).await.unwrap();
+        let events = db.list_events(None).await.unwrap().items;
         assert_eq!(events.len(), 0);
 
         let calendar = Calendar {
@@ -189,7 +263,7 @@ mod tests {
         assert_eq!(event.tracking_id, "event_test");
         assert_eq!(event.google_event_url, None);
 
-        let events = db.list_events(None).await.unwrap();
+        let events = db.list_events(None).await.unwrap().items;
         assert_eq!(events.len(), 1);
     }
 }
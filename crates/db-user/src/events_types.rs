@@ -29,6 +29,7 @@ user_common_derives! {
     pub struct ListEventFilterCommon {
         pub user_id: String,
         pub limit: Option<u32>,
+        pub cursor: Option<String>,
     }
 }
 
@@ -29,7 +29,10 @@ impl UserDatabase {
         ];
 
         let mut rows = conn.query(&sql, params).await?;
-        let row = rows.next().await?.unwrap();
+        let row = rows
+            .next()
+            .await?
+            .ok_or_else(|| crate::Error::NotFound("extension mapping".to_string()))?;
         let item = ExtensionMapping::from_row(&row)?;
         Ok(item)
     }
@@ -1,3 +1,5 @@
+use hypr_db_core::RowExt;
+
 use crate::user_common_derives;
 
 user_common_derives! {
@@ -40,19 +42,13 @@ user_common_derives! {
 }
 
 impl ExtensionMapping {
-    pub fn from_row(row: &libsql::Row) -> Result<Self, serde::de::value::Error> {
+    pub fn from_row(row: &libsql::Row) -> Result<Self, crate::Error> {
         Ok(Self {
-            id: row.get(0).expect("id"),
-            extension_id: row.get(1).expect("extension_id"),
-            user_id: row.get(2).expect("user_id"),
-            config: row
-                .get_str(3)
-                .map(|s| serde_json::from_str(s).unwrap())
-                .unwrap_or_default(),
-            widgets: row
-                .get_str(4)
-                .map(|s| serde_json::from_str(s).unwrap())
-                .unwrap_or_default(),
+            id: row.get_string(0, "id")?,
+            extension_id: row.get_string(1, "extension_id")?,
+            user_id: row.get_string(2, "user_id")?,
+            config: row.get_json(3, "config")?,
+            widgets: row.get_json(4, "widgets")?,
         })
     }
 }
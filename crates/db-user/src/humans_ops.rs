@@ -1,6 +1,9 @@
 use hypr_db_core::SqlTable;
 
-use super::{Human, ListHumanFilter, UserDatabase};
+use super::{
+    page_size, Cursor, Human, ListHumanFilter, ListHumanFilterCommon, ListHumanFilterSpecific,
+    Page, UserDatabase,
+};
 
 impl UserDatabase {
     pub async fn get_human(&self, id: impl Into<String>) -> Result<Option<Human>, crate::Error> {
@@ -56,38 +59,76 @@ impl UserDatabase {
         );
 
         let mut rows = conn.query(&sql, params).await?;
-        let row = rows.next().await?.unwrap();
+        let row = rows
+            .next()
+            .await?
+            .ok_or_else(|| crate::Error::NotFound("human".to_string()))?;
         let human: Human = libsql::de::from_row(&row)?;
         Ok(human)
     }
 
+    // Every human, for pickers that show the whole list. Screens that can grow use `list_humans`.
+    pub async fn list_all_humans(&self) -> Result<Vec<Human>, crate::Error> {
+        let conn = self.conn()?;
+
+        let sql = format!(
+            "SELECT * FROM {} ORDER BY COALESCE(full_name, '') ASC, id ASC",
+            Human::sql_table()
+        );
+        let mut rows = conn.query(&sql, ()).await?;
+
+        let mut items = Vec::new();
+        while let Some(row) = rows.next().await? {
+            let item: Human = libsql::de::from_row(&row)?;
+            items.push(item);
+        }
+        Ok(items)
+    }
+
     pub async fn list_humans(
         &self,
         filter: Option<ListHumanFilter>,
-    ) -> Result<Vec<Human>, crate::Error> {
+    ) -> Result<Page<Human>, crate::Error> {
         let conn = self.conn()?;
 
-        let mut rows = match &filter {
-            None => {
-                let sql = format!("SELECT * FROM {}", Human::sql_table());
-                conn.query(&sql, ()).await?
-            }
-            Some(ListHumanFilter::Search((max, q))) => {
-                let sql = format!(
-                    "SELECT * FROM {} WHERE full_name LIKE ? LIMIT ?",
-                    Human::sql_table()
-                );
-                conn.query(&sql, vec![format!("%{}%", q), max.to_string()])
-                    .await?
-            }
+        let (common, query) = match filter {
+            None => (ListHumanFilterCommon::default(), None),
+            Some(ListHumanFilter {
+                common,
+                specific: ListHumanFilterSpecific::Simple {},
+            }) => (common, None),
+            Some(ListHumanFilter {
+                common,
+                specific: ListHumanFilterSpecific::Search { query },
+            }) => (common, Some(format!("%{}%", query))),
         };
 
-        let mut humans = Vec::new();
-        while let Some(row) = rows.next().await? {
-            let human: Human = libsql::de::from_row(&row)?;
-            humans.push(human);
-        }
-        Ok(humans)
+        let limit = page_size(common.limit);
+        let cursor = Cursor::decode(common.cursor.as_deref())?;
+
+        let sql = format!(
+            "SELECT *, COALESCE(full_name, '') AS cursor_key, id AS cursor_id FROM {}
+            WHERE
+                (:query IS NULL OR full_name LIKE :query) AND
+                (:cursor_key IS NULL OR (COALESCE(full_name, ''), id) > (:cursor_key, :cursor_id))
+            ORDER BY COALESCE(full_name, '') ASC, id ASC
+            LIMIT :limit",
+            Human::sql_table()
+        );
+
+        let rows = conn
+            .query(
+                &sql,
+                libsql::named_params! {
+                    ":query": query,
+                    ":cursor_key": cursor.key,
+                    ":cursor_id": cursor.id,
+                    ":limit": i64::from(limit) + 1,
+                },
+            )
+            .await?;
+
+        Page::from_rows(rows, limit, |row| Ok(libsql::de::from_row(row)?)).await
     }
 }
 
@@ -99,7 +140,7 @@ mod tests {
     async fn test_humans() {
         let db = setup_db().await;
 
-        let humans = db.list_humans(None).await.unwrap();
+        let humans = db.list_humans(None).await.unwrap().items;
         assert!(humans.len() == 0);
 
         let human = Human {
@@ -110,7 +151,7 @@ mod tests {
         let human = db.upsert_human(human).await.unwrap();
         assert_eq!(human.full_name, Some("test".to_string()));
 
-        let humans = db.list_humans(None).await.unwrap();
+        let humans = db.list_humans(None).await.unwrap().items;
         assert!(humans.len() == 1);
     }
 }
@@ -14,9 +14,29 @@ user_common_derives! {
 }
 
 user_common_derives! {
-    pub enum ListHumanFilter {
+    pub struct ListHumanFilter {
+        #[serde(flatten)]
+        pub common: ListHumanFilterCommon,
+        #[serde(flatten)]
+        pub specific: ListHumanFilterSpecific,
+    }
+}
+
+user_common_derives! {
+    #[derive(Default)]
+    pub struct ListHumanFilterCommon {
+        pub limit: Option<u32>,
+        pub cursor: Option<String>,
+    }
+}
+
+user_common_derives! {
+    #[serde(tag = "type")]
+    pub enum ListHumanFilterSpecific {
+        #[serde(rename = "simple")]
+        Simple {},
         #[serde(rename = "search")]
-        Search((u8, String)),
+        Search { query: String },
     }
 }
 
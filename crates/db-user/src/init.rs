@@ -353,10 +353,12 @@ pub async fn seed(db: &UserDatabase, user_id: impl Into<String>) -> Result<(), c
         Tag {
             id: uuid::Uuid::new_v4().to_string(),
             name: "Customer".to_string(),
+            parent_id: None,
         },
         Tag {
             id: uuid::Uuid::new_v4().to_string(),
             name: "Product".to_string(),
+            parent_id: None,
         },
     ];
 
@@ -495,6 +497,7 @@ pub async fn seed(db: &UserDatabase, user_id: impl Into<String>) -> Result<(), c
             user_id: user.clone().id,
             name: Some("Chat Group 1".to_string()),
             created_at: now,
+            session_id: None,
         };
 
         let _ = db.create_chat_group(chat_group_1.clone()).await?;
@@ -505,6 +508,7 @@ pub async fn seed(db: &UserDatabase, user_id: impl Into<String>) -> Result<(), c
             role: ChatMessageRole::User,
             content: "Hello, how are you?".to_string(),
             created_at: now,
+            citations: vec![],
         };
 
         let _ = db.upsert_chat_message(chat_message_1).await?;
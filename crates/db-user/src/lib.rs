@@ -6,6 +6,8 @@ mod chat_messages_ops;
 mod chat_messages_types;
 mod config_ops;
 mod config_types;
+mod doctor_ops;
+mod doctor_types;
 mod events_ops;
 mod events_types;
 mod extensions_ops;
@@ -14,12 +16,23 @@ mod humans_ops;
 mod humans_types;
 mod organizations_ops;
 mod organizations_types;
+mod pagination_types;
+mod passages_ops;
+mod passages_types;
 mod sessions_ops;
 mod sessions_types;
+mod speakers_ops;
+mod speakers_types;
+mod sync_ops;
+mod sync_types;
 mod tags_ops;
 mod tags_types;
 mod templates_ops;
 mod templates_types;
+mod vocabulary_ops;
+mod vocabulary_types;
+mod voice_ops;
+mod voice_types;
 
 #[allow(unused)]
 pub use calendars_ops::*;
@@ -38,6 +51,10 @@ pub use config_ops::*;
 #[allow(unused)]
 pub use config_types::*;
 #[allow(unused)]
+pub use doctor_ops::*;
+#[allow(unused)]
+pub use doctor_types::*;
+#[allow(unused)]
 pub use events_ops::*;
 #[allow(unused)]
 pub use events_types::*;
@@ -54,10 +71,24 @@ pub use organizations_ops::*;
 #[allow(unused)]
 pub use organizations_types::*;
 #[allow(unused)]
+pub use pagination_types::*;
+#[allow(unused)]
+pub use passages_ops::*;
+#[allow(unused)]
+pub use passages_types::*;
+#[allow(unused)]
 pub use sessions_ops::*;
 #[allow(unused)]
 pub use sessions_types::*;
 #[allow(unused)]
+pub use speakers_ops::*;
+#[allow(unused)]
+pub use speakers_types::*;
+#[allow(unused)]
+pub use sync_ops::*;
+#[allow(unused)]
+pub use sync_types::*;
+#[allow(unused)]
 pub use tags_ops::*;
 #[allow(unused)]
 pub use tags_types::*;
@@ -65,6 +96,14 @@ pub use tags_types::*;
 pub use templates_ops::*;
 #[allow(unused)]
 pub use templates_types::*;
+#[allow(unused)]
+pub use vocabulary_ops::*;
+#[allow(unused)]
+pub use vocabulary_types::*;
+#[allow(unused)]
+pub use voice_ops::*;
+#[allow(unused)]
+pub use voice_types::*;
 
 pub mod init;
 
@@ -129,7 +168,7 @@ impl std::ops::Deref for UserDatabase {
 }
 
 // Append only. Do not reorder.
-const MIGRATIONS: [&str; 14] = [
+const MIGRATIONS: [&str; 35] = [
     include_str!("./calendars_migration.sql"),
     include_str!("./configs_migration.sql"),
     include_str!("./events_migration.sql"),
@@ -144,6 +183,27 @@ const MIGRATIONS: [&str; 14] = [
     include_str!("./tags_migration.sql"),
     include_str!("./tag_sessions_migration.sql"),
     include_str!("./calendars_migration_1.sql"),
+    include_str!("./tags_migration_1.sql"),
+    include_str!("./quarantine_migration.sql"),
+    include_str!("./sessions_migration_1.sql"),
+    include_str!("./templates_migration_1.sql"),
+    include_str!("./sync_conflicts_migration.sql"),
+    include_str!("./sync_state_migration.sql"),
+    include_str!("./sync_changes_migration.sql"),
+    include_str!("./session_speakers_migration.sql"),
+    include_str!("./voice_enrollments_migration.sql"),
+    include_str!("./voice_suggestions_migration.sql"),
+    include_str!("./vocabulary_migration.sql"),
+    include_str!("./session_passages_migration.sql"),
+    include_str!("./session_passages_migration_1.sql"),
+    include_str!("./chat_groups_migration_1.sql"),
+    include_str!("./chat_messages_migration_1.sql"),
+    include_str!("./sync_tombstones_migration.sql"),
+    include_str!("./sync_versions_migration.sql"),
+    include_str!("./session_passages_fts_migration.sql"),
+    include_str!("./session_passages_fts_migration_1.sql"),
+    include_str!("./session_passages_fts_migration_2.sql"),
+    include_str!("./session_passages_fts_migration_3.sql"),
 ];
 
 pub async fn migrate(db: &UserDatabase) -> Result<(), crate::Error> {
@@ -1,6 +1,9 @@
 use hypr_db_core::SqlTable;
 
-use super::{Human, ListOrganizationFilter, Organization, UserDatabase};
+use super::{
+    page_size, Cursor, Human, ListOrganizationFilter, ListOrganizationFilterCommon,
+    ListOrganizationFilterSpecific, Organization, Page, UserDatabase,
+};
 
 impl UserDatabase {
     pub async fn upsert_organization(
@@ -24,7 +27,10 @@ impl UserDatabase {
         let params = (organization.id, organization.name, organization.description);
 
         let mut rows = conn.query(&sql, params).await?;
-        let row = rows.next().await?.unwrap();
+        let row = rows
+            .next()
+            .await?
+            .ok_or_else(|| crate::Error::NotFound("organization".to_string()))?;
         let organization: Organization = libsql::de::from_row(&row)?;
         Ok(organization)
     }
@@ -37,26 +43,15 @@ impl UserDatabase {
         Ok(())
     }
 
-    pub async fn list_organizations(
-        &self,
-        filter: Option<ListOrganizationFilter>,
-    ) -> Result<Vec<Organization>, crate::Error> {
+    // Every organization, for pickers that show the whole list. Screens that can grow use `list_organizations`.
+    pub async fn list_all_organizations(&self) -> Result<Vec<Organization>, crate::Error> {
         let conn = self.conn()?;
 
-        let mut rows = match &filter {
-            None => {
-                let sql = format!("SELECT * FROM {}", Organization::sql_table());
-                conn.query(&sql, ()).await?
-            }
-            Some(ListOrganizationFilter::Search((max, q))) => {
-                let sql = format!(
-                    "SELECT * FROM {} WHERE name LIKE ? LIMIT ?",
-                    Organization::sql_table()
-                );
-                let params = (format!("%{}%", q), max.to_string());
-                conn.query(&sql, params).await?
-            }
-        };
+        let sql = format!(
+            "SELECT * FROM {} ORDER BY name ASC, id ASC",
+            Organization::sql_table()
+        );
+        let mut rows = conn.query(&sql, ()).await?;
 
         let mut items = Vec::new();
         while let Some(row) = rows.next().await? {
@@ -66,6 +61,52 @@ impl UserDatabase {
         Ok(items)
     }
 
+    pub async fn list_organizations(
+        &self,
+        filter: Option<ListOrganizationFilter>,
+    ) -> Result<Page<Organization>, crate::Error> {
+        let conn = self.conn()?;
+
+        let (common, query) = match filter {
+            None => (ListOrganizationFilterCommon::default(), None),
+            Some(ListOrganizationFilter {
+                common,
+                specific: ListOrganizationFilterSpecific::Simple {},
+            }) => (common, None),
+            Some(ListOrganizationFilter {
+                common,
+                specific: ListOrganizationFilterSpecific::Search { query },
+            }) => (common, Some(format!("%{}%", query))),
+        };
+
+        let limit = page_size(common.limit);
+        let cursor = Cursor::decode(common.cursor.as_deref())?;
+
+        let sql = format!(
+            "SELECT *, name AS cursor_key, id AS cursor_id FROM {}
+            WHERE
+                (:query IS NULL OR name LIKE :query) AND
+                (:cursor_key IS NULL OR (name, id) > (:cursor_key, :cursor_id))
+            ORDER BY name ASC, id ASC
+            LIMIT :limit",
+            Organization::sql_table()
+        );
+
+        let rows = conn
+            .query(
+                &sql,
+                libsql::named_params! {
+                    ":query": query,
+                    ":cursor_key": cursor.key,
+                    ":cursor_id": cursor.id,
+                    ":limit": i64::from(limit) + 1,
+                },
+            )
+            .await?;
+
+        Page::from_rows(rows, limit, |row| Ok(libsql::de::from_row(row)?)).await
+    }
+
     pub async fn list_organization_members(
         &self,
         organization_id: impl Into<String>,
@@ -132,13 +173,47 @@ impl UserDatabase {
 
 #[cfg(test)]
 mod tests {
-    use crate::tests::setup_db;
+    use crate::{
+        tests::setup_db, ListOrganizationFilter, ListOrganizationFilterCommon,
+        ListOrganizationFilterSpecific, Organization,
+    };
 
     #[tokio::test]
     async fn test_list_organizations() {
         let db = setup_db().await;
 
-        let organizations = db.list_organizations(None).await.unwrap();
+        let organizations = db.list_organizations(None).await.unwrap().items;
         assert!(organizations.len() == 0);
+
+        for i in 0..150 {
+            db.upsert_organization(Organization {
+                id: uuid::Uuid::new_v4().to_string(),
+                name: format!("org-{:03}", i),
+                description: None,
+            })
+            .await
+            .unwrap();
+        }
+
+        let page = db.list_organizations(None).await.unwrap();
+        assert_eq!(page.items.len(), 100);
+        assert!(page.next_cursor.is_some());
+
+        let organizations = db.list_all_organizations().await.unwrap();
+        assert_eq!(organizations.len(), 150);
+        assert_eq!(organizations[0].name, "org-000");
+        assert_eq!(organizations[149].name, "org-149");
+
+        let page = db
+            .list_organizations(Some(ListOrganizationFilter {
+                common: ListOrganizationFilterCommon {
+                    limit: Some(u32::MAX),
+                    cursor: None,
+                },
+                specific: ListOrganizationFilterSpecific::Simple {},
+            }))
+            .await
+            .unwrap();
+        assert_eq!(page.items.len(), 150);
     }
 }
@@ -10,8 +10,28 @@ user_common_derives! {
 }
 
 user_common_derives! {
-    pub enum ListOrganizationFilter {
+    pub struct ListOrganizationFilter {
+        #[serde(flatten)]
+        pub common: ListOrganizationFilterCommon,
+        #[serde(flatten)]
+        pub specific: ListOrganizationFilterSpecific,
+    }
+}
+
+user_common_derives! {
+    #[derive(Default)]
+    pub struct ListOrganizationFilterCommon {
+        pub limit: Option<u32>,
+        pub cursor: Option<String>,
+    }
+}
+
+user_common_derives! {
+    #[serde(tag = "type")]
+    pub enum ListOrganizationFilterSpecific {
+        #[serde(rename = "simple")]
+        Simple {},
         #[serde(rename = "search")]
-        Search((u8, String)),
+        Search { query: String },
     }
 }
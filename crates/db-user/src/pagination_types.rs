@@ -0,0 +1,91 @@
+use crate::user_common_derives;
+
+user_common_derives! {
+    pub struct Page<T> {
+        pub items: Vec<T>,
+        pub next_cursor: Option<String>,
+    }
+}
+
+pub(crate) const DEFAULT_PAGE_SIZE: u32 = 100;
+pub(crate) const MAX_PAGE_SIZE: u32 = 1000;
+
+pub(crate) fn page_size(limit: Option<u32>) -> u32 {
+    limit.unwrap_or(DEFAULT_PAGE_SIZE).min(MAX_PAGE_SIZE)
+}
+
+// Keyset cursor. Encodes the sort key and `id` of the last item on a page.
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct Cursor {
+    pub key: Option<String>,
+    pub id: Option<String>,
+}
+
+impl Cursor {
+    pub fn decode(cursor: Option<&str>) -> Result<Self, crate::Error> {
+        match cursor {
+            None => Ok(Self::default()),
+            Some(s) => {
+                let (key, id): (String, String) = serde_json::from_str(s)?;
+                Ok(Self {
+                    key: Some(key),
+                    id: Some(id),
+                })
+            }
+        }
+    }
+
+    pub fn encode(key: String, id: String) -> String {
+        serde_json::to_string(&(key, id)).unwrap()
+    }
+}
+
+impl<T> Page<T> {
+    // Paginated queries must fetch `limit + 1` rows, ending with `cursor_key` and `cursor_id` columns.
+    pub(crate) async fn from_rows(
+        mut rows: libsql::Rows,
+        limit: u32,
+        map: impl Fn(&libsql::Row) -> Result<T, crate::Error>,
+    ) -> Result<Self, crate::Error> {
+        let mut items = Vec::new();
+        let mut last_cursor = None;
+        let mut next_cursor = None;
+
+        while let Some(row) = rows.next().await? {
+            if items.len() == limit as usize {
+                next_cursor = last_cursor.take();
+                break;
+            }
+
+            let n = row.column_count();
+            let key: String = row.get(n - 2)?;
+            let id: String = row.get(n - 1)?;
+
+            items.push(map(&row)?);
+            last_cursor = Some(Cursor::encode(key, id));
+        }
+
+        Ok(Self { items, next_cursor })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cursor_roundtrip() {
+        assert_eq!(Cursor::decode(None).unwrap(), Cursor::default());
+
+        let encoded = Cursor::encode("2025-01-01T00:00:00Z".to_string(), "a|b".to_string());
+        assert_eq!(
+            Cursor::decode(Some(&encoded)).unwrap(),
+            Cursor {
+                key: Some("2025-01-01T00:00:00Z".to_string()),
+                id: Some("a|b".to_string()),
+            }
+        );
+
+        assert!(Cursor::decode(Some("not-a-cursor")).is_err());
+    }
+}
@@ -0,0 +1,456 @@
+use std::collections::HashMap;
+
+use hypr_db_core::SqlTable;
+
+use super::{
+    encode_embedding, GetSessionFilter, PassageMatch, SessionPassage, SessionSearchResult,
+    UserDatabase,
+};
+
+// Matching passages returned per session.
+const PASSAGES_PER_SESSION: usize = 3;
+
+impl UserDatabase {
+    // Replaces the passages of a session indexed with `model`, e.g. after its note is enhanced again.
+    pub async fn replace_session_passages(
+        &self,
+        session_id: impl Into<String>,
+        model: impl Into<String>,
+        passages: Vec<SessionPassage>,
+    ) -> Result<Vec<SessionPassage>, crate::Error> {
+        let session_id = session_id.into();
+        let model = model.into();
+
+        let conn = self.conn()?;
+        let tx = conn.transaction().await?;
+
+        tx.execute(
+            &format!(
+                "DELETE FROM {} WHERE session_id = ? AND model = ?",
+                SessionPassage::sql_table()
+            ),
+            vec![session_id.clone(), model.clone()],
+        )
+        .await?;
+
+        let mut inserted = Vec::new();
+        for passage in passages {
+            tx.execute(
+                &format!(
+                    "INSERT INTO {} (id, session_id, kind, text, start_ms, end_ms, embedding, model, created_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+                    SessionPassage::sql_table()
+                ),
+                libsql::params![
+                    passage.id.clone(),
+                    session_id.clone(),
+                    serde_json::to_value(&passage.kind)?
+                        .as_str()
+                        .unwrap_or_default()
+                        .to_string(),
+                    passage.text.clone(),
+                    passage.start_ms.map(|v| v as i64),
+                    passage.end_ms.map(|v| v as i64),
+                    encode_embedding(&passage.embedding),
+                    model.clone(),
+                    passage.created_at.to_rfc3339(),
+                ],
+            )
+            .await?;
+
+            inserted.push(SessionPassage {
+                session_id: session_id.clone(),
+                model: model.clone(),
+                ..passage
+            });
+        }
+
+        tx.commit().await?;
+        Ok(inserted)
+    }
+
+    pub async fn list_session_passages(
+        &self,
+        session_id: impl Into<String>,
+    ) -> Result<Vec<SessionPassage>, crate::Error> {
+        let conn = self.conn()?;
+
+        let sql = format!(
+            "SELECT {} FROM {} p WHERE p.session_id = ? ORDER BY p.kind, p.start_ms",
+            SessionPassage::columns("p"),
+            SessionPassage::sql_table()
+        );
+        let mut rows = conn.query(&sql, vec![session_id.into()]).await?;
+
+        let mut items = Vec::new();
+        while let Some(row) = rows.next().await? {
+            items.push(SessionPassage::from_row(&row)?);
+        }
+        Ok(items)
+    }
+
+    // Ranks the user's sessions by cosine similarity of their passages to `query`, which must come
+    // from the same `model`. Scores are computed here rather than in SQL, since a user has at most a
+    // few thousand passages.
+    pub async fn search_sessions_by_embedding(
+        &self,
+        user_id: impl Into<String>,
+        model: impl Into<String>,
+        query: Vec<f32>,
+        limit: usize,
+    ) -> Result<Vec<SessionSearchResult>, crate::Error> {
+        let passages = self.list_passages_in_scope(user_id, None, model).await?;
+
+        let mut by_session: HashMap<String, Vec<PassageMatch>> = HashMap::new();
+        for passage in passages {
+            let score = cosine_similarity(&query, &passage.embedding);
+            by_session
+                .entry(passage.session_id.clone())
+                .or_default()
+                .push(PassageMatch::new(passage, score));
+        }
+
+        let mut ranked = by_session
+            .into_iter()
+            .map(|(session_id, mut passages)| {
+                passages.sort_by(|a, b| b.score.total_cmp(&a.score));
+                passages.truncate(PASSAGES_PER_SESSION);
+                (session_id, passages)
+            })
+            .collect::<Vec<_>>();
+        ranked.sort_by(|a, b| b.1[0].score.total_cmp(&a.1[0].score));
+        ranked.truncate(limit);
+
+        let mut results = Vec::new();
+        for (session_id, passages) in ranked {
+            if let Some(session) = self.get_session(GetSessionFilter::Id(session_id)).await? {
+                results.push(SessionSearchResult {
+                    session,
+                    score: passages[0].score,
+                    passages,
+                });
+            }
+        }
+        Ok(results)
+    }
+
+    // The passages closest to `query`, from one session or, without `session_id`, all of the user's.
+    pub async fn search_passages_by_embedding(
+        &self,
+        user_id: impl Into<String>,
+        session_id: Option<String>,
+        model: impl Into<String>,
+        query: Vec<f32>,
+        limit: usize,
+    ) -> Result<Vec<PassageMatch>, crate::Error> {
+        let passages = self
+            .list_passages_in_scope(user_id, session_id, model)
+            .await?;
+
+        Ok(top(
+            passages.into_iter().map(|passage| {
+                let score = cosine_similarity(&query, &passage.embedding);
+                PassageMatch::new(passage, score)
+            }),
+            limit,
+        ))
+    }
+
+    // For when `query` can't be embedded. Passages containing any of the query's words are ranked
+    // by the full-text index, with BM25.
+    pub async fn search_passages_by_text(
+        &self,
+        user_id: impl Into<String>,
+        session_id: Option<String>,
+        query: impl AsRef<str>,
+        limit: usize,
+    ) -> Result<Vec<PassageMatch>, crate::Error> {
+        // Quoted, so nothing in the question is read as query syntax.
+        let terms = words(query.as_ref())
+            .into_iter()
+            .map(|w| format!("\"{}\"", w))
+            .collect::<Vec<_>>();
+        if terms.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let conn = self.conn()?;
+
+        // BM25 is lower for better matches.
+        let sql = format!(
+            "SELECT {}, -bm25(session_passages_fts) AS score FROM session_passages_fts JOIN {} p ON p.rowid = session_passages_fts.rowid JOIN sessions s ON s.id = p.session_id WHERE session_passages_fts MATCH :query AND s.user_id = :user_id AND (:session_id IS NULL OR p.session_id = :session_id) ORDER BY score DESC LIMIT :limit",
+            SessionPassage::columns("p"),
+            SessionPassage::sql_table()
+        );
+        let mut rows = conn
+            .query(
+                &sql,
+                libsql::named_params! {
+                    ":query": terms.join(" OR "),
+                    ":user_id": user_id.into(),
+                    ":session_id": session_id,
+                    ":limit": limit as i64,
+                },
+            )
+            .await?;
+
+        let mut items = Vec::new();
+        while let Some(row) = rows.next().await? {
+            let score = row
+                .get::<f64>(SessionPassage::COLUMN_COUNT as i32)
+                .map_err(|e| crate::Error::decode("score", e))?;
+            items.push(PassageMatch::new(
+                SessionPassage::from_row(&row)?,
+                score as f32,
+            ));
+        }
+        Ok(items)
+    }
+
+    async fn list_passages_in_scope(
+        &self,
+        user_id: impl Into<String>,
+        session_id: Option<String>,
+        model: impl Into<String>,
+    ) -> Result<Vec<SessionPassage>, crate::Error> {
+        let conn = self.conn()?;
+
+        let sql = format!(
+            "SELECT {} FROM {} p JOIN sessions s ON s.id = p.session_id WHERE s.user_id = :user_id AND (:session_id IS NULL OR p.session_id = :session_id) AND p.model = :model",
+            SessionPassage::columns("p"),
+            SessionPassage::sql_table()
+        );
+        let mut rows = conn
+            .query(
+                &sql,
+                libsql::named_params! {
+                    ":user_id": user_id.into(),
+                    ":session_id": session_id,
+                    ":model": model.into(),
+                },
+            )
+            .await?;
+
+        let mut items = Vec::new();
+        while let Some(row) = rows.next().await? {
+            items.push(SessionPassage::from_row(&row)?);
+        }
+        Ok(items)
+    }
+}
+
+impl PassageMatch {
+    fn new(passage: SessionPassage, score: f32) -> Self {
+        Self {
+            session_id: passage.session_id,
+            kind: passage.kind,
+            text: passage.text,
+            start_ms: passage.start_ms,
+            end_ms: passage.end_ms,
+            score,
+        }
+    }
+}
+
+// Best first, leaving out passages that don't match at all.
+fn top(matches: impl Iterator<Item = PassageMatch>, limit: usize) -> Vec<PassageMatch> {
+    let mut matches = matches.filter(|m| m.score > 0.0).collect::<Vec<_>>();
+    matches.sort_by(|a, b| b.score.total_cmp(&a.score));
+    matches.truncate(limit);
+    matches
+}
+
+fn words(text: &str) -> std::collections::HashSet<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| w.chars().count() > 2)
+        .map(str::to_lowercase)
+        .collect()
+}
+
+fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() {
+        return 0.0;
+    }
+
+    let dot = a.iter().zip(b).map(|(x, y)| x * y).sum::<f32>();
+    let norm_a = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+
+    if norm_a == 0.0 || norm_b == 0.0 {
+        0.0
+    } else {
+        dot / (norm_a * norm_b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{tests::setup_db, Human, Session, SessionPassage, SessionPassageKind};
+
+    fn passage(kind: SessionPassageKind, text: &str, embedding: Vec<f32>) -> SessionPassage {
+        SessionPassage {
+            id: uuid::Uuid::new_v4().to_string(),
+            session_id: String::new(),
+            kind,
+            text: text.to_string(),
+            start_ms: None,
+            end_ms: None,
+            embedding,
+            model: String::new(),
+            created_at: chrono::Utc::now(),
+        }
+    }
+
+    fn session(user_id: &str, title: &str) -> Session {
+        Session {
+            id: uuid::Uuid::new_v4().to_string(),
+            created_at: chrono::Utc::now(),
+            visited_at: chrono::Utc::now(),
+            user_id: user_id.to_string(),
+            calendar_event_id: None,
+            title: title.to_string(),
+            raw_memo_html: "".to_string(),
+            enhanced_memo_html: None,
+            conversations: vec![],
+        }
+    }
+
+    #[tokio::test]
+    async fn test_passages() {
+        let db = setup_db().await;
+
+        let user = db.upsert_human(Human::default()).await.unwrap();
+        let hiring = db
+            .upsert_session(session(&user.id, "hiring"))
+            .await
+            .unwrap();
+        let budget = db
+            .upsert_session(session(&user.id, "budget"))
+            .await
+            .unwrap();
+
+        db.replace_session_passages(
+            &hiring.id,
+            "test",
+            vec![
+                passage(
+                    SessionPassageKind::Transcript,
+                    "we need two engineers",
+                    vec![1.0, 0.0, 0.0],
+                ),
+                passage(SessionPassageKind::Note, "Hiring plan", vec![0.8, 0.6, 0.0]),
+            ],
+        )
+        .await
+        .unwrap();
+        db.replace_session_passages(
+            &budget.id,
+            "test",
+            vec![passage(
+                SessionPassageKind::Note,
+                "Q3 budget",
+                vec![0.0, 1.0, 0.0],
+            )],
+        )
+        .await
+        .unwrap();
+
+        let passages = db.list_session_passages(&hiring.id).await.unwrap();
+        assert_eq!(passages.len(), 2);
+        assert!(passages.iter().all(|p| p.model == "test"));
+        assert_eq!(passages[1].embedding, vec![1.0, 0.0, 0.0]);
+
+        let results = db
+            .search_sessions_by_embedding(&user.id, "test", vec![1.0, 0.1, 0.0], 10)
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].session.id, hiring.id);
+        assert_eq!(results[0].passages[0].text, "we need two engineers");
+        assert!(results[0].score > results[1].score);
+
+        let results = db
+            .search_sessions_by_embedding(&user.id, "test", vec![0.0, 1.0, 0.0], 1)
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].session.id, budget.id);
+
+        let passages = db
+            .search_passages_by_embedding(&user.id, None, "test", vec![1.0, 0.1, 0.0], 2)
+            .await
+            .unwrap();
+        assert_eq!(
+            passages.iter().map(|p| p.text.as_str()).collect::<Vec<_>>(),
+            vec!["we need two engineers", "Hiring plan"]
+        );
+        let passages = db
+            .search_passages_by_embedding(
+                &user.id,
+                Some(budget.id.clone()),
+                "test",
+                vec![1.0, 0.1, 0.0],
+                10,
+            )
+            .await
+            .unwrap();
+        assert!(passages.iter().all(|p| p.session_id == budget.id));
+
+        let passages = db
+            .search_passages_by_text(&user.id, None, "How many engineers do we need?", 10)
+            .await
+            .unwrap();
+        assert_eq!(passages.len(), 1);
+        assert_eq!(passages[0].session_id, hiring.id);
+
+        // Other models' embeddings are not compared.
+        assert!(db
+            .search_sessions_by_embedding(&user.id, "other", vec![1.0, 0.0, 0.0], 10)
+            .await
+            .unwrap()
+            .is_empty());
+
+        let passages = db
+            .search_passages_by_text(&user.id, None, "engineers \"budget\" OR", 10)
+            .await
+            .unwrap();
+        assert_eq!(passages.len(), 2);
+        assert!(passages.iter().all(|p| p.score > 0.0));
+        let passages = db
+            .search_passages_by_text(&user.id, Some(budget.id.clone()), "engineers", 10)
+            .await
+            .unwrap();
+        assert!(passages.is_empty());
+
+        // Reindexing replaces what was there.
+        db.replace_session_passages(&hiring.id, "test", vec![])
+            .await
+            .unwrap();
+        assert!(db
+            .list_session_passages(&hiring.id)
+            .await
+            .unwrap()
+            .is_empty());
+        assert!(db
+            .search_passages_by_text(&user.id, None, "engineers", 10)
+            .await
+            .unwrap()
+            .is_empty());
+
+        // Deleting a session takes its passages, and their full-text rows, with it.
+        db.delete_session(&budget.id).await.unwrap();
+        assert!(db
+            .list_session_passages(&budget.id)
+            .await
+            .unwrap()
+            .is_empty());
+        // Fails if the index still has rows whose passages are gone.
+        db.conn()
+            .unwrap()
+            .execute(
+                "INSERT INTO session_passages_fts (session_passages_fts) VALUES ('integrity-check')",
+                (),
+            )
+            .await
+            .unwrap();
+    }
+}
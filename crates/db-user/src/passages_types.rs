@@ -0,0 +1,114 @@
+use chrono::{DateTime, Utc};
+use hypr_db_core::RowExt;
+
+use crate::{user_common_derives, Session};
+
+user_common_derives! {
+    #[sql_table("session_passages")]
+    pub struct SessionPassage {
+        pub id: String,
+        pub session_id: String,
+        pub kind: SessionPassageKind,
+        pub text: String,
+        // Where a transcript passage starts, from the beginning of the session.
+        pub start_ms: Option<u64>,
+        pub end_ms: Option<u64>,
+        // Stored as little-endian `f32`s.
+        pub embedding: Vec<f32>,
+        // Embeddings from different models are not comparable.
+        pub model: String,
+        pub created_at: DateTime<Utc>,
+    }
+}
+
+user_common_derives! {
+    pub enum SessionPassageKind {
+        #[serde(rename = "transcript")]
+        Transcript,
+        #[serde(rename = "note")]
+        Note,
+    }
+}
+
+user_common_derives! {
+    pub struct PassageMatch {
+        pub session_id: String,
+        pub kind: SessionPassageKind,
+        pub text: String,
+        pub start_ms: Option<u64>,
+        pub end_ms: Option<u64>,
+        pub score: f32,
+    }
+}
+
+user_common_derives! {
+    pub struct SessionSearchResult {
+        pub session: Session,
+        // The best score of its passages.
+        pub score: f32,
+        // Best first.
+        pub passages: Vec<PassageMatch>,
+    }
+}
+
+// What `from_row` reads, in order. Selected by name, so columns added later can't shift them.
+const SESSION_PASSAGE_COLUMNS: [&str; 9] = [
+    "id",
+    "session_id",
+    "kind",
+    "text",
+    "start_ms",
+    "end_ms",
+    "embedding",
+    "model",
+    "created_at",
+];
+
+impl SessionPassage {
+    // Index of the first expression selected after `columns`.
+    pub(crate) const COLUMN_COUNT: usize = SESSION_PASSAGE_COLUMNS.len();
+
+    // The column list for a `SELECT` from the table aliased as `alias`.
+    pub(crate) fn columns(alias: &str) -> String {
+        SESSION_PASSAGE_COLUMNS
+            .iter()
+            .map(|c| format!("{}.{}", alias, c))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    pub fn from_row(row: &libsql::Row) -> Result<Self, crate::Error> {
+        Ok(Self {
+            id: row.get_string(0, "id")?,
+            session_id: row.get_string(1, "session_id")?,
+            kind: serde_json::from_value(serde_json::Value::String(row.get_string(2, "kind")?))
+                .map_err(|e| crate::Error::decode("kind", e))?,
+            text: row.get_string(3, "text")?,
+            start_ms: row
+                .get::<Option<i64>>(4)
+                .map_err(|e| crate::Error::decode("start_ms", e))?
+                .map(|v| v as u64),
+            end_ms: row
+                .get::<Option<i64>>(5)
+                .map_err(|e| crate::Error::decode("end_ms", e))?
+                .map(|v| v as u64),
+            embedding: decode_embedding(
+                &row.get::<Vec<u8>>(6)
+                    .map_err(|e| crate::Error::decode("embedding", e))?,
+            ),
+            model: row.get_string(7, "model")?,
+            created_at: row.get_datetime(8, "created_at")?,
+        })
+    }
+}
+
+pub(crate) fn encode_embedding(embedding: &[f32]) -> Vec<u8> {
+    embedding.iter().flat_map(|v| v.to_le_bytes()).collect()
+}
+
+fn decode_embedding(bytes: &[u8]) -> Vec<f32> {
+    bytes
+        .chunks_exact(4)
+        .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .collect()
+}
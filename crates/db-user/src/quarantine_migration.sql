@@ -0,0 +1,8 @@
+CREATE TABLE IF NOT EXISTS quarantine (
+  id TEXT PRIMARY KEY,
+  source_table TEXT NOT NULL,
+  row_id TEXT NOT NULL,
+  data TEXT NOT NULL,
+  error TEXT NOT NULL,
+  created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))
+);
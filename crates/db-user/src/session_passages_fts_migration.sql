@@ -0,0 +1,6 @@
+CREATE VIRTUAL TABLE IF NOT EXISTS session_passages_fts USING fts5(
+  text,
+  content = 'session_passages',
+  content_rowid = 'rowid',
+  tokenize = 'unicode61 remove_diacritics 2'
+);
@@ -0,0 +1,3 @@
+CREATE TRIGGER IF NOT EXISTS session_passages_fts_insert AFTER INSERT ON session_passages BEGIN
+  INSERT INTO session_passages_fts (rowid, text) VALUES (new.rowid, new.text);
+END;
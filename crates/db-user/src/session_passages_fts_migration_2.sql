@@ -0,0 +1,3 @@
+CREATE TRIGGER IF NOT EXISTS session_passages_fts_delete AFTER DELETE ON session_passages BEGIN
+  INSERT INTO session_passages_fts (session_passages_fts, rowid, text) VALUES ('delete', old.rowid, old.text);
+END;
@@ -0,0 +1 @@
+INSERT INTO session_passages_fts (session_passages_fts) VALUES ('rebuild');
@@ -0,0 +1,12 @@
+CREATE TABLE IF NOT EXISTS session_passages (
+  id TEXT PRIMARY KEY,
+  session_id TEXT NOT NULL,
+  kind TEXT NOT NULL,
+  text TEXT NOT NULL,
+  start_ms INTEGER,
+  end_ms INTEGER,
+  embedding BLOB NOT NULL,
+  model TEXT NOT NULL,
+  created_at TEXT NOT NULL,
+  FOREIGN KEY (session_id) REFERENCES sessions(id) ON DELETE CASCADE
+);
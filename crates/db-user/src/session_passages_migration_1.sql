@@ -0,0 +1 @@
+CREATE INDEX IF NOT EXISTS session_passages_session_id_model ON session_passages(session_id, model);
@@ -0,0 +1,10 @@
+CREATE TABLE IF NOT EXISTS session_speakers (
+  id TEXT PRIMARY KEY,
+  session_id TEXT NOT NULL,
+  speaker INTEGER NOT NULL,
+  human_id TEXT,
+  segments TEXT NOT NULL DEFAULT '[]',
+  merged BOOLEAN NOT NULL DEFAULT FALSE,
+  FOREIGN KEY (session_id) REFERENCES sessions(id) ON DELETE CASCADE,
+  FOREIGN KEY (human_id) REFERENCES humans(id) ON DELETE SET NULL
+);
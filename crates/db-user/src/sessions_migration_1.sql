@@ -0,0 +1 @@
+ALTER TABLE sessions ADD COLUMN field_timestamps TEXT NOT NULL DEFAULT '{}';
@@ -1,6 +1,7 @@
 use super::{
-    Event, GetSessionFilter, Human, ListSessionFilter, ListSessionFilterCommon,
-    ListSessionFilterSpecific, Session, UserDatabase,
+    page_size, record_tombstone, Cursor, Event, GetSessionFilter, Human, ListSessionFilter,
+    ListSessionFilterCommon, ListSessionFilterSpecific, Page, Session, UserDatabase,
+    DEFAULT_PAGE_SIZE,
 };
 
 impl UserDatabase {
@@ -31,8 +32,8 @@ impl UserDatabase {
             Vec<hypr_listener_interface::TranscriptChunk>,
             Vec<hypr_listener_interface::DiarizationChunk>,
         ) = (
-            serde_json::from_str(hypr_data::english_4::TRANSCRIPTION_JSON).unwrap(),
-            serde_json::from_str(hypr_data::english_4::DIARIZATION_JSON).unwrap(),
+            serde_json::from_str(hypr_data::english_4::TRANSCRIPTION_JSON)?,
+            serde_json::from_str(hypr_data::english_4::DIARIZATION_JSON)?,
         );
 
         let mut timeline = hypr_timeline::Timeline::default();
@@ -53,24 +54,29 @@ impl UserDatabase {
         &self,
         session_id: impl Into<String>,
     ) -> Result<Option<hypr_timeline::TimelineView>, crate::Error> {
-        let session = self
-            .get_session(GetSessionFilter::Id(session_id.into()))
-            .await?;
-
-        if session.is_none() {
+        let session_id = session_id.into();
+        let Some(session) = self
+            .get_session(GetSessionFilter::Id(session_id.clone()))
+            .await?
+        else {
             return Ok(None);
-        }
-
-        let session = session.unwrap();
+        };
+        let remap = self.session_speaker_remap(&session_id).await?;
         let mut timeline = hypr_timeline::Timeline::default();
 
         for chunk in session.conversations {
             for transcript in chunk.transcripts {
                 timeline.add_transcription(transcript);
             }
-            for diarization in chunk.diarizations {
+            for mut diarization in chunk.diarizations {
+                if let Some(speaker) = remap.get(&diarization.speaker) {
+                    diarization.speaker = *speaker;
+                }
                 timeline.add_diarization(diarization);
             }
+            for translation in chunk.translations {
+                timeline.add_translation(translation);
+            }
         }
 
         Ok(Some(timeline.view(hypr_timeline::TimelineFilter {
@@ -87,22 +93,19 @@ impl UserDatabase {
         let mut rows = match filter {
             GetSessionFilter::Id(id) => conn
                 .query("SELECT * FROM sessions WHERE id = ?", vec![id])
-                .await
-                .unwrap(),
+                .await?,
             GetSessionFilter::CalendarEventId(id) => conn
                 .query(
                     "SELECT * FROM sessions WHERE calendar_event_id = ?",
                     vec![id],
                 )
-                .await
-                .unwrap(),
+                .await?,
             GetSessionFilter::TagId(id) => conn
                 .query(
-                    "SELECT * FROM sessions WHERE id IN (SELECT session_id FROM tags WHERE id = ?)",
+                    "SELECT * FROM sessions WHERE id IN (SELECT session_id FROM tags_sessions WHERE tag_id = ?) ORDER BY created_at DESC",
                     vec![id],
                 )
-                .await
-                .unwrap(),
+                .await?,
         };
 
         match rows.next().await? {
@@ -126,90 +129,229 @@ impl UserDatabase {
     }
 
     pub async fn delete_session(&self, id: impl Into<String>) -> Result<(), crate::Error> {
+        let id = id.into();
         let conn = self.conn()?;
 
-        conn.execute("DELETE FROM sessions WHERE id = ?", vec![id.into()])
+        let tx = conn.transaction().await?;
+        // Foreign keys aren't enforced, so `ON DELETE CASCADE` never runs. The FTS rows go with the passages.
+        tx.execute(
+            "DELETE FROM session_passages WHERE session_id = ?",
+            vec![id.clone()],
+        )
+        .await?;
+        let deleted = tx
+            .execute("DELETE FROM sessions WHERE id = ?", vec![id.clone()])
             .await?;
+        if deleted > 0 {
+            record_tombstone(&tx, "sessions", &id, chrono::Utc::now()).await?;
+        }
+        tx.commit().await?;
         Ok(())
     }
 
     pub async fn list_sessions(
         &self,
         filter: Option<ListSessionFilter>,
-    ) -> Result<Vec<Session>, crate::Error> {
+    ) -> Result<Page<Session>, crate::Error> {
         let conn = self.conn()?;
 
-        let mut rows = match filter {
+        let (limit, rows) = match filter {
             Some(ListSessionFilter {
-                common: ListSessionFilterCommon { user_id, limit },
+                common:
+                    ListSessionFilterCommon {
+                        user_id,
+                        limit,
+                        cursor,
+                    },
                 specific: ListSessionFilterSpecific::Search { query },
             }) => {
-                conn.query(
-                    "SELECT * FROM sessions WHERE user_id = ? AND title LIKE ? ORDER BY created_at DESC LIMIT ?",
-                    vec![user_id, format!("%{}%", query), limit.unwrap_or(100).to_string()],
-                )
-                .await?
+                let limit = page_size(limit);
+                let cursor = Cursor::decode(cursor.as_deref())?;
+
+                let rows = conn
+                    .query(
+                        "
+                        SELECT *, created_at AS cursor_key, id AS cursor_id FROM sessions
+                        WHERE
+                            user_id = :user_id AND
+                            title LIKE :query AND
+                            (:cursor_key IS NULL OR (created_at, id) < (:cursor_key, :cursor_id))
+                        ORDER BY created_at DESC, id DESC
+                        LIMIT :limit",
+                        libsql::named_params! {
+                            ":user_id": user_id,
+                            ":query": format!("%{}%", query),
+                            ":cursor_key": cursor.key,
+                            ":cursor_id": cursor.id,
+                            ":limit": limit + 1,
+                        },
+                    )
+                    .await?;
+                (limit, rows)
             }
             Some(ListSessionFilter {
-                common: ListSessionFilterCommon { user_id, limit },
+                common:
+                    ListSessionFilterCommon {
+                        user_id,
+                        limit,
+                        cursor,
+                    },
                 specific: ListSessionFilterSpecific::RecentlyVisited {},
             }) => {
-                conn.query(
-                    "SELECT * FROM sessions WHERE user_id = ? ORDER BY visited_at DESC LIMIT ?",
-                    vec![user_id, limit.unwrap_or(100).to_string()],
-                )
-                .await?
+                let limit = page_size(limit);
+                let cursor = Cursor::decode(cursor.as_deref())?;
+
+                let rows = conn
+                    .query(
+                        "
+                        SELECT *, visited_at AS cursor_key, id AS cursor_id FROM sessions
+                        WHERE
+                            user_id = :user_id AND
+                            (:cursor_key IS NULL OR (visited_at, id) < (:cursor_key, :cursor_id))
+                        ORDER BY visited_at DESC, id DESC
+                        LIMIT :limit",
+                        libsql::named_params! {
+                            ":user_id": user_id,
+                            ":cursor_key": cursor.key,
+                            ":cursor_id": cursor.id,
+                            ":limit": limit + 1,
+                        },
+                    )
+                    .await?;
+                (limit, rows)
             }
             Some(ListSessionFilter {
-                common: ListSessionFilterCommon { user_id, limit },
+                common:
+                    ListSessionFilterCommon {
+                        user_id,
+                        limit,
+                        cursor,
+                    },
                 specific: ListSessionFilterSpecific::DateRange { start, end },
             }) => {
-                conn.query(
-                    "
-                    SELECT s.* FROM sessions s
-                    LEFT JOIN events e ON s.calendar_event_id = e.id
-                    WHERE
-                        s.user_id = :user_id AND
-                        (
-                            (s.calendar_event_id IS NULL AND s.created_at BETWEEN :start_time AND :end_time)
-                            OR
-                            (s.calendar_event_id IS NOT NULL AND e.start_date BETWEEN :start_time AND :end_time)
+                let limit = page_size(limit);
+                let cursor = Cursor::decode(cursor.as_deref())?;
+
+                let rows = conn
+                    .query(
+                        "
+                        SELECT * FROM (
+                            SELECT
+                                s.*,
+                                CASE
+                                    WHEN s.calendar_event_id IS NULL THEN s.created_at
+                                    ELSE e.start_date
+                                END AS cursor_key,
+                                s.id AS cursor_id
+                            FROM sessions s
+                            LEFT JOIN events e ON s.calendar_event_id = e.id
+                            WHERE
+                                s.user_id = :user_id AND
+                                (
+                                    (s.calendar_event_id IS NULL AND s.created_at BETWEEN :start_time AND :end_time)
+                                    OR
+                                    (s.calendar_event_id IS NOT NULL AND e.start_date BETWEEN :start_time AND :end_time)
+                                )
                         )
-                    ORDER BY
-                        CASE
-                            WHEN s.calendar_event_id IS NULL THEN s.created_at
-                            ELSE e.start_date
-                        END DESC
-                    LIMIT :limit",
-                    libsql::named_params! {
-                        ":user_id": user_id,
-                        ":start_time": start.to_rfc3339(),
-                        ":end_time": end.to_rfc3339(),
-                        ":limit": limit.unwrap_or(100).to_string(),
+                        WHERE :cursor_key IS NULL OR (cursor_key, cursor_id) < (:cursor_key, :cursor_id)
+                        ORDER BY cursor_key DESC, cursor_id DESC
+                        LIMIT :limit",
+                        libsql::named_params! {
+                            ":user_id": user_id,
+                            ":start_time": start.to_rfc3339(),
+                            ":end_time": end.to_rfc3339(),
+                            ":cursor_key": cursor.key,
+                            ":cursor_id": cursor.id,
+                            ":limit": limit + 1,
+                        },
+                    )
+                    .await?;
+                (limit, rows)
+            }
+            Some(ListSessionFilter {
+                common:
+                    ListSessionFilterCommon {
+                        user_id,
+                        limit,
+                        cursor,
                     },
-                )
-                .await?
+                specific: ListSessionFilterSpecific::Tags { any_of, all_of },
+            }) => {
+                let limit = page_size(limit);
+                let cursor = Cursor::decode(cursor.as_deref())?;
+
+                let rows = conn
+                    .query(
+                        "
+                        SELECT *, created_at AS cursor_key, id AS cursor_id FROM sessions
+                        WHERE
+                            user_id = :user_id AND
+                            (
+                                json_array_length(:any_of) = 0 OR
+                                id IN (
+                                    SELECT session_id FROM tags_sessions
+                                    WHERE tag_id IN (SELECT value FROM json_each(:any_of))
+                                )
+                            ) AND
+                            (
+                                json_array_length(:all_of) = 0 OR
+                                id IN (
+                                    SELECT session_id FROM tags_sessions
+                                    WHERE tag_id IN (SELECT value FROM json_each(:all_of))
+                                    GROUP BY session_id
+                                    HAVING COUNT(DISTINCT tag_id) = (SELECT COUNT(DISTINCT value) FROM json_each(:all_of))
+                                )
+                            ) AND
+                            (:cursor_key IS NULL OR (created_at, id) < (:cursor_key, :cursor_id))
+                        ORDER BY created_at DESC, id DESC
+                        LIMIT :limit",
+                        libsql::named_params! {
+                            ":user_id": user_id,
+                            ":any_of": serde_json::to_string(&any_of)?,
+                            ":all_of": serde_json::to_string(&all_of)?,
+                            ":cursor_key": cursor.key,
+                            ":cursor_id": cursor.id,
+                            ":limit": limit + 1,
+                        },
+                    )
+                    .await?;
+                (limit, rows)
             }
             None => {
-                conn.query(
-                    "SELECT * FROM sessions ORDER BY created_at DESC LIMIT 100",
-                    (),
-                )
-                .await?
+                let rows = conn
+                    .query(
+                        "
+                        SELECT *, created_at AS cursor_key, id AS cursor_id FROM sessions
+                        ORDER BY created_at DESC, id DESC
+                        LIMIT ?",
+                        vec![DEFAULT_PAGE_SIZE + 1],
+                    )
+                    .await?;
+                (DEFAULT_PAGE_SIZE, rows)
             }
         };
 
-        let mut items = Vec::new();
-        while let Some(row) = rows.next().await.unwrap() {
-            let item = Session::from_row(&row)?;
-            items.push(item);
-        }
-        Ok(items)
+        Page::from_rows(rows, limit, Session::from_row).await
     }
 
     pub async fn upsert_session(&self, session: Session) -> Result<Session, crate::Error> {
         let conn = self.conn()?;
 
+        let conversations = serde_json::to_string(&session.conversations)?;
+        let field_timestamps = self
+            .touch_field_timestamps(
+                &conn,
+                "sessions",
+                &session.id,
+                serde_json::json!({
+                    "title": session.title,
+                    "raw_memo_html": session.raw_memo_html,
+                    "enhanced_memo_html": session.enhanced_memo_html,
+                    "conversations": conversations,
+                }),
+            )
+            .await?;
+
         let mut rows = conn
             .query(
                 "INSERT INTO sessions (
@@ -221,8 +363,9 @@ impl UserDatabase {
                     title,
                     raw_memo_html,
                     enhanced_memo_html,
-                    conversations
-                ) VALUES (:id, :created_at, :visited_at, :user_id, :calendar_event_id, :title, :raw_memo_html, :enhanced_memo_html, :conversations)
+                    conversations,
+                    field_timestamps
+                ) VALUES (:id, :created_at, :visited_at, :user_id, :calendar_event_id, :title, :raw_memo_html, :enhanced_memo_html, :conversations, :field_timestamps)
                 ON CONFLICT(id) DO UPDATE SET
                     created_at = :created_at,
                     visited_at = :visited_at,
@@ -231,7 +374,8 @@ impl UserDatabase {
                     title = :title,
                     raw_memo_html = :raw_memo_html,
                     enhanced_memo_html = :enhanced_memo_html,
-                    conversations = :conversations
+                    conversations = :conversations,
+                    field_timestamps = :field_timestamps
                 RETURNING *",
                 libsql::named_params! {
                     ":id": session.id.clone(),
@@ -242,12 +386,16 @@ impl UserDatabase {
                     ":title": session.title.clone(),
                     ":raw_memo_html": session.raw_memo_html.clone(),
                     ":enhanced_memo_html": session.enhanced_memo_html.clone(),
-                    ":conversations": serde_json::to_string(&session.conversations).unwrap(),
+                    ":conversations": conversations,
+                    ":field_timestamps": field_timestamps,
                 },
             )
             .await?;
 
-        let row = rows.next().await?.unwrap();
+        let row = rows
+            .next()
+            .await?
+            .ok_or_else(|| crate::Error::NotFound("session".to_string()))?;
         let session = Session::from_row(&row)?;
         Ok(session)
     }
@@ -313,7 +461,7 @@ impl UserDatabase {
             .await?;
 
         let mut items = Vec::new();
-        while let Some(row) = rows.next().await.unwrap() {
+        while let Some(row) = rows.next().await? {
             let item: Human = libsql::de::from_row(&row)?;
             items.push(item);
         }
@@ -347,13 +495,16 @@ impl UserDatabase {
 
 #[cfg(test)]
 mod tests {
-    use crate::{tests::setup_db, Human, Session};
+    use crate::{
+        tests::setup_db, Human, ListSessionFilter, ListSessionFilterCommon,
+        ListSessionFilterSpecific, Session,
+    };
 
     #[tokio::test]
     async fn test_sessions() {
         let db = setup_db().await;
 
-        let sessions = db.list_sessions(None).await.unwrap();
+        let sessions = db.list_sessions(None).await.unwrap().items;
         assert_eq!(sessions.len(), 0);
 
         let user = db
@@ -382,18 +533,18 @@ mod tests {
         assert_eq!(session.title, "test");
         assert_eq!(session.conversations, vec![]);
 
-        let sessions = db.list_sessions(None).await.unwrap();
+        let sessions = db.list_sessions(None).await.unwrap().items;
         assert_eq!(sessions.len(), 1);
 
         session.raw_memo_html = "raw_memo_html_2".to_string();
         let session = db.upsert_session(session).await.unwrap();
         assert_eq!(session.raw_memo_html, "raw_memo_html_2");
 
-        let sessions = db.list_sessions(None).await.unwrap();
+        let sessions = db.list_sessions(None).await.unwrap().items;
         assert_eq!(sessions.len(), 1);
 
         db.delete_session(&session.id).await.unwrap();
-        let sessions = db.list_sessions(None).await.unwrap();
+        let sessions = db.list_sessions(None).await.unwrap().items;
         assert_eq!(sessions.len(), 0);
 
         let participants = db.session_list_participants(&session.id).await.unwrap();
@@ -401,4 +552,67 @@ mod tests {
 
         assert_eq!(db.session_get_event(&session.id).await.unwrap(), None);
     }
+
+    #[tokio::test]
+    async fn test_list_sessions_pagination() {
+        let db = setup_db().await;
+
+        let user = db.upsert_human(Human::default()).await.unwrap();
+        let now = chrono::Utc::now();
+
+        // Two sessions share a timestamp so the `id` tie-breaker is exercised.
+        for (i, offset) in [0, 1, 1, 2, 3].into_iter().enumerate() {
+            db.upsert_session(Session {
+                id: uuid::Uuid::new_v4().to_string(),
+                user_id: user.id.clone(),
+                created_at: now - chrono::Duration::minutes(offset),
+                visited_at: now,
+                calendar_event_id: None,
+                title: format!("session {}", i),
+                raw_memo_html: "".to_string(),
+                enhanced_memo_html: None,
+                conversations: vec![],
+            })
+            .await
+            .unwrap();
+        }
+
+        let mut cursor = None;
+        let mut titles = Vec::new();
+        let mut pages = 0;
+
+        loop {
+            let page = db
+                .list_sessions(Some(ListSessionFilter {
+                    common: ListSessionFilterCommon {
+                        user_id: user.id.clone(),
+                        limit: Some(2),
+                        cursor,
+                    },
+                    specific: ListSessionFilterSpecific::Search {
+                        query: "session".to_string(),
+                    },
+                }))
+                .await
+                .unwrap();
+
+            pages += 1;
+            titles.extend(page.items.into_iter().map(|s| s.title));
+
+            match page.next_cursor {
+                Some(c) => cursor = Some(c),
+                None => break,
+            }
+        }
+
+        assert_eq!(pages, 3);
+        assert_eq!(titles.len(), 5);
+        assert_eq!(titles[0], "session 0");
+        assert_eq!(titles[4], "session 4");
+
+        let mut deduped = titles.clone();
+        deduped.sort();
+        deduped.dedup();
+        assert_eq!(deduped.len(), 5);
+    }
 }
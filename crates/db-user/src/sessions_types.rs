@@ -1,4 +1,5 @@
 use chrono::{DateTime, Utc};
+use hypr_db_core::RowExt;
 
 use crate::user_common_derives;
 
@@ -17,30 +18,17 @@ user_common_derives! {
 }
 
 impl Session {
-    pub fn from_row(row: &libsql::Row) -> Result<Self, serde::de::value::Error> {
+    pub fn from_row(row: &libsql::Row) -> Result<Self, crate::Error> {
         Ok(Self {
-            id: row.get(0).expect("id"),
-            created_at: {
-                let str = row.get_str(1).expect("created_at");
-                DateTime::parse_from_rfc3339(str)
-                    .unwrap()
-                    .with_timezone(&Utc)
-            },
-            visited_at: {
-                let str = row.get_str(2).expect("visited_at");
-                DateTime::parse_from_rfc3339(str)
-                    .unwrap()
-                    .with_timezone(&Utc)
-            },
-            user_id: row.get(3).expect("user_id"),
-            calendar_event_id: row.get(4).expect("calendar_event_id"),
-            title: row.get(5).expect("title"),
-            raw_memo_html: row.get(6).expect("raw_memo_html"),
-            enhanced_memo_html: row.get(7).expect("enhanced_memo_html"),
-            conversations: row
-                .get_str(8)
-                .map(|s| serde_json::from_str(s).unwrap())
-                .unwrap_or_default(),
+            id: row.get_string(0, "id")?,
+            created_at: row.get_datetime(1, "created_at")?,
+            visited_at: row.get_datetime(2, "visited_at")?,
+            user_id: row.get_string(3, "user_id")?,
+            calendar_event_id: row.get_opt_string(4, "calendar_event_id")?,
+            title: row.get_string(5, "title")?,
+            raw_memo_html: row.get_string(6, "raw_memo_html")?,
+            enhanced_memo_html: row.get_opt_string(7, "enhanced_memo_html")?,
+            conversations: row.get_json(8, "conversations")?,
         })
     }
 }
@@ -51,6 +39,8 @@ user_common_derives! {
         pub end: DateTime<Utc>,
         pub transcripts: Vec<hypr_listener_interface::TranscriptChunk>,
         pub diarizations: Vec<hypr_listener_interface::DiarizationChunk>,
+        #[serde(default)]
+        pub translations: Vec<hypr_listener_interface::TranscriptChunk>,
     }
 }
 
@@ -77,7 +67,8 @@ user_common_derives! {
 user_common_derives! {
     pub struct ListSessionFilterCommon {
         pub user_id: String,
-        pub limit: Option<u8>,
+        pub limit: Option<u32>,
+        pub cursor: Option<String>,
     }
 }
 
@@ -90,5 +81,7 @@ user_common_derives! {
         RecentlyVisited {},
         #[serde(rename = "dateRange")]
         DateRange { start: DateTime<Utc>, end: DateTime<Utc> },
+        #[serde(rename = "tags")]
+        Tags { any_of: Vec<String>, all_of: Vec<String> },
     }
 }
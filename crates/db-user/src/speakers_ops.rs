@@ -0,0 +1,425 @@
+use std::collections::{BTreeMap, HashMap};
+
+use hypr_db_core::SqlTable;
+use hypr_listener_interface::DiarizationChunk;
+
+use super::{GetSessionFilter, ResolvedSpeaker, SessionSpeaker, SpeakerSegment, UserDatabase};
+
+// How the stored speakers line up with the current diarization of a session.
+struct SpeakerResolution {
+    segments: BTreeMap<i32, Vec<std::ops::Range<u64>>>,
+    // Diarization index -> index shown in the timeline. Merged speakers share the lowest index.
+    remap: HashMap<i32, i32>,
+    // Shown index -> stored speaker.
+    assigned: BTreeMap<i32, SessionSpeaker>,
+}
+
+impl SpeakerResolution {
+    fn resolve(diarizations: &[DiarizationChunk], stored: Vec<SessionSpeaker>) -> Self {
+        let segments = hypr_timeline::speaker_segments(diarizations);
+        let anchors = stored.iter().map(|s| s.anchor()).collect::<Vec<_>>();
+        let matched = hypr_timeline::match_speakers(&anchors, &segments);
+
+        let mut canonical: HashMap<usize, i32> = HashMap::new();
+        for (speaker, anchor) in &matched {
+            canonical
+                .entry(*anchor)
+                .and_modify(|c| *c = (*c).min(*speaker))
+                .or_insert(*speaker);
+        }
+
+        let remap = segments
+            .keys()
+            .map(|speaker| {
+                let shown = matched
+                    .get(speaker)
+                    .map(|anchor| canonical[anchor])
+                    .unwrap_or(*speaker);
+                (*speaker, shown)
+            })
+            .collect();
+
+        let assigned = stored
+            .into_iter()
+            .enumerate()
+            .filter_map(|(anchor, s)| canonical.get(&anchor).map(|c| (*c, s)))
+            .collect();
+
+        Self {
+            segments,
+            remap,
+            assigned,
+        }
+    }
+
+    fn shown(&self, speaker: i32) -> i32 {
+        self.remap.get(&speaker).copied().unwrap_or(speaker)
+    }
+
+    // Every segment currently shown as `speaker`.
+    fn segments_of(&self, speaker: i32) -> Vec<SpeakerSegment> {
+        self.segments
+            .iter()
+            .filter(|(s, _)| self.shown(**s) == speaker)
+            .flat_map(|(_, ranges)| ranges.iter())
+            .map(|r| SpeakerSegment {
+                start: r.start,
+                end: r.end,
+            })
+            .collect()
+    }
+}
+
+impl UserDatabase {
+    async fn session_diarizations(
+        &self,
+        session_id: impl Into<String>,
+    ) -> Result<Vec<DiarizationChunk>, crate::Error> {
+        let session = self
+            .get_session(GetSessionFilter::Id(session_id.into()))
+            .await?;
+
+        Ok(session
+            .map(|s| {
+                s.conversations
+                    .into_iter()
+                    .flat_map(|c| c.diarizations)
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+
+    async fn speaker_resolution(
+        &self,
+        session_id: impl Into<String>,
+    ) -> Result<SpeakerResolution, crate::Error> {
+        let session_id = session_id.into();
+
+        let diarizations = self.session_diarizations(&session_id).await?;
+        let stored = self.list_session_speakers(&session_id).await?;
+
+        Ok(SpeakerResolution::resolve(&diarizations, stored))
+    }
+
+    pub async fn list_session_speakers(
+        &self,
+        session_id: impl Into<String>,
+    ) -> Result<Vec<SessionSpeaker>, crate::Error> {
+        let conn = self.conn()?;
+
+        let sql = format!(
+            "SELECT * FROM {} WHERE session_id = ? ORDER BY speaker",
+            SessionSpeaker::sql_table()
+        );
+        let mut rows = conn.query(&sql, vec![session_id.into()]).await?;
+
+        let mut items = Vec::new();
+        while let Some(row) = rows.next().await? {
+            items.push(SessionSpeaker::from_row(&row)?);
+        }
+        Ok(items)
+    }
+
+    // Diarization index -> index shown in the timeline, with merged speakers folded together.
+    pub async fn session_speaker_remap(
+        &self,
+        session_id: impl Into<String>,
+    ) -> Result<HashMap<i32, i32>, crate::Error> {
+        Ok(self.speaker_resolution(session_id).await?.remap)
+    }
+
+    // Every speaker shown in the session's timeline, with the participant assigned to it.
+    pub async fn session_resolve_speakers(
+        &self,
+        session_id: impl Into<String>,
+    ) -> Result<Vec<ResolvedSpeaker>, crate::Error> {
+        let resolution = self.speaker_resolution(session_id).await?;
+
+        let mut shown = resolution.remap.values().copied().collect::<Vec<_>>();
+        shown.sort();
+        shown.dedup();
+
+        let mut speakers = Vec::new();
+        for speaker in shown {
+            let human = match resolution
+                .assigned
+                .get(&speaker)
+                .and_then(|s| s.human_id.as_ref())
+            {
+                Some(human_id) => self.get_human(human_id).await?,
+                None => None,
+            };
+
+            speakers.push(ResolvedSpeaker { speaker, human });
+        }
+
+        Ok(speakers)
+    }
+
+    pub async fn session_speaker_names(
+        &self,
+        session_id: impl Into<String>,
+    ) -> Result<hypr_timeline::SpeakerNames, crate::Error> {
+        Ok(self
+            .session_resolve_speakers(session_id)
+            .await?
+            .into_iter()
+            .filter_map(|s| {
+                let human = s.human?;
+                Some((s.speaker, human.full_name.or(human.email)?))
+            })
+            .collect())
+    }
+
+    // Replaces the stored speakers behind the shown indices `speakers` with a single one anchored on all of their segments.
+    async fn replace_session_speakers(
+        &self,
+        session_id: String,
+        resolution: &SpeakerResolution,
+        speakers: &[i32],
+        shown_as: i32,
+        human_id: Option<String>,
+    ) -> Result<SessionSpeaker, crate::Error> {
+        let segments = speakers
+            .iter()
+            .flat_map(|s| resolution.segments_of(*s))
+            .collect::<Vec<_>>();
+
+        if segments.is_empty() {
+            return Err(crate::Error::NotFound(format!(
+                "speaker {} in session {}",
+                shown_as, session_id
+            )));
+        }
+
+        // Anything covering more than one diarization speaker has to keep matching all of them.
+        let merged = resolution
+            .segments
+            .keys()
+            .filter(|s| speakers.contains(&resolution.shown(**s)))
+            .count()
+            > 1;
+
+        let replaced = speakers
+            .iter()
+            .filter_map(|s| resolution.assigned.get(s))
+            .collect::<Vec<_>>();
+
+        let speaker = SessionSpeaker {
+            id: resolution
+                .assigned
+                .get(&shown_as)
+                .map(|s| s.id.clone())
+                .unwrap_or_else(|| uuid::Uuid::new_v4().to_string()),
+            session_id,
+            speaker: shown_as,
+            human_id,
+            segments,
+            merged,
+        };
+
+        let conn = self.conn()?;
+        let tx = conn.transaction().await?;
+
+        for s in replaced {
+            tx.execute(
+                &format!("DELETE FROM {} WHERE id = ?", SessionSpeaker::sql_table()),
+                vec![s.id.clone()],
+            )
+            .await?;
+        }
+
+        tx.execute(
+            &format!(
+                "INSERT INTO {} (id, session_id, speaker, human_id, segments, merged) VALUES (?, ?, ?, ?, ?, ?)",
+                SessionSpeaker::sql_table()
+            ),
+            libsql::params![
+                speaker.id.clone(),
+                speaker.session_id.clone(),
+                speaker.speaker,
+                speaker.human_id.clone(),
+                serde_json::to_string(&speaker.segments)?,
+                speaker.merged,
+            ],
+        )
+        .await?;
+
+        tx.commit().await?;
+        Ok(speaker)
+    }
+
+    pub async fn session_assign_speaker(
+        &self,
+        session_id: impl Into<String>,
+        speaker: i32,
+        human_id: impl Into<String>,
+    ) -> Result<SessionSpeaker, crate::Error> {
+        let session_id = session_id.into();
+        let resolution = self.speaker_resolution(&session_id).await?;
+
+        self.replace_session_speakers(
+            session_id,
+            &resolution,
+            &[speaker],
+            speaker,
+            Some(human_id.into()),
+        )
+        .await
+    }
+
+    // Folds `from` into `into`. The participant assigned to `into` wins, falling back to the one assigned to `from`.
+    // The merged speaker is shown under the lower of the two indices, which is what gets returned.
+    pub async fn session_merge_speakers(
+        &self,
+        session_id: impl Into<String>,
+        from: i32,
+        into: i32,
+    ) -> Result<SessionSpeaker, crate::Error> {
+        let session_id = session_id.into();
+        let resolution = self.speaker_resolution(&session_id).await?;
+
+        let human_id = [into, from]
+            .iter()
+            .find_map(|s| resolution.assigned.get(s)?.human_id.clone());
+
+        self.replace_session_speakers(
+            session_id,
+            &resolution,
+            &[into, from],
+            into.min(from),
+            human_id,
+        )
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{tests::setup_db, ConversationChunk, Human, Session};
+    use hypr_listener_interface::{DiarizationChunk, TranscriptChunk};
+
+    fn conversation(speakers: &[(u64, u64, i32)]) -> ConversationChunk {
+        ConversationChunk {
+            start: chrono::Utc::now(),
+            end: chrono::Utc::now(),
+            transcripts: speakers
+                .iter()
+                .map(|(start, end, _)| TranscriptChunk {
+                    start: *start,
+                    end: *end,
+                    text: "Hello there.".to_string(),
+                    confidence: None,
+                    is_final: true,
+                    revision: None,
+                    language: None,
+                })
+                .collect(),
+            diarizations: speakers
+                .iter()
+                .map(|(start, end, speaker)| DiarizationChunk {
+                    start: *start,
+                    end: *end,
+                    speaker: *speaker,
+                    confidence: None,
+                })
+                .collect(),
+            translations: vec![],
+        }
+    }
+
+    #[tokio::test]
+    async fn test_session_speakers() {
+        let db = setup_db().await;
+
+        let user = db.upsert_human(Human::default()).await.unwrap();
+        let john = db
+            .upsert_human(Human {
+                full_name: Some("John".to_string()),
+                ..Human::default()
+            })
+            .await
+            .unwrap();
+
+        let session = db
+            .upsert_session(Session {
+                id: uuid::Uuid::new_v4().to_string(),
+                created_at: chrono::Utc::now(),
+                visited_at: chrono::Utc::now(),
+                user_id: user.id.clone(),
+                calendar_event_id: None,
+                title: "test".to_string(),
+                raw_memo_html: "".to_string(),
+                enhanced_memo_html: None,
+                conversations: vec![conversation(&[
+                    (0, 3000, 0),
+                    (4000, 7000, 1),
+                    (8000, 11000, 2),
+                ])],
+            })
+            .await
+            .unwrap();
+
+        db.session_assign_speaker(&session.id, 1, &john.id)
+            .await
+            .unwrap();
+        assert!(db
+            .session_assign_speaker(&session.id, 7, &john.id)
+            .await
+            .is_err());
+
+        let names = db.session_speaker_names(&session.id).await.unwrap();
+        assert_eq!(names.get(&1).map(String::as_str), Some("John"));
+
+        let merged = db.session_merge_speakers(&session.id, 2, 1).await.unwrap();
+        assert_eq!(merged.speaker, 1);
+        assert!(merged.merged);
+
+        let resolved = db.session_resolve_speakers(&session.id).await.unwrap();
+        assert_eq!(
+            resolved
+                .iter()
+                .map(|s| (s.speaker, s.human.as_ref().map(|h| h.id.clone())))
+                .collect::<Vec<_>>(),
+            vec![(0, None), (1, Some(john.id.clone()))]
+        );
+
+        // Re-diarization renumbers the speakers. The assignment and the merge follow the audio.
+        db.upsert_session(Session {
+            conversations: vec![conversation(&[
+                (0, 3000, 2),
+                (4000, 7000, 0),
+                (8000, 11000, 1),
+            ])],
+            ..session.clone()
+        })
+        .await
+        .unwrap();
+
+        let names = db.session_speaker_names(&session.id).await.unwrap();
+        assert_eq!(names.get(&0).map(String::as_str), Some("John"));
+        assert_eq!(names.len(), 1);
+
+        let view = db.get_timeline_view(&session.id).await.unwrap().unwrap();
+        assert_eq!(
+            view.items.iter().map(|i| i.speaker).collect::<Vec<_>>(),
+            vec![2, 0]
+        );
+
+        assert_eq!(
+            db.list_session_speakers(&session.id).await.unwrap().len(),
+            1
+        );
+
+        // The result is the index the timeline shows, not the one merged into.
+        let merged = db.session_merge_speakers(&session.id, 0, 2).await.unwrap();
+        assert_eq!(merged.speaker, 0);
+        assert_eq!(merged.human_id, Some(john.id.clone()));
+
+        let view = db.get_timeline_view(&session.id).await.unwrap().unwrap();
+        assert_eq!(
+            view.items.iter().map(|i| i.speaker).collect::<Vec<_>>(),
+            vec![0]
+        );
+    }
+}
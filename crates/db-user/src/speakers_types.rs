@@ -0,0 +1,55 @@
+use hypr_db_core::RowExt;
+
+use crate::{user_common_derives, Human};
+
+user_common_derives! {
+    #[sql_table("session_speakers")]
+    pub struct SessionSpeaker {
+        pub id: String,
+        pub session_id: String,
+        pub speaker: i32,
+        pub human_id: Option<String>,
+        // Where this speaker talked when it was identified. Used to find it again after re-diarization.
+        pub segments: Vec<SpeakerSegment>,
+        // Whether several speakers were merged into this one.
+        pub merged: bool,
+    }
+}
+
+user_common_derives! {
+    pub struct SpeakerSegment {
+        pub start: u64,
+        pub end: u64,
+    }
+}
+
+user_common_derives! {
+    pub struct ResolvedSpeaker {
+        pub speaker: i32,
+        pub human: Option<Human>,
+    }
+}
+
+impl SessionSpeaker {
+    pub fn from_row(row: &libsql::Row) -> Result<Self, crate::Error> {
+        Ok(Self {
+            id: row.get_string(0, "id")?,
+            session_id: row.get_string(1, "session_id")?,
+            speaker: row
+                .get::<i64>(2)
+                .map_err(|e| crate::Error::decode("speaker", e))? as i32,
+            human_id: row.get_opt_string(3, "human_id")?,
+            segments: row.get_json(4, "segments")?,
+            merged: row
+                .get::<bool>(5)
+                .map_err(|e| crate::Error::decode("merged", e))?,
+        })
+    }
+
+    pub fn anchor(&self) -> hypr_timeline::SpeakerAnchor {
+        hypr_timeline::SpeakerAnchor {
+            ranges: self.segments.iter().map(|s| s.start..s.end).collect(),
+            merged: self.merged,
+        }
+    }
+}
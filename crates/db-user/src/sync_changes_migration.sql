@@ -0,0 +1,5 @@
+CREATE TABLE IF NOT EXISTS sync_changes (
+  seq INTEGER PRIMARY KEY AUTOINCREMENT,
+  table_name TEXT NOT NULL,
+  row_id TEXT NOT NULL
+);
@@ -0,0 +1,12 @@
+CREATE TABLE IF NOT EXISTS sync_conflicts (
+  id TEXT PRIMARY KEY,
+  table_name TEXT NOT NULL,
+  row_id TEXT NOT NULL,
+  field TEXT NOT NULL,
+  local_value TEXT NOT NULL,
+  remote_value TEXT NOT NULL,
+  local_updated_at TEXT DEFAULT NULL,
+  remote_updated_at TEXT DEFAULT NULL,
+  winner TEXT NOT NULL,
+  created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))
+);
@@ -0,0 +1,824 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use hypr_db_core::{
+    merge_records, FieldConflict, SqlTable, SyncPull, SyncRecord, SyncRemote, SyncSide,
+};
+
+use super::{SyncConflict, SyncReport, UserDatabase};
+
+struct SyncedTable {
+    name: &'static str,
+    // Written when the row is first created, never merged.
+    columns: &'static [&'static str],
+    // Merged field by field with last-writer-wins.
+    fields: &'static [&'static str],
+}
+
+const SYNCED_TABLES: [SyncedTable; 2] = [
+    SyncedTable {
+        name: "sessions",
+        columns: &["id", "created_at", "user_id"],
+        // `calendar_event_id` is left out, since events are not synced.
+        fields: &[
+            "title",
+            "raw_memo_html",
+            "enhanced_memo_html",
+            "conversations",
+        ],
+    },
+    SyncedTable {
+        name: "templates",
+        columns: &["id", "user_id"],
+        fields: &["title", "description", "sections", "tags"],
+    },
+];
+
+const SYNC_STATE_ID: &str = "default";
+
+enum ApplyMode {
+    // Remote changes merged into this device. Concurrent edits since the last sync are logged as conflicts.
+    Pull {
+        last_synced_at: Option<DateTime<Utc>>,
+    },
+    // Device changes merged into this database acting as a remote. They are appended to the change feed.
+    Push,
+}
+
+fn synced_table(name: &str) -> Result<&'static SyncedTable, crate::Error> {
+    SYNCED_TABLES
+        .iter()
+        .find(|t| t.name == name)
+        .ok_or_else(|| crate::Error::SyncError(format!("'{}' is not a synced table", name)))
+}
+
+// Fixed precision, so timestamps compare correctly as strings inside SQLite.
+fn format_ts(ts: &DateTime<Utc>) -> String {
+    ts.to_rfc3339_opts(SecondsFormat::Micros, true)
+}
+
+fn encode_field_timestamps(
+    field_timestamps: &BTreeMap<String, DateTime<Utc>>,
+) -> Result<String, crate::Error> {
+    let map: BTreeMap<&String, String> = field_timestamps
+        .iter()
+        .map(|(k, v)| (k, format_ts(v)))
+        .collect();
+    Ok(serde_json::to_string(&map)?)
+}
+
+fn decode_field_timestamps(s: &str) -> Result<BTreeMap<String, DateTime<Utc>>, crate::Error> {
+    let map: BTreeMap<String, String> =
+        serde_json::from_str(s).map_err(|e| crate::Error::decode("field_timestamps", e))?;
+
+    map.into_iter()
+        .map(|(k, v)| {
+            DateTime::parse_from_rfc3339(&v)
+                .map(|ts| (k, ts.with_timezone(&Utc)))
+                .map_err(|e| crate::Error::decode("field_timestamps", e))
+        })
+        .collect()
+}
+
+fn to_sql_value(value: &serde_json::Value) -> libsql::Value {
+    match value {
+        serde_json::Value::Null => libsql::Value::Null,
+        serde_json::Value::Bool(v) => libsql::Value::Integer(*v as i64),
+        serde_json::Value::Number(v) => match v.as_i64() {
+            Some(v) => libsql::Value::Integer(v),
+            None => libsql::Value::Real(v.as_f64().unwrap_or_default()),
+        },
+        serde_json::Value::String(v) => libsql::Value::Text(v.clone()),
+        v => libsql::Value::Text(v.to_string()),
+    }
+}
+
+fn record_from_row(table: &SyncedTable, row: &libsql::Row) -> Result<SyncRecord, crate::Error> {
+    let mut data = hypr_db_core::row_to_json(row);
+
+    let field_timestamps = match data.remove("field_timestamps") {
+        Some(serde_json::Value::String(s)) => decode_field_timestamps(&s)?,
+        _ => BTreeMap::new(),
+    };
+
+    let id = data
+        .get("id")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| crate::Error::decode("id", "missing"))?
+        .to_string();
+
+    let fields = data
+        .into_iter()
+        .filter(|(k, _)| table.columns.contains(&k.as_str()) || table.fields.contains(&k.as_str()))
+        .collect();
+
+    Ok(SyncRecord {
+        table: table.name.to_string(),
+        id,
+        fields,
+        field_timestamps,
+        deleted_at: None,
+    })
+}
+
+fn parse_ts(column: &str, s: &str) -> Result<DateTime<Utc>, crate::Error> {
+    DateTime::parse_from_rfc3339(s)
+        .map(|ts| ts.with_timezone(&Utc))
+        .map_err(|e| crate::Error::decode(column, e))
+}
+
+async fn read_record(
+    conn: &libsql::Connection,
+    table: &SyncedTable,
+    id: &str,
+) -> Result<Option<SyncRecord>, crate::Error> {
+    let mut rows = conn
+        .query(
+            &format!("SELECT * FROM {} WHERE id = ?", table.name),
+            vec![id.to_string()],
+        )
+        .await?;
+
+    if let Some(row) = rows.next().await? {
+        return Ok(Some(record_from_row(table, &row)?));
+    }
+
+    let mut rows = conn
+        .query(
+            "SELECT deleted_at FROM sync_tombstones WHERE table_name = ? AND row_id = ?",
+            vec![table.name.to_string(), id.to_string()],
+        )
+        .await?;
+
+    match rows.next().await? {
+        None => Ok(None),
+        Some(row) => {
+            let deleted_at = parse_ts("deleted_at", &row.get::<String>(0)?)?;
+            Ok(Some(SyncRecord::tombstone(table.name, id, deleted_at)))
+        }
+    }
+}
+
+pub(crate) async fn record_tombstone(
+    conn: &libsql::Connection,
+    table: &str,
+    id: &str,
+    deleted_at: DateTime<Utc>,
+) -> Result<(), crate::Error> {
+    conn.execute(
+        "INSERT OR IGNORE INTO sync_tombstones (table_name, row_id, deleted_at) VALUES (?, ?, ?)",
+        vec![table.to_string(), id.to_string(), format_ts(&deleted_at)],
+    )
+    .await?;
+    Ok(())
+}
+
+// Records the version of a row the remote is known to have, so it is only pushed once it changes again.
+async fn record_version(
+    conn: &libsql::Connection,
+    record: &SyncRecord,
+) -> Result<(), crate::Error> {
+    conn.execute(
+        "INSERT INTO sync_versions (table_name, row_id, version) VALUES (?, ?, ?)
+        ON CONFLICT(table_name, row_id) DO UPDATE SET version = excluded.version",
+        vec![
+            record.table.clone(),
+            record.id.clone(),
+            record
+                .updated_at()
+                .map(|ts| format_ts(&ts))
+                .unwrap_or_default(),
+        ],
+    )
+    .await?;
+    Ok(())
+}
+
+// Rows and tombstones newer than the version the remote has, or that it has never seen.
+async fn changed_records(
+    conn: &libsql::Connection,
+    table: &SyncedTable,
+) -> Result<Vec<SyncRecord>, crate::Error> {
+    let mut rows = conn
+        .query(
+            &format!(
+                "SELECT t.* FROM {} t
+                LEFT JOIN sync_versions v ON v.table_name = :table AND v.row_id = t.id
+                WHERE v.version IS NULL OR (SELECT MAX(value) FROM json_each(t.field_timestamps)) > v.version",
+                table.name
+            ),
+            libsql::named_params! { ":table": table.name },
+        )
+        .await?;
+
+    let mut items = Vec::new();
+    while let Some(row) = rows.next().await? {
+        items.push(record_from_row(table, &row)?);
+    }
+
+    let mut rows = conn
+        .query(
+            "SELECT d.row_id, d.deleted_at FROM sync_tombstones d
+            LEFT JOIN sync_versions v ON v.table_name = d.table_name AND v.row_id = d.row_id
+            WHERE d.table_name = :table AND (v.version IS NULL OR d.deleted_at > v.version)",
+            libsql::named_params! { ":table": table.name },
+        )
+        .await?;
+
+    while let Some(row) = rows.next().await? {
+        let id: String = row.get(0)?;
+        let deleted_at = parse_ts("deleted_at", &row.get::<String>(1)?)?;
+        items.push(SyncRecord::tombstone(table.name, id, deleted_at));
+    }
+    Ok(items)
+}
+
+async fn write_record(
+    conn: &libsql::Connection,
+    table: &SyncedTable,
+    record: &SyncRecord,
+) -> Result<(), crate::Error> {
+    if let Some(deleted_at) = record.deleted_at {
+        conn.execute(
+            &format!("DELETE FROM {} WHERE id = ?", table.name),
+            vec![record.id.clone()],
+        )
+        .await?;
+        return record_tombstone(conn, table.name, &record.id, deleted_at).await;
+    }
+
+    let columns = table
+        .columns
+        .iter()
+        .chain(table.fields.iter())
+        .filter(|c| record.fields.contains_key(**c))
+        .copied()
+        .collect::<Vec<_>>();
+
+    let updates = table
+        .fields
+        .iter()
+        .filter(|c| record.fields.contains_key(**c))
+        .map(|c| format!("{} = excluded.{}", c, c))
+        .chain(std::iter::once(
+            "field_timestamps = excluded.field_timestamps".to_string(),
+        ))
+        .collect::<Vec<_>>();
+
+    let sql = format!(
+        "INSERT INTO {} ({}, field_timestamps) VALUES ({}) ON CONFLICT(id) DO UPDATE SET {}",
+        table.name,
+        columns.join(", "),
+        vec!["?"; columns.len() + 1].join(", "),
+        updates.join(", ")
+    );
+
+    let mut params = columns
+        .iter()
+        .map(|c| to_sql_value(&record.fields[*c]))
+        .collect::<Vec<_>>();
+    params.push(libsql::Value::Text(encode_field_timestamps(
+        &record.field_timestamps,
+    )?));
+
+    conn.execute(&sql, params).await?;
+    Ok(())
+}
+
+async fn insert_conflict(
+    conn: &libsql::Connection,
+    conflict: &FieldConflict,
+) -> Result<(), crate::Error> {
+    let winner = match conflict.winner {
+        SyncSide::Local => "local",
+        SyncSide::Remote => "remote",
+    };
+
+    conn.execute(
+        &format!(
+            "INSERT INTO {} (
+                id,
+                table_name,
+                row_id,
+                field,
+                local_value,
+                remote_value,
+                local_updated_at,
+                remote_updated_at,
+                winner
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            SyncConflict::sql_table()
+        ),
+        vec![
+            libsql::Value::Text(uuid::Uuid::new_v4().to_string()),
+            libsql::Value::Text(conflict.table.clone()),
+            libsql::Value::Text(conflict.id.clone()),
+            libsql::Value::Text(conflict.field.clone()),
+            libsql::Value::Text(conflict.local_value.to_string()),
+            libsql::Value::Text(conflict.remote_value.to_string()),
+            conflict
+                .local_updated_at
+                .map(|ts| libsql::Value::Text(format_ts(&ts)))
+                .unwrap_or(libsql::Value::Null),
+            conflict
+                .remote_updated_at
+                .map(|ts| libsql::Value::Text(format_ts(&ts)))
+                .unwrap_or(libsql::Value::Null),
+            libsql::Value::Text(winner.to_string()),
+        ],
+    )
+    .await?;
+
+    tracing::warn!(
+        table = %conflict.table,
+        row_id = %conflict.id,
+        field = %conflict.field,
+        winner = winner,
+        "sync_conflict"
+    );
+    Ok(())
+}
+
+impl UserDatabase {
+    // Returns the `field_timestamps` to store with a write, bumping every synced field whose value changed.
+    pub(crate) async fn touch_field_timestamps(
+        &self,
+        conn: &libsql::Connection,
+        table: &str,
+        id: &str,
+        values: serde_json::Value,
+    ) -> Result<String, crate::Error> {
+        let table = synced_table(table)?;
+        let existing = read_record(conn, table, id).await?;
+
+        let now = Utc::now();
+        let mut field_timestamps = existing
+            .as_ref()
+            .map(|r| r.field_timestamps.clone())
+            .unwrap_or_default();
+
+        for field in table.fields {
+            let changed = match &existing {
+                None => true,
+                Some(r) => r.fields.get(*field) != values.get(*field),
+            };
+
+            if changed {
+                field_timestamps.insert(field.to_string(), now);
+            }
+        }
+
+        encode_field_timestamps(&field_timestamps)
+    }
+
+    async fn apply_records(
+        &self,
+        records: Vec<SyncRecord>,
+        mode: ApplyMode,
+    ) -> Result<u32, crate::Error> {
+        let conn = self.conn()?;
+        let tx = conn.transaction().await?;
+
+        let mut conflicts = 0;
+        for incoming in records {
+            let table = synced_table(&incoming.table)?;
+
+            let merged = match read_record(&tx, table, &incoming.id).await? {
+                None => incoming.clone(),
+                Some(existing) => match mode {
+                    ApplyMode::Pull { last_synced_at } => {
+                        let (merged, found) = merge_records(&existing, &incoming, last_synced_at);
+                        for conflict in &found {
+                            insert_conflict(&tx, conflict).await?;
+                        }
+                        conflicts += found.len() as u32;
+                        merged
+                    }
+                    ApplyMode::Push => merge_records(&existing, &incoming, None).0,
+                },
+            };
+
+            write_record(&tx, table, &merged).await?;
+
+            // When nothing of ours won, the remote already has this row as merged.
+            if matches!(mode, ApplyMode::Pull { .. })
+                && merged.fields == incoming.fields
+                && merged.deleted_at == incoming.deleted_at
+            {
+                record_version(&tx, &merged).await?;
+            }
+
+            if matches!(mode, ApplyMode::Push) {
+                tx.execute(
+                    "INSERT INTO sync_changes (table_name, row_id) VALUES (?, ?)",
+                    vec![merged.table.clone(), merged.id.clone()],
+                )
+                .await?;
+            }
+        }
+
+        tx.commit().await?;
+        Ok(conflicts)
+    }
+
+    // Pulls remote changes, merges them, then pushes every row the remote doesn't have the latest version of.
+    pub async fn sync_with(&self, remote: &impl SyncRemote) -> Result<SyncReport, crate::Error> {
+        let conn = self.conn()?;
+
+        let (last_pushed_at, remote_cursor) = {
+            let mut rows = conn
+                .query(
+                    "SELECT last_pushed_at, remote_cursor FROM sync_state WHERE id = ?",
+                    vec![SYNC_STATE_ID],
+                )
+                .await?;
+
+            match rows.next().await? {
+                None => (None, None),
+                Some(row) => (row.get::<Option<String>>(0)?, row.get::<Option<String>>(1)?),
+            }
+        };
+
+        let started_at = format_ts(&Utc::now());
+
+        let pull = remote.pull(remote_cursor.clone()).await?;
+        let pulled = pull.records.len() as u32;
+        let last_synced_at = last_pushed_at
+            .as_deref()
+            .map(|ts| parse_ts("last_pushed_at", ts))
+            .transpose()?;
+
+        let conflicts = self
+            .apply_records(pull.records, ApplyMode::Pull { last_synced_at })
+            .await?;
+
+        let mut outgoing = Vec::new();
+        for table in &SYNCED_TABLES {
+            outgoing.extend(changed_records(&conn, table).await?);
+        }
+
+        let pushed = outgoing.len() as u32;
+        if !outgoing.is_empty() {
+            remote.push(outgoing.clone()).await?;
+
+            let tx = conn.transaction().await?;
+            for record in &outgoing {
+                record_version(&tx, record).await?;
+            }
+            tx.commit().await?;
+        }
+
+        conn.execute(
+            "INSERT INTO sync_state (id, last_pushed_at, remote_cursor) VALUES (?, ?, ?)
+            ON CONFLICT(id) DO UPDATE SET
+                last_pushed_at = excluded.last_pushed_at,
+                remote_cursor = excluded.remote_cursor",
+            libsql::params![SYNC_STATE_ID, started_at, pull.cursor.or(remote_cursor)],
+        )
+        .await?;
+
+        tracing::info!(pulled, pushed, conflicts, "sync_finished");
+        Ok(SyncReport {
+            pulled,
+            pushed,
+            conflicts,
+        })
+    }
+
+    pub async fn list_sync_conflicts(&self) -> Result<Vec<SyncConflict>, crate::Error> {
+        let conn = self.conn()?;
+
+        let mut rows = conn
+            .query(
+                &format!(
+                    "SELECT * FROM {} ORDER BY created_at DESC",
+                    SyncConflict::sql_table()
+                ),
+                (),
+            )
+            .await?;
+
+        let mut items = Vec::new();
+        while let Some(row) = rows.next().await? {
+            let item: SyncConflict = libsql::de::from_row(&row)?;
+            items.push(item);
+        }
+        Ok(items)
+    }
+}
+
+// A `UserDatabase` (typically the Turso one) can act as the remote for other devices.
+impl SyncRemote for UserDatabase {
+    async fn pull(&self, cursor: Option<String>) -> Result<SyncPull, hypr_db_core::Error> {
+        let since: i64 = match cursor {
+            None => 0,
+            Some(c) => c
+                .parse()
+                .map_err(|_| crate::Error::SyncError(format!("invalid cursor '{}'", c)))?,
+        };
+
+        let conn = self.conn()?;
+        let mut rows = conn
+            .query(
+                "SELECT table_name, row_id, MAX(seq) AS seq FROM sync_changes WHERE seq > ? GROUP BY table_name, row_id ORDER BY seq",
+                vec![since],
+            )
+            .await?;
+
+        let mut pull = SyncPull::default();
+        while let Some(row) = rows.next().await? {
+            let table: String = row.get(0)?;
+            let id: String = row.get(1)?;
+            let seq: i64 = row.get(2)?;
+
+            if let Some(record) = read_record(&conn, synced_table(&table)?, &id).await? {
+                pull.records.push(record);
+            }
+            pull.cursor = Some(seq.to_string());
+        }
+
+        Ok(pull)
+    }
+
+    async fn push(&self, records: Vec<SyncRecord>) -> Result<(), hypr_db_core::Error> {
+        self.apply_records(records, ApplyMode::Push).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::sync::Mutex;
+
+    use hypr_db_core::{merge_records, SyncPull, SyncRecord, SyncRemote};
+
+    use crate::{
+        tests::setup_db, GetSessionFilter, Human, Session, SyncConflictWinner, Template,
+        UserDatabase,
+    };
+
+    #[derive(Default)]
+    struct FakeRemote {
+        records: Mutex<BTreeMap<(String, String), SyncRecord>>,
+        feed: Mutex<Vec<(String, String)>>,
+    }
+
+    impl SyncRemote for FakeRemote {
+        async fn pull(&self, cursor: Option<String>) -> Result<SyncPull, hypr_db_core::Error> {
+            let since: usize = cursor.map(|c| c.parse().unwrap()).unwrap_or(0);
+
+            let feed = self.feed.lock().unwrap();
+            let records = self.records.lock().unwrap();
+
+            let mut keys = Vec::new();
+            for key in feed.iter().skip(since) {
+                if !keys.contains(key) {
+                    keys.push(key.clone());
+                }
+            }
+
+            Ok(SyncPull {
+                records: keys.iter().map(|k| records[k].clone()).collect(),
+                cursor: (feed.len() > since).then(|| feed.len().to_string()),
+            })
+        }
+
+        async fn push(&self, incoming: Vec<SyncRecord>) -> Result<(), hypr_db_core::Error> {
+            let mut feed = self.feed.lock().unwrap();
+            let mut records = self.records.lock().unwrap();
+
+            for record in incoming {
+                let key = (record.table.clone(), record.id.clone());
+                let merged = match records.get(&key) {
+                    Some(existing) => merge_records(existing, &record, None).0,
+                    None => record,
+                };
+                records.insert(key.clone(), merged);
+                feed.push(key);
+            }
+            Ok(())
+        }
+    }
+
+    async fn setup_device(user_id: &str) -> UserDatabase {
+        let db = setup_db().await;
+        db.upsert_human(Human {
+            id: user_id.to_string(),
+            is_user: true,
+            ..Human::default()
+        })
+        .await
+        .unwrap();
+        db
+    }
+
+    async fn get_session(db: &UserDatabase, id: &str) -> Session {
+        db.get_session(GetSessionFilter::Id(id.to_string()))
+            .await
+            .unwrap()
+            .unwrap()
+    }
+
+    async fn tick() {
+        tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+    }
+
+    #[tokio::test]
+    async fn test_sync_two_devices() {
+        let user_id = uuid::Uuid::new_v4().to_string();
+        let remote = FakeRemote::default();
+        let device_a = setup_device(&user_id).await;
+        let device_b = setup_device(&user_id).await;
+
+        let session = device_a
+            .upsert_session(Session {
+                id: uuid::Uuid::new_v4().to_string(),
+                created_at: chrono::Utc::now(),
+                visited_at: chrono::Utc::now(),
+                user_id: user_id.clone(),
+                calendar_event_id: None,
+                title: "draft".to_string(),
+                raw_memo_html: "".to_string(),
+                enhanced_memo_html: None,
+                conversations: vec![],
+            })
+            .await
+            .unwrap();
+
+        device_a
+            .upsert_template(Template {
+                id: uuid::Uuid::new_v4().to_string(),
+                user_id: user_id.clone(),
+                title: "standup".to_string(),
+                description: "".to_string(),
+                sections: vec![],
+                tags: vec![],
+            })
+            .await
+            .unwrap();
+
+        let report = device_a.sync_with(&remote).await.unwrap();
+        assert_eq!((report.pulled, report.pushed), (0, 2));
+
+        let report = device_b.sync_with(&remote).await.unwrap();
+        assert_eq!((report.pulled, report.pushed), (2, 0));
+        assert_eq!(get_session(&device_b, &session.id).await.title, "draft");
+        assert_eq!(device_b.list_templates(&user_id).await.unwrap().len(), 1);
+
+        // Edits to different fields are both kept.
+        tick().await;
+        device_a
+            .upsert_session(Session {
+                title: "from a".to_string(),
+                ..get_session(&device_a, &session.id).await
+            })
+            .await
+            .unwrap();
+        tick().await;
+        device_b
+            .upsert_session(Session {
+                raw_memo_html: "memo from b".to_string(),
+                ..get_session(&device_b, &session.id).await
+            })
+            .await
+            .unwrap();
+
+        device_a.sync_with(&remote).await.unwrap();
+        device_b.sync_with(&remote).await.unwrap();
+        device_a.sync_with(&remote).await.unwrap();
+
+        for device in [&device_a, &device_b] {
+            let s = get_session(device, &session.id).await;
+            assert_eq!(s.title, "from a");
+            assert_eq!(s.raw_memo_html, "memo from b");
+            assert!(device.list_sync_conflicts().await.unwrap().is_empty());
+        }
+
+        // Edits to the same field: the later one wins and the conflict is logged.
+        tick().await;
+        device_a
+            .upsert_session(Session {
+                title: "title a".to_string(),
+                ..get_session(&device_a, &session.id).await
+            })
+            .await
+            .unwrap();
+        tick().await;
+        device_b
+            .upsert_session(Session {
+                title: "title b".to_string(),
+                ..get_session(&device_b, &session.id).await
+            })
+            .await
+            .unwrap();
+
+        device_a.sync_with(&remote).await.unwrap();
+        device_b.sync_with(&remote).await.unwrap();
+        device_a.sync_with(&remote).await.unwrap();
+
+        for device in [&device_a, &device_b] {
+            assert_eq!(get_session(device, &session.id).await.title, "title b");
+        }
+
+        // Device B merged the two edits, so it is the one that saw the conflict.
+        assert!(device_a.list_sync_conflicts().await.unwrap().is_empty());
+
+        let conflicts = device_b.list_sync_conflicts().await.unwrap();
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].row_id, session.id);
+        assert_eq!(conflicts[0].field, "title");
+        assert_eq!(conflicts[0].local_value, "\"title b\"");
+        assert_eq!(conflicts[0].remote_value, "\"title a\"");
+        assert_eq!(conflicts[0].winner, SyncConflictWinner::Local);
+    }
+
+    #[tokio::test]
+    async fn test_sync_with_database_remote() {
+        let user_id = uuid::Uuid::new_v4().to_string();
+        let remote = setup_device(&user_id).await;
+        let device_a = setup_device(&user_id).await;
+        let device_b = setup_device(&user_id).await;
+
+        let session = device_a
+            .upsert_session(Session {
+                id: uuid::Uuid::new_v4().to_string(),
+                created_at: chrono::Utc::now(),
+                visited_at: chrono::Utc::now(),
+                user_id: user_id.clone(),
+                calendar_event_id: None,
+                title: "shared".to_string(),
+                raw_memo_html: "".to_string(),
+                enhanced_memo_html: None,
+                conversations: vec![],
+            })
+            .await
+            .unwrap();
+
+        device_a.sync_with(&remote).await.unwrap();
+        device_b.sync_with(&remote).await.unwrap();
+        assert_eq!(get_session(&device_b, &session.id).await.title, "shared");
+
+        // Nothing new on either side.
+        let report = device_b.sync_with(&remote).await.unwrap();
+        assert_eq!((report.pushed, report.conflicts), (0, 0));
+        let report = device_a.sync_with(&remote).await.unwrap();
+        assert_eq!((report.pushed, report.conflicts), (0, 0));
+    }
+
+    #[tokio::test]
+    async fn test_sync_deletes() {
+        let user_id = uuid::Uuid::new_v4().to_string();
+        let remote = setup_device(&user_id).await;
+        let device_a = setup_device(&user_id).await;
+        let device_b = setup_device(&user_id).await;
+
+        let session = device_a
+            .upsert_session(Session {
+                id: uuid::Uuid::new_v4().to_string(),
+                created_at: chrono::Utc::now(),
+                visited_at: chrono::Utc::now(),
+                user_id: user_id.clone(),
+                calendar_event_id: None,
+                title: "to delete".to_string(),
+                raw_memo_html: "".to_string(),
+                enhanced_memo_html: None,
+                conversations: vec![],
+            })
+            .await
+            .unwrap();
+
+        device_a.sync_with(&remote).await.unwrap();
+        device_b.sync_with(&remote).await.unwrap();
+
+        // Deleted on A while B edits it. The delete wins everywhere.
+        device_a.delete_session(&session.id).await.unwrap();
+        tick().await;
+        device_b
+            .upsert_session(Session {
+                title: "edited".to_string(),
+                ..get_session(&device_b, &session.id).await
+            })
+            .await
+            .unwrap();
+
+        let report = device_a.sync_with(&remote).await.unwrap();
+        assert_eq!(report.pushed, 1);
+        device_b.sync_with(&remote).await.unwrap();
+        device_a.sync_with(&remote).await.unwrap();
+
+        for device in [&device_a, &device_b, &remote] {
+            let found = device
+                .get_session(GetSessionFilter::Id(session.id.clone()))
+                .await
+                .unwrap();
+            assert_eq!(found, None);
+        }
+
+        // The tombstone is only pushed once.
+        for device in [&device_a, &device_b] {
+            let report = device.sync_with(&remote).await.unwrap();
+            assert_eq!(report.pushed, 0);
+        }
+    }
+}
@@ -0,0 +1,5 @@
+CREATE TABLE IF NOT EXISTS sync_state (
+  id TEXT PRIMARY KEY,
+  last_pushed_at TEXT DEFAULT NULL,
+  remote_cursor TEXT DEFAULT NULL
+);
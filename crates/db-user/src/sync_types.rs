@@ -0,0 +1,46 @@
+use chrono::{DateTime, Utc};
+
+use crate::user_common_derives;
+
+user_common_derives! {
+    #[sql_table("sync_conflicts")]
+    pub struct SyncConflict {
+        pub id: String,
+        pub table_name: String,
+        pub row_id: String,
+        pub field: String,
+        pub local_value: String,
+        pub remote_value: String,
+        pub local_updated_at: Option<DateTime<Utc>>,
+        pub remote_updated_at: Option<DateTime<Utc>>,
+        pub winner: SyncConflictWinner,
+        pub created_at: DateTime<Utc>,
+    }
+}
+
+user_common_derives! {
+    pub enum SyncConflictWinner {
+        #[serde(rename = "local")]
+        Local,
+        #[serde(rename = "remote")]
+        Remote,
+    }
+}
+
+impl From<hypr_db_core::SyncSide> for SyncConflictWinner {
+    fn from(side: hypr_db_core::SyncSide) -> Self {
+        match side {
+            hypr_db_core::SyncSide::Local => SyncConflictWinner::Local,
+            hypr_db_core::SyncSide::Remote => SyncConflictWinner::Remote,
+        }
+    }
+}
+
+user_common_derives! {
+    #[derive(Default)]
+    pub struct SyncReport {
+        pub pulled: u32,
+        pub pushed: u32,
+        pub conflicts: u32,
+    }
+}
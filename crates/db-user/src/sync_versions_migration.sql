@@ -0,0 +1,6 @@
+CREATE TABLE IF NOT EXISTS sync_versions (
+  table_name TEXT NOT NULL,
+  row_id TEXT NOT NULL,
+  version TEXT NOT NULL,
+  PRIMARY KEY (table_name, row_id)
+);
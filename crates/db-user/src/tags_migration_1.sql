@@ -0,0 +1,4 @@
+ALTER TABLE
+  tags
+ADD
+  COLUMN parent_id TEXT DEFAULT NULL REFERENCES tags(id);
@@ -1,33 +1,147 @@
-use super::{Tag, UserDatabase};
+use super::{Tag, TagWithUsage, UserDatabase};
 
 impl UserDatabase {
     pub async fn upsert_tag(&self, tag: Tag) -> Result<Tag, crate::Error> {
         let conn = self.conn()?;
 
+        if let Some(parent_id) = &tag.parent_id {
+            if in_subtree(&conn, &tag.id, parent_id).await? {
+                return Err(crate::Error::InvalidInput(format!(
+                    "tag '{}' can't be a parent of its own ancestor '{}'",
+                    parent_id, tag.id
+                )));
+            }
+        }
+
         let mut rows = conn
             .query(
-                "INSERT OR REPLACE INTO tags (
+                "INSERT INTO tags (
                     id,
-                    name
-                ) VALUES (?, ?)
+                    name,
+                    parent_id
+                ) VALUES (?, ?, ?)
+                ON CONFLICT (id) DO UPDATE SET
+                    name = excluded.name,
+                    parent_id = excluded.parent_id
                 RETURNING *",
-                (tag.id, tag.name),
+                (tag.id, tag.name, tag.parent_id),
             )
             .await?;
 
-        let row = rows.next().await.unwrap().unwrap();
-        let tag: Tag = libsql::de::from_row(&row).unwrap();
+        let row = rows
+            .next()
+            .await?
+            .ok_or_else(|| crate::Error::NotFound("tag".to_string()))?;
+        let tag: Tag = libsql::de::from_row(&row)?;
         Ok(tag)
     }
 
+    pub async fn get_tag(&self, tag_id: impl Into<String>) -> Result<Option<Tag>, crate::Error> {
+        let conn = self.conn()?;
+
+        let mut rows = conn
+            .query("SELECT * FROM tags WHERE id = ?", vec![tag_id.into()])
+            .await?;
+
+        let row = rows.next().await?;
+        Ok(row.map(|row| libsql::de::from_row(&row)).transpose()?)
+    }
+
+    pub async fn rename_tag(
+        &self,
+        tag_id: impl Into<String>,
+        name: impl Into<String>,
+    ) -> Result<Option<Tag>, crate::Error> {
+        let conn = self.conn()?;
+
+        let mut rows = conn
+            .query(
+                "UPDATE tags SET name = ? WHERE id = ? RETURNING *",
+                vec![name.into(), tag_id.into()],
+            )
+            .await?;
+
+        let row = rows.next().await?;
+        Ok(row.map(|row| libsql::de::from_row(&row)).transpose()?)
+    }
+
+    // Children of the deleted tag are moved up to its parent.
     pub async fn delete_tag(&self, tag_id: impl Into<String>) -> Result<(), crate::Error> {
+        let tag_id = tag_id.into();
         let conn = self.conn()?;
 
-        conn.query("DELETE FROM tags WHERE id = ?", vec![tag_id.into()])
+        let tx = conn.transaction().await?;
+        tx.execute(
+            "UPDATE tags SET parent_id = (SELECT parent_id FROM tags WHERE id = :id) WHERE parent_id = :id",
+            libsql::named_params! { ":id": tag_id.clone() },
+        )
+        .await?;
+        tx.execute(
+            "DELETE FROM tags_sessions WHERE tag_id = ?",
+            vec![tag_id.clone()],
+        )
+        .await?;
+        tx.execute("DELETE FROM tags WHERE id = ?", vec![tag_id])
             .await?;
+        tx.commit().await?;
+
         Ok(())
     }
 
+    // Moves every session and child tag of `from_id` onto `into_id`, then deletes `from_id`.
+    pub async fn merge_tags(
+        &self,
+        from_id: impl Into<String>,
+        into_id: impl Into<String>,
+    ) -> Result<Option<Tag>, crate::Error> {
+        let (from_id, into_id) = (from_id.into(), into_id.into());
+
+        if from_id == into_id {
+            return self.get_tag(into_id).await;
+        }
+
+        let conn = self.conn()?;
+
+        let tx = conn.transaction().await?;
+        if !tag_exists(&tx, &into_id).await? {
+            return Err(crate::Error::NotFound(format!("tag {}", into_id)));
+        }
+        // Its children would be moved under itself.
+        if in_subtree(&tx, &from_id, &into_id).await? {
+            return Err(crate::Error::InvalidInput(format!(
+                "can't merge tag '{}' into its own descendant '{}'",
+                from_id, into_id
+            )));
+        }
+
+        tx.execute(
+            "INSERT OR IGNORE INTO tags_sessions (tag_id, session_id)
+            SELECT :into_id, session_id FROM tags_sessions WHERE tag_id = :from_id",
+            libsql::named_params! { ":from_id": from_id.clone(), ":into_id": into_id.clone() },
+        )
+        .await?;
+        tx.execute(
+            "DELETE FROM tags_sessions WHERE tag_id = ?",
+            vec![from_id.clone()],
+        )
+        .await?;
+        tx.execute(
+            "UPDATE tags SET parent_id = :into_id WHERE parent_id = :from_id AND id != :into_id",
+            libsql::named_params! { ":from_id": from_id.clone(), ":into_id": into_id.clone() },
+        )
+        .await?;
+        tx.execute(
+            "UPDATE tags SET parent_id = (SELECT parent_id FROM tags WHERE id = :from_id) WHERE id = :into_id AND parent_id = :from_id",
+            libsql::named_params! { ":from_id": from_id.clone(), ":into_id": into_id.clone() },
+        )
+        .await?;
+        tx.execute("DELETE FROM tags WHERE id = ?", vec![from_id])
+            .await?;
+        tx.commit().await?;
+
+        self.get_tag(into_id).await
+    }
+
     pub async fn assign_tag_to_session(
         &self,
         tag_id: impl Into<String>,
@@ -36,7 +150,7 @@ impl UserDatabase {
         let conn = self.conn()?;
 
         conn.execute(
-            "INSERT INTO tags_sessions (tag_id, session_id) VALUES (?, ?)",
+            "INSERT OR IGNORE INTO tags_sessions (tag_id, session_id) VALUES (?, ?)",
             vec![tag_id.into(), session_id.into()],
         )
         .await?;
@@ -61,11 +175,54 @@ impl UserDatabase {
     pub async fn list_all_tags(&self) -> Result<Vec<Tag>, crate::Error> {
         let conn = self.conn()?;
 
-        let mut rows = conn.query("SELECT * FROM tags", ()).await?;
+        let mut rows = conn.query("SELECT * FROM tags ORDER BY name", ()).await?;
 
         let mut items = Vec::new();
-        while let Some(row) = rows.next().await.unwrap() {
-            let item: Tag = libsql::de::from_row(&row).unwrap();
+        while let Some(row) = rows.next().await? {
+            let item: Tag = libsql::de::from_row(&row)?;
+            items.push(item);
+        }
+        Ok(items)
+    }
+
+    pub async fn list_tags_with_usage(&self) -> Result<Vec<TagWithUsage>, crate::Error> {
+        let conn = self.conn()?;
+
+        let mut rows = conn
+            .query(
+                "SELECT t.*, COUNT(ts.session_id) AS session_count FROM tags t
+                LEFT JOIN tags_sessions ts ON t.id = ts.tag_id
+                GROUP BY t.id
+                ORDER BY session_count DESC, t.name",
+                (),
+            )
+            .await?;
+
+        let mut items = Vec::new();
+        while let Some(row) = rows.next().await? {
+            let tag: Tag = libsql::de::from_row(&row)?;
+            let session_count: u32 = row.get(3)?;
+            items.push(TagWithUsage { tag, session_count });
+        }
+        Ok(items)
+    }
+
+    pub async fn list_tag_children(
+        &self,
+        parent_id: impl Into<String>,
+    ) -> Result<Vec<Tag>, crate::Error> {
+        let conn = self.conn()?;
+
+        let mut rows = conn
+            .query(
+                "SELECT * FROM tags WHERE parent_id = ? ORDER BY name",
+                vec![parent_id.into()],
+            )
+            .await?;
+
+        let mut items = Vec::new();
+        while let Some(row) = rows.next().await? {
+            let item: Tag = libsql::de::from_row(&row)?;
             items.push(item);
         }
         Ok(items)
@@ -79,23 +236,78 @@ impl UserDatabase {
 
         let mut rows = conn
             .query(
-                "SELECT * FROM tags WHERE session_id = ?",
+                "SELECT t.* FROM tags t
+                JOIN tags_sessions ts ON t.id = ts.tag_id
+                WHERE ts.session_id = ?
+                ORDER BY t.name",
                 vec![session_id.into()],
             )
             .await?;
 
         let mut items = Vec::new();
-        while let Some(row) = rows.next().await.unwrap() {
-            let item: Tag = libsql::de::from_row(&row).unwrap();
+        while let Some(row) = rows.next().await? {
+            let item: Tag = libsql::de::from_row(&row)?;
             items.push(item);
         }
         Ok(items)
     }
 }
 
+async fn tag_exists(conn: &libsql::Connection, tag_id: &str) -> Result<bool, crate::Error> {
+    let mut rows = conn
+        .query("SELECT 1 FROM tags WHERE id = ?", vec![tag_id.to_string()])
+        .await?;
+    Ok(rows.next().await?.is_some())
+}
+
+// Whether `tag_id` is `root_id` or one of its descendants.
+async fn in_subtree(
+    conn: &libsql::Connection,
+    root_id: &str,
+    tag_id: &str,
+) -> Result<bool, crate::Error> {
+    let mut rows = conn
+        .query(
+            "WITH RECURSIVE subtree(id) AS (
+                SELECT :root_id
+                UNION
+                SELECT t.id FROM tags t JOIN subtree s ON t.parent_id = s.id
+            )
+            SELECT 1 FROM subtree WHERE id = :tag_id",
+            libsql::named_params! { ":root_id": root_id, ":tag_id": tag_id },
+        )
+        .await?;
+    Ok(rows.next().await?.is_some())
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::{tests::setup_db, Human, Session, Tag};
+    use crate::{
+        tests::setup_db, GetSessionFilter, Human, ListSessionFilter, ListSessionFilterCommon,
+        ListSessionFilterSpecific, Session, Tag,
+    };
+
+    fn new_tag(name: &str, parent_id: Option<String>) -> Tag {
+        Tag {
+            id: uuid::Uuid::new_v4().to_string(),
+            name: name.to_string(),
+            parent_id,
+        }
+    }
+
+    fn new_session(user_id: &str, title: &str) -> Session {
+        Session {
+            id: uuid::Uuid::new_v4().to_string(),
+            user_id: user_id.to_string(),
+            created_at: chrono::Utc::now(),
+            visited_at: chrono::Utc::now(),
+            calendar_event_id: None,
+            title: title.to_string(),
+            raw_memo_html: "".to_string(),
+            enhanced_memo_html: None,
+            conversations: vec![],
+        }
+    }
 
     #[tokio::test]
     async fn test_tags() {
@@ -109,31 +321,205 @@ mod tests {
             .await
             .unwrap();
 
-        let _ = db
-            .upsert_session(Session {
-                id: uuid::Uuid::new_v4().to_string(),
-                user_id: user.id.clone(),
-                created_at: chrono::Utc::now(),
-                visited_at: chrono::Utc::now(),
-                calendar_event_id: None,
-                title: "Test Session".to_string(),
-                raw_memo_html: "".to_string(),
-                enhanced_memo_html: None,
-                conversations: vec![],
-            })
+        let session = db
+            .upsert_session(new_session(&user.id, "Test Session"))
             .await
             .unwrap();
 
         assert_eq!(db.list_all_tags().await.unwrap().len(), 0);
 
-        let _ = db
+        let tag = db.upsert_tag(new_tag("Test Tag", None)).await.unwrap();
+        assert_eq!(db.list_all_tags().await.unwrap().len(), 1);
+
+        db.assign_tag_to_session(&tag.id, &session.id)
+            .await
+            .unwrap();
+        db.assign_tag_to_session(&tag.id, &session.id)
+            .await
+            .unwrap();
+        assert_eq!(
+            db.list_session_tags(&session.id).await.unwrap(),
+            vec![tag.clone()]
+        );
+
+        let found = db
+            .get_session(GetSessionFilter::TagId(tag.id.clone()))
+            .await
+            .unwrap();
+        assert_eq!(found.map(|s| s.id), Some(session.id.clone()));
+
+        let renamed = db.rename_tag(&tag.id, "Renamed").await.unwrap().unwrap();
+        assert_eq!(renamed.name, "Renamed");
+
+        db.unassign_tag_from_session(&tag.id, &session.id)
+            .await
+            .unwrap();
+        assert_eq!(db.list_session_tags(&session.id).await.unwrap().len(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_tags_merge_and_hierarchy() {
+        let db = setup_db().await;
+
+        let user = db.upsert_human(Human::default()).await.unwrap();
+        let s1 = db
+            .upsert_session(new_session(&user.id, "s1"))
+            .await
+            .unwrap();
+        let s2 = db
+            .upsert_session(new_session(&user.id, "s2"))
+            .await
+            .unwrap();
+
+        let customer = db.upsert_tag(new_tag("Customer", None)).await.unwrap();
+        let acme = db
+            .upsert_tag(new_tag("Acme", Some(customer.id.clone())))
+            .await
+            .unwrap();
+        let acme_dup = db
+            .upsert_tag(new_tag("ACME Inc", Some(customer.id.clone())))
+            .await
+            .unwrap();
+        let acme_eu = db
+            .upsert_tag(new_tag("Acme EU", Some(acme_dup.id.clone())))
+            .await
+            .unwrap();
+
+        assert_eq!(db.list_tag_children(&customer.id).await.unwrap().len(), 2);
+
+        db.assign_tag_to_session(&acme.id, &s1.id).await.unwrap();
+        db.assign_tag_to_session(&acme_dup.id, &s1.id)
+            .await
+            .unwrap();
+        db.assign_tag_to_session(&acme_dup.id, &s2.id)
+            .await
+            .unwrap();
+
+        let merged = db
+            .merge_tags(&acme_dup.id, &acme.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(merged.parent_id, Some(customer.id.clone()));
+        assert_eq!(db.get_tag(&acme_dup.id).await.unwrap(), None);
+        let children = db.list_tag_children(&acme.id).await.unwrap();
+        assert_eq!(children.len(), 1);
+        assert_eq!(children[0].id, acme_eu.id);
+
+        let usage = db.list_tags_with_usage().await.unwrap();
+        assert_eq!(usage[0].tag.id, acme.id);
+        assert_eq!(usage[0].session_count, 2);
+
+        db.delete_tag(&acme.id).await.unwrap();
+        assert_eq!(
+            db.get_tag(&acme_eu.id).await.unwrap().unwrap().parent_id,
+            Some(customer.id.clone())
+        );
+        assert_eq!(db.list_session_tags(&s1.id).await.unwrap().len(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_tags_reject_cycles() {
+        let db = setup_db().await;
+
+        let a = db.upsert_tag(new_tag("a", None)).await.unwrap();
+        let b = db
+            .upsert_tag(new_tag("b", Some(a.id.clone())))
+            .await
+            .unwrap();
+        let c = db
+            .upsert_tag(new_tag("c", Some(b.id.clone())))
+            .await
+            .unwrap();
+
+        for parent_id in [&a.id, &c.id] {
+            let err = db
+                .upsert_tag(Tag {
+                    parent_id: Some(parent_id.clone()),
+                    ..a.clone()
+                })
+                .await
+                .unwrap_err();
+            assert!(matches!(err, crate::Error::InvalidInput(_)));
+        }
+
+        let err = db.merge_tags(&a.id, &c.id).await.unwrap_err();
+        assert!(matches!(err, crate::Error::InvalidInput(_)));
+        let err = db.merge_tags(&b.id, "missing").await.unwrap_err();
+        assert!(matches!(err, crate::Error::NotFound(_)));
+        assert_eq!(db.list_tag_children(&a.id).await.unwrap(), vec![b.clone()]);
+
+        // Moving under a sibling's subtree is fine.
+        let d = db.upsert_tag(new_tag("d", None)).await.unwrap();
+        let moved = db
             .upsert_tag(Tag {
-                id: uuid::Uuid::new_v4().to_string(),
-                name: "Test Tag".to_string(),
+                parent_id: Some(d.id.clone()),
+                ..c.clone()
             })
             .await
             .unwrap();
+        assert_eq!(moved.parent_id, Some(d.id));
+    }
 
-        assert_eq!(db.list_all_tags().await.unwrap().len(), 1);
+    #[tokio::test]
+    async fn test_list_sessions_by_tags() {
+        let db = setup_db().await;
+
+        let user = db.upsert_human(Human::default()).await.unwrap();
+        let s1 = db
+            .upsert_session(new_session(&user.id, "s1"))
+            .await
+            .unwrap();
+        let s2 = db
+            .upsert_session(new_session(&user.id, "s2"))
+            .await
+            .unwrap();
+        let _s3 = db
+            .upsert_session(new_session(&user.id, "s3"))
+            .await
+            .unwrap();
+
+        let a = db.upsert_tag(new_tag("a", None)).await.unwrap();
+        let b = db.upsert_tag(new_tag("b", None)).await.unwrap();
+
+        db.assign_tag_to_session(&a.id, &s1.id).await.unwrap();
+        db.assign_tag_to_session(&b.id, &s1.id).await.unwrap();
+        db.assign_tag_to_session(&a.id, &s2.id).await.unwrap();
+
+        let list = |any_of: Vec<String>, all_of: Vec<String>| {
+            let db = db.clone();
+            let user_id = user.id.clone();
+            async move {
+                let mut ids = db
+                    .list_sessions(Some(ListSessionFilter {
+                        common: ListSessionFilterCommon {
+                            user_id,
+                            limit: None,
+                            cursor: None,
+                        },
+                        specific: ListSessionFilterSpecific::Tags { any_of, all_of },
+                    }))
+                    .await
+                    .unwrap()
+                    .items
+                    .into_iter()
+                    .map(|s| s.title)
+                    .collect::<Vec<_>>();
+                ids.sort();
+                ids
+            }
+        };
+
+        assert_eq!(list(vec![a.id.clone()], vec![]).await, vec!["s1", "s2"]);
+        assert_eq!(list(vec![b.id.clone()], vec![]).await, vec!["s1"]);
+        assert_eq!(
+            list(vec![], vec![a.id.clone(), b.id.clone()]).await,
+            vec!["s1"]
+        );
+        assert_eq!(
+            list(vec![a.id.clone(), b.id.clone()], vec![a.id.clone()]).await,
+            vec!["s1", "s2"]
+        );
+        assert_eq!(list(vec![], vec![]).await, vec!["s1", "s2", "s3"]);
     }
 }
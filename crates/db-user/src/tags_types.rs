@@ -4,5 +4,14 @@ user_common_derives! {
     pub struct Tag {
         pub id: String,
         pub name: String,
+        pub parent_id: Option<String>,
+    }
+}
+
+user_common_derives! {
+    pub struct TagWithUsage {
+        #[serde(flatten)]
+        pub tag: Tag,
+        pub session_count: u32,
     }
 }
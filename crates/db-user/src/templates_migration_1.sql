@@ -0,0 +1 @@
+ALTER TABLE templates ADD COLUMN field_timestamps TEXT NOT NULL DEFAULT '{}';
@@ -1,4 +1,4 @@
-use super::{Template, UserDatabase};
+use super::{record_tombstone, Template, UserDatabase};
 
 impl UserDatabase {
     pub async fn list_templates(
@@ -15,7 +15,7 @@ impl UserDatabase {
             .await?;
 
         let mut items = Vec::new();
-        while let Some(row) = rows.next().await.unwrap() {
+        while let Some(row) = rows.next().await? {
             let item = Template::from_row(&row)?;
             items.push(item);
         }
@@ -25,6 +25,22 @@ impl UserDatabase {
     pub async fn upsert_template(&self, template: Template) -> Result<Template, crate::Error> {
         let conn = self.conn()?;
 
+        let sections = serde_json::to_string(&template.sections)?;
+        let tags = serde_json::to_string(&template.tags)?;
+        let field_timestamps = self
+            .touch_field_timestamps(
+                &conn,
+                "templates",
+                &template.id,
+                serde_json::json!({
+                    "title": template.title,
+                    "description": template.description,
+                    "sections": sections,
+                    "tags": tags,
+                }),
+            )
+            .await?;
+
         let mut rows = conn
             .query(
                 "INSERT INTO templates (
@@ -33,32 +49,39 @@ impl UserDatabase {
                     title,
                     description,
                     sections,
-                    tags
+                    tags,
+                    field_timestamps
                 ) VALUES (
                     :id,
                     :user_id,
                     :title,
                     :description,
                     :sections,
-                    :tags
+                    :tags,
+                    :field_timestamps
                 ) ON CONFLICT(id) DO UPDATE SET
                     title = :title,
                     description = :description,
                     sections = :sections,
-                    tags = :tags
+                    tags = :tags,
+                    field_timestamps = :field_timestamps
                 RETURNING *",
                 libsql::named_params! {
                     ":id": template.id,
                     ":user_id": template.user_id,
                     ":title": template.title,
                     ":description": template.description,
-                    ":sections": serde_json::to_string(&template.sections).unwrap(),
-                    ":tags": serde_json::to_string(&template.tags).unwrap(),
+                    ":sections": sections,
+                    ":tags": tags,
+                    ":field_timestamps": field_timestamps,
                 },
             )
             .await?;
 
-        let row = rows.next().await?.unwrap();
+        let row = rows
+            .next()
+            .await?
+            .ok_or_else(|| crate::Error::NotFound("template".to_string()))?;
         let template = Template::from_row(&row)?;
         Ok(template)
     }
@@ -66,8 +89,14 @@ impl UserDatabase {
     pub async fn delete_template(&self, id: String) -> Result<(), crate::Error> {
         let conn = self.conn()?;
 
-        conn.query("DELETE FROM templates WHERE id = ?", vec![id])
+        let tx = conn.transaction().await?;
+        let deleted = tx
+            .execute("DELETE FROM templates WHERE id = ?", vec![id.clone()])
             .await?;
+        if deleted > 0 {
+            record_tombstone(&tx, "templates", &id, chrono::Utc::now()).await?;
+        }
+        tx.commit().await?;
         Ok(())
     }
 }
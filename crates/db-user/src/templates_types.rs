@@ -1,3 +1,5 @@
+use hypr_db_core::RowExt;
+
 use crate::user_common_derives;
 
 user_common_derives! {
@@ -19,20 +21,14 @@ user_common_derives! {
 }
 
 impl Template {
-    pub fn from_row(row: &libsql::Row) -> Result<Self, serde::de::value::Error> {
+    pub fn from_row(row: &libsql::Row) -> Result<Self, crate::Error> {
         Ok(Self {
-            id: row.get(0).expect("id"),
-            user_id: row.get(1).expect("user_id"),
-            title: row.get(2).expect("title"),
-            description: row.get(3).expect("description"),
-            sections: row
-                .get_str(4)
-                .map(|s| serde_json::from_str(s).unwrap())
-                .unwrap_or_default(),
-            tags: row
-                .get_str(5)
-                .map(|s| serde_json::from_str(s).unwrap())
-                .unwrap_or_default(),
+            id: row.get_string(0, "id")?,
+            user_id: row.get_string(1, "user_id")?,
+            title: row.get_string(2, "title")?,
+            description: row.get_string(3, "description")?,
+            sections: row.get_json(4, "sections")?,
+            tags: row.get_json(5, "tags")?,
         })
     }
 }
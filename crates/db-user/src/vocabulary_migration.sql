@@ -0,0 +1,9 @@
+CREATE TABLE IF NOT EXISTS vocabulary (
+  id TEXT PRIMARY KEY,
+  user_id TEXT NOT NULL,
+  session_id TEXT,
+  term TEXT NOT NULL,
+  boost REAL,
+  pronunciations TEXT NOT NULL DEFAULT '[]',
+  FOREIGN KEY (session_id) REFERENCES sessions(id) ON DELETE CASCADE
+);
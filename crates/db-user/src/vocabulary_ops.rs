@@ -0,0 +1,212 @@
+use hypr_db_core::SqlTable;
+use hypr_listener_interface::VocabularyTerm;
+
+use super::{UserDatabase, VocabularyEntry};
+
+impl UserDatabase {
+    pub async fn upsert_vocabulary_entry(
+        &self,
+        entry: VocabularyEntry,
+    ) -> Result<VocabularyEntry, crate::Error> {
+        let conn = self.conn()?;
+
+        conn.execute(
+            &format!(
+                "INSERT INTO {} (id, user_id, session_id, term, boost, pronunciations) VALUES (?, ?, ?, ?, ?, ?)
+                ON CONFLICT (id) DO UPDATE SET
+                    term = excluded.term,
+                    boost = excluded.boost,
+                    pronunciations = excluded.pronunciations",
+                VocabularyEntry::sql_table()
+            ),
+            libsql::params![
+                entry.id.clone(),
+                entry.user_id.clone(),
+                entry.session_id.clone(),
+                entry.term.clone(),
+                entry.boost.map(|b| b as f64),
+                serde_json::to_string(&entry.pronunciations)?,
+            ],
+        )
+        .await?;
+
+        Ok(entry)
+    }
+
+    // Entries of the user, plus those of `session_id` when given.
+    pub async fn list_vocabulary(
+        &self,
+        user_id: impl Into<String>,
+        session_id: Option<String>,
+    ) -> Result<Vec<VocabularyEntry>, crate::Error> {
+        let conn = self.conn()?;
+
+        let sql = format!(
+            "SELECT * FROM {} WHERE user_id = ? AND (session_id IS NULL OR session_id = ?) ORDER BY term",
+            VocabularyEntry::sql_table()
+        );
+        let mut rows = conn
+            .query(&sql, libsql::params![user_id.into(), session_id])
+            .await?;
+
+        let mut items = Vec::new();
+        while let Some(row) = rows.next().await? {
+            items.push(VocabularyEntry::from_row(&row)?);
+        }
+        Ok(items)
+    }
+
+    pub async fn delete_vocabulary_entry(&self, id: impl Into<String>) -> Result<(), crate::Error> {
+        let conn = self.conn()?;
+
+        conn.execute(
+            &format!("DELETE FROM {} WHERE id = ?", VocabularyEntry::sql_table()),
+            vec![id.into()],
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    // Everything a listen session should know about: the configured jargons, the user's entries,
+    // and the session's own. Later ones win when the same term appears twice.
+    pub async fn vocabulary_terms(
+        &self,
+        user_id: impl Into<String>,
+        session_id: Option<String>,
+    ) -> Result<Vec<VocabularyTerm>, crate::Error> {
+        let user_id = user_id.into();
+
+        let jargons = self
+            .get_config(&user_id)
+            .await?
+            .map_or_else(Vec::new, |c| c.general.jargons);
+
+        let mut entries = self.list_vocabulary(&user_id, session_id).await?;
+        // Session entries sort last so that they override user-wide ones.
+        entries.sort_by_key(|e| e.session_id.is_some());
+
+        let mut terms: Vec<VocabularyTerm> = Vec::new();
+        for term in jargons
+            .into_iter()
+            .map(|term| VocabularyTerm {
+                term,
+                boost: None,
+                pronunciations: vec![],
+            })
+            .chain(entries.into_iter().map(VocabularyTerm::from))
+        {
+            if term.term.trim().is_empty() {
+                continue;
+            }
+
+            match terms
+                .iter_mut()
+                .find(|t| t.term.trim().eq_ignore_ascii_case(term.term.trim()))
+            {
+                Some(existing) => *existing = term,
+                None => terms.push(term),
+            }
+        }
+
+        Ok(terms)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        tests::setup_db, Config, ConfigAI, ConfigGeneral, ConfigNotification, Human, Session,
+        VocabularyEntry,
+    };
+
+    fn new_entry(user_id: &str, session_id: Option<&str>, term: &str) -> VocabularyEntry {
+        VocabularyEntry {
+            id: uuid::Uuid::new_v4().to_string(),
+            user_id: user_id.to_string(),
+            session_id: session_id.map(String::from),
+            term: term.to_string(),
+            boost: None,
+            pronunciations: vec![],
+        }
+    }
+
+    #[tokio::test]
+    async fn test_vocabulary() {
+        let db = setup_db().await;
+
+        let user = db.upsert_human(Human::default()).await.unwrap();
+        let new_session = || Session {
+            id: uuid::Uuid::new_v4().to_string(),
+            user_id: user.id.clone(),
+            created_at: chrono::Utc::now(),
+            visited_at: chrono::Utc::now(),
+            calendar_event_id: None,
+            title: "".to_string(),
+            raw_memo_html: "".to_string(),
+            enhanced_memo_html: None,
+            conversations: vec![],
+        };
+        let session = db.upsert_session(new_session()).await.unwrap();
+        let other = db.upsert_session(new_session()).await.unwrap();
+
+        db.set_config(Config {
+            id: uuid::Uuid::new_v4().to_string(),
+            user_id: user.id.clone(),
+            general: ConfigGeneral {
+                jargons: vec!["Hyprnote".to_string(), "kubernetes".to_string()],
+                ..ConfigGeneral::default()
+            },
+            notification: ConfigNotification::default(),
+            ai: ConfigAI::default(),
+        })
+        .await
+        .unwrap();
+
+        let kubernetes = db
+            .upsert_vocabulary_entry(VocabularyEntry {
+                boost: Some(0.5),
+                pronunciations: vec!["cooper netties".to_string()],
+                ..new_entry(&user.id, None, "Kubernetes")
+            })
+            .await
+            .unwrap();
+        db.upsert_vocabulary_entry(VocabularyEntry {
+            boost: Some(1.0),
+            ..new_entry(&user.id, Some(&session.id), "Kubernetes")
+        })
+        .await
+        .unwrap();
+        db.upsert_vocabulary_entry(new_entry(&user.id, Some(&other.id), "Acme"))
+            .await
+            .unwrap();
+
+        assert_eq!(db.list_vocabulary(&user.id, None).await.unwrap().len(), 1);
+        assert_eq!(
+            db.list_vocabulary(&user.id, Some(session.id.clone()))
+                .await
+                .unwrap()
+                .len(),
+            2
+        );
+
+        let terms = db.vocabulary_terms(&user.id, None).await.unwrap();
+        assert_eq!(
+            terms
+                .iter()
+                .map(|t| (t.term.as_str(), t.boost))
+                .collect::<Vec<_>>(),
+            vec![("Hyprnote", None), ("Kubernetes", Some(0.5))]
+        );
+        assert_eq!(terms[1].pronunciations, vec!["cooper netties"]);
+
+        let terms = db
+            .vocabulary_terms(&user.id, Some(session.id.clone()))
+            .await
+            .unwrap();
+        assert_eq!(terms[1].boost, Some(1.0));
+
+        db.delete_vocabulary_entry(&kubernetes.id).await.unwrap();
+        assert_eq!(db.list_vocabulary(&user.id, None).await.unwrap().len(), 0);
+    }
+}
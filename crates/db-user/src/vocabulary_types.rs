@@ -0,0 +1,44 @@
+use hypr_db_core::RowExt;
+
+use crate::user_common_derives;
+
+user_common_derives! {
+    #[sql_table("vocabulary")]
+    pub struct VocabularyEntry {
+        pub id: String,
+        pub user_id: String,
+        // Entries without a session apply to every session of the user.
+        pub session_id: Option<String>,
+        pub term: String,
+        // From 0 to 1.
+        pub boost: Option<f32>,
+        // How the term sounds, e.g. "cooper netties" for "Kubernetes".
+        pub pronunciations: Vec<String>,
+    }
+}
+
+impl VocabularyEntry {
+    pub fn from_row(row: &libsql::Row) -> Result<Self, crate::Error> {
+        Ok(Self {
+            id: row.get_string(0, "id")?,
+            user_id: row.get_string(1, "user_id")?,
+            session_id: row.get_opt_string(2, "session_id")?,
+            term: row.get_string(3, "term")?,
+            boost: row
+                .get::<Option<f64>>(4)
+                .map_err(|e| crate::Error::decode("boost", e))?
+                .map(|b| b as f32),
+            pronunciations: row.get_json(5, "pronunciations")?,
+        })
+    }
+}
+
+impl From<VocabularyEntry> for hypr_listener_interface::VocabularyTerm {
+    fn from(entry: VocabularyEntry) -> Self {
+        Self {
+            term: entry.term,
+            boost: entry.boost,
+            pronunciations: entry.pronunciations,
+        }
+    }
+}
@@ -0,0 +1,9 @@
+CREATE TABLE IF NOT EXISTS voice_enrollments (
+  id TEXT PRIMARY KEY,
+  human_id TEXT NOT NULL,
+  embedding BLOB NOT NULL,
+  encrypted INTEGER NOT NULL DEFAULT 0,
+  model TEXT NOT NULL,
+  created_at TEXT NOT NULL,
+  FOREIGN KEY (human_id) REFERENCES humans(id) ON DELETE CASCADE
+);
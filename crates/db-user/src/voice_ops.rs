@@ -0,0 +1,337 @@
+use hypr_db_core::SqlTable;
+
+use super::{
+    SessionSpeaker, UserDatabase, VoiceEnrollment, VoiceSuggestion, VoiceSuggestionStatus,
+};
+
+impl UserDatabase {
+    pub async fn add_voice_enrollment(
+        &self,
+        enrollment: VoiceEnrollment,
+    ) -> Result<VoiceEnrollment, crate::Error> {
+        let conn = self.conn()?;
+
+        conn.execute(
+            &format!(
+                "INSERT INTO {} (id, human_id, embedding, encrypted, model, created_at) VALUES (?, ?, ?, ?, ?, ?)",
+                VoiceEnrollment::sql_table()
+            ),
+            libsql::params![
+                enrollment.id.clone(),
+                enrollment.human_id.clone(),
+                enrollment.embedding.clone(),
+                enrollment.encrypted,
+                enrollment.model.clone(),
+                enrollment.created_at.to_rfc3339(),
+            ],
+        )
+        .await?;
+
+        Ok(enrollment)
+    }
+
+    pub async fn list_voice_enrollments(
+        &self,
+        model: impl Into<String>,
+    ) -> Result<Vec<VoiceEnrollment>, crate::Error> {
+        let conn = self.conn()?;
+
+        let sql = format!(
+            "SELECT * FROM {} WHERE model = ? ORDER BY created_at",
+            VoiceEnrollment::sql_table()
+        );
+        let mut rows = conn.query(&sql, vec![model.into()]).await?;
+
+        let mut items = Vec::new();
+        while let Some(row) = rows.next().await? {
+            items.push(VoiceEnrollment::from_row(&row)?);
+        }
+        Ok(items)
+    }
+
+    pub async fn delete_voice_enrollments(
+        &self,
+        human_id: impl Into<String>,
+    ) -> Result<(), crate::Error> {
+        let conn = self.conn()?;
+
+        conn.execute(
+            &format!(
+                "DELETE FROM {} WHERE human_id = ?",
+                VoiceEnrollment::sql_table()
+            ),
+            vec![human_id.into()],
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    // Replaces the pending suggestions of a session. Pairs the user already rejected are not suggested again.
+    pub async fn replace_voice_suggestions(
+        &self,
+        session_id: impl Into<String>,
+        suggestions: Vec<VoiceSuggestion>,
+    ) -> Result<Vec<VoiceSuggestion>, crate::Error> {
+        let session_id = session_id.into();
+
+        let rejected = self
+            .list_voice_suggestions(&session_id)
+            .await?
+            .into_iter()
+            .filter(|s| s.status == VoiceSuggestionStatus::Rejected)
+            .map(|s| (s.speaker, s.human_id))
+            .collect::<std::collections::HashSet<_>>();
+
+        let conn = self.conn()?;
+        let tx = conn.transaction().await?;
+
+        tx.execute(
+            &format!(
+                "DELETE FROM {} WHERE session_id = ? AND status = 'pending'",
+                VoiceSuggestion::sql_table()
+            ),
+            vec![session_id.clone()],
+        )
+        .await?;
+
+        let mut inserted = Vec::new();
+        for suggestion in suggestions {
+            if rejected.contains(&(suggestion.speaker, suggestion.human_id.clone())) {
+                continue;
+            }
+
+            tx.execute(
+                &format!(
+                    "INSERT INTO {} (id, session_id, speaker, human_id, score, status, created_at) VALUES (?, ?, ?, ?, ?, 'pending', ?)",
+                    VoiceSuggestion::sql_table()
+                ),
+                libsql::params![
+                    suggestion.id.clone(),
+                    session_id.clone(),
+                    suggestion.speaker,
+                    suggestion.human_id.clone(),
+                    suggestion.score as f64,
+                    suggestion.created_at.to_rfc3339(),
+                ],
+            )
+            .await?;
+
+            inserted.push(VoiceSuggestion {
+                session_id: session_id.clone(),
+                status: VoiceSuggestionStatus::Pending,
+                ..suggestion
+            });
+        }
+
+        tx.commit().await?;
+        Ok(inserted)
+    }
+
+    pub async fn list_voice_suggestions(
+        &self,
+        session_id: impl Into<String>,
+    ) -> Result<Vec<VoiceSuggestion>, crate::Error> {
+        let conn = self.conn()?;
+
+        let sql = format!(
+            "SELECT * FROM {} WHERE session_id = ? ORDER BY speaker, score DESC",
+            VoiceSuggestion::sql_table()
+        );
+        let mut rows = conn.query(&sql, vec![session_id.into()]).await?;
+
+        let mut items = Vec::new();
+        while let Some(row) = rows.next().await? {
+            let item: VoiceSuggestion = libsql::de::from_row(&row)?;
+            items.push(item);
+        }
+        Ok(items)
+    }
+
+    async fn set_voice_suggestion_status(
+        &self,
+        id: impl Into<String>,
+        status: VoiceSuggestionStatus,
+    ) -> Result<Option<VoiceSuggestion>, crate::Error> {
+        let conn = self.conn()?;
+
+        let mut rows = conn
+            .query(
+                &format!(
+                    "UPDATE {} SET status = ? WHERE id = ? RETURNING *",
+                    VoiceSuggestion::sql_table()
+                ),
+                vec![
+                    serde_json::to_value(&status)?
+                        .as_str()
+                        .unwrap_or_default()
+                        .to_string(),
+                    id.into(),
+                ],
+            )
+            .await?;
+
+        let row = rows.next().await?;
+        Ok(row.map(|row| libsql::de::from_row(&row)).transpose()?)
+    }
+
+    // Accepts a suggestion by assigning its participant to the speaker.
+    pub async fn confirm_voice_suggestion(
+        &self,
+        id: impl Into<String>,
+    ) -> Result<SessionSpeaker, crate::Error> {
+        let id = id.into();
+
+        let suggestion = self
+            .set_voice_suggestion_status(&id, VoiceSuggestionStatus::Confirmed)
+            .await?
+            .ok_or_else(|| crate::Error::NotFound(format!("voice suggestion {}", id)))?;
+
+        self.session_assign_speaker(
+            suggestion.session_id,
+            suggestion.speaker,
+            suggestion.human_id,
+        )
+        .await
+    }
+
+    pub async fn reject_voice_suggestion(&self, id: impl Into<String>) -> Result<(), crate::Error> {
+        let id = id.into();
+
+        self.set_voice_suggestion_status(&id, VoiceSuggestionStatus::Rejected)
+            .await?
+            .ok_or_else(|| crate::Error::NotFound(format!("voice suggestion {}", id)))?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        tests::setup_db, ConversationChunk, Human, Session, VoiceEnrollment, VoiceSuggestion,
+        VoiceSuggestionStatus,
+    };
+    use hypr_listener_interface::DiarizationChunk;
+
+    fn suggestion(speaker: i32, human_id: &str) -> VoiceSuggestion {
+        VoiceSuggestion {
+            id: uuid::Uuid::new_v4().to_string(),
+            session_id: String::new(),
+            speaker,
+            human_id: human_id.to_string(),
+            score: 0.8,
+            status: VoiceSuggestionStatus::Pending,
+            created_at: chrono::Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_voice() {
+        let db = setup_db().await;
+
+        let user = db.upsert_human(Human::default()).await.unwrap();
+        let john = db
+            .upsert_human(Human {
+                full_name: Some("John".to_string()),
+                ..Human::default()
+            })
+            .await
+            .unwrap();
+
+        db.add_voice_enrollment(VoiceEnrollment {
+            id: uuid::Uuid::new_v4().to_string(),
+            human_id: john.id.clone(),
+            embedding: vec![1, 2, 3, 4],
+            encrypted: true,
+            model: "test".to_string(),
+            created_at: chrono::Utc::now(),
+        })
+        .await
+        .unwrap();
+
+        let enrollments = db.list_voice_enrollments("test").await.unwrap();
+        assert_eq!(enrollments.len(), 1);
+        assert_eq!(enrollments[0].embedding, vec![1, 2, 3, 4]);
+        assert!(enrollments[0].encrypted);
+        assert!(db.list_voice_enrollments("other").await.unwrap().is_empty());
+
+        let session = db
+            .upsert_session(Session {
+                id: uuid::Uuid::new_v4().to_string(),
+                created_at: chrono::Utc::now(),
+                visited_at: chrono::Utc::now(),
+                user_id: user.id.clone(),
+                calendar_event_id: None,
+                title: "test".to_string(),
+                raw_memo_html: "".to_string(),
+                enhanced_memo_html: None,
+                conversations: vec![ConversationChunk {
+                    start: chrono::Utc::now(),
+                    end: chrono::Utc::now(),
+                    transcripts: vec![],
+                    diarizations: vec![
+                        DiarizationChunk {
+                            start: 0,
+                            end: 2000,
+                            speaker: 0,
+                            confidence: None,
+                        },
+                        DiarizationChunk {
+                            start: 2000,
+                            end: 4000,
+                            speaker: 1,
+                            confidence: None,
+                        },
+                    ],
+                    translations: vec![],
+                }],
+            })
+            .await
+            .unwrap();
+
+        let suggestions = db
+            .replace_voice_suggestions(
+                &session.id,
+                vec![suggestion(0, &john.id), suggestion(1, &john.id)],
+            )
+            .await
+            .unwrap();
+        assert_eq!(suggestions.len(), 2);
+
+        db.reject_voice_suggestion(&suggestions[0].id)
+            .await
+            .unwrap();
+        let speaker = db
+            .confirm_voice_suggestion(&suggestions[1].id)
+            .await
+            .unwrap();
+        assert_eq!(speaker.speaker, 1);
+        assert_eq!(speaker.human_id, Some(john.id.clone()));
+
+        // A rejected pair is not suggested again.
+        let suggestions = db
+            .replace_voice_suggestions(&session.id, vec![suggestion(0, &john.id)])
+            .await
+            .unwrap();
+        assert!(suggestions.is_empty());
+
+        let statuses = db
+            .list_voice_suggestions(&session.id)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|s| (s.speaker, s.status))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            statuses,
+            vec![
+                (0, VoiceSuggestionStatus::Rejected),
+                (1, VoiceSuggestionStatus::Confirmed)
+            ]
+        );
+
+        db.delete_voice_enrollments(&john.id).await.unwrap();
+        assert!(db.list_voice_enrollments("test").await.unwrap().is_empty());
+    }
+}
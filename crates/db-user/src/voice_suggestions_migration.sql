@@ -0,0 +1,11 @@
+CREATE TABLE IF NOT EXISTS voice_suggestions (
+  id TEXT PRIMARY KEY,
+  session_id TEXT NOT NULL,
+  speaker INTEGER NOT NULL,
+  human_id TEXT NOT NULL,
+  score REAL NOT NULL,
+  status TEXT NOT NULL DEFAULT 'pending',
+  created_at TEXT NOT NULL,
+  FOREIGN KEY (session_id) REFERENCES sessions(id) ON DELETE CASCADE,
+  FOREIGN KEY (human_id) REFERENCES humans(id) ON DELETE CASCADE
+);
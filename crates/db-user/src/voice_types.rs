@@ -0,0 +1,60 @@
+use chrono::{DateTime, Utc};
+use hypr_db_core::RowExt;
+
+use crate::user_common_derives;
+
+user_common_derives! {
+    #[sql_table("voice_enrollments")]
+    pub struct VoiceEnrollment {
+        pub id: String,
+        pub human_id: String,
+        // Little-endian `f32`s, or their ciphertext when `encrypted` is set.
+        pub embedding: Vec<u8>,
+        pub encrypted: bool,
+        // Embeddings from different models are not comparable.
+        pub model: String,
+        pub created_at: DateTime<Utc>,
+    }
+}
+
+user_common_derives! {
+    #[sql_table("voice_suggestions")]
+    pub struct VoiceSuggestion {
+        pub id: String,
+        pub session_id: String,
+        pub speaker: i32,
+        pub human_id: String,
+        pub score: f32,
+        pub status: VoiceSuggestionStatus,
+        pub created_at: DateTime<Utc>,
+    }
+}
+
+user_common_derives! {
+    pub enum VoiceSuggestionStatus {
+        #[serde(rename = "pending")]
+        Pending,
+        #[serde(rename = "confirmed")]
+        Confirmed,
+        #[serde(rename = "rejected")]
+        Rejected,
+    }
+}
+
+impl VoiceEnrollment {
+    pub fn from_row(row: &libsql::Row) -> Result<Self, crate::Error> {
+        Ok(Self {
+            id: row.get_string(0, "id")?,
+            human_id: row.get_string(1, "human_id")?,
+            embedding: row
+                .get::<Vec<u8>>(2)
+                .map_err(|e| crate::Error::decode("embedding", e))?,
+            encrypted: row
+                .get::<i64>(3)
+                .map_err(|e| crate::Error::decode("encrypted", e))?
+                != 0,
+            model: row.get_string(4, "model")?,
+            created_at: row.get_datetime(5, "created_at")?,
+        })
+    }
+}
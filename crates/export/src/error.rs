@@ -0,0 +1,20 @@
+use serde::{ser::Serializer, Serialize};
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error(transparent)]
+    BufferError(#[from] hypr_buffer::Error),
+    #[error(transparent)]
+    DatabaseError(#[from] hypr_db_user::Error),
+    #[error(transparent)]
+    IoError(#[from] std::io::Error),
+}
+
+impl Serialize for Error {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(self.to_string().as_ref())
+    }
+}
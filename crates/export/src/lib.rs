@@ -0,0 +1,432 @@
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use hypr_db_user::{Event, GetSessionFilter, Human, Session, Tag, UserDatabase};
+use hypr_timeline::{speaker_label, SpeakerNames, TimelineView, TranscriptFormat};
+
+mod error;
+pub use error::*;
+
+const ID_KEY: &str = "hyprnote_id";
+
+pub struct SessionExport {
+    pub session: Session,
+    pub event: Option<Event>,
+    pub participants: Vec<Human>,
+    pub tags: Vec<Tag>,
+    pub timeline: Option<TimelineView>,
+    // Names of the participants assigned to speaker indices. Unassigned indices render as `Speaker N`.
+    pub speakers: SpeakerNames,
+}
+
+impl SessionExport {
+    pub async fn load(
+        db: &UserDatabase,
+        session_id: impl Into<String>,
+    ) -> Result<Option<Self>, crate::Error> {
+        let session_id = session_id.into();
+
+        let Some(session) = db
+            .get_session(GetSessionFilter::Id(session_id.clone()))
+            .await?
+        else {
+            return Ok(None);
+        };
+
+        let event = match &session.calendar_event_id {
+            Some(id) => db.get_event(id).await?,
+            None => None,
+        };
+
+        Ok(Some(Self {
+            participants: db.session_list_participants(&session_id).await?,
+            tags: db.list_session_tags(&session_id).await?,
+            timeline: db.get_timeline_view(&session_id).await?,
+            speakers: db.session_speaker_names(&session_id).await?,
+            event,
+            session,
+        }))
+    }
+
+    pub fn date(&self) -> chrono::DateTime<chrono::Utc> {
+        self.event
+            .as_ref()
+            .map(|e| e.start_date)
+            .unwrap_or(self.session.created_at)
+    }
+
+    pub fn title(&self) -> &str {
+        match self.session.title.trim() {
+            "" => "Untitled",
+            title => title,
+        }
+    }
+
+    // `2025-01-31 Weekly sync.md`. Characters that are not allowed in file names or Obsidian links are dropped.
+    pub fn file_name(&self) -> String {
+        let title = self
+            .title()
+            .chars()
+            .filter(|c| !c.is_control() && !r#"/\:*?"<>|#^[]"#.contains(*c))
+            .take(100)
+            .collect::<String>();
+
+        format!("{} {}.md", self.date().format("%Y-%m-%d"), title.trim())
+    }
+
+    pub fn to_markdown(&self) -> Result<String, crate::Error> {
+        let mut out = String::new();
+
+        out.push_str("---\n");
+        push_yaml(&mut out, ID_KEY, &self.session.id);
+        push_yaml(&mut out, "title", self.title());
+        out.push_str(&format!(
+            "date: {}\n",
+            self.date()
+                .to_rfc3339_opts(chrono::SecondsFormat::Secs, true)
+        ));
+        if let Some(event) = &self.event {
+            push_yaml(&mut out, "event", &event.name);
+        }
+        push_yaml_list(
+            &mut out,
+            "participants",
+            self.participants.iter().map(participant_name),
+        );
+        push_yaml_list(
+            &mut out,
+            "tags",
+            self.tags
+                .iter()
+                .map(|t| t.name.split_whitespace().collect::<Vec<_>>().join("-")),
+        );
+        out.push_str("---\n\n");
+
+        out.push_str(&format!("# {}\n", self.title()));
+
+        if let Some(enhanced) = &self.session.enhanced_memo_html {
+            push_section(&mut out, "Summary", &hypr_buffer::html_to_md(enhanced)?);
+        }
+        push_section(
+            &mut out,
+            "Notes",
+            &hypr_buffer::html_to_md(&self.session.raw_memo_html)?,
+        );
+        if let Some(timeline) = &self.timeline {
+            push_section(
+                &mut out,
+                "Transcript",
+                &transcript_to_markdown(timeline, &self.speakers),
+            );
+        }
+
+        Ok(out)
+    }
+
+    pub fn transcript(&self, format: TranscriptFormat) -> Option<String> {
+        self.timeline
+            .as_ref()
+            .map(|timeline| timeline.render(format, &self.speakers))
+    }
+}
+
+fn participant_name(human: &Human) -> String {
+    human
+        .full_name
+        .clone()
+        .or(human.email.clone())
+        .unwrap_or_else(|| "Unknown".to_string())
+}
+
+fn push_yaml(out: &mut String, key: &str, value: &str) {
+    // JSON strings are valid double-quoted YAML scalars.
+    out.push_str(&format!("{}: {}\n", key, serde_json::Value::from(value)));
+}
+
+fn push_yaml_list(out: &mut String, key: &str, values: impl Iterator<Item = String>) {
+    out.push_str(&format!("{}:\n", key));
+    for value in values {
+        out.push_str(&format!("  - {}\n", serde_json::Value::from(value)));
+    }
+}
+
+fn push_section(out: &mut String, heading: &str, body: &str) {
+    let body = body.trim();
+    if !body.is_empty() {
+        out.push_str(&format!("\n## {}\n\n{}\n", heading, body));
+    }
+}
+
+fn format_timestamp(ms: u64) -> String {
+    let secs = ms / 1000;
+    let (h, m, s) = (secs / 3600, (secs % 3600) / 60, secs % 60);
+
+    if h > 0 {
+        format!("{:02}:{:02}:{:02}", h, m, s)
+    } else {
+        format!("{:02}:{:02}", m, s)
+    }
+}
+
+pub fn transcript_to_markdown(timeline: &TimelineView, speakers: &SpeakerNames) -> String {
+    timeline
+        .items
+        .iter()
+        .filter(|item| !item.text.trim().is_empty())
+        .map(|item| {
+            format!(
+                "**{}** [{}]\n{}\n",
+                speaker_label(speakers, item.speaker),
+                format_timestamp(item.start),
+                item.text.trim()
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[derive(Debug, Default)]
+pub struct ExportReport {
+    pub written: Vec<PathBuf>,
+    pub unchanged: Vec<PathBuf>,
+}
+
+// Finds previously exported files by the id stored in their front matter.
+fn index_exported_files(dir: &Path) -> Result<HashMap<String, PathBuf>, crate::Error> {
+    let mut index = HashMap::new();
+
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().is_none_or(|ext| ext != "md") {
+            continue;
+        }
+
+        let Ok(content) = std::fs::read_to_string(&path) else {
+            continue;
+        };
+
+        let id = content
+            .strip_prefix("---\n")
+            .and_then(|rest| rest.split("\n---").next())
+            .and_then(|front_matter| {
+                front_matter.lines().find_map(|line| {
+                    line.strip_prefix(ID_KEY)?
+                        .strip_prefix(':')
+                        .and_then(|v| serde_json::from_str::<String>(v.trim()).ok())
+                })
+            });
+
+        if let Some(id) = id {
+            index.insert(id, path);
+        }
+    }
+
+    Ok(index)
+}
+
+// Writes one Markdown file per session into `dir`.
+// Re-exporting updates files in place (renaming them if the title or date changed) and leaves unchanged files untouched.
+pub async fn export_sessions(
+    db: &UserDatabase,
+    dir: impl AsRef<Path>,
+    session_ids: impl IntoIterator<Item = String>,
+) -> Result<ExportReport, crate::Error> {
+    let dir = dir.as_ref().to_path_buf();
+
+    let mut exports = Vec::new();
+    for session_id in session_ids {
+        if let Some(export) = SessionExport::load(db, &session_id).await? {
+            exports.push(export);
+        }
+    }
+
+    // Everything below touches the filesystem with blocking calls, so it runs off the async runtime.
+    tokio::task::spawn_blocking(move || write_exports(&dir, exports))
+        .await
+        .map_err(std::io::Error::from)?
+}
+
+fn write_exports(dir: &Path, exports: Vec<SessionExport>) -> Result<ExportReport, crate::Error> {
+    std::fs::create_dir_all(dir)?;
+
+    let existing = index_exported_files(dir)?;
+    let mut claimed = existing
+        .values()
+        .map(|p| p.to_path_buf())
+        .collect::<HashSet<_>>();
+
+    let mut report = ExportReport::default();
+
+    for export in exports {
+        let session_id = &export.session.id;
+        let previous = existing.get(session_id);
+
+        // Taken by another exported session, or by a file of the user's that isn't an export.
+        let taken =
+            |path: &PathBuf| Some(path) != previous && (claimed.contains(path) || path.exists());
+
+        let base = dir.join(export.file_name());
+        let stem = base.file_stem().unwrap_or_default().to_string_lossy();
+        let short_id = session_id.chars().take(8).collect::<String>();
+
+        let mut path = base.clone();
+        let mut n = 1;
+        while taken(&path) {
+            let suffix = match n {
+                1 => short_id.clone(),
+                n => format!("{} {}", short_id, n),
+            };
+            path = base.with_file_name(format!("{} ({}).md", stem, suffix));
+            n += 1;
+        }
+
+        if let Some(previous) = previous {
+            if previous != &path {
+                std::fs::rename(previous, &path)?;
+                claimed.remove(previous);
+            }
+        }
+        claimed.insert(path.clone());
+
+        let content = export.to_markdown()?;
+        if std::fs::read_to_string(&path).is_ok_and(|c| c == content) {
+            report.unchanged.push(path);
+        } else {
+            std::fs::write(&path, content)?;
+            report.written.push(path);
+        }
+    }
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn setup_db() -> UserDatabase {
+        let base_db = hypr_db_core::DatabaseBuilder::default()
+            .memory()
+            .build()
+            .await
+            .unwrap();
+        let db = UserDatabase::from(base_db);
+        hypr_db_user::migrate(&db).await.unwrap();
+        db
+    }
+
+    async fn create_session(db: &UserDatabase, title: &str) -> Session {
+        let user = db.upsert_human(Human::default()).await.unwrap();
+
+        db.upsert_session(Session {
+            id: uuid::Uuid::new_v4().to_string(),
+            created_at: chrono::DateTime::parse_from_rfc3339("2025-01-31T10:00:00Z")
+                .unwrap()
+                .into(),
+            visited_at: chrono::Utc::now(),
+            user_id: user.id,
+            calendar_event_id: None,
+            title: title.to_string(),
+            raw_memo_html: "<p>hello</p>".to_string(),
+            enhanced_memo_html: None,
+            conversations: vec![],
+        })
+        .await
+        .unwrap()
+    }
+
+    #[test]
+    fn test_transcript_to_markdown() {
+        let timeline = TimelineView {
+            items: vec![
+                hypr_timeline::TimelineViewItem {
+                    start: 1500,
+                    end: 3000,
+                    speaker: 0,
+                    text: "Hello there.".to_string(),
+                    confidence: 1.0,
+                    interim: false,
+                    translation: None,
+                },
+                hypr_timeline::TimelineViewItem {
+                    start: 3_725_000,
+                    end: 3_726_000,
+                    speaker: -1,
+                    text: "Bye.".to_string(),
+                    confidence: 1.0,
+                    interim: false,
+                    translation: None,
+                },
+            ],
+        };
+
+        assert_eq!(
+            transcript_to_markdown(&timeline, &SpeakerNames::default()),
+            "**Speaker 1** [00:01]\nHello there.\n\n**Unknown** [01:02:05]\nBye.\n"
+        );
+
+        let speakers = SpeakerNames::from([(0, "John Doe".to_string())]);
+        assert!(transcript_to_markdown(&timeline, &speakers).starts_with("**John Doe** [00:01]\n"));
+    }
+
+    #[tokio::test]
+    async fn test_export_sessions() {
+        let db = setup_db().await;
+        let dir = tempfile::tempdir().unwrap();
+
+        let a = create_session(&db, "Weekly: sync").await;
+        let b = create_session(&db, "Weekly: sync").await;
+        let ids = vec![a.id.clone(), b.id.clone()];
+
+        let report = export_sessions(&db, dir.path(), ids.clone()).await.unwrap();
+        assert_eq!(report.written.len(), 2);
+        assert_ne!(report.written[0], report.written[1]);
+        assert!(report.written[0].ends_with("2025-01-31 Weekly sync.md"));
+
+        let content = std::fs::read_to_string(&report.written[0]).unwrap();
+        assert!(content.starts_with(&format!("---\nhyprnote_id: \"{}\"\n", a.id)));
+        assert!(content.contains("title: \"Weekly: sync\"\n"));
+        assert!(content.contains("date: 2025-01-31T10:00:00Z\n"));
+        assert!(content.contains("\n## Notes\n\nhello\n"));
+
+        let report = export_sessions(&db, dir.path(), ids.clone()).await.unwrap();
+        assert!(report.written.is_empty());
+        assert_eq!(report.unchanged.len(), 2);
+
+        db.upsert_session(Session {
+            title: "Renamed".to_string(),
+            ..a.clone()
+        })
+        .await
+        .unwrap();
+
+        let report = export_sessions(&db, dir.path(), ids).await.unwrap();
+        assert_eq!(report.written.len(), 1);
+        assert!(report.written[0].ends_with("2025-01-31 Renamed.md"));
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_export_keeps_user_files() {
+        let db = setup_db().await;
+        let dir = tempfile::tempdir().unwrap();
+
+        let own = dir.path().join("2025-01-31 Planning.md");
+        std::fs::write(&own, "my own note").unwrap();
+
+        let session = create_session(&db, "Planning").await;
+        let report = export_sessions(&db, dir.path(), vec![session.id.clone()])
+            .await
+            .unwrap();
+
+        assert_eq!(std::fs::read_to_string(&own).unwrap(), "my own note");
+        assert_eq!(report.written.len(), 1);
+        assert_ne!(report.written[0], own);
+
+        // Re-exporting finds the suffixed file by its id.
+        let report = export_sessions(&db, dir.path(), vec![session.id])
+            .await
+            .unwrap();
+        assert_eq!(report.unchanged.len(), 1);
+    }
+}
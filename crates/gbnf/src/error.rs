@@ -0,0 +1,23 @@
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("invalid schema at `{path}`: {reason}")]
+    InvalidSchema { path: String, reason: String },
+    #[error("unsupported schema at `{path}`: {construct}")]
+    UnsupportedSchema { path: String, construct: String },
+}
+
+impl Error {
+    pub(crate) fn invalid(path: &str, reason: impl Into<String>) -> Self {
+        Self::InvalidSchema {
+            path: path.to_string(),
+            reason: reason.into(),
+        }
+    }
+
+    pub(crate) fn unsupported(path: &str, construct: impl Into<String>) -> Self {
+        Self::UnsupportedSchema {
+            path: path.to_string(),
+            construct: construct.into(),
+        }
+    }
+}
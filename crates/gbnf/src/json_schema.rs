@@ -0,0 +1,765 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde_json::{Map, Value};
+
+const WS: &str = r#"ws ::= | " " | "\n" [ \t]{0,20}"#;
+
+// Shared rules, emitted only when referenced.
+const PRIMITIVES: [(&str, &str); 8] = [
+    ("string", r#""\"" char* "\"" ws"#),
+    (
+        "char",
+        r#"[^"\\\x7F\x00-\x1F] | "\\" (["\\/bfnrt] | "u" [0-9a-fA-F]{4})"#,
+    ),
+    (
+        "number",
+        r#""-"? ([0] | [1-9] [0-9]{0,15}) ("." [0-9]{1,16})? ([eE] [-+]? [0-9]{1,16})? ws"#,
+    ),
+    ("integer", r#""-"? ([0] | [1-9] [0-9]{0,15}) ws"#),
+    ("boolean", r#"("true" | "false") ws"#),
+    ("null", r#""null" ws"#),
+    (
+        "object",
+        r#""{" ws (string ":" ws value ("," ws string ":" ws value)*)? "}" ws"#,
+    ),
+    (
+        "value",
+        r#"object | "[" ws (value ("," ws value)*)? "]" ws | string | number | boolean | null"#,
+    ),
+];
+
+// Keywords that do not constrain the output by themselves.
+const ANNOTATIONS: [&str; 9] = [
+    "$schema",
+    "$id",
+    "$defs",
+    "definitions",
+    "title",
+    "description",
+    "default",
+    "examples",
+    "additionalProperties",
+];
+
+// Compiles a JSON Schema into a GBNF grammar whose `root` accepts matching JSON documents.
+//
+// Supported: `type` (a name or a list of names), `properties` and `required`, `enum` and `const`,
+// `items` with `minItems` and `maxItems`, `pattern`, `minLength` and `maxLength`, `oneOf` and `anyOf`,
+// and `$ref` into the same document. Properties are generated in `required` order, then in key order.
+// `oneOf` is treated like `anyOf`, since a grammar cannot check that only one alternative matches.
+pub fn compile(schema: &Value) -> Result<String, crate::Error> {
+    let mut compiler = Compiler {
+        document: schema,
+        rules: BTreeMap::new(),
+        names: ["root", "ws"]
+            .into_iter()
+            .chain(PRIMITIVES.iter().map(|(name, _)| *name))
+            .map(String::from)
+            .collect(),
+        refs: BTreeMap::new(),
+    };
+    let root = compiler.visit(schema, "root", "#")?;
+    if root != "root" {
+        compiler.rules.insert("root".to_string(), root);
+    }
+    Ok(compiler.render())
+}
+
+struct Compiler<'a> {
+    document: &'a Value,
+    rules: BTreeMap<String, String>,
+    // Every rule name handed out so far, including ones that ended up unused.
+    names: BTreeSet<String>,
+    // `$ref` pointer -> the rule compiled for it.
+    refs: BTreeMap<String, String>,
+}
+
+impl Compiler<'_> {
+    // Returns an expression matching `schema`, followed by optional whitespace.
+    fn visit(&mut self, schema: &Value, name: &str, path: &str) -> Result<String, crate::Error> {
+        let schema = match schema {
+            Value::Bool(true) => return Ok(self.primitive("value")),
+            Value::Object(schema) => schema,
+            _ => return Err(crate::Error::invalid(path, "expected a schema object")),
+        };
+
+        if let Some(reference) = schema.get("$ref") {
+            check_alone(schema, path, "$ref")?;
+            return self.visit_ref(reference, path);
+        }
+
+        for keyword in ["oneOf", "anyOf"] {
+            if let Some(alternatives) = schema.get(keyword) {
+                check_alone(schema, path, keyword)?;
+                return self.visit_alternatives(
+                    alternatives,
+                    name,
+                    &format!("{}/{}", path, keyword),
+                );
+            }
+        }
+
+        if let Some(value) = schema.get("const") {
+            check_keywords(schema, path, &["const", "type"])?;
+            return Ok(format!("{} ws", literal(&value.to_string())));
+        }
+
+        if let Some(values) = schema.get("enum") {
+            check_keywords(schema, path, &["enum", "type"])?;
+            let values = values
+                .as_array()
+                .filter(|values| !values.is_empty())
+                .ok_or_else(|| crate::Error::invalid(path, "`enum` must be a non-empty array"))?;
+
+            let alternatives = values
+                .iter()
+                .map(|v| literal(&v.to_string()))
+                .collect::<Vec<_>>();
+            return Ok(format!("({}) ws", alternatives.join(" | ")));
+        }
+
+        let kinds = match schema.get("type") {
+            Some(Value::String(kind)) => vec![kind.as_str()],
+            // `["string", "null"]` and the like, for nullable values.
+            Some(Value::Array(kinds)) if !kinds.is_empty() => kinds
+                .iter()
+                .map(|kind| {
+                    kind.as_str().ok_or_else(|| {
+                        crate::Error::invalid(
+                            path,
+                            "`type` must be a string or an array of strings",
+                        )
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()?,
+            Some(_) => {
+                return Err(crate::Error::invalid(
+                    path,
+                    "`type` must be a string or a non-empty array of strings",
+                ))
+            }
+            None if schema.contains_key("properties") => vec!["object"],
+            None if schema.contains_key("items") => vec!["array"],
+            None => {
+                check_keywords(schema, path, &[])?;
+                return Ok(self.primitive("value"));
+            }
+        };
+
+        let mut supported = vec!["type"];
+        for kind in &kinds {
+            supported.extend(match *kind {
+                "object" => &["properties", "required"][..],
+                "array" => &["items", "minItems", "maxItems"][..],
+                "string" => &["pattern", "minLength", "maxLength"][..],
+                "number" | "integer" | "boolean" | "null" => &[][..],
+                _ => {
+                    return Err(crate::Error::invalid(
+                        path,
+                        format!("unknown type `{}`", kind),
+                    ))
+                }
+            });
+        }
+        check_keywords(schema, path, &supported)?;
+
+        if let [kind] = kinds[..] {
+            return self.visit_type(kind, schema, name, path);
+        }
+
+        let mut alternatives = Vec::new();
+        for kind in kinds {
+            let name = self.name(format!("{}-{}", name, kind));
+            alternatives.push(self.visit_type(kind, schema, &name, path)?);
+        }
+        Ok(format!("({})", alternatives.join(" | ")))
+    }
+
+    fn visit_type(
+        &mut self,
+        kind: &str,
+        schema: &Map<String, Value>,
+        name: &str,
+        path: &str,
+    ) -> Result<String, crate::Error> {
+        match kind {
+            "object" => self.visit_object(schema, name, path),
+            "array" => self.visit_array(schema, name, path),
+            "string" => self.visit_string(schema, name, path),
+            _ => Ok(self.primitive(kind)),
+        }
+    }
+
+    fn visit_object(
+        &mut self,
+        schema: &Map<String, Value>,
+        name: &str,
+        path: &str,
+    ) -> Result<String, crate::Error> {
+        let properties = match schema.get("properties") {
+            None => return Ok(self.primitive("object")),
+            Some(Value::Object(properties)) => properties,
+            Some(_) => {
+                return Err(crate::Error::invalid(
+                    path,
+                    "`properties` must be an object",
+                ))
+            }
+        };
+
+        let required = match schema.get("required") {
+            None => vec![],
+            Some(Value::Array(required)) => required
+                .iter()
+                .map(|r| {
+                    r.as_str().ok_or_else(|| {
+                        crate::Error::invalid(path, "`required` must be an array of strings")
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()?,
+            Some(_) => return Err(crate::Error::invalid(path, "`required` must be an array")),
+        };
+
+        if let Some(missing) = required.iter().find(|r| !properties.contains_key(**r)) {
+            return Err(crate::Error::unsupported(
+                path,
+                format!("required property `{}` without a schema", missing),
+            ));
+        }
+
+        // Required properties come first, in the order `required` lists them, so that only optional ones
+        // can be left out. Optional properties keep the key order of the schema map, which is alphabetical.
+        let mut members = Vec::new();
+        for (key, optional) in required.iter().map(|k| (*k, false)).chain(
+            properties
+                .keys()
+                .filter(|k| !required.contains(&k.as_str()))
+                .map(|k| (k.as_str(), true)),
+        ) {
+            let member = self.name(format!("{}-{}", name, rule_name(key)));
+            let value = self.visit(
+                &properties[key],
+                &member,
+                &format!("{}/properties/{}", path, key),
+            )?;
+            members.push((
+                format!(
+                    r#"{} ":" ws {}"#,
+                    literal(&Value::from(key).to_string()),
+                    value
+                ),
+                optional,
+            ));
+        }
+
+        let body = join_members(&members);
+        Ok(self.rule(name, format!(r#""{{" ws {}"}}" ws"#, body)))
+    }
+
+    fn visit_array(
+        &mut self,
+        schema: &Map<String, Value>,
+        name: &str,
+        path: &str,
+    ) -> Result<String, crate::Error> {
+        let item = match schema.get("items") {
+            Some(items) => {
+                let item = self.name(format!("{}-item", name));
+                self.visit(items, &item, &format!("{}/items", path))?
+            }
+            None => self.primitive("value"),
+        };
+
+        let (min, max) = bounds(schema, path, "minItems", "maxItems")?;
+        let body = match (min, max) {
+            (_, Some(0)) => r#""[" ws "]" ws"#.to_string(),
+            (0, max) => format!(
+                r#""[" ws ({item} ("," ws {item}){rest})? "]" ws"#,
+                item = item,
+                rest = repeat(0, max.map(|max| max - 1)),
+            ),
+            (min, max) => format!(
+                r#""[" ws {item} ("," ws {item}){rest} "]" ws"#,
+                item = item,
+                rest = repeat(min - 1, max.map(|max| max - 1)),
+            ),
+        };
+        Ok(self.rule(name, body))
+    }
+
+    fn visit_string(
+        &mut self,
+        schema: &Map<String, Value>,
+        name: &str,
+        path: &str,
+    ) -> Result<String, crate::Error> {
+        let (min, max) = bounds(schema, path, "minLength", "maxLength")?;
+
+        match schema.get("pattern") {
+            Some(_) if min > 0 || max.is_some() => Err(crate::Error::unsupported(
+                path,
+                "`pattern` together with `minLength` or `maxLength` (put the length in the pattern instead)",
+            )),
+            Some(Value::String(pattern)) => {
+                let expr = crate::regex::to_gbnf(pattern).map_err(|reason| {
+                    crate::Error::unsupported(
+                        &format!("{}/pattern", path),
+                        format!("pattern `{}`: {}", pattern, reason),
+                    )
+                })?;
+                Ok(self.rule(name, format!(r#""\"" {} "\"" ws"#, expr)))
+            }
+            Some(_) => Err(crate::Error::invalid(path, "`pattern` must be a string")),
+            None if min == 0 && max.is_none() => Ok(self.primitive("string")),
+            None => {
+                self.primitive("char");
+                Ok(self.rule(name, format!(r#""\"" char{} "\"" ws"#, repeat(min, max))))
+            }
+        }
+    }
+
+    fn visit_alternatives(
+        &mut self,
+        alternatives: &Value,
+        name: &str,
+        path: &str,
+    ) -> Result<String, crate::Error> {
+        let alternatives = alternatives
+            .as_array()
+            .filter(|alternatives| !alternatives.is_empty())
+            .ok_or_else(|| crate::Error::invalid(path, "expected a non-empty array of schemas"))?;
+
+        let mut exprs = Vec::new();
+        for (i, alternative) in alternatives.iter().enumerate() {
+            let name = self.name(format!("{}-{}", name, i));
+            exprs.push(self.visit(alternative, &name, &format!("{}/{}", path, i))?);
+        }
+        Ok(format!("({})", exprs.join(" | ")))
+    }
+
+    // Each definition becomes a rule of its own, so definitions can refer to themselves.
+    fn visit_ref(&mut self, reference: &Value, path: &str) -> Result<String, crate::Error> {
+        let pointer = reference
+            .as_str()
+            .and_then(|r| r.strip_prefix('#'))
+            .ok_or_else(|| crate::Error::unsupported(path, "`$ref` outside of this schema"))?;
+
+        if pointer.is_empty() {
+            return Ok("root".to_string());
+        }
+
+        if let Some(name) = self.refs.get(pointer) {
+            return Ok(name.clone());
+        }
+
+        let target = self.document.pointer(pointer).ok_or_else(|| {
+            crate::Error::invalid(path, format!("`$ref` to missing `#{}`", pointer))
+        })?;
+
+        // Named after the whole pointer, leaving out the usual `$defs` or `definitions` prefix.
+        let segments = pointer
+            .split('/')
+            .skip(1)
+            .enumerate()
+            .filter(|(i, s)| !(*i == 0 && (*s == "$defs" || *s == "definitions")))
+            .map(|(_, s)| rule_name(s))
+            .collect::<Vec<_>>();
+        let name = self.name(format!("def-{}", segments.join("-")));
+        self.refs.insert(pointer.to_string(), name.clone());
+
+        // A placeholder, until the definition is compiled.
+        self.rules.insert(name.clone(), String::new());
+        let expr = self.visit(target, &name, &format!("#{}", pointer))?;
+        if expr != name {
+            self.rules.insert(name.clone(), expr);
+        }
+        Ok(name)
+    }
+
+    // `base`, or `base` with a counter when another schema already took it.
+    // Distinct keys like `a-b` and `a_b`, or a property `0` and the first `oneOf` alternative, can share a base.
+    fn name(&mut self, base: String) -> String {
+        let mut name = base.clone();
+        let mut n = 1;
+        while !self.names.insert(name.clone()) {
+            n += 1;
+            name = format!("{}-{}", base, n);
+        }
+        name
+    }
+
+    fn rule(&mut self, name: &str, body: String) -> String {
+        self.rules.insert(name.to_string(), body);
+        name.to_string()
+    }
+
+    fn primitive(&mut self, name: &str) -> String {
+        let dependencies: &[&str] = match name {
+            "value" | "object" => &[
+                "value", "object", "string", "char", "number", "boolean", "null",
+            ],
+            "string" => &["char"],
+            _ => &[],
+        };
+
+        for dependency in dependencies.iter().chain(std::iter::once(&name)) {
+            let (_, body) = PRIMITIVES.iter().find(|(n, _)| n == dependency).unwrap();
+            self.rules.insert(dependency.to_string(), body.to_string());
+        }
+
+        name.to_string()
+    }
+
+    fn render(&self) -> String {
+        let mut out = format!("root ::= {}\n", self.rules["root"]);
+        for (name, body) in self.rules.iter().filter(|(n, _)| *n != "root") {
+            out.push_str(&format!("{} ::= {}\n", name, body));
+        }
+        out.push_str(WS);
+        out.push('\n');
+        out
+    }
+}
+
+// Members separated by commas, where optional ones may be left out along with their comma.
+fn join_members(members: &[(String, bool)]) -> String {
+    let tail = |rest: &[(String, bool)]| {
+        rest.iter()
+            .map(|(member, optional)| match optional {
+                true => format!(r#"("," ws {})? "#, member),
+                false => format!(r#""," ws {} "#, member),
+            })
+            .collect::<String>()
+    };
+
+    match members.first() {
+        None => String::new(),
+        Some((first, false)) => format!("{} {}", first, tail(&members[1..])),
+        // Required members come first, so here every member is optional and any of them can lead.
+        Some(_) => {
+            let alternatives = (0..members.len())
+                .map(|i| {
+                    format!("{} {}", members[i].0, tail(&members[i + 1..]))
+                        .trim_end()
+                        .to_string()
+                })
+                .collect::<Vec<_>>();
+            format!("({})? ", alternatives.join(" | "))
+        }
+    }
+}
+
+// `minItems` and `maxItems`, or `minLength` and `maxLength`.
+fn bounds(
+    schema: &Map<String, Value>,
+    path: &str,
+    min_keyword: &str,
+    max_keyword: &str,
+) -> Result<(u64, Option<u64>), crate::Error> {
+    let get = |keyword: &str| match schema.get(keyword) {
+        None => Ok(None),
+        Some(value) => value.as_u64().map(Some).ok_or_else(|| {
+            crate::Error::invalid(
+                path,
+                format!("`{}` must be a non-negative integer", keyword),
+            )
+        }),
+    };
+
+    let min = get(min_keyword)?.unwrap_or(0);
+    let max = get(max_keyword)?;
+    match max {
+        Some(max) if max < min => Err(crate::Error::invalid(
+            path,
+            format!("`{}` is greater than `{}`", min_keyword, max_keyword),
+        )),
+        _ => Ok((min, max)),
+    }
+}
+
+// A GBNF repetition suffix for between `min` and `max` occurrences.
+fn repeat(min: u64, max: Option<u64>) -> String {
+    match (min, max) {
+        (0, None) => "*".to_string(),
+        (1, None) => "+".to_string(),
+        (0, Some(1)) => "?".to_string(),
+        (min, None) => format!("{{{},}}", min),
+        (min, Some(max)) if min == max => format!("{{{}}}", min),
+        (min, Some(max)) => format!("{{{},{}}}", min, max),
+    }
+}
+
+fn check_keywords(
+    schema: &Map<String, Value>,
+    path: &str,
+    supported: &[&str],
+) -> Result<(), crate::Error> {
+    let keyword = match schema
+        .keys()
+        .find(|k| !supported.contains(&k.as_str()) && !ANNOTATIONS.contains(&k.as_str()))
+    {
+        Some(keyword) => keyword,
+        None => return Ok(()),
+    };
+
+    let hint = match keyword.as_str() {
+        "minimum" | "maximum" | "exclusiveMinimum" | "exclusiveMaximum" | "multipleOf" => {
+            " (numeric bounds cannot be expressed in a grammar, so check them after parsing)"
+        }
+        "format" => " (use `pattern` instead)",
+        "allOf" | "not" | "if" => " (merge the schemas into one instead)",
+        "uniqueItems" | "contains" | "prefixItems" => {
+            " (only `items`, `minItems` and `maxItems` are supported)"
+        }
+        "patternProperties" | "propertyNames" | "minProperties" | "maxProperties" => {
+            " (only `properties` and `required` are supported)"
+        }
+        _ => "",
+    };
+    Err(crate::Error::unsupported(
+        path,
+        format!("`{}`{}", keyword, hint),
+    ))
+}
+
+// `$ref`, `oneOf` and `anyOf` replace the rest of the schema, so anything next to them would be ignored.
+fn check_alone(schema: &Map<String, Value>, path: &str, keyword: &str) -> Result<(), crate::Error> {
+    match schema
+        .keys()
+        .find(|k| *k != keyword && !ANNOTATIONS.contains(&k.as_str()))
+    {
+        Some(other) => Err(crate::Error::unsupported(
+            path,
+            format!(
+                "`{}` alongside `{}` (move it into the referenced or alternative schemas)",
+                other, keyword
+            ),
+        )),
+        None => Ok(()),
+    }
+}
+
+// A GBNF string literal matching `text` exactly.
+pub(crate) fn literal(text: &str) -> String {
+    let escaped = text
+        .chars()
+        .map(|c| match c {
+            '"' => "\\\"".to_string(),
+            '\\' => "\\\\".to_string(),
+            '\n' => "\\n".to_string(),
+            '\r' => "\\r".to_string(),
+            '\t' => "\\t".to_string(),
+            c => c.to_string(),
+        })
+        .collect::<String>();
+    format!("\"{}\"", escaped)
+}
+
+fn rule_name(key: &str) -> String {
+    key.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_compile() {
+        let grammar = compile(&json!({
+            "type": "object",
+            "properties": {
+                "title": { "type": "string" },
+                "tags": { "type": "array", "items": { "enum": ["a", "b"] } }
+            },
+            "required": ["title"]
+        }))
+        .unwrap();
+
+        insta::assert_snapshot!(grammar, @r###"
+        root ::= "{" ws "\"title\"" ":" ws string ("," ws "\"tags\"" ":" ws root-tags)? "}" ws
+        char ::= [^"\\\x7F\x00-\x1F] | "\\" (["\\/bfnrt] | "u" [0-9a-fA-F]{4})
+        root-tags ::= "[" ws (("\"a\"" | "\"b\"") ws ("," ws ("\"a\"" | "\"b\"") ws)*)? "]" ws
+        string ::= "\"" char* "\"" ws
+        ws ::= | " " | "\n" [ \t]{0,20}
+        "###);
+
+        let grammar = compile(&json!({
+            "type": "object",
+            "properties": {
+                "code": { "type": "string", "pattern": "^[A-Z]{2}-\\d+$" },
+                "points": { "type": "array", "items": { "type": "integer" }, "minItems": 1, "maxItems": 3 },
+                "note": { "type": ["string", "null"], "maxLength": 4 },
+                "node": { "$ref": "#/$defs/node" }
+            },
+            "required": ["points", "code"],
+            "$defs": {
+                "node": {
+                    "oneOf": [
+                        { "type": "object", "properties": { "next": { "$ref": "#/$defs/node" } }, "required": ["next"] },
+                        { "const": "leaf" }
+                    ]
+                }
+            }
+        }))
+        .unwrap();
+
+        insta::assert_snapshot!(grammar, @r###"
+        root ::= "{" ws "\"points\"" ":" ws root-points "," ws "\"code\"" ":" ws root-code ("," ws "\"node\"" ":" ws def-node)? ("," ws "\"note\"" ":" ws (root-note-string | null))? "}" ws
+        char ::= [^"\\\x7F\x00-\x1F] | "\\" (["\\/bfnrt] | "u" [0-9a-fA-F]{4})
+        def-node ::= (def-node-0 | "\"leaf\"" ws)
+        def-node-0 ::= "{" ws "\"next\"" ":" ws def-node "}" ws
+        integer ::= "-"? ([0] | [1-9] [0-9]{0,15}) ws
+        null ::= "null" ws
+        root-code ::= "\"" [A-Z]{2} "-" [0-9]+ "\"" ws
+        root-note-string ::= "\"" char{0,4} "\"" ws
+        root-points ::= "[" ws integer ("," ws integer){0,2} "]" ws
+        ws ::= | " " | "\n" [ \t]{0,20}
+        "###);
+    }
+
+    #[test]
+    fn test_rule_names() {
+        let grammar = compile(&json!({
+            "type": "object",
+            "properties": {
+                "a": { "$ref": "#/$defs/x/properties/v" },
+                "b": { "$ref": "#/$defs/y/properties/v" },
+                "c-d": { "type": "string", "maxLength": 2 },
+                "c_d": { "type": "string", "maxLength": 3 }
+            },
+            "required": ["a", "b", "c-d", "c_d"],
+            "$defs": {
+                "x": { "type": "object", "properties": { "v": { "type": "string", "minLength": 1 } } },
+                "y": { "type": "object", "properties": { "v": { "type": "array", "items": { "type": "integer" } } } }
+            }
+        }))
+        .unwrap();
+
+        insta::assert_snapshot!(grammar, @r###"
+        root ::= "{" ws "\"a\"" ":" ws def-x-properties-v "," ws "\"b\"" ":" ws def-y-properties-v "," ws "\"c-d\"" ":" ws root-c-d "," ws "\"c_d\"" ":" ws root-c-d-2 "}" ws
+        char ::= [^"\\\x7F\x00-\x1F] | "\\" (["\\/bfnrt] | "u" [0-9a-fA-F]{4})
+        def-x-properties-v ::= "\"" char+ "\"" ws
+        def-y-properties-v ::= "[" ws (integer ("," ws integer)*)? "]" ws
+        integer ::= "-"? ([0] | [1-9] [0-9]{0,15}) ws
+        root-c-d ::= "\"" char{0,2} "\"" ws
+        root-c-d-2 ::= "\"" char{0,3} "\"" ws
+        ws ::= | " " | "\n" [ \t]{0,20}
+        "###);
+
+        let grammar = compile(&json!({
+            "type": "object",
+            "properties": {
+                "a": { "oneOf": [{ "type": "string", "maxLength": 1 }, { "type": "null" }] },
+                "a-0": { "type": "string", "maxLength": 2 }
+            },
+            "required": ["a", "a-0"]
+        }))
+        .unwrap();
+
+        insta::assert_snapshot!(grammar, @r###"
+        root ::= "{" ws "\"a\"" ":" ws (root-a-0 | null) "," ws "\"a-0\"" ":" ws root-a-0-2 "}" ws
+        char ::= [^"\\\x7F\x00-\x1F] | "\\" (["\\/bfnrt] | "u" [0-9a-fA-F]{4})
+        null ::= "null" ws
+        root-a-0 ::= "\"" char? "\"" ws
+        root-a-0-2 ::= "\"" char{0,2} "\"" ws
+        ws ::= | " " | "\n" [ \t]{0,20}
+        "###);
+    }
+
+    #[test]
+    fn test_errors() {
+        let error = |schema: Value| compile(&schema).unwrap_err().to_string();
+
+        assert_eq!(
+            error(json!({ "type": "string", "format": "email" })),
+            "unsupported schema at `#`: `format` (use `pattern` instead)"
+        );
+        assert_eq!(
+            error(json!({ "properties": { "age": { "type": "integer", "minimum": 0 } } })),
+            "unsupported schema at `#/properties/age`: `minimum` (numeric bounds cannot be expressed in a grammar, so check them after parsing)"
+        );
+        assert_eq!(
+            error(json!({ "type": "array", "items": { "type": "string", "pattern": "(?!x)" } })),
+            "unsupported schema at `#/items/pattern`: pattern `(?!x)`: lookaround `(?!` is not supported"
+        );
+        assert_eq!(
+            error(json!({ "type": "array", "minItems": 3, "maxItems": 1 })),
+            "invalid schema at `#`: `minItems` is greater than `maxItems`"
+        );
+        assert_eq!(
+            error(json!({ "$ref": "#/$defs/missing" })),
+            "invalid schema at `#`: `$ref` to missing `#/$defs/missing`"
+        );
+    }
+
+    #[test]
+    fn test_validate() {
+        let validator = gbnf_validator::Validator::new().unwrap();
+
+        let grammar = compile(&json!({
+            "type": "object",
+            "properties": {
+                "name": { "type": "string", "minLength": 1 },
+                "code": { "type": "string", "pattern": "^[A-Z]{2}-\\d+$" },
+                "kind": { "enum": ["person", "team"] },
+                "scores": { "type": "array", "items": { "type": "number" }, "minItems": 1, "maxItems": 2 },
+                "parent": { "oneOf": [{ "type": "null" }, { "type": "integer" }] }
+            },
+            "required": ["name", "kind", "scores"]
+        }))
+        .unwrap();
+
+        for document in [
+            r#"{"name": "Ada", "kind": "person", "scores": [1.5]}"#,
+            r#"{"name": "Ada", "kind": "team", "scores": [1, -2e3], "code": "AB-12", "parent": null}"#,
+            "{\n  \"name\": \"A\\\"da\",\n  \"kind\": \"person\",\n  \"scores\": [0],\n  \"parent\": 3\n}",
+        ] {
+            assert!(validator.validate(&grammar, document).unwrap(), "{}", document);
+        }
+
+        for document in [
+            // Missing a required property.
+            r#"{"name": "Ada", "kind": "person"}"#,
+            // Out of order.
+            r#"{"kind": "person", "name": "Ada", "scores": [1]}"#,
+            r#"{"name": "", "kind": "person", "scores": [1]}"#,
+            r#"{"name": "Ada", "kind": "robot", "scores": [1]}"#,
+            r#"{"name": "Ada", "kind": "person", "scores": []}"#,
+            r#"{"name": "Ada", "kind": "person", "scores": [1, 2, 3]}"#,
+            r#"{"name": "Ada", "kind": "person", "scores": [1], "code": "ab-12"}"#,
+            r#"{"name": "Ada", "kind": "person", "scores": [1], "parent": "x"}"#,
+        ] {
+            assert!(
+                !validator.validate(&grammar, document).unwrap(),
+                "{}",
+                document
+            );
+        }
+    }
+
+    #[test]
+    fn test_validate_any_object() {
+        let validator = gbnf_validator::Validator::new().unwrap();
+
+        let grammar = compile(&json!({ "type": "object" })).unwrap();
+
+        for document in [r#"{}"#, r#"{"query": "budget", "limit": [1, {"x": null}]}"#] {
+            assert!(
+                validator.validate(&grammar, document).unwrap(),
+                "{}",
+                document
+            );
+        }
+
+        for document in [r#""x""#, "1", "true", "null", r#"[{"query": "budget"}]"#] {
+            assert!(
+                !validator.validate(&grammar, document).unwrap(),
+                "{}",
+                document
+            );
+        }
+    }
+}
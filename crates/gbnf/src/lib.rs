@@ -1,63 +1,321 @@
+mod error;
+pub mod json_schema;
+mod regex;
+
+pub use error::*;
+
 pub const ENHANCE_AUTO: &str = include_str!("../assets/enhance-auto.gbnf");
 pub const ENHANCE_TEMPLATE: &str = include_str!("../assets/enhance-template.gbnf");
+pub const JSON: &str = include_str!("../assets/json.gbnf");
+pub const TITLE_SCHEMA: &str = include_str!("../assets/title.schema.json");
+pub const ANNOTATION_SCHEMA: &str = include_str!("../assets/annotation.schema.json");
+
+// Rules shared by the grammars built from template sections.
+const ENHANCE_SECTION_RULES: &str = r#"bullet ::= "- " nonws ([^\n[] | link)+ "\n"
+nonws ::= [^ \t\n\r]
+link ::= "[" [^\]]+ "]" "(" [^)]+ ")"
+"#;
+
 pub enum GBNF {
+    // With section titles, exactly those headings in order. With none, any headings.
     Enhance(Option<Vec<String>>),
+    // Any JSON object, for `response_format: json_object`.
+    Json,
+    // `{"title": ...}`, as `create_title` asks for.
+    Title,
+    // `{"blocks": [...]}`, as `show_annotation` asks for.
+    Annotation,
+    // A single call, `{"name": ..., "parameters": {...}}`, to one of the tools, given as names and
+    // parameter schemas. This is how Llama 3 chat templates write tool calls.
+    ToolCall(Vec<(String, Option<serde_json::Value>)>),
 }
 
 impl GBNF {
-    pub fn build(&self) -> String {
-        match self {
-            GBNF::Enhance(Some(_)) => ENHANCE_TEMPLATE.to_string(),
-            GBNF::Enhance(None) => ENHANCE_AUTO.to_string(),
+    // Grammars a request can ask for by name.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "enhance-auto" => Some(GBNF::Enhance(None)),
+            "enhance-template" => Some(GBNF::Enhance(Some(vec![]))),
+            "title" => Some(GBNF::Title),
+            "annotation" => Some(GBNF::Annotation),
+            _ => None,
         }
     }
+
+    // Only `ToolCall`, which is built from a client's tools, can fail.
+    pub fn build(&self) -> Result<String, crate::Error> {
+        Ok(match self {
+            GBNF::Enhance(Some(sections)) => {
+                enhance_sections(sections).unwrap_or_else(|| ENHANCE_TEMPLATE.to_string())
+            }
+            GBNF::Enhance(None) => ENHANCE_AUTO.to_string(),
+            GBNF::Json => JSON.to_string(),
+            GBNF::Title => compile_schema(TITLE_SCHEMA),
+            GBNF::Annotation => compile_schema(ANNOTATION_SCHEMA),
+            GBNF::ToolCall(tools) => tool_call(tools)?,
+        })
+    }
+}
+
+// The `<headers>` preamble lists the template's headings, and the note then uses each of them, in order,
+// for a short bullet list.
+fn enhance_sections(sections: &[String]) -> Option<String> {
+    let titles = sections
+        .iter()
+        .map(|s| s.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>();
+
+    if titles.is_empty() {
+        return None;
+    }
+
+    let mut out = String::from("root ::= thinking");
+    for i in 0..titles.len() {
+        out.push_str(&format!(" section-{}", i));
+    }
+    out.push_str("\n\n");
+
+    out.push_str(r#"thinking ::= "<headers>\n""#);
+    for title in &titles {
+        out.push_str(&format!(
+            " {}",
+            json_schema::literal(&format!("- {}\n", title))
+        ));
+    }
+    out.push_str(" \"</headers>\"\n\n");
+
+    for (i, title) in titles.iter().enumerate() {
+        out.push_str(&format!(
+            "section-{} ::= {} bullet bullet? bullet? bullet? bullet? \"\\n\"\n",
+            i,
+            json_schema::literal(&format!("# {}\n\n", title))
+        ));
+    }
+    out.push('\n');
+
+    out.push_str(ENHANCE_SECTION_RULES);
+    Some(out)
+}
+
+// Parameter schemas that can't be compiled on their own, including ones with `$ref`s, which would
+// point elsewhere once nested, accept any arguments rather than failing the request.
+fn tool_call(tools: &[(String, Option<serde_json::Value>)]) -> Result<String, crate::Error> {
+    use serde_json::json;
+
+    let alternatives = tools
+        .iter()
+        .map(|(name, parameters)| {
+            let parameters = match parameters {
+                Some(parameters)
+                    if !parameters.to_string().contains("\"$ref\"")
+                        && json_schema::compile(parameters).is_ok() =>
+                {
+                    parameters.clone()
+                }
+                Some(_) => json!({ "type": "object" }),
+                None => json!({ "type": "object", "properties": {} }),
+            };
+
+            json!({
+                "type": "object",
+                "properties": {
+                    "name": { "const": name },
+                    "parameters": parameters,
+                },
+                "required": ["name", "parameters"],
+            })
+        })
+        .collect::<Vec<_>>();
+
+    json_schema::compile(&json!({ "oneOf": alternatives }))
+}
+
+// Bundled schemas are covered by tests, so they always compile.
+fn compile_schema(schema: &str) -> String {
+    json_schema::compile(&serde_json::from_str(schema).unwrap()).unwrap()
 }
 
 #[cfg(test)]
 mod tests {
+    use super::*;
     use indoc::indoc;
 
     #[test]
-    fn test_1() {
-        let input_1 = "<headers>\n- Objective\n- Key Takeaways\n- Importance of Complementary Skills\n- Benefits of Using Online Resources\n- Advice for Undergrad Students\n</headers># Objective\n\n- **Search is the Best Way to Find Answers**: The speaker emphasizes the importance of utilizing online resources like Google to find answers to questions.\n- **Value in Complementary Skills**: The speaker highlights the need to acquire complementary skills to traditional research methods.\n\n# Key Takeaways\n\n- **Complementary skills include both traditional research and online resource utilization**: The speaker suggests that skills like using a blank sheet of paper with no Internet and effective Google searching are essential.\n- **Online resources can help find pre-solved problems**: The speaker advises investing time in finding existing resources and communities that have already solved problems.\n\n# Importance of Complementary Skills\n\n- **Traditional research is just the starting point**: The speaker suggests that traditional research methods are just the beginning and should be complemented with other skills.\n- **Effective use of online resources can save time and effort**: The speaker highlights the benefits of utilizing online resources in research and problem-solving.\n\n# Benefits of Using Online Resources\n\n- **Access to knowledge from experts and communities**: The speaker suggests that online resources provide access to knowledge and expertise from experienced individuals.\n- **Time-saving and efficient**: The speaker emphasizes the benefits of finding pre-solved problems through online resources.\n\n# Advice for Undergrad Students\n\n- **Start by searching online**: The speaker advises undergrad students to start by searching online for answers to questions and exploring different resources.\n- **Be open to finding existing solutions**: The speaker emphasizes the importance of being open to finding pre-solved problems and leveraging existing resources.\n\n";
-        let input_2 = indoc! {"
+    fn test_enhance_sections() {
+        let grammar = GBNF::Enhance(Some(vec![
+            "Decisions".to_string(),
+            "  ".to_string(),
+            "Next \"steps\"".to_string(),
+        ]))
+        .build()
+        .unwrap();
+
+        insta::assert_snapshot!(grammar, @r###"
+        root ::= thinking section-0 section-1
+
+        thinking ::= "<headers>\n" "- Decisions\n" "- Next \"steps\"\n" "</headers>"
+
+        section-0 ::= "# Decisions\n\n" bullet bullet? bullet? bullet? bullet? "\n"
+        section-1 ::= "# Next \"steps\"\n\n" bullet bullet? bullet? bullet? bullet? "\n"
+
+        bullet ::= "- " nonws ([^\n[] | link)+ "\n"
+        nonws ::= [^ \t\n\r]
+        link ::= "[" [^\]]+ "]" "(" [^)]+ ")"
+        "###);
+
+        let gbnf = gbnf_validator::Validator::new().unwrap();
+        let note = indoc! {r#"
+            <headers>
+            - Decisions
+            - Next "steps"
+            </headers># Decisions
+
+            - Ship the [beta](https://example.com) on **Friday**.
+
+            # Next "steps"
+
+            - Alice writes the release notes
+            - Bob updates the docs
+
+        "#};
+        assert!(gbnf.validate(&grammar, note).unwrap());
+        // Headings must match the template, in order.
+        assert!(!gbnf
+            .validate(&grammar, &note.replace("# Decisions", "# Summary"))
+            .unwrap());
+
+        assert_eq!(
+            GBNF::Enhance(Some(vec![])).build().unwrap(),
+            ENHANCE_TEMPLATE
+        );
+    }
+
+    #[test]
+    fn test_schemas() {
+        let gbnf = gbnf_validator::Validator::new().unwrap();
+
+        let title = GBNF::Title.build().unwrap();
+        assert!(gbnf
+            .validate(&title, r#"{"title": "Weekly sync"}"#)
+            .unwrap());
+        assert!(!gbnf.validate(&title, r#"{"title": ""}"#).unwrap());
+
+        // The example output that `show_annotation` gives the model.
+        let template = include_str!("../../template/assets/show_annotation.system.jinja");
+        let example = template
+            .split("# Example Output:")
+            .nth(1)
+            .and_then(|rest| rest.split("```json").nth(1))
+            .and_then(|rest| rest.split("```").next())
+            .unwrap()
+            .trim();
+
+        let annotation = GBNF::Annotation.build().unwrap();
+        assert!(gbnf.validate(&annotation, example).unwrap());
+        assert!(!gbnf.validate(&annotation, r#"{"blocks": []}"#).unwrap());
+        assert!(!gbnf
+            .validate(
+                &annotation,
+                r#"{"blocks": [{"type": "quote", "content": "x"}]}"#
+            )
+            .unwrap());
+    }
+
+    #[test]
+    fn test_tool_call() {
+        let grammar = GBNF::ToolCall(vec![
+            (
+                "get_weather".to_string(),
+                Some(serde_json::json!({
+                    "type": "object",
+                    "properties": { "city": { "type": "string" } },
+                    "required": ["city"],
+                })),
+            ),
+            ("now".to_string(), None),
+            (
+                "search".to_string(),
+                Some(serde_json::json!({ "type": "object", "minProperties": 1 })),
+            ),
+        ])
+        .build()
+        .unwrap();
+
+        let gbnf = gbnf_validator::Validator::new().unwrap();
+        assert!(gbnf
+            .validate(
+                &grammar,
+                r#"{"name": "get_weather", "parameters": {"city": "Seoul"}}"#
+            )
+            .unwrap());
+        assert!(gbnf
+            .validate(&grammar, r#"{"name": "now", "parameters": {}}"#)
+            .unwrap());
+        assert!(gbnf
+            .validate(
+                &grammar,
+                r#"{"name": "search", "parameters": {"query": "budget"}}"#
+            )
+            .unwrap());
+        assert!(!gbnf
+            .validate(&grammar, r#"{"name": "get_weather", "parameters": {}}"#)
+            .unwrap());
+        assert!(!gbnf
+            .validate(&grammar, r#"{"name": "delete", "parameters": {}}"#)
+            .unwrap());
+        // A schema that falls back to any object still only takes an object.
+        assert!(!gbnf
+            .validate(&grammar, r#"{"name": "search", "parameters": "budget"}"#)
+            .unwrap());
+    }
+
+    #[test]
+    fn test_enhance_auto() {
+        let gbnf = gbnf_validator::Validator::new().unwrap();
+        let note = indoc! {"
             <headers>
             - Objective
             - Key Takeaways
-            - Importance of Complementary Skills
-            - Benefits of Using Online Resources
-            - Advice for Undergrad Students
+            - Complementary Skills
+            - Advice for Students
             </headers># Objective
-    
-            - **Search is the Best Way to Find Answers**: The speaker emphasizes the importance of utilizing online resources like Google to find answers to questions.
-            - **Value in Complementary Skills**: The speaker highlights the need to acquire complementary skills to traditional research methods.
-    
+
+            - Search is the best way to find answers to questions.
+
             # Key Takeaways
-    
-            - **Complementary skills include both traditional research and online resource utilization**: The speaker suggests that skills like using a blank sheet of paper with no Internet and effective Google searching are essential.
-            - **Online resources can help find pre-solved problems**: The speaker advises investing time in finding existing resources and communities that have already solved problems.
-    
-            # Importance of Complementary Skills
-    
-            - **Traditional research is just the starting point**: The speaker suggests that traditional research methods are just the beginning and should be complemented with other skills.
-            - **Effective use of online resources can save time and effort**: The speaker highlights the benefits of utilizing online resources in research and problem-solving.
-    
-            # Benefits of Using Online Resources
-    
-            - **Access to knowledge from experts and communities**: The speaker suggests that online resources provide access to knowledge and expertise from experienced individuals.
-            - **Time-saving and efficient**: The speaker emphasizes the benefits of finding pre-solved problems through online resources.
-    
-            # Advice for Undergrad Students
-    
-            - **Start by searching online**: The speaker advises undergrad students to start by searching online for answers to questions and exploring different resources.
-            - **Be open to finding existing solutions**: The speaker emphasizes the importance of being open to finding pre-solved problems and leveraging existing resources.
-            
-            "};
-
-        assert_eq!(input_1, input_2);
-
-        let _gbnf = gbnf_validator::Validator::new().unwrap();
-        // assert!(gbnf.validate(ENHANCE_AUTO, input_1).unwrap());
+
+            - **Search first**: Most problems have already been solved by someone online.
+            - **Communities help**: Forums and communities share what they learned.
+
+            # Complementary Skills
+
+            - **Thinking alone**: A blank sheet of paper with no Internet still matters.
+            - **Searching well**: Good queries save time and effort.
+
+            # Advice for Students
+
+            - **Start online**: Look for existing answers before starting from scratch.
+            - **Stay open**: Existing solutions are a starting point and not a shortcut.
+
+        "};
+        assert!(gbnf.validate(ENHANCE_AUTO, note).unwrap());
+        // The objective is plain sentences, and every other section has at least two bold-led bullets.
+        assert!(!gbnf
+            .validate(
+                ENHANCE_AUTO,
+                &note.replace("- Search is", "- **Search** is")
+            )
+            .unwrap());
+        assert!(!gbnf
+            .validate(
+                ENHANCE_AUTO,
+                &note.replace(
+                    "- **Stay open**: Existing solutions are a starting point and not a shortcut.\n",
+                    ""
+                )
+            )
+            .unwrap());
     }
 
     #[allow(dead_code)]
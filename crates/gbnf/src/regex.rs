@@ -0,0 +1,305 @@
+// Translates the common subset of ECMAScript regular expressions that JSON Schema `pattern`s use
+// into a GBNF expression over the characters of a JSON string.
+//
+// Patterns match the whole string, as if anchored, since a grammar has to decide where the string ends.
+// Only characters that are valid unescaped in JSON are produced.
+
+// Characters allowed unescaped inside a JSON string, excluding what a negated class leaves out.
+const JSON_EXCLUDED: &str = r#""\\\x7F\x00-\x1F"#;
+
+pub(crate) fn to_gbnf(pattern: &str) -> Result<String, String> {
+    let pattern = pattern.strip_prefix('^').unwrap_or(pattern);
+    let pattern = match pattern.strip_suffix('$') {
+        Some(p) if !p.ends_with('\\') => p,
+        _ => pattern,
+    };
+
+    let mut parser = Parser {
+        chars: pattern.chars().collect(),
+        pos: 0,
+    };
+
+    let expr = parser.alternation()?;
+    match parser.peek() {
+        None => Ok(expr),
+        Some(c) => Err(format!("unexpected `{}` at {}", c, parser.pos)),
+    }
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek();
+        self.pos += 1;
+        c
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(c) {
+            self.pos += 1;
+            return true;
+        }
+        false
+    }
+
+    fn alternation(&mut self) -> Result<String, String> {
+        let mut alternatives = vec![self.concat()?];
+        while self.eat('|') {
+            alternatives.push(self.concat()?);
+        }
+
+        Ok(match alternatives.len() {
+            1 => alternatives.pop().unwrap(),
+            _ => format!("({})", alternatives.join(" | ")),
+        })
+    }
+
+    fn concat(&mut self) -> Result<String, String> {
+        let mut items = Vec::new();
+        while let Some(c) = self.peek() {
+            if c == '|' || c == ')' {
+                break;
+            }
+            let atom = self.atom()?;
+            items.push(self.quantifier(atom)?);
+        }
+
+        Ok(match items.len() {
+            0 => "\"\"".to_string(),
+            _ => items.join(" "),
+        })
+    }
+
+    fn quantifier(&mut self, atom: String) -> Result<String, String> {
+        let quantified = match self.peek() {
+            Some(q @ ('*' | '+' | '?')) => {
+                self.pos += 1;
+                format!("{}{}", atom, q)
+            }
+            Some('{') => {
+                self.pos += 1;
+                let min = self.number()?;
+                let max = match self.eat(',') {
+                    true if self.peek() == Some('}') => None,
+                    true => Some(self.number()?),
+                    false => Some(min),
+                };
+                if !self.eat('}') {
+                    return Err("unterminated `{` quantifier".to_string());
+                }
+                match max {
+                    Some(max) if max < min => {
+                        return Err(format!("quantifier `{{{},{}}}` is empty", min, max))
+                    }
+                    Some(max) if max == min => format!("{}{{{}}}", atom, min),
+                    Some(max) => format!("{}{{{},{}}}", atom, min, max),
+                    None => format!("{}{{{},}}", atom, min),
+                }
+            }
+            _ => return Ok(atom),
+        };
+
+        // Lazy and greedy quantifiers match the same strings.
+        self.eat('?');
+        Ok(quantified)
+    }
+
+    fn number(&mut self) -> Result<usize, String> {
+        let start = self.pos;
+        while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+            self.pos += 1;
+        }
+        self.chars[start..self.pos]
+            .iter()
+            .collect::<String>()
+            .parse()
+            .map_err(|_| "expected a number in `{}` quantifier".to_string())
+    }
+
+    fn atom(&mut self) -> Result<String, String> {
+        match self.next().unwrap() {
+            '(' => {
+                if self.eat('?') {
+                    match self.next() {
+                        Some(':') => {}
+                        Some(c @ ('=' | '!' | '<')) => {
+                            return Err(format!("lookaround `(?{}` is not supported", c))
+                        }
+                        _ => return Err("unsupported group syntax `(?`".to_string()),
+                    }
+                }
+                let inner = self.alternation()?;
+                if !self.eat(')') {
+                    return Err("unterminated group".to_string());
+                }
+                Ok(format!("({})", inner))
+            }
+            '[' => self.class(),
+            '.' => Ok(format!("[^{}]", JSON_EXCLUDED)),
+            '\\' => match self.next() {
+                None => Err("trailing `\\`".to_string()),
+                Some(c) => match shorthand(c) {
+                    Some((class, negated)) => Ok(class_expr(class, negated)),
+                    None if c.is_ascii_digit() => {
+                        Err(format!("backreference `\\{}` is not supported", c))
+                    }
+                    None if c == 'b' || c == 'B' => {
+                        Err(format!("word boundary `\\{}` is not supported", c))
+                    }
+                    None => Ok(literal(escaped(c))),
+                },
+            },
+            c @ ('^' | '$') => Err(format!("`{}` is only supported at the ends", c)),
+            c @ ('*' | '+' | '?' | '{') => Err(format!("nothing to repeat before `{}`", c)),
+            c => Ok(literal(c)),
+        }
+    }
+
+    fn class(&mut self) -> Result<String, String> {
+        let negated = self.eat('^');
+        let mut body = String::new();
+        let mut dash = false;
+        let mut first = true;
+
+        loop {
+            let c = match self.next() {
+                None => return Err("unterminated `[`".to_string()),
+                Some(']') if !first => break,
+                Some(c) => c,
+            };
+            first = false;
+
+            let c = match c {
+                '\\' => match self.next() {
+                    None => return Err("unterminated `[`".to_string()),
+                    Some(e) => match shorthand(e) {
+                        Some((class, false)) => {
+                            body.push_str(class);
+                            continue;
+                        }
+                        Some((_, true)) => {
+                            return Err(format!("`\\{}` inside a class is not supported", e))
+                        }
+                        None => escaped(e),
+                    },
+                },
+                c => c,
+            };
+
+            if c == '-' && (body.is_empty() || self.peek() == Some(']')) {
+                dash = true;
+                continue;
+            }
+
+            if self.peek() == Some('-') && self.chars.get(self.pos + 1) != Some(&']') {
+                self.pos += 1;
+                let end = match self.next() {
+                    Some('\\') => self.next().map(escaped),
+                    end => end,
+                }
+                .ok_or("unterminated `[`")?;
+                if end < c {
+                    return Err(format!("range `{}-{}` is out of order", c, end));
+                }
+                body.push_str(&format!("{}-{}", class_char(c)?, class_char(end)?));
+                continue;
+            }
+
+            body.push_str(&class_char(c)?);
+        }
+
+        if dash {
+            body.push('-');
+        }
+
+        // A leading `^` would negate the GBNF class.
+        if !negated && body.starts_with('^') {
+            if body == "^" {
+                return Ok(literal('^'));
+            }
+            body = format!("{}^", &body[1..]);
+        }
+
+        Ok(class_expr(&body, negated))
+    }
+}
+
+// `\d`, `\w` and `\s`, and whether they are negated.
+fn shorthand(c: char) -> Option<(&'static str, bool)> {
+    match c {
+        'd' => Some(("0-9", false)),
+        'D' => Some(("0-9", true)),
+        'w' => Some(("a-zA-Z0-9_", false)),
+        'W' => Some(("a-zA-Z0-9_", true)),
+        // Tabs and newlines would need escaping in JSON.
+        's' => Some((" ", false)),
+        'S' => Some((" ", true)),
+        _ => None,
+    }
+}
+
+fn escaped(c: char) -> char {
+    match c {
+        'n' => '\n',
+        't' => '\t',
+        'r' => '\r',
+        c => c,
+    }
+}
+
+fn class_expr(body: &str, negated: bool) -> String {
+    match negated {
+        // Negated classes must still leave out what JSON strings cannot hold unescaped.
+        true => format!("[^{}{}]", body, JSON_EXCLUDED),
+        false => format!("[{}]", body),
+    }
+}
+
+fn class_char(c: char) -> Result<String, String> {
+    match c {
+        '"' | '\\' => Err(format!(
+            "`{}` inside a class is not supported, since JSON escapes it",
+            c
+        )),
+        c if (c as u32) < 0x20 || c == '\x7F' => {
+            Err("control characters are not supported, since JSON escapes them".to_string())
+        }
+        ']' => Ok("\\]".to_string()),
+        c => Ok(c.to_string()),
+    }
+}
+
+// A GBNF literal for `c` as it appears inside a JSON string.
+fn literal(c: char) -> String {
+    let json = serde_json::to_string(&c.to_string()).unwrap();
+    crate::json_schema::literal(&json[1..json.len() - 1])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_gbnf() {
+        assert_eq!(to_gbnf("^[A-Z]{2}$").unwrap(), "[A-Z]{2}");
+        assert_eq!(to_gbnf(r"\d{3}-\d{4}").unwrap(), r#"[0-9]{3} "-" [0-9]{4}"#);
+        assert_eq!(to_gbnf("a|bc?").unwrap(), r#"("a" | "b" "c"?)"#);
+        assert_eq!(to_gbnf("[^a-c]+").unwrap(), r#"[^a-c"\\\x7F\x00-\x1F]+"#);
+        assert_eq!(to_gbnf("(?:x|y)*").unwrap(), r#"(("x" | "y"))*"#);
+        assert_eq!(to_gbnf(r#"""#).unwrap(), r#""\\\"""#);
+        assert_eq!(to_gbnf("[a-]").unwrap(), "[a-]");
+        assert_eq!(to_gbnf(r"[\^]").unwrap(), r#""^""#);
+
+        assert!(to_gbnf("(?=a)").unwrap_err().contains("lookaround"));
+        assert!(to_gbnf(r"(a)\1").unwrap_err().contains("backreference"));
+        assert!(to_gbnf("a{3,1}").unwrap_err().contains("empty"));
+    }
+}
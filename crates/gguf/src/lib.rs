@@ -19,6 +19,7 @@ pub use utils::*;
 
 pub trait GgufExt {
     fn gguf_chat_format(&self) -> Result<Option<ChatTemplate>>;
+    fn gguf_context_length(&self) -> Result<Option<u32>>;
 }
 
 impl<T: AsRef<Path>> GgufExt for T {
@@ -130,6 +131,63 @@ impl<T: AsRef<Path>> GgufExt for T {
             Ok(None)
         }
     }
+
+    // `{general.architecture}.context_length`, the number of tokens the model was trained on.
+    fn gguf_context_length(&self) -> Result<Option<u32>> {
+        let file = File::open(self.as_ref())?;
+        let map = unsafe { Mmap::map(&file)? };
+        let mut reader = Cursor::new(&map[..]);
+
+        let (version, is_little_endian) = read_header(&mut reader)?;
+
+        let _tensor_count = read_versioned_size(&mut reader, version, is_little_endian)?;
+        let metadata_kv_count = read_versioned_size(&mut reader, version, is_little_endian)?;
+
+        let mut architecture = None;
+        let mut context_lengths = Vec::new();
+
+        for _ in 0..metadata_kv_count {
+            let key = read_string(&mut reader, version, is_little_endian)?;
+
+            let value_type_raw = if is_little_endian {
+                reader.read_u32::<LittleEndian>()?
+            } else {
+                reader.read_u32::<BigEndian>()?
+            };
+            let value_type = GGUFMetadataValueType::try_from(value_type_raw)?;
+
+            match (key.as_str(), value_type) {
+                ("general.architecture", GGUFMetadataValueType::String) => {
+                    architecture = Some(read_string(&mut reader, version, is_little_endian)?);
+                }
+                (k, GGUFMetadataValueType::Uint32) if k.ends_with(".context_length") => {
+                    let value = if is_little_endian {
+                        reader.read_u32::<LittleEndian>()?
+                    } else {
+                        reader.read_u32::<BigEndian>()?
+                    };
+                    context_lengths.push((key, value));
+                }
+                (k, GGUFMetadataValueType::Uint64) if k.ends_with(".context_length") => {
+                    let value = if is_little_endian {
+                        reader.read_u64::<LittleEndian>()?
+                    } else {
+                        reader.read_u64::<BigEndian>()?
+                    };
+                    context_lengths.push((key, value.try_into().unwrap_or(u32::MAX)));
+                }
+                _ => skip_value(&mut reader, value_type, version, is_little_endian)?,
+            }
+        }
+
+        Ok(architecture.and_then(|arch| {
+            let key = format!("{}.context_length", arch);
+            context_lengths
+                .into_iter()
+                .find(|(k, _)| *k == key)
+                .map(|(_, v)| v)
+        }))
+    }
 }
 
 #[cfg(test)]
@@ -146,4 +204,10 @@ mod tests {
         assert!(test_path.exists());
         assert!(test_path.gguf_chat_format().is_ok());
     }
+
+    #[test]
+    fn test_gguf_context_length() {
+        let test_path = concat!(env!("CARGO_MANIFEST_DIR"), "/../llama/assets/tiny.gguf");
+        assert_eq!(test_path.gguf_context_length().unwrap(), Some(2048));
+    }
 }
@@ -3,6 +3,27 @@ use std::io::{Read, Seek, SeekFrom};
 
 use crate::{value::GGUFMetadataValueType, Error};
 
+// Checks the magic and returns the version and endianness, leaving the reader at the tensor count.
+pub fn read_header<R: Read + Seek>(reader: &mut R) -> Result<(u32, bool), Error> {
+    let magic = reader.read_u32::<LittleEndian>()?;
+    if magic != crate::GGUF_MAGIC {
+        return Err(Error::InvalidMagic);
+    }
+
+    let version_le = reader.read_u32::<LittleEndian>()?;
+    let (version, is_little_endian) = if version_le & 65535 != 0 {
+        (version_le, true)
+    } else {
+        (version_le.swap_bytes(), false)
+    };
+
+    if version > 3 {
+        return Err(Error::UnsupportedVersion(version));
+    }
+
+    Ok((version, is_little_endian))
+}
+
 pub fn read_versioned_size<R: Read + Seek>(
     reader: &mut R,
     version: u32,
@@ -250,6 +250,14 @@ impl TryInto<Language> for hypr_whisper::Language {
     }
 }
 
+#[cfg(feature = "whisper")]
+impl Language {
+    // Parses a language as Whisper reports it, e.g. `jw` for Javanese.
+    pub fn from_whisper_code(code: &str) -> Option<Self> {
+        code.parse::<hypr_whisper::Language>().ok()?.try_into().ok()
+    }
+}
+
 impl Language {
     pub fn iso639(&self) -> ISO639 {
         self.iso639
@@ -0,0 +1,165 @@
+use async_openai::types::ChatCompletionTool;
+use minijinja::{Environment, Error, ErrorKind};
+use serde_json::{json, Value};
+
+use crate::LlamaChatMessage;
+
+const NAME: &str = "chat";
+
+// The model's own Jinja template, as Hugging Face renders it. The templates built into llama.cpp
+// don't know about tools, so prompts with tools, tool calls or tool results go through this one.
+pub(crate) struct JinjaTemplate {
+    env: Environment<'static>,
+    eos_token: String,
+}
+
+impl JinjaTemplate {
+    pub fn new(source: String, eos_token: String) -> Result<Self, crate::Error> {
+        let mut env = Environment::new();
+        env.set_trim_blocks(true);
+        env.set_lstrip_blocks(true);
+        env.set_unknown_method_callback(minijinja_contrib::pycompat::unknown_method_callback);
+
+        env.add_function(
+            "raise_exception",
+            |message: String| -> Result<String, Error> {
+                Err(Error::new(ErrorKind::InvalidOperation, message))
+            },
+        );
+        // Llama 3 templates put the date in the system prompt.
+        env.add_function("strftime_now", |format: String| {
+            chrono::Local::now().format(&format).to_string()
+        });
+
+        env.add_template_owned(NAME, source)
+            .map_err(|e| crate::Error::ChatTemplateError(e.to_string()))?;
+
+        Ok(Self { env, eos_token })
+    }
+
+    // The prompt, with the generation prompt appended. The BOS token is left to the tokenizer.
+    pub fn render(
+        &self,
+        messages: &[LlamaChatMessage],
+        tools: &[ChatCompletionTool],
+    ) -> Result<String, crate::Error> {
+        let messages = messages.iter().map(message).collect::<Vec<_>>();
+
+        let ctx = json!({
+            "messages": messages,
+            // Templates check whether tools are defined, rather than whether there are any.
+            "tools": if tools.is_empty() { Value::Null } else { json!(tools) },
+            "add_generation_prompt": true,
+            "bos_token": "",
+            "eos_token": self.eos_token,
+        });
+
+        self.env
+            .get_template(NAME)
+            .and_then(|tpl| tpl.render(ctx))
+            .map_err(|e| crate::Error::ChatTemplateError(e.to_string()))
+    }
+}
+
+// Templates look for `tool_calls` with `in`, so it is only set when there are any. Arguments are
+// objects there, unlike in the OpenAI API.
+fn message(message: &LlamaChatMessage) -> Value {
+    let mut value = json!({
+        "role": message.role,
+        "content": message.content,
+    });
+
+    if !message.tool_calls.is_empty() {
+        value["tool_calls"] = message
+            .tool_calls
+            .iter()
+            .map(|call| {
+                json!({
+                    "id": call.id,
+                    "type": "function",
+                    "function": {
+                        "name": call.name,
+                        "arguments": serde_json::from_str::<Value>(&call.arguments)
+                            .unwrap_or_else(|_| Value::String(call.arguments.clone())),
+                    },
+                })
+            })
+            .collect();
+    }
+    if let Some(id) = &message.tool_call_id {
+        value["tool_call_id"] = json!(id);
+    }
+
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LlamaToolCall;
+
+    // The tool parts of the Llama 3.2 template, cut down.
+    const TEMPLATE: &str = r#"{{- bos_token }}
+{%- if tools is not none %}
+{{- "Tools: " }}
+{%- for t in tools %}
+{{- t | tojson }}
+{%- endfor %}
+{{- "\n" }}
+{%- endif %}
+{%- for message in messages %}
+{%- if 'tool_calls' in message %}
+{%- set tool_call = message.tool_calls[0].function %}
+{{- '<|' + message.role + '|>{"name": "' + tool_call.name + '", "parameters": ' + tool_call.arguments | tojson + '}<|eot_id|>' }}
+{%- elif message.role == "tool" %}
+{{- "<|ipython|>" + message.content.strip() + "<|eot_id|>" }}
+{%- else %}
+{{- '<|' + message.role + '|>' + message.content | trim + '<|eot_id|>' }}
+{%- endif %}
+{%- endfor %}
+{%- if add_generation_prompt %}
+{{- '<|assistant|>' }}
+{%- endif %}"#;
+
+    #[test]
+    fn test_render() {
+        let tpl = JinjaTemplate::new(TEMPLATE.to_string(), "<|eot_id|>".to_string()).unwrap();
+
+        let tools: Vec<ChatCompletionTool> = serde_json::from_value(json!([{
+            "type": "function",
+            "function": {
+                "name": "get_weather",
+                "parameters": { "type": "object", "properties": { "city": { "type": "string" } } },
+            },
+        }]))
+        .unwrap();
+
+        let mut call = LlamaChatMessage::new("assistant".into(), "".into()).unwrap();
+        call.tool_calls = vec![LlamaToolCall {
+            id: "call_1".into(),
+            name: "get_weather".into(),
+            arguments: r#"{"city":"Seoul"}"#.into(),
+        }];
+        let mut result = LlamaChatMessage::new("tool".into(), " sunny ".into()).unwrap();
+        result.tool_call_id = Some("call_1".into());
+
+        let messages = vec![
+            LlamaChatMessage::new("user".into(), "Weather in Seoul?".into()).unwrap(),
+            call,
+            result,
+        ];
+
+        let prompt = tpl.render(&messages, &tools).unwrap();
+        assert!(prompt.starts_with("Tools: {"));
+        assert!(prompt.contains(r#""name":"get_weather""#));
+        assert!(prompt.ends_with(concat!(
+            "<|user|>Weather in Seoul?<|eot_id|>",
+            r#"<|assistant|>{"name": "get_weather", "parameters": {"city":"Seoul"}}<|eot_id|>"#,
+            "<|ipython|>sunny<|eot_id|>",
+            "<|assistant|>",
+        )));
+
+        let prompt = tpl.render(&messages[..1], &[]).unwrap();
+        assert_eq!(prompt, "<|user|>Weather in Seoul?<|eot_id|><|assistant|>");
+    }
+}
@@ -0,0 +1,244 @@
+use crate::LlamaChatMessage;
+
+// Stands in for the text cut out of a message.
+const MARKER: &str = "\n\n[...]\n\n";
+// Token counts per character vary, so cuts are re-checked this many times before giving up.
+const MAX_ATTEMPTS: usize = 8;
+
+// What was dropped to make a prompt fit the context window.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Truncation {
+    pub prompt_tokens: usize,
+    pub kept_tokens: usize,
+    pub dropped_messages: usize,
+}
+
+pub(crate) struct Fitted<T> {
+    pub messages: Vec<LlamaChatMessage>,
+    pub tokens: Vec<T>,
+    pub truncation: Option<Truncation>,
+}
+
+// Fits `messages` into `budget` tokens, middle-out. Turns between the leading system message and
+// the final turn are dropped first, oldest first. Then the middle of the longest remaining
+// non-system message is cut out, which keeps the system prompt, and the start and end of the final
+// turn, where instructions usually are.
+//
+// `prompt` renders and tokenizes the whole prompt, and `count` the content of a single message.
+// Trimming works on per-message counts, each taken once, and the whole prompt is only tokenized
+// again to confirm the result. Its tokens are returned along with the messages.
+pub(crate) fn fit<T>(
+    mut messages: Vec<LlamaChatMessage>,
+    budget: usize,
+    prompt: impl Fn(&[LlamaChatMessage]) -> Result<Vec<T>, crate::Error>,
+    count: impl Fn(&str) -> Result<usize, crate::Error>,
+) -> Result<Fitted<T>, crate::Error> {
+    let mut tokens = prompt(&messages)?;
+    let prompt_tokens = tokens.len();
+    if prompt_tokens <= budget {
+        return Ok(Fitted {
+            messages,
+            tokens,
+            truncation: None,
+        });
+    }
+
+    let mut counts = messages
+        .iter()
+        .map(|m| count(&m.content))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // What the template adds around the contents is spread evenly over the messages, so dropping
+    // one also drops its share. The rest stays with the prompt.
+    let overhead = prompt_tokens.saturating_sub(counts.iter().sum());
+    let share = overhead / messages.len().max(1);
+    counts.iter_mut().for_each(|c| *c += share);
+    let mut overhead = overhead - share * messages.len();
+
+    let first = match messages.first() {
+        Some(m) if m.role == "system" => 1,
+        _ => 0,
+    };
+
+    let mut dropped_messages = 0;
+    let mut attempts = 0;
+    loop {
+        let mut estimate = overhead + counts.iter().sum::<usize>();
+
+        while estimate > budget && messages.len() > first + 1 {
+            messages.remove(first);
+            estimate -= counts.remove(first);
+            dropped_messages += 1;
+        }
+
+        while estimate > budget {
+            attempts += 1;
+
+            let target = messages
+                .iter()
+                .enumerate()
+                .skip(first)
+                .max_by_key(|(_, m)| m.content.len())
+                .map(|(i, _)| i);
+
+            let cut = match target {
+                Some(i) if attempts <= MAX_ATTEMPTS => {
+                    let chars = messages.iter().map(|m| m.content.len()).sum::<usize>();
+                    // Overshoot a little, since the estimate is rough.
+                    let bytes_per_token = chars as f64 / estimate as f64;
+                    let excess =
+                        ((estimate - budget) as f64 * bytes_per_token * 1.1).ceil() as usize;
+                    cut_middle(&messages[i].content, excess + MARKER.len()).map(|c| (i, c))
+                }
+                _ => None,
+            };
+
+            let (i, content) = cut.ok_or(crate::Error::ContextLengthExceeded {
+                tokens: prompt_tokens,
+                limit: budget,
+            })?;
+
+            let n = count(&content)? + share;
+            estimate = estimate - counts[i] + n;
+            counts[i] = n;
+            messages[i].content = content;
+        }
+
+        tokens = prompt(&messages)?;
+        if tokens.len() <= budget {
+            break;
+        }
+
+        // Tokens merge differently around the cuts, so the estimate came out short. What it
+        // missed is added to the overhead, and trimming goes on.
+        overhead += tokens.len() - estimate;
+    }
+
+    let kept_tokens = tokens.len();
+    Ok(Fitted {
+        messages,
+        tokens,
+        truncation: Some(Truncation {
+            prompt_tokens,
+            kept_tokens,
+            dropped_messages,
+        }),
+    })
+}
+
+// Removes about `len` bytes from the middle of `text`, preferring line breaks as cut points.
+fn cut_middle(text: &str, len: usize) -> Option<String> {
+    if len + MARKER.len() >= text.len() {
+        return None;
+    }
+
+    let floor = |mut i: usize| {
+        while !text.is_char_boundary(i) {
+            i -= 1;
+        }
+        i
+    };
+    let ceil = |mut i: usize| {
+        while !text.is_char_boundary(i) {
+            i += 1;
+        }
+        i
+    };
+
+    let start = floor((text.len() - len) / 2);
+    let end = ceil(start + len);
+
+    // Widen to whole lines when there is a line break close enough.
+    let slack = len / 2;
+    let bytes = text.as_bytes();
+    let start = match bytes[..=start].iter().rposition(|&b| b == b'\n') {
+        Some(i) if start - i <= slack => i,
+        _ => start,
+    };
+    let end = match bytes[end - 1..].iter().position(|&b| b == b'\n') {
+        Some(i) if i <= slack => end + i,
+        _ => end,
+    };
+
+    Some(format!("{}{}{}", &text[..start], MARKER, &text[end..]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(role: &str, content: impl Into<String>) -> LlamaChatMessage {
+        LlamaChatMessage::new(role.into(), content.into()).unwrap()
+    }
+
+    // One token per word.
+    fn count(text: &str) -> Result<usize, crate::Error> {
+        Ok(text.split_whitespace().count())
+    }
+
+    // And a few for each message's template overhead.
+    fn prompt(messages: &[LlamaChatMessage]) -> Result<Vec<()>, crate::Error> {
+        let mut n = 0;
+        for m in messages {
+            n += 4 + count(&m.content)?;
+        }
+        Ok(vec![(); n])
+    }
+
+    #[test]
+    fn test_fit() {
+        let short = vec![message("system", "be brief"), message("user", "hello")];
+        assert_eq!(fit(short, 100, prompt, count).unwrap().truncation, None);
+
+        let transcript = (0..500)
+            .map(|i| format!("line {}", i))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let messages = vec![
+            message("system", "be brief"),
+            message("user", "first question"),
+            message("assistant", "first answer"),
+            message(
+                "user",
+                format!("<transcript>\n{}\n</transcript>\nSummarize.", transcript),
+            ),
+        ];
+
+        let Fitted {
+            messages: fitted,
+            tokens,
+            truncation,
+        } = fit(messages, 300, prompt, count).unwrap();
+        let truncation = truncation.unwrap();
+        assert_eq!(truncation.dropped_messages, 2);
+        assert!(truncation.kept_tokens <= 300);
+        assert_eq!(truncation.kept_tokens, tokens.len());
+        assert_eq!(truncation.kept_tokens, prompt(&fitted).unwrap().len());
+
+        assert_eq!(fitted.len(), 2);
+        assert_eq!(fitted[0].content, "be brief");
+        let last = &fitted[1].content;
+        assert!(last.starts_with("<transcript>\nline 0\n"));
+        assert!(last.ends_with("line 499\n</transcript>\nSummarize."));
+        assert!(last.contains("\n[...]\n"));
+
+        assert!(matches!(
+            fit(vec![message("system", "be brief")], 2, prompt, count),
+            Err(crate::Error::ContextLengthExceeded { .. })
+        ));
+    }
+
+    #[test]
+    fn test_cut_middle() {
+        assert_eq!(cut_middle("abc", 10), None);
+        assert!(cut_middle("안녕하세요 반갑습니다 여러분", 10).is_some());
+        assert_eq!(
+            cut_middle(
+                "aaaa\nbbbb\ncccc\ndddd\neeee\nffff\ngggg\nhhhh\niiii\njjjj",
+                12
+            )
+            .unwrap(),
+            "aaaa\nbbbb\ncccc\n\n[...]\n\ngggg\nhhhh\niiii\njjjj"
+        );
+    }
+}
@@ -19,7 +19,27 @@ pub enum Error {
     #[error(transparent)]
     DecodeError(#[from] llama_cpp_2::DecodeError),
     #[error(transparent)]
-    TaskSendError(#[from] tokio::sync::mpsc::error::SendError<crate::Task>),
+    EmbeddingsError(#[from] llama_cpp_2::EmbeddingsError),
+    #[error(transparent)]
+    GbnfError(#[from] hypr_gbnf::Error),
+    #[error("chat template error: {0}")]
+    ChatTemplateError(String),
+    #[error("unsupported message: {0}")]
+    UnsupportedMessage(String),
+    #[error("model has no chat template")]
+    MissingChatTemplate,
+    #[error("prompt is empty")]
+    EmptyPrompt,
+    #[error("prompt has {tokens} tokens and can't be cut to fit the {limit} token context window")]
+    ContextLengthExceeded { tokens: usize, limit: usize },
+    #[error("too many requests are waiting for the model")]
+    QueueFull,
+    #[error("model worker has stopped")]
+    WorkerStopped,
+    #[error("model worker panicked")]
+    WorkerPanicked,
+    #[error("generation timed out")]
+    Timeout,
 }
 
 impl Serialize for Error {
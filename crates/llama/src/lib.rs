@@ -1,43 +1,88 @@
-use std::sync::{Arc, OnceLock};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::task::{Context, Poll};
+use std::time::Instant;
 
+use futures_util::StreamExt;
 use llama_cpp_2::{
-    context::params::LlamaContextParams,
+    context::params::{LlamaContextParams, LlamaPoolingType},
     llama_backend::LlamaBackend,
     llama_batch::LlamaBatch,
     model::{params::LlamaModelParams, AddBos, LlamaChatTemplate, LlamaModel, Special},
-    sampling::LlamaSampler,
-    send_logs_to_tracing, LogOptions,
+    send_logs_to_tracing,
+    token::LlamaToken,
+    LogOptions,
 };
+use tokio::sync::mpsc::{error::TrySendError, UnboundedSender};
 use tokio_stream::wrappers::UnboundedReceiverStream;
+use tokio_util::sync::CancellationToken;
 
 use hypr_gguf::GgufExt;
 
+mod chat_template;
+mod context;
 mod error;
+mod sampling;
+mod stop;
 mod stream;
+mod tools;
 mod types;
 
+pub use context::Truncation;
 pub use error::*;
 pub use stream::filter_tag;
+pub use tools::{may_be_tool_call, parse_tool_call};
 pub use types::*;
 
-const DEFAULT_MAX_INPUT_TOKENS: u32 = 1024 * 8;
+// For models whose GGUF metadata has no context length.
+const DEFAULT_CONTEXT_LENGTH: u32 = 1024 * 4;
+// Caps what the model supports, to bound the memory a context takes.
+const MAX_CONTEXT_LENGTH: u32 = 1024 * 16;
 const DEFAULT_MAX_OUTPUT_TOKENS: u32 = 1024;
+// Requests waiting behind the one being generated. Beyond this, `generate_stream` fails with `QueueFull`.
+const DEFAULT_QUEUE_SIZE: usize = 4;
 
 static LLAMA_BACKEND: OnceLock<Arc<LlamaBackend>> = OnceLock::new();
 
 pub struct Llama {
-    task_sender: tokio::sync::mpsc::UnboundedSender<Task>,
+    task_sender: tokio::sync::mpsc::Sender<Task>,
+    model: Arc<LlamaModel>,
+    template: Arc<Template>,
+    context_length: u32,
+}
+
+// Renders and tokenizes prompts. Shared with blocking tasks, since that takes a while for long ones.
+struct Template {
+    model: Arc<LlamaModel>,
+    tpl: LlamaChatTemplate,
+    // Unset when the model has no Jinja template of its own, or one that can't be parsed.
+    jinja: Option<chat_template::JinjaTemplate>,
 }
 
 pub enum Task {
     Generate {
         request: LlamaRequest,
-        response_sender: tokio::sync::mpsc::UnboundedSender<String>,
+        tokens: Vec<LlamaToken>,
+        max_tokens: u32,
+        response_sender: UnboundedSender<Result<String, crate::Error>>,
+        cancellation_token: CancellationToken,
+        deadline: Option<Instant>,
+    },
+    Embed {
+        inputs: Vec<Vec<LlamaToken>>,
+        response_sender: tokio::sync::oneshot::Sender<Result<Vec<Vec<f32>>, crate::Error>>,
     },
 }
 
 impl Llama {
     pub fn new(model_path: impl AsRef<std::path::Path>) -> Result<Self, crate::Error> {
+        Self::with_queue_size(model_path, DEFAULT_QUEUE_SIZE)
+    }
+
+    pub fn with_queue_size(
+        model_path: impl AsRef<std::path::Path>,
+        queue_size: usize,
+    ) -> Result<Self, crate::Error> {
         send_logs_to_tracing(LogOptions::default().with_logs_enabled(false));
 
         let backend = LLAMA_BACKEND
@@ -47,133 +92,482 @@ impl Llama {
             })
             .clone();
 
-        let fmt = model_path.gguf_chat_format()?.unwrap();
-        let tpl = LlamaChatTemplate::new(fmt.as_ref()).unwrap();
+        let fmt = model_path
+            .gguf_chat_format()?
+            .ok_or(crate::Error::MissingChatTemplate)?;
+        let tpl = LlamaChatTemplate::new(fmt.as_ref())
+            .map_err(|e| crate::Error::ChatTemplateError(e.to_string()))?;
+
+        let context_length = context_length(&model_path)?;
 
         let params = LlamaModelParams::default();
-        let model = LlamaModel::load_from_file(&backend, model_path, &params)?;
+        let model = Arc::new(LlamaModel::load_from_file(&backend, model_path, &params)?);
+
+        let jinja = match fmt {
+            hypr_gguf::ChatTemplate::TemplateValue(source) => {
+                let eos_token = model.token_to_bytes(model.token_eos(), Special::Tokenize)?;
+                chat_template::JinjaTemplate::new(
+                    source,
+                    String::from_utf8_lossy(&eos_token).into_owned(),
+                )
+                .inspect_err(|e| tracing::warn!("llama_jinja_template_unsupported: {}", e))
+                .ok()
+            }
+            hypr_gguf::ChatTemplate::TemplateKey(_) => None,
+        };
 
-        let (task_sender, mut task_receiver) = tokio::sync::mpsc::unbounded_channel::<Task>();
+        let (task_sender, mut task_receiver) =
+            tokio::sync::mpsc::channel::<Task>(queue_size.max(1));
 
         std::thread::spawn({
+            let model = model.clone();
+
             move || {
                 while let Some(task) = task_receiver.blocking_recv() {
                     match task {
                         Task::Generate {
                             request,
+                            tokens,
+                            max_tokens,
                             response_sender,
+                            cancellation_token,
+                            deadline,
                         } => {
-                            let prompt = model
-                                .apply_chat_template(&tpl, &request.messages, true)
-                                .unwrap();
-
-                            let mut ctx = model
-                                .new_context(
-                                    &backend,
-                                    // https://github.com/ggml-org/llama.cpp/blob/492d7f1/src/llama-context.cpp#L2261
-                                    LlamaContextParams::default()
-                                        .with_n_ctx(std::num::NonZeroU32::new(
-                                            DEFAULT_MAX_INPUT_TOKENS + DEFAULT_MAX_OUTPUT_TOKENS,
-                                        ))
-                                        .with_n_batch(DEFAULT_MAX_INPUT_TOKENS)
-                                        .with_n_ubatch(512)
-                                        .with_embeddings(false)
-                                        .with_flash_attention(true),
-                                )
-                                .unwrap();
-
-                            let mut tokens_list =
-                                model.str_to_token(&prompt, AddBos::Always).unwrap();
-                            tokens_list.truncate(DEFAULT_MAX_INPUT_TOKENS as usize);
-
-                            let batch_size = tokens_list.len().max(512);
-                            let mut batch = LlamaBatch::new(batch_size, 1);
-
-                            let last_index = (tokens_list.len() - 1) as i32;
-                            for (i, token) in (0_i32..).zip(tokens_list.into_iter()) {
-                                let is_last = i == last_index;
-                                batch.add(token, i, &[0], is_last).unwrap();
+                            // Nobody is waiting for it anymore.
+                            if cancellation_token.is_cancelled() || response_sender.is_closed() {
+                                continue;
                             }
 
-                            ctx.decode(&mut batch).unwrap();
-
-                            let mut n_cur = batch.n_tokens();
-                            let mut decoder = encoding_rs::UTF_8.new_decoder();
-
-                            let mut sampler = match request.grammar {
-                                Some(grammar) => LlamaSampler::chain_simple([
-                                    LlamaSampler::grammar(&model, grammar.as_str(), "root"),
-                                    LlamaSampler::temp(0.8),
-                                    LlamaSampler::penalties(0, 1.4, 0.1, 0.0),
-                                    LlamaSampler::mirostat_v2(1234, 3.0, 0.2),
-                                ]),
-                                None => LlamaSampler::chain_simple([
-                                    LlamaSampler::temp(0.8),
-                                    LlamaSampler::penalties(0, 1.4, 0.1, 0.0),
-                                    LlamaSampler::mirostat_v2(1234, 3.0, 0.2),
-                                ]),
+                            // A failed or panicking request must not take the worker, and every request after it, down.
+                            let result =
+                                std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                                    generate(
+                                        &backend,
+                                        &model,
+                                        request,
+                                        tokens,
+                                        max_tokens,
+                                        &response_sender,
+                                        &cancellation_token,
+                                        deadline,
+                                    )
+                                }));
+
+                            let error = match result {
+                                Ok(Ok(())) => continue,
+                                Ok(Err(e)) => e,
+                                Err(_) => crate::Error::WorkerPanicked,
                             };
-                            while n_cur <= last_index + DEFAULT_MAX_OUTPUT_TOKENS as i32 {
-                                let token = sampler.sample(&ctx, batch.n_tokens() - 1);
-
-                                if model.is_eog_token(token) {
-                                    break;
-                                }
-
-                                let output_bytes =
-                                    model.token_to_bytes(token, Special::Tokenize).unwrap();
-                                let mut output_string = String::with_capacity(32);
-                                let _decode_result = decoder.decode_to_string(
-                                    &output_bytes,
-                                    &mut output_string,
-                                    false,
-                                );
-
-                                if response_sender.send(output_string).is_err() {
-                                    break;
-                                }
-
-                                batch.clear();
-                                batch.add(token, n_cur, &[0], true).unwrap();
-
-                                n_cur += 1;
-                                ctx.decode(&mut batch).unwrap();
+                            tracing::error!("llama_generate_failed: {}", error);
+                            let _ = response_sender.send(Err(error));
+                        }
+                        Task::Embed {
+                            inputs,
+                            response_sender,
+                        } => {
+                            if response_sender.is_closed() {
+                                continue;
                             }
 
-                            drop(response_sender);
+                            let result =
+                                std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                                    embed(&backend, &model, inputs)
+                                }))
+                                .unwrap_or(Err(crate::Error::WorkerPanicked));
+
+                            if let Err(e) = &result {
+                                tracing::error!("llama_embed_failed: {}", e);
+                            }
+                            let _ = response_sender.send(result);
                         }
                     }
                 }
             }
         });
 
-        Ok(Self { task_sender })
+        Ok(Self {
+            task_sender,
+            template: Arc::new(Template {
+                model: model.clone(),
+                tpl,
+                jinja,
+            }),
+            model,
+            context_length,
+        })
+    }
+
+    // Requests waiting for the worker, not counting the one it is generating.
+    pub fn queue_len(&self) -> usize {
+        self.task_sender.max_capacity() - self.task_sender.capacity()
     }
 
-    pub fn generate_stream(
+    pub async fn generate_stream(
         &self,
         request: LlamaRequest,
-    ) -> Result<impl futures_util::Stream<Item = String>, crate::Error> {
-        let (response_sender, response_receiver) = tokio::sync::mpsc::unbounded_channel::<String>();
+    ) -> Result<LlamaStream, crate::Error> {
+        self.generate_stream_with_cancellation(request, CancellationToken::new())
+            .await
+    }
+
+    // Generation stops when `cancellation_token` is cancelled, or as soon as the returned stream is dropped,
+    // e.g. when an HTTP client disconnects.
+    pub async fn generate_stream_with_cancellation(
+        &self,
+        mut request: LlamaRequest,
+        cancellation_token: CancellationToken,
+    ) -> Result<LlamaStream, crate::Error> {
+        // Half the window at most goes to the output, so a large `max_tokens` can't crowd out the prompt.
+        let max_tokens = request
+            .max_tokens
+            .unwrap_or(DEFAULT_MAX_OUTPUT_TOKENS)
+            .min(self.context_length / 2);
+        let budget = (self.context_length - max_tokens) as usize;
+
+        // With `tool_choice: none`, the model isn't told about the tools at all.
+        let tools = match request.tool_choice {
+            Some(ChatCompletionToolChoiceOption::None) => vec![],
+            _ => request.tools.clone(),
+        };
+
+        // Tokenizing a long prompt, and cutting it down, would stall the async runtime.
+        let template = self.template.clone();
+        let messages = std::mem::take(&mut request.messages);
+        let context::Fitted {
+            tokens, truncation, ..
+        } = tokio::task::spawn_blocking(move || {
+            context::fit(
+                messages,
+                budget,
+                |messages| template.tokenize(messages, &tools),
+                |text| template.count(text),
+            )
+        })
+        .await
+        .map_err(|_| crate::Error::WorkerPanicked)??;
+
+        if let Some(truncation) = &truncation {
+            tracing::warn!(
+                "llama_prompt_truncated: {} -> {} tokens, {} messages dropped",
+                truncation.prompt_tokens,
+                truncation.kept_tokens,
+                truncation.dropped_messages
+            );
+        }
+
+        if tokens.is_empty() {
+            return Err(crate::Error::EmptyPrompt);
+        }
+
+        // Built here rather than on the worker, so a tool schema that can't be compiled fails the request up front.
+        request.grammar = request.grammar()?;
+
+        let (response_sender, response_receiver) =
+            tokio::sync::mpsc::unbounded_channel::<Result<String, crate::Error>>();
+
+        let cancellation_token = cancellation_token.child_token();
+        let deadline = request.timeout.map(|timeout| Instant::now() + timeout);
 
         let task = Task::Generate {
             request,
+            tokens,
+            max_tokens,
             response_sender,
+            cancellation_token: cancellation_token.clone(),
+            deadline,
         };
 
-        self.task_sender.send(task)?;
+        self.task_sender.try_send(task).map_err(|e| match e {
+            TrySendError::Full(_) => crate::Error::QueueFull,
+            TrySendError::Closed(_) => crate::Error::WorkerStopped,
+        })?;
+
+        let guard = cancellation_token.drop_guard();
+
+        // Text goes through the tag filter, and an error, which always ends generation, comes last.
+        let error = Arc::new(Mutex::new(None));
+        let text =
+            UnboundedReceiverStream::new(response_receiver).scan(error.clone(), |error, item| {
+                futures_util::future::ready(match item {
+                    Ok(text) => Some(text),
+                    Err(e) => {
+                        *error.lock().unwrap() = Some(e);
+                        None
+                    }
+                })
+            });
+
+        let rest = futures_util::stream::once(async move {
+            drop(guard);
+            error.lock().unwrap().take()
+        })
+        .filter_map(|error| futures_util::future::ready(error.map(Err)));
+
+        Ok(LlamaStream {
+            inner: Box::pin(
+                stream::filter_tag(Box::pin(text), "headers")
+                    .map(Ok)
+                    .chain(rest),
+            ),
+            truncation,
+        })
+    }
+
+    // One L2-normalized vector per text, mean-pooled over the chat model's hidden states, so the
+    // dot product of two is their cosine similarity. Texts longer than the context window are cut.
+    pub async fn embed(&self, texts: Vec<String>) -> Result<Vec<Vec<f32>>, crate::Error> {
+        let limit = self.context_length as usize;
+        let model = self.model.clone();
+
+        // Tokenizing a batch of long texts takes a while, so it stays off the async runtime.
+        let inputs = tokio::task::spawn_blocking(move || {
+            texts
+                .iter()
+                .map(|text| {
+                    let mut tokens = model.str_to_token(text, AddBos::Always)?;
+                    if tokens.len() > limit {
+                        tracing::warn!(
+                            "llama_embed_truncated: {} -> {} tokens",
+                            tokens.len(),
+                            limit
+                        );
+                        tokens.truncate(limit);
+                    }
+                    Ok(tokens)
+                })
+                .collect::<Result<Vec<_>, crate::Error>>()
+        })
+        .await
+        .map_err(|_| crate::Error::WorkerPanicked)??;
+
+        if inputs.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let (response_sender, response_receiver) = tokio::sync::oneshot::channel();
+
+        self.task_sender
+            .try_send(Task::Embed {
+                inputs,
+                response_sender,
+            })
+            .map_err(|e| match e {
+                TrySendError::Full(_) => crate::Error::QueueFull,
+                TrySendError::Closed(_) => crate::Error::WorkerStopped,
+            })?;
+
+        response_receiver
+            .await
+            .map_err(|_| crate::Error::WorkerStopped)?
+    }
+}
+
+impl Template {
+    // The templated prompt, with the generation prompt appended, as tokens.
+    fn tokenize(
+        &self,
+        messages: &[LlamaChatMessage],
+        tools: &[ChatCompletionTool],
+    ) -> Result<Vec<LlamaToken>, crate::Error> {
+        let uses_tools = !tools.is_empty()
+            || messages
+                .iter()
+                .any(|m| m.role == "tool" || !m.tool_calls.is_empty());
+
+        if uses_tools {
+            let jinja = self.jinja.as_ref().ok_or_else(|| {
+                crate::Error::ChatTemplateError(
+                    "the model's chat template can't render tools".to_string(),
+                )
+            })?;
+            let prompt = jinja.render(messages, tools)?;
+            return Ok(self.model.str_to_token(&prompt, AddBos::Always)?);
+        }
+
+        let messages = messages
+            .iter()
+            .map(|m| llama_cpp_2::model::LlamaChatMessage::new(m.role.clone(), m.content.clone()))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| crate::Error::ChatTemplateError(e.to_string()))?;
+
+        let prompt = self
+            .model
+            .apply_chat_template(&self.tpl, &messages, true)
+            .map_err(|e| crate::Error::ChatTemplateError(e.to_string()))?;
+
+        Ok(self.model.str_to_token(&prompt, AddBos::Always)?)
+    }
+
+    // Tokens in `text` on its own, without the template around it.
+    fn count(&self, text: &str) -> Result<usize, crate::Error> {
+        Ok(self.model.str_to_token(text, AddBos::Never)?.len())
+    }
+}
+
+// Tokens of prompt and output that `Llama` fits into one context for the model at `model_path`.
+pub fn context_length(model_path: impl AsRef<std::path::Path>) -> Result<u32, crate::Error> {
+    Ok(model_path
+        .as_ref()
+        .gguf_context_length()?
+        .unwrap_or(DEFAULT_CONTEXT_LENGTH)
+        .min(MAX_CONTEXT_LENGTH))
+}
+
+pub struct LlamaStream {
+    inner: Pin<Box<dyn futures_util::Stream<Item = Result<String, crate::Error>> + Send>>,
+    // Set when the prompt had to be cut to fit the context window.
+    pub truncation: Option<Truncation>,
+}
 
-        let stream = UnboundedReceiverStream::new(response_receiver);
+impl futures_util::Stream for LlamaStream {
+    type Item = Result<String, crate::Error>;
 
-        Ok(stream::filter_tag(Box::pin(stream), "headers"))
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.inner.as_mut().poll_next(cx)
     }
 }
 
+fn generate(
+    backend: &LlamaBackend,
+    model: &LlamaModel,
+    request: LlamaRequest,
+    tokens: Vec<LlamaToken>,
+    max_tokens: u32,
+    response_sender: &UnboundedSender<Result<String, crate::Error>>,
+    cancellation_token: &CancellationToken,
+    deadline: Option<Instant>,
+) -> Result<(), crate::Error> {
+    let is_expired = || deadline.is_some_and(|deadline| Instant::now() >= deadline);
+    if is_expired() {
+        return Err(crate::Error::Timeout);
+    }
+
+    // The prompt was fitted to the model's context window, so it is decoded in one batch.
+    let n_prompt = tokens.len() as u32;
+    let mut ctx = model.new_context(
+        backend,
+        // https://github.com/ggml-org/llama.cpp/blob/492d7f1/src/llama-context.cpp#L2261
+        LlamaContextParams::default()
+            .with_n_ctx(std::num::NonZeroU32::new(n_prompt + max_tokens))
+            .with_n_batch(n_prompt.max(512))
+            .with_n_ubatch(512)
+            .with_embeddings(false)
+            .with_flash_attention(true),
+    )?;
+
+    let batch_size = tokens.len().max(512);
+    let mut batch = LlamaBatch::new(batch_size, 1);
+
+    let last_index = (tokens.len() - 1) as i32;
+    for (i, token) in (0_i32..).zip(tokens.into_iter()) {
+        let is_last = i == last_index;
+        batch.add(token, i, &[0], is_last)?;
+    }
+
+    ctx.decode(&mut batch)?;
+
+    let mut n_cur = batch.n_tokens();
+    let mut decoder = encoding_rs::UTF_8.new_decoder();
+
+    let mut sampler = request.sampler(model);
+    let mut stop = stop::StopSequences::new(request.stop);
+
+    while n_cur <= last_index + max_tokens as i32 {
+        if cancellation_token.is_cancelled() || response_sender.is_closed() {
+            return Ok(());
+        }
+        if is_expired() {
+            return Err(crate::Error::Timeout);
+        }
+
+        let token = sampler.sample(&ctx, batch.n_tokens() - 1);
+
+        if model.is_eog_token(token) {
+            break;
+        }
+
+        let output_bytes = model.token_to_bytes(token, Special::Tokenize)?;
+        let mut output_string = String::with_capacity(32);
+        let _decode_result = decoder.decode_to_string(&output_bytes, &mut output_string, false);
+
+        let output_string = match stop.push(&output_string) {
+            stop::StopResult::Continue(text) => text,
+            stop::StopResult::Stop(text) => {
+                let _ = response_sender.send(Ok(text));
+                return Ok(());
+            }
+        };
+
+        if !output_string.is_empty() && response_sender.send(Ok(output_string)).is_err() {
+            return Ok(());
+        }
+
+        batch.clear();
+        batch.add(token, n_cur, &[0], true)?;
+
+        n_cur += 1;
+        ctx.decode(&mut batch)?;
+    }
+
+    let rest = stop.flush();
+    if !rest.is_empty() {
+        let _ = response_sender.send(Ok(rest));
+    }
+
+    Ok(())
+}
+
+fn embed(
+    backend: &LlamaBackend,
+    model: &LlamaModel,
+    inputs: Vec<Vec<LlamaToken>>,
+) -> Result<Vec<Vec<f32>>, crate::Error> {
+    // Pooling needs each input in a single micro-batch.
+    let n_max = inputs
+        .iter()
+        .map(|tokens| tokens.len())
+        .max()
+        .unwrap_or(1)
+        .max(1) as u32;
+    let mut ctx = model.new_context(
+        backend,
+        LlamaContextParams::default()
+            .with_n_ctx(std::num::NonZeroU32::new(n_max))
+            .with_n_batch(n_max)
+            .with_n_ubatch(n_max)
+            .with_embeddings(true)
+            .with_pooling_type(LlamaPoolingType::Mean),
+    )?;
+
+    let mut batch = LlamaBatch::new(n_max as usize, 1);
+    let mut embeddings = Vec::with_capacity(inputs.len());
+
+    for tokens in inputs {
+        batch.clear();
+        for (i, token) in (0_i32..).zip(tokens.into_iter()) {
+            batch.add(token, i, &[0], true)?;
+        }
+
+        ctx.clear_kv_cache();
+        ctx.decode(&mut batch)?;
+
+        let embedding = ctx.embeddings_seq_ith(0)?;
+        let norm = embedding.iter().map(|v| v * v).sum::<f32>().sqrt();
+        embeddings.push(
+            embedding
+                .iter()
+                .map(|v| if norm > 0.0 { v / norm } else { 0.0 })
+                .collect(),
+        );
+    }
+
+    Ok(embeddings)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use futures_util::StreamExt;
-    use llama_cpp_2::model::LlamaChatMessage;
 
     macro_rules! init_timeline {
         ($module:ident) => {{
@@ -197,12 +591,13 @@ mod tests {
         use futures_util::pin_mut;
         use std::io::{self, Write};
 
-        let stream = model.generate_stream(request).unwrap();
+        let stream = model.generate_stream(request).await.unwrap();
         pin_mut!(stream);
 
         let mut acc = String::new();
 
         while let Some(token) = stream.next().await {
+            let token = token.unwrap();
             acc += &token;
             if print_stream {
                 print!("{}", token);
@@ -217,6 +612,10 @@ mod tests {
         acc
     }
 
+    fn get_tiny_model() -> Llama {
+        Llama::new(concat!(env!("CARGO_MANIFEST_DIR"), "/assets/tiny.gguf")).unwrap()
+    }
+
     fn get_model() -> Llama {
         let model_path = dirs::data_dir()
             .unwrap()
@@ -371,6 +770,178 @@ mod tests {
         ]
     }
 
+    fn tiny_request() -> LlamaRequest {
+        LlamaRequest {
+            messages: vec![LlamaChatMessage::new("user".into(), "hello".into()).unwrap()],
+            max_tokens: Some(16),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sampling_seed() {
+        let llama = get_tiny_model();
+
+        let seeded = || LlamaRequest {
+            seed: Some(42),
+            ..tiny_request()
+        };
+        assert_eq!(
+            run(&llama, seeded(), false).await,
+            run(&llama, seeded(), false).await
+        );
+
+        let greedy = || LlamaRequest {
+            temperature: Some(0.0),
+            ..tiny_request()
+        };
+        assert_eq!(
+            run(&llama, greedy(), false).await,
+            run(&llama, greedy(), false).await
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sampling_max_tokens_and_stop() {
+        let llama = get_tiny_model();
+
+        let greedy = |max_tokens, stop: Vec<String>| LlamaRequest {
+            temperature: Some(0.0),
+            max_tokens: Some(max_tokens),
+            stop,
+            ..tiny_request()
+        };
+
+        let short = run(&llama, greedy(2, vec![]), false).await;
+        let long = run(&llama, greedy(16, vec![]), false).await;
+        assert!(long.starts_with(&short));
+        assert!(long.len() > short.len());
+
+        let stop = long[short.len()..].chars().take(2).collect::<String>();
+        let stopped = run(&llama, greedy(16, vec![stop.clone()]), false).await;
+        assert_eq!(stopped, &long[..long.find(&stop).unwrap()]);
+    }
+
+    #[tokio::test]
+    async fn test_cancellation() {
+        let llama = get_tiny_model();
+
+        let long = || LlamaRequest {
+            temperature: Some(0.0),
+            max_tokens: Some(4096),
+            ..tiny_request()
+        };
+
+        // Dropping the stream, as an HTTP disconnect does, stops generation.
+        let mut stream = Box::pin(llama.generate_stream(long()).await.unwrap());
+        assert!(stream.next().await.unwrap().is_ok());
+        drop(stream);
+
+        let token = CancellationToken::new();
+        let mut stream = Box::pin(
+            llama
+                .generate_stream_with_cancellation(long(), token.clone())
+                .await
+                .unwrap(),
+        );
+        assert!(stream.next().await.unwrap().is_ok());
+        token.cancel();
+        while let Some(item) = stream.next().await {
+            assert!(item.is_ok());
+        }
+
+        // The worker is free again.
+        assert!(!run(&llama, tiny_request(), false).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_queue_and_timeout() {
+        let llama =
+            Llama::with_queue_size(concat!(env!("CARGO_MANIFEST_DIR"), "/assets/tiny.gguf"), 1)
+                .unwrap();
+
+        let long = LlamaRequest {
+            temperature: Some(0.0),
+            max_tokens: Some(4096),
+            ..tiny_request()
+        };
+
+        let mut running = Box::pin(llama.generate_stream(long).await.unwrap());
+        assert!(running.next().await.unwrap().is_ok());
+
+        // One request may wait while the worker is busy. The next one is turned away.
+        let waiting = llama
+            .generate_stream(LlamaRequest {
+                timeout: Some(std::time::Duration::ZERO),
+                ..tiny_request()
+            })
+            .await
+            .unwrap();
+        assert_eq!(llama.queue_len(), 1);
+        assert!(matches!(
+            llama.generate_stream(tiny_request()).await,
+            Err(crate::Error::QueueFull)
+        ));
+
+        drop(running);
+
+        // Its deadline passed while it was queued.
+        let items = waiting.collect::<Vec<_>>().await;
+        assert!(matches!(items[..], [Err(crate::Error::Timeout)]));
+    }
+
+    #[tokio::test]
+    async fn test_context_window() {
+        let llama = get_tiny_model();
+
+        let transcript = (0..2000)
+            .map(|i| format!("line {}", i))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let request = LlamaRequest {
+            messages: vec![
+                LlamaChatMessage::new("system".into(), "be brief".into()).unwrap(),
+                LlamaChatMessage::new("user".into(), transcript).unwrap(),
+            ],
+            max_tokens: Some(16),
+            ..Default::default()
+        };
+
+        let stream = llama.generate_stream(request).await.unwrap();
+        let truncation = stream.truncation.clone().unwrap();
+        assert!(truncation.prompt_tokens > 2048);
+        assert!(truncation.kept_tokens <= 2048 - 16);
+
+        let items = stream.collect::<Vec<_>>().await;
+        assert!(items.iter().all(|item| item.is_ok()));
+
+        let stream = llama.generate_stream(tiny_request()).await.unwrap();
+        assert_eq!(stream.truncation, None);
+    }
+
+    #[tokio::test]
+    async fn test_embed() {
+        let llama = get_tiny_model();
+
+        let texts = [
+            "the budget for next quarter",
+            "the budget for next quarter",
+            "hello",
+        ];
+        let embeddings = llama
+            .embed(texts.iter().map(|t| t.to_string()).collect())
+            .await
+            .unwrap();
+        assert_eq!(embeddings.len(), 3);
+
+        let dot = |a: &[f32], b: &[f32]| a.iter().zip(b).map(|(x, y)| x * y).sum::<f32>();
+        assert!((dot(&embeddings[0], &embeddings[0]) - 1.0).abs() < 1e-3);
+        assert!((dot(&embeddings[0], &embeddings[1]) - 1.0).abs() < 1e-3);
+        assert!(dot(&embeddings[0], &embeddings[2]) < 0.999);
+
+        assert!(llama.embed(vec![]).await.unwrap().is_empty());
+    }
+
     #[test]
     fn test_tag() {
         assert!(hypr_template::ENHANCE_USER_TPL.contains("<headers>"));
@@ -383,7 +954,12 @@ mod tests {
         let llama = get_model();
         let request = LlamaRequest {
             messages: english_1_messages(),
-            grammar: Some(hypr_gbnf::GBNF::Enhance(Some(vec!["header".to_string()])).build()),
+            grammar: Some(
+                hypr_gbnf::GBNF::Enhance(Some(vec!["header".to_string()]))
+                    .build()
+                    .unwrap(),
+            ),
+            ..Default::default()
         };
 
         run(&llama, request, true).await;
@@ -396,7 +972,8 @@ mod tests {
         let llama = get_model();
         let request = LlamaRequest {
             messages: english_4_messages(),
-            grammar: Some(hypr_gbnf::GBNF::Enhance(None).build()),
+            grammar: Some(hypr_gbnf::GBNF::Enhance(None).build().unwrap()),
+            ..Default::default()
         };
 
         run(&llama, request, true).await;
@@ -409,7 +986,8 @@ mod tests {
         let llama = get_model();
         let request = LlamaRequest {
             messages: english_5_messages(),
-            grammar: Some(hypr_gbnf::GBNF::Enhance(None).build()),
+            grammar: Some(hypr_gbnf::GBNF::Enhance(None).build().unwrap()),
+            ..Default::default()
         };
 
         run(&llama, request, true).await;
@@ -0,0 +1,75 @@
+use llama_cpp_2::{model::LlamaModel, sampling::LlamaSampler};
+
+use crate::LlamaRequest;
+
+// OpenAI defaults.
+const DEFAULT_TEMPERATURE: f32 = 1.0;
+const DEFAULT_TOP_P: f32 = 1.0;
+// Tokens looked back on for frequency and presence penalties.
+const PENALTY_LAST_N: i32 = 64;
+// `LLAMA_DEFAULT_SEED`, which picks a random seed.
+const RANDOM_SEED: u32 = u32::MAX;
+
+impl LlamaRequest {
+    pub(crate) fn grammar(&self) -> Result<Option<String>, crate::Error> {
+        use async_openai::types::ResponseFormat;
+
+        if let Some(grammar) = &self.grammar {
+            return Ok(Some(grammar.clone()));
+        }
+        if let Some(grammar) = crate::tools::grammar(&self.tools, self.tool_choice.as_ref())? {
+            return Ok(Some(grammar));
+        }
+
+        match &self.response_format {
+            Some(ResponseFormat::JsonObject | ResponseFormat::JsonSchema { .. }) => {
+                Ok(Some(hypr_gbnf::GBNF::Json.build()?))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    pub(crate) fn sampler(&self, model: &LlamaModel) -> LlamaSampler {
+        let mut samplers = Vec::new();
+
+        // Resolved by `grammar` before the request is queued.
+        if let Some(grammar) = &self.grammar {
+            samplers.push(LlamaSampler::grammar(model, grammar, "root"));
+        }
+
+        let frequency_penalty = self.frequency_penalty.unwrap_or(0.0);
+        let presence_penalty = self.presence_penalty.unwrap_or(0.0);
+        if frequency_penalty != 0.0 || presence_penalty != 0.0 {
+            samplers.push(LlamaSampler::penalties(
+                PENALTY_LAST_N,
+                1.0,
+                frequency_penalty,
+                presence_penalty,
+            ));
+        }
+
+        let temperature = self.temperature.unwrap_or(DEFAULT_TEMPERATURE);
+        if temperature <= 0.0 {
+            samplers.push(LlamaSampler::greedy());
+            return LlamaSampler::chain_simple(samplers);
+        }
+
+        if let Some(top_k) = self.top_k.filter(|k| *k > 0) {
+            samplers.push(LlamaSampler::top_k(top_k));
+        }
+
+        let top_p = self.top_p.unwrap_or(DEFAULT_TOP_P);
+        if top_p < 1.0 {
+            samplers.push(LlamaSampler::top_p(top_p, 1));
+        }
+
+        if let Some(min_p) = self.min_p.filter(|p| *p > 0.0) {
+            samplers.push(LlamaSampler::min_p(min_p, 1));
+        }
+
+        samplers.push(LlamaSampler::temp(temperature));
+        samplers.push(LlamaSampler::dist(self.seed.unwrap_or(RANDOM_SEED)));
+
+        LlamaSampler::chain_simple(samplers)
+    }
+}
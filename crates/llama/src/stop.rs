@@ -0,0 +1,98 @@
+// Cuts generated text at the first stop sequence. Text that could still turn into one is held back,
+// so a stop sequence split across tokens never reaches the caller.
+pub struct StopSequences {
+    stops: Vec<String>,
+    pending: String,
+}
+
+pub enum StopResult {
+    Continue(String),
+    Stop(String),
+}
+
+impl StopSequences {
+    pub fn new(stops: impl IntoIterator<Item = String>) -> Self {
+        Self {
+            stops: stops.into_iter().filter(|s| !s.is_empty()).collect(),
+            pending: String::new(),
+        }
+    }
+
+    pub fn push(&mut self, piece: &str) -> StopResult {
+        self.pending.push_str(piece);
+
+        if let Some(idx) = self.stops.iter().filter_map(|s| self.pending.find(s)).min() {
+            self.pending.truncate(idx);
+            return StopResult::Stop(std::mem::take(&mut self.pending));
+        }
+
+        let held = self
+            .stops
+            .iter()
+            .map(|s| partial_suffix(&self.pending, s))
+            .max()
+            .unwrap_or(0);
+
+        let rest = self.pending.split_off(self.pending.len() - held);
+        StopResult::Continue(std::mem::replace(&mut self.pending, rest))
+    }
+
+    // Text held back when generation ends without a stop sequence.
+    pub fn flush(&mut self) -> String {
+        std::mem::take(&mut self.pending)
+    }
+}
+
+// Length of the longest suffix of `text` that is a proper prefix of `stop`.
+fn partial_suffix(text: &str, stop: &str) -> usize {
+    (1..stop.len().min(text.len() + 1))
+        .rev()
+        .filter(|&len| stop.is_char_boundary(len) && text.is_char_boundary(text.len() - len))
+        .find(|&len| text.ends_with(&stop[..len]))
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(stops: &[&str], pieces: &[&str]) -> (String, bool) {
+        let mut stop = StopSequences::new(stops.iter().map(|s| s.to_string()));
+        let mut out = String::new();
+
+        for piece in pieces {
+            match stop.push(piece) {
+                StopResult::Continue(text) => out.push_str(&text),
+                StopResult::Stop(text) => {
+                    out.push_str(&text);
+                    return (out, true);
+                }
+            }
+        }
+
+        out.push_str(&stop.flush());
+        (out, false)
+    }
+
+    #[test]
+    fn test_stop_sequences() {
+        assert_eq!(
+            run(&[], &["Hello", " world"]),
+            ("Hello world".to_string(), false)
+        );
+        assert_eq!(
+            run(&["\n\n"], &["Hello", "\n", "\n", "world"]),
+            ("Hello".to_string(), true)
+        );
+        assert_eq!(
+            run(&["END", "STOP"], &["aSTO", "P and END"]),
+            ("a".to_string(), true)
+        );
+        // A partial match that never completes is released.
+        assert_eq!(run(&["</s>"], &["a </", "b"]), ("a </b".to_string(), false));
+        assert_eq!(
+            run(&["다."], &["안녕하", "세요다", "."]),
+            ("안녕하세요".to_string(), true)
+        );
+    }
+}
@@ -0,0 +1,128 @@
+use async_openai::types::{ChatCompletionTool, ChatCompletionToolChoiceOption};
+use serde_json::Value;
+
+use crate::LlamaToolCall;
+
+// Llama 3 starts calls to code-interpreter-style tools with this.
+const PYTHON_TAG: &str = "<|python_tag|>";
+
+// For `required` and named tool choices, a grammar that only allows a call. With `auto`, the model
+// decides, and `parse_tool_call` tells afterwards.
+pub(crate) fn grammar(
+    tools: &[ChatCompletionTool],
+    tool_choice: Option<&ChatCompletionToolChoiceOption>,
+) -> Result<Option<String>, crate::Error> {
+    let Some(tool_choice) = tool_choice else {
+        return Ok(None);
+    };
+
+    let tools = match tool_choice {
+        ChatCompletionToolChoiceOption::Required => tools.iter().collect::<Vec<_>>(),
+        ChatCompletionToolChoiceOption::Named(named) => tools
+            .iter()
+            .filter(|tool| tool.function.name == named.function.name)
+            .collect(),
+        ChatCompletionToolChoiceOption::Auto | ChatCompletionToolChoiceOption::None => {
+            return Ok(None)
+        }
+    };
+
+    if tools.is_empty() {
+        return Ok(None);
+    }
+
+    let grammar = hypr_gbnf::GBNF::ToolCall(
+        tools
+            .into_iter()
+            .map(|tool| (tool.function.name.clone(), tool.function.parameters.clone()))
+            .collect(),
+    )
+    .build()?;
+
+    Ok(Some(grammar))
+}
+
+// A whole completion that is a call to one of `tools`, written the way the grammar asks for, or as
+// `{"name": ..., "arguments": ...}`, which some models write on their own.
+pub fn parse_tool_call(text: &str, tools: &[ChatCompletionTool]) -> Option<LlamaToolCall> {
+    let text = text.trim();
+    let text = text.strip_prefix(PYTHON_TAG).unwrap_or(text).trim();
+    if !text.starts_with('{') {
+        return None;
+    }
+
+    let value: Value = serde_json::from_str(text).ok()?;
+    let name = value.get("name")?.as_str()?;
+    if !tools.iter().any(|tool| tool.function.name == name) {
+        return None;
+    }
+
+    let arguments = match value.get("parameters").or_else(|| value.get("arguments")) {
+        Some(Value::String(arguments)) => arguments.clone(),
+        Some(arguments @ Value::Object(_)) => arguments.to_string(),
+        None => "{}".to_string(),
+        Some(_) => return None,
+    };
+
+    Some(LlamaToolCall {
+        id: format!("call_{}", uuid::Uuid::new_v4().simple()),
+        name: name.to_string(),
+        arguments,
+    })
+}
+
+// Whether `text`, the start of a completion, could still turn out to be a tool call, so streamed
+// output is held back until it is known.
+pub fn may_be_tool_call(text: &str) -> bool {
+    let text = text.trim_start();
+    text.is_empty()
+        || text.starts_with('{')
+        || PYTHON_TAG.starts_with(text)
+        || text.starts_with(PYTHON_TAG)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tools() -> Vec<ChatCompletionTool> {
+        serde_json::from_value(serde_json::json!([{
+            "type": "function",
+            "function": { "name": "get_weather", "parameters": { "type": "object" } },
+        }]))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_parse_tool_call() {
+        let call = parse_tool_call(
+            r#" {"name": "get_weather", "parameters": {"city": "Seoul"}}"#,
+            &tools(),
+        )
+        .unwrap();
+        assert_eq!(call.name, "get_weather");
+        assert_eq!(call.arguments, r#"{"city":"Seoul"}"#);
+        assert!(call.id.starts_with("call_"));
+
+        let call = parse_tool_call(
+            r#"<|python_tag|>{"name": "get_weather", "arguments": "{\"city\": \"Seoul\"}"}"#,
+            &tools(),
+        )
+        .unwrap();
+        assert_eq!(call.arguments, r#"{"city": "Seoul"}"#);
+
+        assert!(parse_tool_call("It is sunny.", &tools()).is_none());
+        assert!(parse_tool_call(r#"{"name": "delete", "parameters": {}}"#, &tools()).is_none());
+        assert!(parse_tool_call(r#"{"name": "get_weather""#, &tools()).is_none());
+    }
+
+    #[test]
+    fn test_may_be_tool_call() {
+        assert!(may_be_tool_call(" "));
+        assert!(may_be_tool_call("<|pyth"));
+        assert!(may_be_tool_call("<|python_tag|>{\"na"));
+        assert!(may_be_tool_call("\n{\"name\""));
+        assert!(!may_be_tool_call("It"));
+        assert!(!may_be_tool_call("<b>"));
+    }
+}
@@ -1,47 +1,177 @@
 use async_openai::types::{
-    ChatCompletionRequestAssistantMessageContent, ChatCompletionRequestMessage,
-    ChatCompletionRequestSystemMessageContent, ChatCompletionRequestUserMessageContent,
+    ChatCompletionRequestAssistantMessageContent, ChatCompletionRequestAssistantMessageContentPart,
+    ChatCompletionRequestDeveloperMessageContent, ChatCompletionRequestMessage,
+    ChatCompletionRequestSystemMessageContent, ChatCompletionRequestSystemMessageContentPart,
+    ChatCompletionRequestToolMessageContent, ChatCompletionRequestToolMessageContentPart,
+    ChatCompletionRequestUserMessageContent, ChatCompletionRequestUserMessageContentPart,
 };
 
-pub use llama_cpp_2::model::LlamaChatMessage;
+pub use async_openai::types::{ChatCompletionTool, ChatCompletionToolChoiceOption, ResponseFormat};
 
-pub trait FromOpenAI {
-    fn from_openai(message: &ChatCompletionRequestMessage) -> Self;
+// Plain strings, unlike `llama_cpp_2::model::LlamaChatMessage`, so the prompt can be trimmed to fit the context window.
+#[derive(Debug, Clone)]
+pub struct LlamaChatMessage {
+    pub role: String,
+    pub content: String,
+    // Calls an assistant message made, answered by the `tool` messages after it.
+    pub tool_calls: Vec<LlamaToolCall>,
+    // For `tool` messages, the call they answer.
+    pub tool_call_id: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LlamaToolCall {
+    pub id: String,
+    pub name: String,
+    // JSON, as OpenAI passes it.
+    pub arguments: String,
+}
+
+impl LlamaChatMessage {
+    // Rejects what `llama_cpp_2` would, interior NUL bytes.
+    pub fn new(role: String, content: String) -> Result<Self, crate::Error> {
+        if role.contains('\0') || content.contains('\0') {
+            return Err(crate::Error::ChatTemplateError(
+                "message contains a NUL byte".to_string(),
+            ));
+        }
+
+        Ok(Self {
+            role,
+            content,
+            tool_calls: vec![],
+            tool_call_id: None,
+        })
+    }
+}
+
+pub trait FromOpenAI: Sized {
+    fn from_openai(message: &ChatCompletionRequestMessage) -> Result<Self, crate::Error>;
 }
 
 impl FromOpenAI for LlamaChatMessage {
-    fn from_openai(message: &ChatCompletionRequestMessage) -> Self {
+    fn from_openai(message: &ChatCompletionRequestMessage) -> Result<Self, crate::Error> {
         match message {
             ChatCompletionRequestMessage::System(system) => {
                 let content = match &system.content {
-                    ChatCompletionRequestSystemMessageContent::Text(text) => text,
-                    _ => todo!(),
+                    ChatCompletionRequestSystemMessageContent::Text(text) => text.clone(),
+                    ChatCompletionRequestSystemMessageContent::Array(parts) => parts
+                        .iter()
+                        .map(|part| match part {
+                            ChatCompletionRequestSystemMessageContentPart::Text(part) => {
+                                part.text.as_str()
+                            }
+                        })
+                        .collect(),
                 };
 
-                LlamaChatMessage::new("system".into(), content.into()).unwrap()
+                LlamaChatMessage::new("system".into(), content)
+            }
+            // Newer name for system messages, which chat templates don't know.
+            ChatCompletionRequestMessage::Developer(developer) => {
+                let content = match &developer.content {
+                    ChatCompletionRequestDeveloperMessageContent::Text(text) => text.clone(),
+                    ChatCompletionRequestDeveloperMessageContent::Array(parts) => {
+                        parts.iter().map(|part| part.text.as_str()).collect()
+                    }
+                };
+
+                LlamaChatMessage::new("system".into(), content)
             }
             ChatCompletionRequestMessage::Assistant(assistant) => {
                 let content = match &assistant.content {
-                    Some(ChatCompletionRequestAssistantMessageContent::Text(text)) => text,
-                    _ => todo!(),
+                    Some(ChatCompletionRequestAssistantMessageContent::Text(text)) => text.clone(),
+                    Some(ChatCompletionRequestAssistantMessageContent::Array(parts)) => parts
+                        .iter()
+                        .map(|part| match part {
+                            ChatCompletionRequestAssistantMessageContentPart::Text(part) => {
+                                part.text.as_str()
+                            }
+                            ChatCompletionRequestAssistantMessageContentPart::Refusal(part) => {
+                                part.refusal.as_str()
+                            }
+                        })
+                        .collect(),
+                    None => assistant.refusal.clone().unwrap_or_default(),
                 };
-                LlamaChatMessage::new("assistant".into(), content.into()).unwrap()
+
+                let mut message = LlamaChatMessage::new("assistant".into(), content)?;
+                message.tool_calls = assistant
+                    .tool_calls
+                    .iter()
+                    .flatten()
+                    .map(|call| LlamaToolCall {
+                        id: call.id.clone(),
+                        name: call.function.name.clone(),
+                        arguments: call.function.arguments.clone(),
+                    })
+                    .collect();
+                Ok(message)
             }
             ChatCompletionRequestMessage::User(user) => {
                 let content = match &user.content {
-                    ChatCompletionRequestUserMessageContent::Text(text) => text,
-                    _ => todo!(),
+                    ChatCompletionRequestUserMessageContent::Text(text) => text.clone(),
+                    ChatCompletionRequestUserMessageContent::Array(parts) => parts
+                        .iter()
+                        .map(|part| match part {
+                            ChatCompletionRequestUserMessageContentPart::Text(part) => {
+                                Ok(part.text.as_str())
+                            }
+                            _ => Err(crate::Error::UnsupportedMessage(
+                                "only text content is supported".to_string(),
+                            )),
+                        })
+                        .collect::<Result<String, _>>()?,
                 };
 
-                LlamaChatMessage::new("user".into(), content.into()).unwrap()
+                LlamaChatMessage::new("user".into(), content)
+            }
+            ChatCompletionRequestMessage::Tool(tool) => {
+                let content = match &tool.content {
+                    ChatCompletionRequestToolMessageContent::Text(text) => text.clone(),
+                    ChatCompletionRequestToolMessageContent::Array(parts) => parts
+                        .iter()
+                        .map(|part| match part {
+                            ChatCompletionRequestToolMessageContentPart::Text(part) => {
+                                part.text.as_str()
+                            }
+                        })
+                        .collect(),
+                };
+
+                let mut message = LlamaChatMessage::new("tool".into(), content)?;
+                message.tool_call_id = Some(tool.tool_call_id.clone());
+                Ok(message)
+            }
+            // Deprecated by OpenAI in favor of `tool`, which it is passed on as.
+            ChatCompletionRequestMessage::Function(function) => {
+                LlamaChatMessage::new("tool".into(), function.content.clone().unwrap_or_default())
             }
-            _ => todo!(),
         }
     }
 }
 
+// Sampling fields follow the OpenAI API. `None` means the OpenAI default.
 #[derive(Default)]
 pub struct LlamaRequest {
     pub grammar: Option<String>,
     pub messages: Vec<LlamaChatMessage>,
+    pub temperature: Option<f32>,
+    pub top_p: Option<f32>,
+    // Not part of the OpenAI API, and off unless set.
+    pub top_k: Option<i32>,
+    pub min_p: Option<f32>,
+    pub seed: Option<u32>,
+    pub max_tokens: Option<u32>,
+    pub stop: Vec<String>,
+    pub frequency_penalty: Option<f32>,
+    pub presence_penalty: Option<f32>,
+    // Constrains the output to JSON when no `grammar` is given.
+    pub response_format: Option<ResponseFormat>,
+    // Rendered into the prompt through the model's chat template.
+    pub tools: Vec<ChatCompletionTool>,
+    // `required` or a named tool constrains the output to a call when no `grammar` is given.
+    pub tool_choice: Option<ChatCompletionToolChoiceOption>,
+    // Counted from when the request is queued, so time spent waiting for the model is included.
+    pub timeout: Option<std::time::Duration>,
 }
@@ -75,9 +75,29 @@ impl OpenAIClient {
             "application/json"
         };
 
+        let builder = self.client.post(url).header("Accept", accept).json(&req);
+
+        // The timeout covers reading the body, which a streamed answer can take longer than.
+        let builder = if stream {
+            builder
+        } else {
+            builder.timeout(std::time::Duration::from_secs(30))
+        };
+
+        builder.send().await
+    }
+
+    #[tracing::instrument]
+    pub async fn embeddings(
+        &self,
+        req: &async_openai::types::CreateEmbeddingRequest,
+    ) -> Result<reqwest::Response, reqwest_middleware::Error> {
+        let mut url = self.api_base.clone();
+        url.path_segments_mut().unwrap().push("embeddings");
+
         self.client
             .post(url)
-            .header("Accept", accept)
+            .header("Accept", "application/json")
             .json(&req)
             .timeout(std::time::Duration::from_secs(30))
             .send()
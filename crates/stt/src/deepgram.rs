@@ -1,8 +1,12 @@
+use deepgram::common::options::Keyword;
+use hypr_listener_interface::VocabularyTerm;
+
 #[derive(Debug, Default)]
 pub struct DeepgramClientBuilder {
     api_key: Option<String>,
     language: Option<hypr_language::Language>,
-    keywords: Option<Vec<String>>,
+    vocabulary: Option<Vec<VocabularyTerm>>,
+    interim_results: bool,
 }
 
 impl DeepgramClientBuilder {
@@ -16,8 +20,14 @@ impl DeepgramClientBuilder {
         self
     }
 
-    pub fn keywords(mut self, keywords: impl Into<Vec<String>>) -> Self {
-        self.keywords = Some(keywords.into());
+    pub fn vocabulary(mut self, vocabulary: impl Into<Vec<VocabularyTerm>>) -> Self {
+        self.vocabulary = Some(vocabulary.into());
+        self
+    }
+
+    // Only used for realtime transcription.
+    pub fn interim_results(mut self, interim_results: bool) -> Self {
+        self.interim_results = interim_results;
         self
     }
 
@@ -33,7 +43,18 @@ impl DeepgramClientBuilder {
         Ok(DeepgramClient {
             client,
             language: language.for_deepgram()?,
-            keywords: self.keywords.unwrap_or_default(),
+            interim_results: self.interim_results,
+            keywords: self
+                .vocabulary
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|t| !t.term.trim().is_empty())
+                .map(|t| Keyword {
+                    keyword: t.term.trim().to_string(),
+                    // Boosts from 0 to 1 become intensifiers from 1 to 10.
+                    intensifier: t.boost.map(|b| 1.0 + 9.0 * b.clamp(0.0, 1.0) as f64),
+                })
+                .collect(),
         })
     }
 }
@@ -42,7 +63,8 @@ impl DeepgramClientBuilder {
 pub struct DeepgramClient {
     pub client: deepgram::Deepgram,
     pub language: deepgram::common::options::Language,
-    pub keywords: Vec<String>,
+    pub keywords: Vec<Keyword>,
+    pub interim_results: bool,
 }
 
 impl DeepgramClient {
@@ -8,4 +8,6 @@ pub enum Error {
     Clova(#[from] hypr_clova::Error),
     #[error("clova error {0}")]
     ClovaError(String),
+    #[error("no speech-to-text provider for {0}")]
+    NoneProvider(String),
 }
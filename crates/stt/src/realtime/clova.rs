@@ -35,6 +35,9 @@ impl<S, E> RealtimeSpeechToText<S, E> for hypr_clova::realtime::Client {
                             start: r.transcription.start_timestamp,
                             end: r.transcription.end_timestamp,
                             confidence: None,
+                            is_final: true,
+                            revision: None,
+                            language: None,
                         }],
                     })),
                     clova::StreamResponse::Config(_) => None,
@@ -31,7 +31,7 @@ impl<S, E> RealtimeSpeechToText<S, E> for crate::deepgram::DeepgramClient {
             .language(self.language.clone())
             .filler_words(false)
             .diarize(true)
-            .keywords(self.keywords.iter().map(String::as_str))
+            .keywords_with_intensifiers(self.keywords.clone())
             .build();
 
         let deepgram_stream = self
@@ -39,6 +39,7 @@ impl<S, E> RealtimeSpeechToText<S, E> for crate::deepgram::DeepgramClient {
             .transcription()
             .stream_request_with_options(options)
             .keep_alive()
+            .interim_results(self.interim_results)
             .sample_rate(16 * 1000)
             .channels(1)
             .encoding(Encoding::Linear16)
@@ -49,11 +50,35 @@ impl<S, E> RealtimeSpeechToText<S, E> for crate::deepgram::DeepgramClient {
             let item = match result {
                 Err(e) => Some(Err(e.into())),
                 Ok(resp) => match resp {
-                    DeepgramStreamResponse::TranscriptResponse { channel, .. } => {
+                    DeepgramStreamResponse::TranscriptResponse {
+                        channel,
+                        start,
+                        is_final,
+                        ..
+                    } => {
                         let data = channel.alternatives.first().unwrap();
+                        // Interim results for a segment share its start until it is finalized.
+                        let revision = Some((start * 1000.0) as u64);
 
                         if data.words.is_empty() {
                             None
+                        } else if !is_final {
+                            // Words and speakers still change, so only the text is forwarded.
+                            let first = data.words.first().unwrap();
+                            let last = data.words.last().unwrap();
+
+                            Some(Ok(ListenOutputChunk {
+                                diarizations: vec![],
+                                transcripts: vec![TranscriptChunk {
+                                    text: data.transcript.clone(),
+                                    start: (first.start * 1000.0) as u64,
+                                    end: (last.end * 1000.0) as u64,
+                                    confidence: Some(data.confidence as f32),
+                                    is_final: false,
+                                    revision,
+                                    language: None,
+                                }],
+                            }))
                         } else {
                             let mut diarizations = Vec::new();
                             let mut transcripts = Vec::new();
@@ -66,6 +91,9 @@ impl<S, E> RealtimeSpeechToText<S, E> for crate::deepgram::DeepgramClient {
                                     start: (w.start * 1000.0) as u64,
                                     end: (w.end * 1000.0) as u64,
                                     confidence: Some(w.confidence as f32),
+                                    is_final: true,
+                                    revision,
+                                    language: None,
                                 });
 
                                 if let Some(speaker) = w.speaker {
@@ -7,7 +7,7 @@ mod deepgram;
 mod whisper;
 
 use crate::deepgram::DeepgramClient;
-use hypr_listener_interface::ListenOutputChunk;
+use hypr_listener_interface::{ListenOutputChunk, VocabularyTerm};
 
 #[allow(dead_code)]
 pub trait RealtimeSpeechToText<S, E> {
@@ -68,41 +68,87 @@ impl Client {
         ClientBuilder::default()
     }
 
-    pub async fn for_language(&self, language: hypr_language::Language) -> MultiClient {
+    // `None` detects the language from the audio. Languages without a dedicated provider,
+    // or whose provider has no API key, fall back to Whisper.
+    // `interim_results` only reaches Deepgram. Callers drop interims other providers send anyway.
+    pub async fn for_language(
+        &self,
+        language: Option<hypr_language::Language>,
+        vocabulary: &[VocabularyTerm],
+        interim_results: bool,
+    ) -> Result<MultiClient, crate::Error> {
+        let Some(language) = language else {
+            return self.whisper(None, vocabulary);
+        };
+
         match language.iso639() {
-            hypr_language::ISO639::Ko => {
+            hypr_language::ISO639::Ko if self.clova_api_key.is_some() => {
                 let clova = hypr_clova::realtime::Client::builder()
                     .api_key(self.clova_api_key.as_ref().unwrap())
-                    .keywords(vec!["하이퍼노트".to_string()])
+                    .boostings(clova_boostings(vocabulary))
                     .build()
-                    .await
-                    .unwrap();
-                MultiClient::Clova(clova)
+                    .await?;
+                Ok(MultiClient::Clova(clova))
             }
-            hypr_language::ISO639::De => {
-                let whisper = hypr_whisper::cloud::WhisperClient::builder()
-                    .api_base(std::env::var("WHISPER_API_BASE").unwrap())
-                    .api_key(std::env::var("WHISPER_API_KEY").unwrap())
-                    .language(language.try_into().unwrap())
-                    .build();
-
-                MultiClient::Whisper(whisper)
-            }
-            hypr_language::ISO639::En => {
+            hypr_language::ISO639::De => self.whisper(Some(language), vocabulary),
+            _ if self.deepgram_api_key.is_some() && language.clone().for_deepgram().is_ok() => {
                 let deepgram = DeepgramClient::builder()
                     .api_key(self.deepgram_api_key.as_ref().unwrap())
-                    .keywords(vec!["Hyprnote".to_string()])
+                    .vocabulary(vocabulary)
                     .language(language)
-                    .build()
-                    .unwrap();
+                    .interim_results(interim_results)
+                    .build()?;
 
-                MultiClient::Deepgram(deepgram)
+                Ok(MultiClient::Deepgram(deepgram))
             }
-            _ => panic!("Unsupported language: {:?}", language),
+            _ => self.whisper(Some(language), vocabulary),
+        }
+    }
+
+    fn whisper(
+        &self,
+        language: Option<hypr_language::Language>,
+        vocabulary: &[VocabularyTerm],
+    ) -> Result<MultiClient, crate::Error> {
+        let (Ok(api_base), Ok(api_key)) = (
+            std::env::var("WHISPER_API_BASE"),
+            std::env::var("WHISPER_API_KEY"),
+        ) else {
+            return Err(crate::Error::NoneProvider(
+                language.map_or("auto".to_string(), |l| l.to_string()),
+            ));
+        };
+
+        let mut builder = hypr_whisper::cloud::WhisperClient::builder()
+            .api_base(api_base)
+            .api_key(api_key)
+            .prompt(hypr_vocabulary::whisper_prompt(vocabulary));
+
+        // Whisper detects languages it is not told.
+        if let Some(Ok(language)) = language.map(TryInto::<hypr_whisper::Language>::try_into) {
+            builder = builder.language(language);
         }
+
+        Ok(MultiClient::Whisper(builder.build()))
     }
 }
 
+// Clova weights run from 1 to 5.
+fn clova_boostings(
+    vocabulary: &[VocabularyTerm],
+) -> Vec<hypr_clova::realtime::interface::KeywordBoostingItem> {
+    vocabulary
+        .iter()
+        .filter(|t| !t.term.trim().is_empty())
+        .map(|t| hypr_clova::realtime::interface::KeywordBoostingItem {
+            words: t.term.trim().to_string(),
+            weight: t
+                .boost
+                .map_or(1.0, |b| 1.0 + 4.0 * b.clamp(0.0, 1.0) as f64),
+        })
+        .collect()
+}
+
 impl<S, E> RealtimeSpeechToText<S, E> for MultiClient
 where
     S: Stream<Item = Result<Bytes, E>> + Send + Unpin + 'static,
@@ -198,8 +244,9 @@ mod tests {
         let mut client = Client::builder()
             .deepgram_api_key(std::env::var("DEEPGRAM_API_KEY").unwrap())
             .build()
-            .for_language(hypr_language::ISO639::En.into())
-            .await;
+            .for_language(Some(hypr_language::ISO639::En.into()), &[], true)
+            .await
+            .unwrap();
 
         let mut transcript_stream = client.transcribe(audio_stream).await.unwrap();
 
@@ -208,7 +255,7 @@ mod tests {
             let data = result.unwrap();
             println!("{:?}", data);
 
-            for t in data.transcripts {
+            for t in data.transcripts.into_iter().filter(|t| t.is_final) {
                 acc.push(t);
             }
         }
@@ -227,8 +274,9 @@ mod tests {
         let mut client = Client::builder()
             .clova_api_key(std::env::var("CLOVA_API_KEY").unwrap())
             .build()
-            .for_language(hypr_language::ISO639::Ko.into())
-            .await;
+            .for_language(Some(hypr_language::ISO639::Ko.into()), &[], true)
+            .await
+            .unwrap();
 
         let mut transcript_stream = client.transcribe(audio_stream).await.unwrap();
 
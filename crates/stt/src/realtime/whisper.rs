@@ -28,6 +28,10 @@ impl<S, E> RealtimeSpeechToText<S, E> for WhisperClient {
                     start: 0,
                     end: 0,
                     confidence: None,
+                    is_final: true,
+                    revision: None,
+                    language: hypr_language::Language::from_whisper_code(&output.language)
+                        .map(|l| l.code().to_string()),
                 }],
                 diarizations: vec![],
             })
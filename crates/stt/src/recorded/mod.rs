@@ -57,25 +57,30 @@ impl Client {
         ClientBuilder::default()
     }
 
-    pub async fn for_language(&self, language: hypr_language::Language) -> MultiClient {
-        match language.iso639() {
-            hypr_language::ISO639::Ko => {
+    // Languages Deepgram does not support, and `None` for detection, are transcribed as English for now.
+    pub async fn for_language(
+        &self,
+        language: Option<hypr_language::Language>,
+        vocabulary: &[hypr_listener_interface::VocabularyTerm],
+    ) -> Result<MultiClient, crate::Error> {
+        match language {
+            Some(language) if language.iso639() == hypr_language::ISO639::Ko => {
                 let clova = hypr_clova::recorded::Client::builder()
                     .api_key(&self.clova_api_key)
                     .build();
-                MultiClient::Clova(clova)
+                Ok(MultiClient::Clova(clova))
             }
-            hypr_language::ISO639::En => {
-                let deepgram = DeepgramClient::builder()
+            language => {
+                let mut builder = DeepgramClient::builder()
                     .api_key(&self.deepgram_api_key)
-                    .keywords(vec!["Hyprnote".to_string()])
-                    .language(language.into())
-                    .build()
-                    .unwrap();
+                    .vocabulary(vocabulary);
 
-                MultiClient::Deepgram(deepgram)
+                if let Some(language) = language.filter(|l| l.clone().for_deepgram().is_ok()) {
+                    builder = builder.language(language);
+                }
+
+                Ok(MultiClient::Deepgram(builder.build()?))
             }
-            _ => panic!("Unsupported language: {:?}", language),
         }
     }
 }
@@ -31,6 +31,20 @@ pub enum PredefinedTemplate {
     EnhanceSystem,
     #[strum(serialize = "enhance.user")]
     EnhanceUser,
+    #[strum(serialize = "enhance_chunk.system")]
+    EnhanceChunkSystem,
+    #[strum(serialize = "enhance_chunk.user")]
+    EnhanceChunkUser,
+    #[strum(serialize = "enhance_merge.user")]
+    EnhanceMergeUser,
+    #[strum(serialize = "translate.system")]
+    TranslateSystem,
+    #[strum(serialize = "translate.user")]
+    TranslateUser,
+    #[strum(serialize = "ai_chat.system")]
+    AiChatSystem,
+    #[strum(serialize = "ai_chat.user")]
+    AiChatUser,
 }
 
 impl From<PredefinedTemplate> for Template {
@@ -40,12 +54,36 @@ impl From<PredefinedTemplate> for Template {
                 Template::Static(PredefinedTemplate::EnhanceSystem)
             }
             PredefinedTemplate::EnhanceUser => Template::Static(PredefinedTemplate::EnhanceUser),
+            PredefinedTemplate::EnhanceChunkSystem => {
+                Template::Static(PredefinedTemplate::EnhanceChunkSystem)
+            }
+            PredefinedTemplate::EnhanceChunkUser => {
+                Template::Static(PredefinedTemplate::EnhanceChunkUser)
+            }
+            PredefinedTemplate::EnhanceMergeUser => {
+                Template::Static(PredefinedTemplate::EnhanceMergeUser)
+            }
+            PredefinedTemplate::TranslateSystem => {
+                Template::Static(PredefinedTemplate::TranslateSystem)
+            }
+            PredefinedTemplate::TranslateUser => {
+                Template::Static(PredefinedTemplate::TranslateUser)
+            }
+            PredefinedTemplate::AiChatSystem => Template::Static(PredefinedTemplate::AiChatSystem),
+            PredefinedTemplate::AiChatUser => Template::Static(PredefinedTemplate::AiChatUser),
         }
     }
 }
 
 pub const ENHANCE_SYSTEM_TPL: &str = include_str!("../assets/enhance.system.jinja");
 pub const ENHANCE_USER_TPL: &str = include_str!("../assets/enhance.user.jinja");
+pub const ENHANCE_CHUNK_SYSTEM_TPL: &str = include_str!("../assets/enhance_chunk.system.jinja");
+pub const ENHANCE_CHUNK_USER_TPL: &str = include_str!("../assets/enhance_chunk.user.jinja");
+pub const ENHANCE_MERGE_USER_TPL: &str = include_str!("../assets/enhance_merge.user.jinja");
+pub const TRANSLATE_SYSTEM_TPL: &str = include_str!("../assets/translate.system.jinja");
+pub const TRANSLATE_USER_TPL: &str = include_str!("../assets/translate.user.jinja");
+pub const AI_CHAT_SYSTEM_TPL: &str = include_str!("../assets/ai_chat.system.jinja");
+pub const AI_CHAT_USER_TPL: &str = include_str!("../assets/ai_chat.user.jinja");
 
 pub fn init(env: &mut minijinja::Environment) {
     env.set_unknown_method_callback(minijinja_contrib::pycompat::unknown_method_callback);
@@ -57,6 +95,38 @@ pub fn init(env: &mut minijinja::Environment) {
     .unwrap();
     env.add_template(PredefinedTemplate::EnhanceUser.as_ref(), ENHANCE_USER_TPL)
         .unwrap();
+    env.add_template(
+        PredefinedTemplate::EnhanceChunkSystem.as_ref(),
+        ENHANCE_CHUNK_SYSTEM_TPL,
+    )
+    .unwrap();
+    env.add_template(
+        PredefinedTemplate::EnhanceChunkUser.as_ref(),
+        ENHANCE_CHUNK_USER_TPL,
+    )
+    .unwrap();
+    env.add_template(
+        PredefinedTemplate::EnhanceMergeUser.as_ref(),
+        ENHANCE_MERGE_USER_TPL,
+    )
+    .unwrap();
+    env.add_template(
+        PredefinedTemplate::TranslateSystem.as_ref(),
+        TRANSLATE_SYSTEM_TPL,
+    )
+    .unwrap();
+    env.add_template(
+        PredefinedTemplate::TranslateUser.as_ref(),
+        TRANSLATE_USER_TPL,
+    )
+    .unwrap();
+    env.add_template(
+        PredefinedTemplate::AiChatSystem.as_ref(),
+        AI_CHAT_SYSTEM_TPL,
+    )
+    .unwrap();
+    env.add_template(PredefinedTemplate::AiChatUser.as_ref(), AI_CHAT_USER_TPL)
+        .unwrap();
 
     env.add_filter("language", filters::language);
 
@@ -0,0 +1,262 @@
+use hypr_listener_interface::TranscriptChunk;
+
+use crate::TimelineViewItem;
+
+// Decides how transcript chunks are stitched into paragraphs.
+pub trait MergeStrategy {
+    // Whether `next` continues the last stored chunk, before any diarization is applied.
+    fn merge_transcripts(&self, last: &TranscriptChunk, next: &TranscriptChunk) -> bool;
+
+    // Whether the transcripts look like word-by-word streaming output rather than full sentences.
+    fn streaming_mode(&self, transcripts: &[TranscriptChunk]) -> bool;
+
+    // Whether `next` from the same speaker continues `last` in the view.
+    fn merge_items(&self, last: &TimelineViewItem, next: &TranscriptChunk, streaming: bool)
+        -> bool;
+
+    // Whether `next`, which no diarization segment covers, continues `last` in the view.
+    fn merge_unattributed(
+        &self,
+        last: &TimelineViewItem,
+        next: &TranscriptChunk,
+        streaming: bool,
+    ) -> bool;
+
+    // How far a transcript may sit outside a diarization segment and still be attributed to it.
+    fn speaker_padding_ms(&self) -> u64;
+
+    fn is_eos(&self, text: &str) -> bool;
+
+    fn join(&self, text: &mut String, next: &str) {
+        join_text(text, next, false);
+    }
+}
+
+crate::common_derives! {
+    #[serde(rename_all = "snake_case")]
+    pub enum SentenceBoundary {
+        // `.`, `?`, `!` and their full-width forms.
+        #[default]
+        Punctuation,
+        // Also `。`, `．`, `…` and closing quotes. Chinese and Japanese text is joined without spaces.
+        Cjk,
+        // Also declarative and interrogative endings, since Korean STT output often lacks punctuation.
+        Korean,
+    }
+}
+
+impl SentenceBoundary {
+    pub fn for_language(code: &str) -> Self {
+        match code.to_lowercase().as_str() {
+            "zh" | "ja" => Self::Cjk,
+            "ko" => Self::Korean,
+            _ => Self::Punctuation,
+        }
+    }
+
+    pub fn is_eos(&self, text: &str) -> bool {
+        let text = text.trim_end();
+
+        let text = match self {
+            Self::Punctuation => text,
+            Self::Cjk | Self::Korean => text.trim_end_matches(['」', '』', '”', '"', '）', ')']),
+        };
+
+        let Some(last) = text.chars().last() else {
+            return false;
+        };
+
+        match self {
+            Self::Punctuation => matches!(last, '.' | '?' | '!' | '？' | '！'),
+            Self::Cjk => matches!(last, '.' | '?' | '!' | '？' | '！' | '。' | '．' | '…'),
+            Self::Korean => {
+                // -다, -요, -죠 and -까 close almost every sentence in both formal and polite speech.
+                matches!(
+                    last,
+                    '.' | '?' | '!' | '？' | '！' | '。' | '…' | '다' | '요' | '죠' | '까'
+                )
+            }
+        }
+    }
+}
+
+// Thresholds of the default merge strategy.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize, specta::Type)]
+#[serde(default)]
+pub struct TimelineConfig {
+    // Chunks closer than this are stored as one, unless the first ends a sentence.
+    pub transcript_gap_ms: u64,
+    // Streaming mode applies once there are more than this many chunks...
+    pub streaming_min_chunks: usize,
+    // ...averaging fewer characters than this.
+    pub streaming_max_avg_chars: f32,
+    // In streaming mode, chunks closer than this are merged regardless of punctuation.
+    pub streaming_gap_ms: u64,
+    // Items with fewer words than this absorb the next chunk within `short_item_gap_ms`.
+    pub short_item_words: usize,
+    pub short_item_gap_ms: u64,
+    // An unfinished sentence absorbs the next chunk within this gap.
+    pub open_sentence_gap_ms: u64,
+    // A finished sentence absorbs the next chunk within this gap, up to `max_merged_words`.
+    pub closed_sentence_gap_ms: u64,
+    pub max_merged_words: usize,
+    pub speaker_padding_ms: u64,
+    pub sentence_boundary: SentenceBoundary,
+}
+
+impl Default for TimelineConfig {
+    fn default() -> Self {
+        Self {
+            transcript_gap_ms: 500,
+            streaming_min_chunks: 5,
+            streaming_max_avg_chars: 10.0,
+            streaming_gap_ms: 800,
+            short_item_words: 5,
+            short_item_gap_ms: 5000,
+            open_sentence_gap_ms: 2000,
+            closed_sentence_gap_ms: 500,
+            max_merged_words: 20,
+            speaker_padding_ms: 100,
+            sentence_boundary: SentenceBoundary::default(),
+        }
+    }
+}
+
+impl MergeStrategy for TimelineConfig {
+    fn merge_transcripts(&self, last: &TranscriptChunk, next: &TranscriptChunk) -> bool {
+        next.start <= last.end + self.transcript_gap_ms && !self.is_eos(&last.text)
+    }
+
+    fn streaming_mode(&self, transcripts: &[TranscriptChunk]) -> bool {
+        if transcripts.len() <= self.streaming_min_chunks {
+            return false;
+        }
+
+        let avg_length = transcripts.iter().map(|t| t.text.len()).sum::<usize>() as f32
+            / transcripts.len() as f32;
+        avg_length < self.streaming_max_avg_chars
+    }
+
+    fn merge_items(
+        &self,
+        last: &TimelineViewItem,
+        next: &TranscriptChunk,
+        streaming: bool,
+    ) -> bool {
+        let gap = last.end.abs_diff(next.start);
+        let words = last.text.split_whitespace().count();
+        let eos = self.is_eos(&last.text);
+
+        (streaming && next.start <= last.end + self.streaming_gap_ms)
+            || (words < self.short_item_words && gap < self.short_item_gap_ms)
+            || (!eos && gap < self.open_sentence_gap_ms)
+            || (eos && gap < self.closed_sentence_gap_ms && words <= self.max_merged_words)
+    }
+
+    fn merge_unattributed(
+        &self,
+        last: &TimelineViewItem,
+        next: &TranscriptChunk,
+        streaming: bool,
+    ) -> bool {
+        streaming && next.start <= last.end + self.streaming_gap_ms
+    }
+
+    fn speaker_padding_ms(&self) -> u64 {
+        self.speaker_padding_ms
+    }
+
+    fn is_eos(&self, text: &str) -> bool {
+        self.sentence_boundary.is_eos(text)
+    }
+
+    fn join(&self, text: &mut String, next: &str) {
+        join_text(text, next, self.sentence_boundary == SentenceBoundary::Cjk);
+    }
+}
+
+fn is_cjk(c: char) -> bool {
+    matches!(c,
+        '\u{3000}'..='\u{30FF}' // punctuation, hiragana, katakana
+        | '\u{3400}'..='\u{4DBF}'
+        | '\u{4E00}'..='\u{9FFF}'
+        | '\u{FF00}'..='\u{FFEF}')
+}
+
+// Appends `next` with a single space, or none between CJK characters when `cjk` is set.
+pub(crate) fn join_text(text: &mut String, next: &str, cjk: bool) {
+    if next.trim().is_empty() {
+        return;
+    }
+
+    let needs_space = match (text.chars().last(), next.chars().next()) {
+        (None, _) => false,
+        (Some(a), Some(b)) if a.is_whitespace() || b.is_whitespace() => false,
+        (Some(a), Some(b)) if cjk && is_cjk(a) && is_cjk(b) => false,
+        _ => true,
+    };
+
+    if needs_space {
+        text.push(' ');
+    }
+    text.push_str(next);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sentence_boundary() {
+        let default = SentenceBoundary::Punctuation;
+        assert!(default.is_eos("Hello."));
+        assert!(default.is_eos("Really？ "));
+        assert!(!default.is_eos("你好。"));
+        assert!(!default.is_eos(""));
+        assert!(!default.is_eos("   "));
+
+        let cjk = SentenceBoundary::for_language("ja");
+        assert!(cjk.is_eos("こんにちは。"));
+        assert!(cjk.is_eos("「そうですね。」"));
+        assert!(!cjk.is_eos("今日は"));
+
+        let korean = SentenceBoundary::for_language("ko");
+        assert!(korean.is_eos("감사합니다"));
+        assert!(korean.is_eos("어디 가세요"));
+        assert!(!korean.is_eos("그래서 우리는"));
+    }
+
+    #[test]
+    fn test_join_text() {
+        let mut text = String::new();
+        join_text(&mut text, "Fastest", false);
+        join_text(&mut text, " AI", false);
+        join_text(&mut text, "chat", false);
+        join_text(&mut text, "  ", false);
+        assert_eq!(text, "Fastest AI chat");
+
+        let mut text = "今日は".to_string();
+        join_text(&mut text, "いい天気", true);
+        join_text(&mut text, "OK", true);
+        assert_eq!(text, "今日はいい天気 OK");
+    }
+
+    #[test]
+    fn test_default_config() {
+        insta::assert_json_snapshot!(TimelineConfig::default(), @r###"
+        {
+          "transcript_gap_ms": 500,
+          "streaming_min_chunks": 5,
+          "streaming_max_avg_chars": 10.0,
+          "streaming_gap_ms": 800,
+          "short_item_words": 5,
+          "short_item_gap_ms": 5000,
+          "open_sentence_gap_ms": 2000,
+          "closed_sentence_gap_ms": 500,
+          "max_merged_words": 20,
+          "speaker_padding_ms": 100,
+          "sentence_boundary": "punctuation"
+        }
+        "###);
+    }
+}
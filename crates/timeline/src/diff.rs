@@ -0,0 +1,89 @@
+use crate::{TimelineView, TimelineViewItem};
+
+// A change to a `TimelineView`. Applied in order, they turn the previous view into the current one.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize, specta::Type)]
+#[serde(tag = "type")]
+pub enum TimelineDiff {
+    #[serde(rename = "itemAppended")]
+    ItemAppended { item: TimelineViewItem },
+    #[serde(rename = "itemUpdated")]
+    ItemUpdated {
+        index: usize,
+        item: TimelineViewItem,
+    },
+    // Items from `len` on were merged into earlier ones.
+    #[serde(rename = "truncated")]
+    Truncated { len: usize },
+}
+
+impl TimelineView {
+    pub fn apply(&mut self, diffs: &[TimelineDiff]) {
+        for diff in diffs {
+            match diff {
+                TimelineDiff::ItemAppended { item } => self.items.push(item.clone()),
+                TimelineDiff::ItemUpdated { index, item } => {
+                    if let Some(existing) = self.items.get_mut(*index) {
+                        *existing = item.clone();
+                    }
+                }
+                TimelineDiff::Truncated { len } => self.items.truncate(*len),
+            }
+        }
+    }
+}
+
+crate::common_derives! {
+    // The view as of the last diff, and what changed since.
+    pub(crate) struct ViewCache {
+        pub items: Vec<TimelineViewItem>,
+        // Index of the first transcript of each item.
+        pub starts: Vec<usize>,
+        pub streaming: bool,
+        pub max_confidence: f32,
+        // Earliest transcript changed since the last diff.
+        pub dirty_transcript: Option<usize>,
+        // Earliest diarization changed since the last diff.
+        pub dirty_time: Option<u64>,
+        pub dirty_interims: bool,
+    }
+}
+
+impl ViewCache {
+    pub fn mark_transcript(&mut self, index: usize) {
+        self.dirty_transcript = Some(self.dirty_transcript.map_or(index, |i| i.min(index)));
+    }
+
+    pub fn mark_time(&mut self, time: u64) {
+        self.dirty_time = Some(self.dirty_time.map_or(time, |t| t.min(time)));
+    }
+}
+
+// Diffs turning the tail `old` into `new`, both starting at item `offset`.
+pub(crate) fn diff_items(
+    old: &[TimelineViewItem],
+    new: &[TimelineViewItem],
+    offset: usize,
+) -> Vec<TimelineDiff> {
+    let mut diffs = Vec::new();
+
+    for (i, (old, new)) in old.iter().zip(new).enumerate() {
+        if old != new {
+            diffs.push(TimelineDiff::ItemUpdated {
+                index: offset + i,
+                item: new.clone(),
+            });
+        }
+    }
+
+    if new.len() < old.len() {
+        diffs.push(TimelineDiff::Truncated {
+            len: offset + new.len(),
+        });
+    }
+
+    for item in new.iter().skip(old.len()) {
+        diffs.push(TimelineDiff::ItemAppended { item: item.clone() });
+    }
+
+    diffs
+}
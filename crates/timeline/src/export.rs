@@ -0,0 +1,340 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use crate::{TimelineView, TimelineViewItem};
+
+// Bumped whenever a field of `TranscriptDocument` changes meaning or is removed.
+pub const TRANSCRIPT_SCHEMA_VERSION: u32 = 1;
+
+// Display names for speaker indices. Indices without a name fall back to `Speaker N`.
+pub type SpeakerNames = HashMap<i32, String>;
+
+pub fn speaker_label(names: &SpeakerNames, speaker: i32) -> String {
+    match names.get(&speaker) {
+        Some(name) if !name.trim().is_empty() => name.trim().to_string(),
+        _ if speaker < 0 => "Unknown".to_string(),
+        _ => format!("Speaker {}", speaker + 1),
+    }
+}
+
+crate::common_derives! {
+    #[derive(Copy, Eq)]
+    #[serde(rename_all = "lowercase")]
+    pub enum TranscriptFormat {
+        Srt,
+        Vtt,
+        #[default]
+        Text,
+        Json,
+    }
+}
+
+impl TranscriptFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            TranscriptFormat::Srt => "srt",
+            TranscriptFormat::Vtt => "vtt",
+            TranscriptFormat::Text => "txt",
+            TranscriptFormat::Json => "json",
+        }
+    }
+}
+
+crate::common_derives! {
+    pub struct TranscriptDocument {
+        pub version: u32,
+        pub speakers: Vec<TranscriptSpeaker>,
+        pub segments: Vec<TranscriptSegment>,
+    }
+}
+
+crate::common_derives! {
+    pub struct TranscriptSpeaker {
+        pub index: i32,
+        pub name: String,
+    }
+}
+
+crate::common_derives! {
+    pub struct TranscriptSegment {
+        pub start_ms: u64,
+        pub end_ms: u64,
+        pub speaker: i32,
+        pub speaker_name: String,
+        pub text: String,
+        pub confidence: f32,
+    }
+}
+
+// `HH:MM:SS` followed by `separator` and milliseconds, as used by SRT (`,`) and WebVTT (`.`).
+fn cue_timestamp(ms: u64, separator: char) -> String {
+    let secs = ms / 1000;
+    format!(
+        "{:02}:{:02}:{:02}{}{:03}",
+        secs / 3600,
+        (secs % 3600) / 60,
+        secs % 60,
+        separator,
+        ms % 1000
+    )
+}
+
+fn text_timestamp(ms: u64) -> String {
+    let secs = ms / 1000;
+    format!(
+        "{:02}:{:02}:{:02}",
+        secs / 3600,
+        (secs % 3600) / 60,
+        secs % 60
+    )
+}
+
+fn escape_vtt(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+impl TimelineView {
+    fn cues(&self) -> impl Iterator<Item = (&TimelineViewItem, &str, u64)> {
+        self.items
+            .iter()
+            .filter(|item| !item.text.trim().is_empty())
+            // Players drop cues that end before they start.
+            .map(|item| (item, item.text.trim(), item.end.max(item.start + 1)))
+    }
+
+    pub fn render(&self, format: TranscriptFormat, names: &SpeakerNames) -> String {
+        match format {
+            TranscriptFormat::Srt => self.to_srt(names),
+            TranscriptFormat::Vtt => self.to_vtt(names),
+            TranscriptFormat::Text => self.to_text(names),
+            TranscriptFormat::Json => {
+                serde_json::to_string_pretty(&self.to_document(names)).unwrap_or_default()
+            }
+        }
+    }
+
+    pub fn to_srt(&self, names: &SpeakerNames) -> String {
+        let mut out = String::new();
+
+        for (i, (item, text, end)) in self.cues().enumerate() {
+            let _ = write!(
+                out,
+                "{}\n{} --> {}\n{}: {}\n\n",
+                i + 1,
+                cue_timestamp(item.start, ','),
+                cue_timestamp(end, ','),
+                speaker_label(names, item.speaker),
+                text
+            );
+        }
+
+        out
+    }
+
+    pub fn to_vtt(&self, names: &SpeakerNames) -> String {
+        let mut out = String::from("WEBVTT\n\n");
+
+        for (i, (item, text, end)) in self.cues().enumerate() {
+            let _ = write!(
+                out,
+                "{}\n{} --> {}\n<v {}>{}\n\n",
+                i + 1,
+                cue_timestamp(item.start, '.'),
+                cue_timestamp(end, '.'),
+                escape_vtt(&speaker_label(names, item.speaker)),
+                escape_vtt(text)
+            );
+        }
+
+        out
+    }
+
+    pub fn to_text(&self, names: &SpeakerNames) -> String {
+        let mut out = String::new();
+
+        for (item, text, _) in self.cues() {
+            let _ = writeln!(
+                out,
+                "[{}] {}: {}",
+                text_timestamp(item.start),
+                speaker_label(names, item.speaker),
+                text
+            );
+        }
+
+        out
+    }
+
+    pub fn to_document(&self, names: &SpeakerNames) -> TranscriptDocument {
+        let mut speakers = self
+            .items
+            .iter()
+            .map(|item| item.speaker)
+            .collect::<Vec<_>>();
+        speakers.sort();
+        speakers.dedup();
+
+        TranscriptDocument {
+            version: TRANSCRIPT_SCHEMA_VERSION,
+            speakers: speakers
+                .into_iter()
+                .map(|index| TranscriptSpeaker {
+                    index,
+                    name: speaker_label(names, index),
+                })
+                .collect(),
+            segments: self
+                .cues()
+                .map(|(item, text, end)| TranscriptSegment {
+                    start_ms: item.start,
+                    end_ms: end,
+                    speaker: item.speaker,
+                    speaker_name: speaker_label(names, item.speaker),
+                    text: text.to_string(),
+                    confidence: item.confidence,
+                })
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn view() -> TimelineView {
+        TimelineView {
+            items: vec![
+                TimelineViewItem {
+                    start: 1500,
+                    end: 3000,
+                    speaker: 0,
+                    text: "Hello <there> & welcome.".to_string(),
+                    confidence: 1.0,
+                    interim: false,
+                    translation: None,
+                },
+                TimelineViewItem {
+                    start: 3000,
+                    end: 3000,
+                    speaker: 1,
+                    text: " ".to_string(),
+                    confidence: 1.0,
+                    interim: false,
+                    translation: None,
+                },
+                TimelineViewItem {
+                    start: 3_725_000,
+                    end: 3_726_250,
+                    speaker: 1,
+                    text: "Bye.".to_string(),
+                    confidence: 0.5,
+                    interim: false,
+                    translation: None,
+                },
+                TimelineViewItem {
+                    start: 3_727_000,
+                    end: 3_727_000,
+                    speaker: -1,
+                    text: "Hm.".to_string(),
+                    confidence: 0.5,
+                    interim: false,
+                    translation: None,
+                },
+            ],
+        }
+    }
+
+    fn names() -> SpeakerNames {
+        SpeakerNames::from([(0, "John Doe".to_string())])
+    }
+
+    #[test]
+    fn test_to_srt() {
+        insta::assert_snapshot!(view().to_srt(&names()), @r###"
+        1
+        00:00:01,500 --> 00:00:03,000
+        John Doe: Hello <there> & welcome.
+
+        2
+        01:02:05,000 --> 01:02:06,250
+        Speaker 2: Bye.
+
+        3
+        01:02:07,000 --> 01:02:07,001
+        Unknown: Hm.
+        "###);
+    }
+
+    #[test]
+    fn test_to_vtt() {
+        insta::assert_snapshot!(view().to_vtt(&names()), @r###"
+        WEBVTT
+
+        1
+        00:00:01.500 --> 00:00:03.000
+        <v John Doe>Hello &lt;there&gt; &amp; welcome.
+
+        2
+        01:02:05.000 --> 01:02:06.250
+        <v Speaker 2>Bye.
+
+        3
+        01:02:07.000 --> 01:02:07.001
+        <v Unknown>Hm.
+        "###);
+    }
+
+    #[test]
+    fn test_to_text() {
+        insta::assert_snapshot!(view().to_text(&names()), @r###"
+        [00:00:01] John Doe: Hello <there> & welcome.
+        [01:02:05] Speaker 2: Bye.
+        [01:02:07] Unknown: Hm.
+        "###);
+    }
+
+    #[test]
+    fn test_render() {
+        let view = view();
+        let names = names();
+
+        assert_eq!(
+            view.render(TranscriptFormat::Srt, &names),
+            view.to_srt(&names)
+        );
+
+        let document: TranscriptDocument =
+            serde_json::from_str(&view.render(TranscriptFormat::Json, &names)).unwrap();
+        assert_eq!(document, view.to_document(&names));
+    }
+
+    #[test]
+    fn test_to_document() {
+        let document = view().to_document(&names());
+
+        assert_eq!(document.version, TRANSCRIPT_SCHEMA_VERSION);
+        assert_eq!(
+            document
+                .speakers
+                .iter()
+                .map(|s| (s.index, s.name.as_str()))
+                .collect::<Vec<_>>(),
+            vec![(-1, "Unknown"), (0, "John Doe"), (1, "Speaker 2")]
+        );
+        assert_eq!(document.segments.len(), 3);
+
+        insta::assert_json_snapshot!(document.segments[0], @r###"
+        {
+          "start_ms": 1500,
+          "end_ms": 3000,
+          "speaker": 0,
+          "speaker_name": "John Doe",
+          "text": "Hello <there> & welcome.",
+          "confidence": 1.0
+        }
+        "###);
+    }
+}
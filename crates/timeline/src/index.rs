@@ -0,0 +1,77 @@
+use hypr_listener_interface::DiarizationChunk;
+
+crate::common_derives! {
+    // Diarization segments sorted by start, updated in place as results arrive.
+    pub struct DiarizationIndex {
+        chunks: Vec<DiarizationChunk>,
+        // Longest segment so far. Bounds how far back a query has to look.
+        max_len: u64,
+    }
+}
+
+impl DiarizationIndex {
+    // A segment with the same start replaces the previous one, as diarizers refine their output.
+    pub fn insert(&mut self, item: DiarizationChunk) {
+        self.max_len = self.max_len.max(item.end.saturating_sub(item.start));
+
+        match self.chunks.binary_search_by_key(&item.start, |d| d.start) {
+            Ok(index) => self.chunks[index] = item,
+            Err(index) => self.chunks.insert(index, item),
+        }
+    }
+
+    // Segments overlapping `range`, in order of start.
+    pub fn query(
+        &self,
+        range: std::ops::Range<u64>,
+    ) -> impl Iterator<Item = &DiarizationChunk> + '_ {
+        let lo = self
+            .chunks
+            .partition_point(|d| d.start < range.start.saturating_sub(self.max_len));
+        let hi = self.chunks.partition_point(|d| d.start < range.end);
+
+        self.chunks[lo..hi.max(lo)]
+            .iter()
+            .filter(move |d| d.end > range.start)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &DiarizationChunk> {
+        self.chunks.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.chunks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.chunks.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_query() {
+        let chunk = |start, end, speaker| DiarizationChunk {
+            start,
+            end,
+            speaker,
+            confidence: None,
+        };
+
+        let mut index = DiarizationIndex::default();
+        index.insert(chunk(5000, 6000, 1));
+        index.insert(chunk(0, 4000, 0));
+        index.insert(chunk(4000, 5000, 2));
+        index.insert(chunk(4000, 4500, 3));
+
+        let speakers = |range| index.query(range).map(|d| d.speaker).collect::<Vec<_>>();
+
+        assert_eq!(speakers(3900..4100), vec![0, 3]);
+        assert_eq!(speakers(4500..5500), vec![1]);
+        assert_eq!(speakers(7000..8000), Vec::<i32>::new());
+        assert_eq!(index.len(), 3);
+    }
+}
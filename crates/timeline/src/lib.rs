@@ -1,7 +1,18 @@
-use intervaltree::IntervalTree;
-
 use hypr_listener_interface::{DiarizationChunk, TranscriptChunk};
 
+mod config;
+mod diff;
+mod export;
+mod index;
+mod speakers;
+mod window;
+
+pub use config::*;
+pub use diff::TimelineDiff;
+pub use export::*;
+pub use index::*;
+pub use speakers::*;
+
 #[macro_export]
 macro_rules! common_derives {
     ($item:item) => {
@@ -57,8 +68,18 @@ impl Interval for std::ops::Range<u64> {
 common_derives! {
     pub struct Timeline {
         transcripts: Vec<TranscriptChunk>,
-        diarizations: Vec<DiarizationChunk>,
+        diarizations: DiarizationIndex,
         max_confidence: f32,
+        // Provisional transcripts, shown after the final ones until a final transcript replaces them.
+        #[serde(default)]
+        interims: Vec<TranscriptChunk>,
+        // Translated text, each shown on the item holding its start.
+        #[serde(default)]
+        translations: Vec<TranscriptChunk>,
+        #[serde(default)]
+        config: TimelineConfig,
+        #[serde(skip)]
+        cache: diff::ViewCache,
     }
 }
 
@@ -75,6 +96,10 @@ common_derives! {
         pub speaker: i32,
         pub text: String,
         pub confidence: f32,
+        #[serde(default)]
+        pub interim: bool,
+        #[serde(default)]
+        pub translation: Option<String>,
     }
 }
 
@@ -84,6 +109,17 @@ common_derives! {
     }
 }
 
+common_derives! {
+    #[derive(Copy, Eq)]
+    #[serde(rename_all = "lowercase")]
+    pub enum TranscriptDisplay {
+        #[default]
+        Original,
+        Translated,
+        Both,
+    }
+}
+
 impl std::fmt::Display for TimelineView {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         for item in self.items.iter() {
@@ -99,183 +135,329 @@ impl std::fmt::Display for TimelineViewItem {
     }
 }
 
-impl TimelineViewItem {
-    fn eos(&self) -> bool {
-        matches!(
-            self.text.chars().last().unwrap(),
-            '.' | '?' | '!' | '？' | '！'
-        )
-    }
-
-    fn num_words(&self) -> usize {
-        self.text.split_whitespace().count()
+impl TimelineView {
+    // The same items with `text` replaced as `display` asks, so every renderer and exporter can show translations.
+    pub fn displayed(&self, display: TranscriptDisplay) -> TimelineView {
+        TimelineView {
+            items: self
+                .items
+                .iter()
+                .map(|item| TimelineViewItem {
+                    text: item.display_text(display),
+                    ..item.clone()
+                })
+                .collect(),
+        }
     }
+}
 
-    fn time_diff(&self, other: &TranscriptChunk) -> u64 {
-        self.end.abs_diff(other.start)
+impl TimelineViewItem {
+    // Falls back to the original text until a translation arrives.
+    pub fn display_text(&self, display: TranscriptDisplay) -> String {
+        match (display, &self.translation) {
+            (TranscriptDisplay::Translated, Some(translation)) => translation.clone(),
+            (TranscriptDisplay::Both, Some(translation)) => {
+                format!("{}\n{}", self.text, translation)
+            }
+            _ => self.text.clone(),
+        }
     }
 
-    fn merge(&mut self, other: &TranscriptChunk) {
+    fn merge(&mut self, other: &TranscriptChunk, strategy: &impl MergeStrategy) {
         self.end = other.end;
-
-        self.text.push(' ');
-        self.text.push_str(&other.text);
+        strategy.join(&mut self.text, &other.text);
     }
 }
 
 impl Timeline {
+    pub fn new(config: TimelineConfig) -> Self {
+        Self {
+            config,
+            ..Default::default()
+        }
+    }
+
+    pub fn config(&self) -> &TimelineConfig {
+        &self.config
+    }
+
     pub fn add_transcription(&mut self, item: TranscriptChunk) {
+        let config = self.config.clone();
+        self.add_transcription_with(item, &config);
+    }
+
+    pub fn add_transcription_with(&mut self, item: TranscriptChunk, strategy: &impl MergeStrategy) {
+        if !item.is_final {
+            // Without a revision, the latest interim stands for everything not yet final.
+            self.interims
+                .retain(|t| item.revision.is_some() && t.revision != item.revision);
+            self.interims.push(item);
+            self.cache.dirty_interims = true;
+            return;
+        }
+
+        let interims = self.interims.len();
+        self.interims.retain(|t| {
+            !(t.revision.is_none() || t.revision == item.revision || t.start < item.end)
+        });
+        self.cache.dirty_interims |= interims != self.interims.len();
+
+        // An empty final transcript only retracts the interim ones.
+        if item.text.trim().is_empty() {
+            return;
+        }
+
         if let Some(confidence) = item.confidence {
             if confidence > self.max_confidence || self.max_confidence == 0.0 {
                 self.max_confidence = confidence;
             }
         }
 
-        if !self.transcripts.is_empty() {
-            let last = self.transcripts.last().unwrap();
-
-            if item.start <= last.end + 500
-                && !last.text.ends_with(|c| matches!(c, '.' | '?' | '!' | '！'))
-            {
-                let mut merged = last.clone();
-                merged.end = item.end;
-
-                merged.text.push_str(" ");
-                merged.text.push_str(&item.text);
+        if let Some(last) = self.transcripts.last_mut() {
+            if strategy.merge_transcripts(last, &item) {
+                last.end = item.end;
+                strategy.join(&mut last.text, &item.text);
 
-                *self.transcripts.last_mut().unwrap() = merged;
+                self.cache.mark_transcript(self.transcripts.len() - 1);
                 return;
             }
         }
 
         self.transcripts.push(item);
+        self.cache.mark_transcript(self.transcripts.len() - 1);
     }
 
-    pub fn add_diarization(&mut self, item: DiarizationChunk) {
-        if let Some(index) = self
-            .diarizations
-            .iter()
-            .position(|x| x.start() == item.start())
-        {
-            self.diarizations[index] = item;
-        } else {
-            self.diarizations.push(item);
+    pub fn add_translation(&mut self, item: TranscriptChunk) {
+        if item.text.trim().is_empty() {
+            return;
         }
+
+        let index = self.transcripts.partition_point(|t| t.end <= item.start);
+        self.cache.mark_transcript(index);
+
+        let at = self.translations.partition_point(|t| t.start <= item.start);
+        self.translations.insert(at, item);
     }
 
-    pub fn view(&self, filter: TimelineFilter) -> TimelineView {
-        let tree: IntervalTree<u64, i32> = IntervalTree::from_iter(
-            self.diarizations
-                .iter()
-                .map(|d| (d.start..d.end, d.speaker.clone())),
-        );
+    pub fn add_diarization(&mut self, item: DiarizationChunk) {
+        self.cache.mark_time(item.start);
+        self.diarizations.insert(item);
+    }
 
-        let mut items: Vec<TimelineViewItem> = vec![];
+    pub fn view(&self, filter: TimelineFilter) -> TimelineView {
+        self.view_with(filter, &self.config)
+    }
 
+    pub fn view_with(&self, filter: TimelineFilter, strategy: &impl MergeStrategy) -> TimelineView {
         let max_end = self.transcripts.iter().map(|t| t.end).max().unwrap_or(0);
-        let filtered_transcripts = self.transcripts.iter().filter(|t| {
+        let filtered_transcripts = self.transcripts.iter().enumerate().filter(|(_, t)| {
             filter
                 .last_n_seconds
                 .is_none_or(|n| t.end >= max_end.saturating_sub(n * 1000))
         });
 
-        let mut streaming_mode = false;
-        if self.transcripts.len() > 5 {
-            let avg_length = self.transcripts.iter().map(|t| t.text.len()).sum::<usize>() as f32
-                / self.transcripts.len() as f32;
+        let mut items = vec![];
+        let streaming_mode = strategy.streaming_mode(&self.transcripts);
+        self.extend_items(
+            &mut items,
+            &mut vec![],
+            filtered_transcripts,
+            strategy,
+            streaming_mode,
+        );
+        self.extend_interims(&mut items, strategy);
+
+        TimelineView { items }
+    }
 
-            streaming_mode = avg_length < 10.0;
+    // The view as of the last `take_diffs`. Sent in full to new subscribers, who then follow the diffs.
+    pub fn snapshot(&self) -> TimelineView {
+        TimelineView {
+            items: self.cache.items.clone(),
         }
+    }
 
-        for transcript in filtered_transcripts {
-            let range = transcript.start.saturating_sub(100)..transcript.end.saturating_add(100);
-            let speakers: Vec<_> = tree.query(range).collect();
-
-            let normalized_confidence = transcript
-                .confidence
-                .map(|conf| {
-                    if self.max_confidence > 0.0 {
-                        conf / self.max_confidence
-                    } else {
-                        1.0
-                    }
-                })
-                .unwrap_or(1.0);
-
-            if speakers.is_empty() {
-                if streaming_mode && !items.is_empty() {
-                    let last_item = items.last_mut().unwrap();
-                    if transcript.start <= last_item.end + 800 {
-                        last_item.merge(transcript);
-                        continue;
-                    }
-                }
+    pub fn take_diffs(&mut self) -> Vec<TimelineDiff> {
+        let config = self.config.clone();
+        self.take_diffs_with(&config)
+    }
 
-                items.push(TimelineViewItem {
-                    start: transcript.start,
-                    end: transcript.end,
-                    speaker: -1,
-                    text: transcript.text.clone(),
-                    confidence: normalized_confidence,
-                });
+    // Rebuilds only the items that the transcripts and diarizations added since the last call can touch.
+    pub fn take_diffs_with(&mut self, strategy: &impl MergeStrategy) -> Vec<TimelineDiff> {
+        let streaming_mode = strategy.streaming_mode(&self.transcripts);
+
+        let mut first = self.cache.dirty_transcript.take();
+        if let Some(time) = self.cache.dirty_time.take() {
+            let padding = strategy.speaker_padding_ms();
+            let affected = self
+                .transcripts
+                .partition_point(|t| t.end.saturating_add(padding) <= time);
+            first = Some(first.map_or(affected, |f| f.min(affected)));
+        }
 
-                continue;
-            }
+        // Both change how every item is built.
+        let rebuild = streaming_mode != self.cache.streaming
+            || self.max_confidence != self.cache.max_confidence;
+
+        let restart = match (rebuild, first) {
+            (true, _) => 0,
+            // The item holding `first` may now merge into its predecessor, so that one is rebuilt too.
+            (false, Some(first)) => self
+                .cache
+                .starts
+                .partition_point(|&s| s <= first)
+                .saturating_sub(2),
+            // Only the interim items after the last final one changed.
+            (false, None) if self.cache.dirty_interims => self.cache.starts.len(),
+            (false, None) => return vec![],
+        };
+        self.cache.dirty_interims = false;
+
+        let from = match restart {
+            0 => 0,
+            r => self
+                .cache
+                .starts
+                .get(r)
+                .copied()
+                .unwrap_or(self.transcripts.len()),
+        };
+
+        let mut items = std::mem::take(&mut self.cache.items);
+        let mut starts = std::mem::take(&mut self.cache.starts);
+
+        let old = items.split_off(restart);
+        starts.truncate(restart);
+
+        self.extend_items(
+            &mut items,
+            &mut starts,
+            self.transcripts.iter().enumerate().skip(from),
+            strategy,
+            streaming_mode,
+        );
+        self.extend_interims(&mut items, strategy);
 
-            let speaker = speakers
-                .iter()
-                .map(|entry| {
-                    let diarization_interval = entry.range.start..entry.range.end;
-                    let overlap = transcript.overlaps(&diarization_interval).unwrap_or(0);
+        let diffs = diff::diff_items(&old, &items[restart..], restart);
 
-                    (entry.value.clone(), overlap)
-                })
-                .max_by_key(|(_, overlap)| *overlap)
-                .map(|(speaker, _)| speaker)
-                .unwrap();
+        self.cache.items = items;
+        self.cache.starts = starts;
+        self.cache.streaming = streaming_mode;
+        self.cache.max_confidence = self.max_confidence;
+
+        diffs
+    }
+
+    fn extend_items<'a>(
+        &self,
+        items: &mut Vec<TimelineViewItem>,
+        starts: &mut Vec<usize>,
+        transcripts: impl Iterator<Item = (usize, &'a TranscriptChunk)>,
+        strategy: &impl MergeStrategy,
+        streaming_mode: bool,
+    ) {
+        let padding = strategy.speaker_padding_ms();
+        // The last item may still grow, so its translation is recomputed too.
+        let first = items.len().saturating_sub(1);
+
+        for (index, transcript) in transcripts {
+            let speaker = self.speaker(transcript, padding);
 
             if let Some(last_item) = items.last_mut() {
-                if transcript.text.trim().is_empty() {
-                    last_item.merge(transcript);
+                let merge = transcript.text.trim().is_empty()
+                    || match speaker {
+                        Some(speaker) => {
+                            last_item.speaker == speaker
+                                && strategy.merge_items(last_item, transcript, streaming_mode)
+                        }
+                        None => strategy.merge_unattributed(last_item, transcript, streaming_mode),
+                    };
+
+                if merge {
+                    last_item.merge(transcript, strategy);
                     continue;
                 }
-
-                if last_item.speaker == speaker {
-                    if streaming_mode && transcript.start <= last_item.end + 800 {
-                        last_item.merge(transcript);
-                        continue;
-                    }
-
-                    if last_item.num_words() < 5 && last_item.time_diff(transcript) < 5000 {
-                        last_item.merge(transcript);
-                        continue;
-                    }
-
-                    if !last_item.eos() && last_item.time_diff(transcript) < 2000 {
-                        last_item.merge(transcript);
-                        continue;
-                    }
-
-                    if last_item.eos()
-                        && last_item.time_diff(transcript) < 500
-                        && last_item.num_words() <= 20
-                    {
-                        last_item.merge(transcript);
-                        continue;
-                    }
-                }
+            } else if transcript.text.trim().is_empty() {
+                continue;
             }
 
+            starts.push(index);
             items.push(TimelineViewItem {
                 start: transcript.start,
                 end: transcript.end,
-                speaker,
+                speaker: speaker.unwrap_or(-1),
                 text: transcript.text.clone(),
-                confidence: normalized_confidence,
+                confidence: self.normalized_confidence(transcript),
+                interim: false,
+                translation: None,
             });
         }
 
-        TimelineView { items }
+        for item in items.iter_mut().skip(first) {
+            item.translation = self.translation(item.start..item.end);
+        }
+    }
+
+    // Interim transcripts are never merged into final items, so replacing them only touches the tail.
+    fn extend_interims(&self, items: &mut Vec<TimelineViewItem>, strategy: &impl MergeStrategy) {
+        let mut interims = self
+            .interims
+            .iter()
+            .filter(|t| !t.text.trim().is_empty())
+            .collect::<Vec<_>>();
+        interims.sort_by_key(|t| t.start);
+
+        for transcript in interims {
+            items.push(TimelineViewItem {
+                start: transcript.start,
+                end: transcript.end,
+                speaker: self
+                    .speaker(transcript, strategy.speaker_padding_ms())
+                    .unwrap_or(-1),
+                text: transcript.text.trim().to_string(),
+                confidence: self.normalized_confidence(transcript),
+                interim: true,
+                translation: None,
+            });
+        }
+    }
+
+    fn speaker(&self, transcript: &TranscriptChunk, padding: u64) -> Option<i32> {
+        let range =
+            transcript.start.saturating_sub(padding)..transcript.end.saturating_add(padding);
+
+        self.diarizations
+            .query(range)
+            .map(|d| {
+                let overlap = transcript.overlaps(&(d.start..d.end)).unwrap_or(0);
+                (d.speaker, overlap)
+            })
+            .max_by_key(|(_, overlap)| *overlap)
+            .map(|(speaker, _)| speaker)
+    }
+
+    fn translation(&self, range: std::ops::Range<u64>) -> Option<String> {
+        let from = self.translations.partition_point(|t| t.start < range.start);
+        let texts = self.translations[from..]
+            .iter()
+            .take_while(|t| t.start < range.end)
+            .map(|t| t.text.trim())
+            .collect::<Vec<_>>();
+
+        (!texts.is_empty()).then(|| texts.join(" "))
+    }
+
+    fn normalized_confidence(&self, transcript: &TranscriptChunk) -> f32 {
+        transcript
+            .confidence
+            .map(|conf| {
+                if self.max_confidence > 0.0 {
+                    conf / self.max_confidence
+                } else {
+                    1.0
+                }
+            })
+            .unwrap_or(1.0)
     }
 }
 
@@ -414,6 +596,48 @@ mod tests {
         "###);
     }
 
+    #[test]
+    fn test_incremental_diffs() {
+        let transcripts: Vec<TranscriptChunk> =
+            serde_json::from_str(hypr_data::english_3::TRANSCRIPTION_JSON).unwrap();
+        let diarizations: Vec<DiarizationChunk> =
+            serde_json::from_str(hypr_data::english_3::DIARIZATION_JSON).unwrap();
+
+        let mut timeline = Timeline::default();
+        let mut client = TimelineView::default();
+        let mut updates = 0;
+
+        // Diarization lags behind the transcript, as with live STT.
+        let mut pending = diarizations.into_iter().peekable();
+        for t in transcripts {
+            while let Some(d) = pending.next_if(|d| d.end <= t.start) {
+                timeline.add_diarization(d);
+            }
+            timeline.add_transcription(t);
+
+            let diffs = timeline.take_diffs();
+            updates += diffs
+                .iter()
+                .filter(|d| matches!(d, TimelineDiff::ItemUpdated { .. }))
+                .count();
+
+            client.apply(&diffs);
+            assert_eq!(client, timeline.view(TimelineFilter::default()));
+        }
+
+        for d in pending {
+            timeline.add_diarization(d);
+        }
+        client.apply(&timeline.take_diffs());
+
+        assert_eq!(client, timeline.view(TimelineFilter::default()));
+        assert_eq!(client, timeline.snapshot());
+        assert!(timeline.take_diffs().is_empty());
+
+        // Only the tail is ever touched.
+        assert!(updates < client.items.len() * 4);
+    }
+
     #[test]
     fn test_streaming_transcription() {
         let mut timeline = Timeline::default();
@@ -423,24 +647,36 @@ mod tests {
             end: 1500,
             text: "Fastest".to_string(),
             confidence: Some(0.9),
+            is_final: true,
+            revision: None,
+            language: None,
         });
         timeline.add_transcription(TranscriptChunk {
             start: 1500,
             end: 1800,
             text: " AI".to_string(),
             confidence: Some(0.9),
+            is_final: true,
+            revision: None,
+            language: None,
         });
         timeline.add_transcription(TranscriptChunk {
             start: 1800,
             end: 2000,
             text: " chat".to_string(),
             confidence: Some(0.9),
+            is_final: true,
+            revision: None,
+            language: None,
         });
         timeline.add_transcription(TranscriptChunk {
             start: 2000,
             end: 2400,
             text: " app".to_string(),
             confidence: Some(0.9),
+            is_final: true,
+            revision: None,
+            language: None,
         });
 
         timeline.add_transcription(TranscriptChunk {
@@ -448,6 +684,9 @@ mod tests {
             end: 4000,
             text: "It's really good.".to_string(),
             confidence: Some(0.9),
+            is_final: true,
+            revision: None,
+            language: None,
         });
 
         let view = timeline.view(TimelineFilter::default());
@@ -456,4 +695,142 @@ mod tests {
         assert_eq!(view.items[0].text, "Fastest AI chat app");
         assert_eq!(view.items[1].text, "It's really good.");
     }
+
+    #[test]
+    fn test_empty_and_korean_transcription() {
+        let chunk = |start, end, text: &str| TranscriptChunk {
+            start,
+            end,
+            text: text.to_string(),
+            confidence: None,
+            is_final: true,
+            revision: None,
+            language: None,
+        };
+
+        let mut timeline = Timeline::default();
+        timeline.add_transcription(chunk(0, 500, ""));
+        timeline.add_transcription(chunk(3000, 3500, "   "));
+        timeline.add_diarization(DiarizationChunk {
+            start: 0,
+            end: 4000,
+            speaker: 0,
+            confidence: None,
+        });
+        assert!(timeline.view(TimelineFilter::default()).items.is_empty());
+
+        let transcripts = [
+            chunk(0, 1000, "오늘 회의를 시작하겠습니다"),
+            chunk(1200, 2000, "먼저 지난주 내용을"),
+            chunk(2100, 3000, "정리해 볼게요"),
+        ];
+
+        let mut timeline = Timeline::new(TimelineConfig {
+            sentence_boundary: SentenceBoundary::for_language("ko"),
+            ..Default::default()
+        });
+        for t in transcripts.clone() {
+            timeline.add_transcription(t);
+        }
+        insta::assert_snapshot!(timeline.view(TimelineFilter::default()).to_string(), @r###"
+        -1
+        오늘 회의를 시작하겠습니다
+
+        -1
+        먼저 지난주 내용을 정리해 볼게요
+        "###);
+
+        // Without Korean endings, the unpunctuated sentences run together.
+        let mut timeline = Timeline::default();
+        for t in transcripts {
+            timeline.add_transcription(t);
+        }
+        assert_eq!(timeline.view(TimelineFilter::default()).items.len(), 1);
+    }
+
+    #[test]
+    fn test_interim_transcription() {
+        let chunk = |start, end, text: &str, is_final, revision| TranscriptChunk {
+            start,
+            end,
+            text: text.to_string(),
+            confidence: None,
+            is_final,
+            revision: Some(revision),
+            language: None,
+        };
+
+        let mut timeline = Timeline::default();
+        let mut client = TimelineView::default();
+        let mut sync = |timeline: &mut Timeline| {
+            client.apply(&timeline.take_diffs());
+            assert_eq!(client, timeline.view(TimelineFilter::default()));
+            client.clone()
+        };
+
+        timeline.add_transcription(chunk(0, 500, "Hello", false, 0));
+        timeline.add_transcription(chunk(0, 900, "Hello there", false, 0));
+        let view = sync(&mut timeline);
+        assert_eq!(view.items.len(), 1);
+        assert_eq!(view.items[0].text, "Hello there");
+        assert!(view.items[0].interim);
+
+        timeline.add_transcription(chunk(2000, 2500, "How are", false, 2000));
+        timeline.add_transcription(chunk(0, 1000, "Hello there.", true, 0));
+        let view = sync(&mut timeline);
+        assert_eq!(view.items.len(), 2);
+        assert_eq!(view.items[0].text, "Hello there.");
+        assert!(!view.items[0].interim);
+        assert!(view.items[1].interim);
+
+        timeline.add_transcription(chunk(2000, 3000, "How are you?", true, 2000));
+        let view = sync(&mut timeline);
+        assert!(view.items.iter().all(|item| !item.interim));
+        assert_eq!(view, timeline.snapshot());
+    }
+
+    #[test]
+    fn test_translation() {
+        let chunk = |start, end, text: &str| TranscriptChunk {
+            start,
+            end,
+            text: text.to_string(),
+            confidence: None,
+            is_final: true,
+            revision: None,
+            language: Some("ko".to_string()),
+        };
+
+        let mut timeline = Timeline::default();
+        let mut client = TimelineView::default();
+
+        timeline.add_transcription(chunk(0, 1000, "안녕하세요."));
+        timeline.add_transcription(chunk(3000, 4000, "회의를 시작하겠습니다."));
+        client.apply(&timeline.take_diffs());
+        assert!(client.items.iter().all(|item| item.translation.is_none()));
+
+        // Translations arrive late and out of order.
+        timeline.add_translation(chunk(3000, 4000, "Let's start the meeting."));
+        timeline.add_translation(chunk(0, 1000, "Hello."));
+        client.apply(&timeline.take_diffs());
+        assert_eq!(client, timeline.view(TimelineFilter::default()));
+
+        insta::assert_snapshot!(client.displayed(TranscriptDisplay::Both).to_string(), @r###"
+        -1
+        안녕하세요.
+        Hello.
+
+        -1
+        회의를 시작하겠습니다.
+        Let's start the meeting.
+        "###);
+        assert_eq!(
+            client.items[0].display_text(TranscriptDisplay::Translated),
+            "Hello."
+        );
+        assert_eq!(
+            client.items[0].display_text(TranscriptDisplay::Original),
+            "안녕하세요."
+        );
+    }
 }
@@ -0,0 +1,126 @@
+use std::collections::BTreeMap;
+use std::ops::Range;
+
+use hypr_listener_interface::DiarizationChunk;
+
+use crate::Interval;
+
+// Time ranges covered by each diarization speaker index.
+pub fn speaker_segments(diarizations: &[DiarizationChunk]) -> BTreeMap<i32, Vec<Range<u64>>> {
+    let mut segments: BTreeMap<i32, Vec<Range<u64>>> = BTreeMap::new();
+
+    for d in diarizations {
+        segments.entry(d.speaker).or_default().push(d.start..d.end);
+    }
+
+    segments
+}
+
+fn total_overlap(a: &[Range<u64>], b: &[Range<u64>]) -> u64 {
+    a.iter()
+        .flat_map(|x| b.iter().filter_map(move |y| x.overlaps(y)))
+        .sum()
+}
+
+// Segments a speaker covered when it was identified.
+// They keep pointing at the same voice after re-diarization renumbers the speakers.
+pub struct SpeakerAnchor {
+    pub ranges: Vec<Range<u64>>,
+    // Set when several speakers were folded into this one on purpose.
+    pub merged: bool,
+}
+
+fn duration(ranges: &[Range<u64>]) -> u64 {
+    ranges.iter().map(|r| r.end.saturating_sub(r.start)).sum()
+}
+
+// Pairs speaker indices with the anchor covering most of their speech.
+// A speaker only matches when the anchor covers the majority of its duration, and an anchor keeps a single speaker
+// unless it was merged, in which case every matching index lands on it.
+pub fn match_speakers(
+    anchors: &[SpeakerAnchor],
+    segments: &BTreeMap<i32, Vec<Range<u64>>>,
+) -> BTreeMap<i32, usize> {
+    let mut candidates: BTreeMap<usize, Vec<(i32, u64)>> = BTreeMap::new();
+
+    for (speaker, ranges) in segments {
+        let total = duration(ranges);
+
+        let best = anchors
+            .iter()
+            .enumerate()
+            .map(|(i, anchor)| (i, total_overlap(&anchor.ranges, ranges)))
+            .filter(|(_, overlap)| *overlap * 2 > total)
+            .max_by_key(|(i, overlap)| (*overlap, std::cmp::Reverse(*i)));
+
+        if let Some((anchor, overlap)) = best {
+            candidates
+                .entry(anchor)
+                .or_default()
+                .push((*speaker, overlap));
+        }
+    }
+
+    candidates
+        .into_iter()
+        .flat_map(|(anchor, mut speakers)| {
+            if !anchors[anchor].merged {
+                speakers.sort_by_key(|(speaker, overlap)| {
+                    (std::cmp::Reverse(*overlap), *speaker)
+                });
+                speakers.truncate(1);
+            }
+            speakers.into_iter().map(move |(speaker, _)| (speaker, anchor))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chunk(start: u64, end: u64, speaker: i32) -> DiarizationChunk {
+        DiarizationChunk {
+            start,
+            end,
+            speaker,
+            confidence: None,
+        }
+    }
+
+    fn anchor(ranges: &[(u64, u64)], merged: bool) -> SpeakerAnchor {
+        SpeakerAnchor {
+            ranges: ranges.iter().map(|(start, end)| *start..*end).collect(),
+            merged,
+        }
+    }
+
+    #[test]
+    fn test_match_speakers() {
+        // Re-diarization swapped the indices and split the first voice in two.
+        let segments = speaker_segments(&[
+            chunk(0, 1000, 1),
+            chunk(1000, 2000, 0),
+            chunk(2000, 3000, 2),
+            chunk(2900, 6000, 3),
+        ]);
+
+        let anchors = vec![
+            anchor(&[(0, 1000), (2000, 3000)], false),
+            anchor(&[(1000, 2000)], false),
+        ];
+        assert_eq!(
+            match_speakers(&anchors, &segments),
+            BTreeMap::from([(0, 1), (1, 0)])
+        );
+
+        let anchors = vec![
+            anchor(&[(0, 1000), (2000, 3000)], true),
+            anchor(&[(1000, 2000)], false),
+        ];
+        assert_eq!(
+            match_speakers(&anchors, &segments),
+            BTreeMap::from([(0, 1), (1, 0), (2, 0)])
+        );
+    }
+}
@@ -0,0 +1,134 @@
+use crate::{TimelineView, TimelineViewItem};
+
+impl TimelineView {
+    // Splits the view into consecutive windows of at most `max_chars` characters of text, for models
+    // that can't take the whole transcript at once. Windows end where the speaker changes when there
+    // is such a point in their second half, so a turn is rarely cut off from its reply. Items longer
+    // than a window are split between words.
+    pub fn windows(&self, max_chars: usize) -> Vec<TimelineView> {
+        let max_chars = max_chars.max(1);
+
+        let mut windows = vec![];
+        let mut current: Vec<TimelineViewItem> = vec![];
+        let mut chars = 0;
+
+        for item in self
+            .items
+            .iter()
+            .flat_map(|item| split_item(item, max_chars))
+        {
+            let len = item.text.chars().count();
+
+            if chars + len > max_chars && !current.is_empty() {
+                let at = (current.len() / 2..current.len())
+                    .rev()
+                    .find(|&i| i > 0 && current[i].speaker != current[i - 1].speaker)
+                    .unwrap_or(current.len());
+
+                let rest = current.split_off(at);
+                windows.push(TimelineView { items: current });
+                current = rest;
+                chars = current.iter().map(|i| i.text.chars().count()).sum();
+
+                // What was carried over may still not leave room.
+                if chars + len > max_chars && !current.is_empty() {
+                    windows.push(TimelineView {
+                        items: std::mem::take(&mut current),
+                    });
+                    chars = 0;
+                }
+            }
+
+            chars += len;
+            current.push(item);
+        }
+
+        if !current.is_empty() {
+            windows.push(TimelineView { items: current });
+        }
+
+        windows
+    }
+}
+
+fn split_item(item: &TimelineViewItem, max_chars: usize) -> Vec<TimelineViewItem> {
+    if item.text.chars().count() <= max_chars {
+        return vec![item.clone()];
+    }
+
+    let mut parts = vec![];
+    let mut part = String::new();
+
+    for word in item.text.split_whitespace() {
+        let len = part.chars().count() + 1 + word.chars().count();
+        if !part.is_empty() && len > max_chars {
+            parts.push(std::mem::take(&mut part));
+        }
+        if !part.is_empty() {
+            part.push(' ');
+        }
+        part.push_str(word);
+    }
+    if !part.is_empty() {
+        parts.push(part);
+    }
+
+    parts
+        .into_iter()
+        .map(|text| TimelineViewItem {
+            text,
+            ..item.clone()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(speaker: i32, text: &str) -> TimelineViewItem {
+        TimelineViewItem {
+            speaker,
+            text: text.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn texts(windows: &[TimelineView]) -> Vec<Vec<&str>> {
+        windows
+            .iter()
+            .map(|w| w.items.iter().map(|i| i.text.as_str()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_windows() {
+        let view = TimelineView {
+            items: vec![
+                item(0, "aaaa"),
+                item(1, "bbbb"),
+                item(1, "cccc"),
+                item(0, "dddd"),
+                item(0, "eeee"),
+            ],
+        };
+
+        assert_eq!(view.windows(100).len(), 1);
+        assert!(TimelineView::default().windows(10).is_empty());
+
+        // The first three would fit together, but windows end where the speaker changes.
+        assert_eq!(
+            texts(&view.windows(12)),
+            vec![vec!["aaaa"], vec!["bbbb", "cccc"], vec!["dddd", "eeee"]]
+        );
+        assert_eq!(texts(&view.windows(4)).len(), 5);
+
+        let view = TimelineView {
+            items: vec![item(0, "one two three four five")],
+        };
+        assert_eq!(
+            texts(&view.windows(9)),
+            vec![vec!["one two"], vec!["three"], vec!["four five"]]
+        );
+    }
+}
@@ -0,0 +1,250 @@
+use hypr_listener_interface::VocabularyTerm;
+
+// Misheard terms rarely span more words than this.
+const MAX_WINDOW: usize = 3;
+// Below this many letters, near misses are mostly other real words,
+// and so are exact matches that ignore case ("Go", "IT").
+const MIN_FUZZY_LEN: usize = 4;
+// Minimum similarity, from 0 to 1, for a replacement.
+const THRESHOLD: f32 = 0.8;
+
+// Terms for Whisper's initial prompt, most boosted first. Whisper only reads the prompt as context,
+// so this is the best it can do.
+pub fn whisper_prompt(terms: &[VocabularyTerm]) -> String {
+    let mut terms = terms.iter().collect::<Vec<_>>();
+    terms.sort_by(|a, b| b.boost.unwrap_or(0.0).total_cmp(&a.boost.unwrap_or(0.0)));
+
+    terms
+        .iter()
+        .map(|t| t.term.trim())
+        .filter(|t| !t.is_empty())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+struct Candidate {
+    key: Vec<char>,
+    // Set for short terms, which then only match with the same case.
+    cased: Option<Vec<char>>,
+    term: String,
+}
+
+// Replaces words that nearly match a vocabulary term, or one of its pronunciations, with the term.
+pub struct Corrector {
+    candidates: Vec<Candidate>,
+}
+
+impl Corrector {
+    pub fn new(terms: &[VocabularyTerm]) -> Self {
+        let candidates = terms
+            .iter()
+            .filter(|t| !t.term.trim().is_empty())
+            .flat_map(|t| {
+                std::iter::once(&t.term)
+                    .chain(t.pronunciations.iter())
+                    .map(|spoken| {
+                        let key = key(spoken);
+                        Candidate {
+                            cased: (key.len() < MIN_FUZZY_LEN).then(|| cased_key(spoken)),
+                            key,
+                            term: t.term.trim().to_string(),
+                        }
+                    })
+            })
+            .filter(|c| !c.key.is_empty())
+            .collect();
+
+        Self { candidates }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.candidates.is_empty()
+    }
+
+    pub fn correct(&self, text: &str) -> String {
+        if self.candidates.is_empty() {
+            return text.to_string();
+        }
+
+        let words = words(text);
+        let mut out = String::with_capacity(text.len());
+        let mut copied = 0;
+        let mut i = 0;
+
+        while i < words.len() {
+            let Some((len, term)) = self.best_match(&words[i..]) else {
+                i += 1;
+                continue;
+            };
+
+            let (start, end) = (words[i].start, words[i + len - 1].end);
+            if text[start..end] != *term {
+                out.push_str(&text[copied..start]);
+                out.push_str(term);
+                copied = end;
+            }
+            i += len;
+        }
+
+        out.push_str(&text[copied..]);
+        out
+    }
+
+    // The number of words to replace and the term replacing them.
+    fn best_match(&self, words: &[Word]) -> Option<(usize, &str)> {
+        let mut best: Option<(f32, usize, &str)> = None;
+
+        for len in 1..=MAX_WINDOW.min(words.len()) {
+            let window = words[..len]
+                .iter()
+                .flat_map(|w| w.key.iter().copied())
+                .collect::<Vec<_>>();
+            let cased_window = words[..len]
+                .iter()
+                .flat_map(|w| w.cased.iter().copied())
+                .collect::<Vec<_>>();
+
+            for candidate in &self.candidates {
+                let score = similarity(&window, &candidate.key);
+                let matched = match &candidate.cased {
+                    Some(cased) => *cased == cased_window,
+                    None => {
+                        let fuzzy = window.len().min(candidate.key.len()) >= MIN_FUZZY_LEN;
+                        score == 1.0 || (fuzzy && score >= THRESHOLD)
+                    }
+                };
+
+                if matched && best.is_none_or(|(s, _, _)| score > s) {
+                    best = Some((score, len, &candidate.term));
+                }
+            }
+        }
+
+        best.map(|(_, len, term)| (len, term))
+    }
+}
+
+struct Word {
+    // Byte range without surrounding punctuation.
+    start: usize,
+    end: usize,
+    key: Vec<char>,
+    cased: Vec<char>,
+}
+
+fn words(text: &str) -> Vec<Word> {
+    let mut words = Vec::new();
+    let mut offset = 0;
+
+    for token in text.split_whitespace() {
+        let start = offset + text[offset..].find(token).unwrap();
+        offset = start + token.len();
+
+        let trimmed = token.trim_matches(|c: char| !c.is_alphanumeric());
+        if trimmed.is_empty() {
+            continue;
+        }
+
+        let start = start + token.find(trimmed).unwrap();
+        words.push(Word {
+            start,
+            end: start + trimmed.len(),
+            key: key(trimmed),
+            cased: cased_key(trimmed),
+        });
+    }
+
+    words
+}
+
+// Lowercased letters and digits. Spacing and punctuation are often misheard, so they are ignored.
+fn key(text: &str) -> Vec<char> {
+    cased_key(text)
+        .into_iter()
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+fn cased_key(text: &str) -> Vec<char> {
+    text.chars().filter(|c| c.is_alphanumeric()).collect()
+}
+
+fn similarity(a: &[char], b: &[char]) -> f32 {
+    let len = a.len().max(b.len());
+    if len == 0 {
+        return 1.0;
+    }
+
+    1.0 - levenshtein(a, b) as f32 / len as f32
+}
+
+fn levenshtein(a: &[char], b: &[char]) -> usize {
+    let mut prev = (0..=b.len()).collect::<Vec<_>>();
+    let mut curr = vec![0; b.len() + 1];
+
+    for (i, ca) in a.iter().enumerate() {
+        curr[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != cb);
+            curr[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(curr[j] + 1);
+        }
+        std::mem::swap(&mut prev, &mut curr);
+    }
+
+    prev[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn term(term: &str, boost: Option<f32>, pronunciations: &[&str]) -> VocabularyTerm {
+        VocabularyTerm {
+            term: term.to_string(),
+            boost,
+            pronunciations: pronunciations.iter().map(|p| p.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_whisper_prompt() {
+        let terms = [
+            term("Kubernetes", None, &[]),
+            term("Hyprnote", Some(0.9), &[]),
+            term(" ", Some(1.0), &[]),
+        ];
+
+        assert_eq!(whisper_prompt(&terms), "Hyprnote, Kubernetes");
+    }
+
+    #[test]
+    fn test_correct() {
+        let corrector = Corrector::new(&[
+            term("Hyprnote", None, &[]),
+            term("Kubernetes", None, &["cooper netties"]),
+            term("SQL", None, &["sequel"]),
+        ]);
+
+        assert_eq!(
+            corrector.correct(" We built hyper note on Cooper Netty's."),
+            " We built Hyprnote on Kubernetes."
+        );
+        assert_eq!(
+            corrector.correct("Write the sequel query."),
+            "Write the SQL query."
+        );
+
+        // Short words only match exactly.
+        let corrector = Corrector::new(&[term("Jira", None, &[])]);
+        assert_eq!(corrector.correct("Jim, hire her."), "Jim, hire her.");
+        assert_eq!(corrector.correct("Check jira!"), "Check Jira!");
+
+        // Very short terms need the same case, or common words would be rewritten.
+        let corrector = Corrector::new(&[term("Go", None, &[]), term("IT", None, &[])]);
+        assert_eq!(
+            corrector.correct("Let's go, it works."),
+            "Let's go, it works."
+        );
+        assert_eq!(corrector.correct("Ask IT about Go."), "Ask IT about Go.");
+    }
+}
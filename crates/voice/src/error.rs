@@ -0,0 +1,24 @@
+use serde::{ser::Serializer, Serialize};
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error(transparent)]
+    OrtError(#[from] hypr_onnx::ort::Error),
+    #[error(transparent)]
+    ShapeError(#[from] hypr_onnx::ndarray::ShapeError),
+    #[error(transparent)]
+    IoError(#[from] std::io::Error),
+    #[error("model has no inputs")]
+    InvalidModel,
+    #[error("no audio to embed")]
+    EmptyAudio,
+}
+
+impl Serialize for Error {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(self.to_string().as_ref())
+    }
+}
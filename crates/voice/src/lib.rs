@@ -0,0 +1,214 @@
+use hypr_listener_interface::DiarizationChunk;
+use hypr_onnx::{ndarray::Array2, ort::session::Session};
+
+mod error;
+pub use error::*;
+
+pub const SAMPLE_RATE: u32 = 16000;
+
+// Cosine similarity above which an enrolled voice is suggested for a speaker.
+pub const DEFAULT_THRESHOLD: f32 = 0.6;
+
+// Diarization segments shorter than this are mostly crosstalk and backchannels.
+const MIN_SEGMENT_MS: u64 = 1000;
+// Enough speech for a stable embedding. More only adds compute.
+const MAX_SPEECH_MS: u64 = 30_000;
+
+// Speaker embedding model taking mono 16kHz samples (`[1, samples]`) and returning one embedding per batch item.
+pub struct SpeakerEmbedder {
+    session: Session,
+    input: String,
+}
+
+impl SpeakerEmbedder {
+    pub fn new(model: &[u8]) -> Result<Self, crate::Error> {
+        let session = hypr_onnx::load_model(model)?;
+        let input = session
+            .inputs
+            .first()
+            .map(|i| i.name.clone())
+            .ok_or(crate::Error::InvalidModel)?;
+
+        Ok(Self { session, input })
+    }
+
+    pub fn from_file(path: impl AsRef<std::path::Path>) -> Result<Self, crate::Error> {
+        Self::new(&std::fs::read(path)?)
+    }
+
+    pub fn embed(&self, samples: &[f32]) -> Result<Vec<f32>, crate::Error> {
+        if samples.is_empty() {
+            return Err(crate::Error::EmptyAudio);
+        }
+
+        let input = Array2::from_shape_vec((1, samples.len()), samples.to_vec())?;
+        let outputs = self
+            .session
+            .run(hypr_onnx::ort::inputs![self.input.as_str() => input.view()]?)?;
+
+        let embedding = outputs[0].try_extract_tensor::<f32>()?;
+        Ok(normalize(embedding.iter().copied().collect()))
+    }
+}
+
+pub fn normalize(mut v: Vec<f32>) -> Vec<f32> {
+    let norm = v.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        v.iter_mut().for_each(|x| *x /= norm);
+    }
+    v
+}
+
+// Embeddings are stored as little-endian `f32`s.
+pub fn to_bytes(embedding: &[f32]) -> Vec<u8> {
+    embedding.iter().flat_map(|x| x.to_le_bytes()).collect()
+}
+
+pub fn from_bytes(bytes: &[u8]) -> Vec<f32> {
+    bytes
+        .chunks_exact(4)
+        .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .collect()
+}
+
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() {
+        return 0.0;
+    }
+
+    let dot = a.iter().zip(b).map(|(x, y)| x * y).sum::<f32>();
+    let norm = |v: &[f32]| v.iter().map(|x| x * x).sum::<f32>().sqrt();
+
+    match norm(a) * norm(b) {
+        n if n > 0.0 => dot / n,
+        _ => 0.0,
+    }
+}
+
+// Normalized mean of several embeddings of the same voice.
+pub fn centroid<'a>(embeddings: impl IntoIterator<Item = &'a [f32]>) -> Option<Vec<f32>> {
+    let mut sum: Option<Vec<f32>> = None;
+
+    for embedding in embeddings {
+        match &mut sum {
+            Some(sum) if sum.len() == embedding.len() => {
+                sum.iter_mut().zip(embedding).for_each(|(s, x)| *s += x)
+            }
+            Some(_) => continue,
+            None => sum = Some(embedding.to_vec()),
+        }
+    }
+
+    sum.map(normalize)
+}
+
+// Samples of `speaker` from a mono 16kHz recording, cut along the diarization.
+pub fn speaker_audio(samples: &[f32], diarizations: &[DiarizationChunk], speaker: i32) -> Vec<f32> {
+    let to_index = |ms: u64| ((ms * SAMPLE_RATE as u64 / 1000) as usize).min(samples.len());
+
+    let mut audio = Vec::new();
+    let max_len = (MAX_SPEECH_MS * SAMPLE_RATE as u64 / 1000) as usize;
+
+    for d in diarizations
+        .iter()
+        .filter(|d| d.speaker == speaker && d.end.saturating_sub(d.start) >= MIN_SEGMENT_MS)
+    {
+        let (start, end) = (to_index(d.start), to_index(d.end));
+        let remaining = max_len - audio.len();
+
+        audio.extend_from_slice(&samples[start..end.min(start + remaining)]);
+        if audio.len() >= max_len {
+            break;
+        }
+    }
+
+    audio
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct VoiceMatch {
+    pub human_id: String,
+    pub score: f32,
+}
+
+// The enrolled voice most similar to `embedding`, if it clears `threshold`.
+pub fn best_match<'a>(
+    embedding: &[f32],
+    enrolled: impl IntoIterator<Item = (&'a str, &'a [f32])>,
+    threshold: f32,
+) -> Option<VoiceMatch> {
+    enrolled
+        .into_iter()
+        .map(|(human_id, voice)| VoiceMatch {
+            human_id: human_id.to_string(),
+            score: cosine_similarity(embedding, voice),
+        })
+        .filter(|m| m.score >= threshold)
+        .max_by(|a, b| a.score.total_cmp(&b.score))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_best_match() {
+        let john = normalize(vec![1.0, 0.1, 0.0]);
+        let jane = normalize(vec![0.0, 1.0, 0.2]);
+        let enrolled = [("john", john.as_slice()), ("jane", jane.as_slice())];
+
+        let m = best_match(&[0.9, 0.2, 0.0], enrolled, DEFAULT_THRESHOLD).unwrap();
+        assert_eq!(m.human_id, "john");
+        assert!(m.score > 0.9);
+
+        assert_eq!(
+            best_match(&[0.0, 0.0, 1.0], enrolled, DEFAULT_THRESHOLD),
+            None
+        );
+        assert_eq!(best_match(&[1.0, 0.0], enrolled, 0.1), None);
+    }
+
+    #[test]
+    fn test_bytes() {
+        let embedding = vec![0.5, -1.25, 3.0];
+        assert_eq!(from_bytes(&to_bytes(&embedding)), embedding);
+    }
+
+    #[test]
+    fn test_centroid() {
+        let a = [1.0, 0.0];
+        let b = [0.0, 1.0];
+
+        let c = centroid([a.as_slice(), b.as_slice()]).unwrap();
+        assert!((c[0] - c[1]).abs() < 1e-6);
+        assert!((cosine_similarity(&c, &[1.0, 1.0]) - 1.0).abs() < 1e-6);
+
+        assert_eq!(centroid(Vec::<&[f32]>::new()), None);
+    }
+
+    #[test]
+    fn test_speaker_audio() {
+        let samples = (0..SAMPLE_RATE * 5).map(|i| i as f32).collect::<Vec<_>>();
+        let chunk = |start, end, speaker| DiarizationChunk {
+            start,
+            end,
+            speaker,
+            confidence: None,
+        };
+
+        let audio = speaker_audio(
+            &samples,
+            &[
+                chunk(0, 1500, 0),
+                chunk(1500, 2000, 0),
+                chunk(2000, 4000, 1),
+                chunk(4000, 9000, 0),
+            ],
+            0,
+        );
+
+        // 1.5s from the first segment, the short one skipped, and the last one clipped to the recording.
+        assert_eq!(audio.len(), 24000 + 16000);
+        assert_eq!(audio[24000], 64000.0);
+    }
+}
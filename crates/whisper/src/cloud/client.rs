@@ -11,6 +11,7 @@ pub struct WhisperClientBuilder {
     api_base: Option<String>,
     api_key: Option<String>,
     language: Option<crate::Language>,
+    prompt: Option<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -34,6 +35,11 @@ impl WhisperClientBuilder {
         self
     }
 
+    pub fn prompt(mut self, prompt: impl Into<String>) -> Self {
+        self.prompt = Some(prompt.into());
+        self
+    }
+
     pub fn build(self) -> WhisperClient {
         let uri = {
             let mut url: url::Url = self.api_base.unwrap().parse().unwrap();
@@ -49,6 +55,10 @@ impl WhisperClientBuilder {
                 if let Some(language) = self.language {
                     pairs.append_pair("language", language.as_ref());
                 }
+
+                if let Some(prompt) = self.prompt.filter(|p| !p.is_empty()) {
+                    pairs.append_pair("prompt", &prompt);
+                }
             }
 
             url.to_string().parse().unwrap()
@@ -5,7 +5,7 @@ pub mod local;
 pub mod cloud;
 
 // https://github.com/openai/whisper/blob/ba3f3cd/whisper/tokenizer.py#L10-L128
-#[derive(strum::EnumString, strum::Display, strum::AsRefStr)]
+#[derive(Debug, Clone, Copy, PartialEq, strum::EnumString, strum::Display, strum::AsRefStr)]
 pub enum Language {
     #[strum(serialize = "en")]
     En,
@@ -0,0 +1,71 @@
+use crate::Language;
+
+// A first detection below this is used for its own chunk only.
+const MIN_PROBABILITY: f32 = 0.5;
+// Switching to another language takes this many confident detections in a row,
+// so that a single accented or code-switched sentence does not flip the transcript.
+const SWITCH_PROBABILITY: f32 = 0.8;
+const SWITCH_AFTER: usize = 2;
+
+#[derive(Debug, Default)]
+pub struct LanguageDetector {
+    current: Option<Language>,
+    candidate: Option<(Language, usize)>,
+}
+
+impl LanguageDetector {
+    pub fn current(&self) -> Option<Language> {
+        self.current
+    }
+
+    // Records the detection for a chunk, and returns the language to transcribe it in.
+    pub fn update(&mut self, detected: Language, probability: f32) -> Language {
+        let Some(current) = self.current else {
+            if probability >= MIN_PROBABILITY {
+                self.current = Some(detected);
+            }
+            return detected;
+        };
+
+        if detected == current || probability < SWITCH_PROBABILITY {
+            self.candidate = None;
+            return current;
+        }
+
+        let count = match self.candidate {
+            Some((language, count)) if language == detected => count + 1,
+            _ => 1,
+        };
+
+        if count >= SWITCH_AFTER {
+            self.current = Some(detected);
+            self.candidate = None;
+            detected
+        } else {
+            self.candidate = Some((detected, count));
+            current
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_language_detector() {
+        let mut detector = LanguageDetector::default();
+
+        assert_eq!(detector.update(Language::Ko, 0.3), Language::Ko);
+        assert_eq!(detector.current(), None);
+
+        assert_eq!(detector.update(Language::En, 0.9), Language::En);
+        assert_eq!(detector.update(Language::De, 0.95), Language::En);
+        assert_eq!(detector.update(Language::En, 0.9), Language::En);
+        assert_eq!(detector.update(Language::De, 0.7), Language::En);
+
+        assert_eq!(detector.update(Language::De, 0.9), Language::En);
+        assert_eq!(detector.update(Language::De, 0.9), Language::De);
+        assert_eq!(detector.current(), Some(Language::De));
+    }
+}
@@ -6,5 +6,8 @@ pub use stream::*;
 mod model;
 pub use model::*;
 
+mod detect;
+pub use detect::*;
+
 mod error;
 pub use error::*;
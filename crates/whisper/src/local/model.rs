@@ -49,10 +49,10 @@ impl WhisperBuilder {
         let state = ctx.create_state().unwrap();
         let eot = ctx.token_eot();
 
-        let language = self.language.unwrap_or(crate::Language::En);
-
         Whisper {
-            language,
+            language: self.language,
+            detector: super::LanguageDetector::default(),
+            partial_language: None,
             static_prompt: self.static_prompt.unwrap_or_default(),
             dynamic_prompt: self.dynamic_prompt.unwrap_or_default(),
             state,
@@ -72,7 +72,11 @@ impl WhisperBuilder {
 }
 
 pub struct Whisper {
-    language: crate::Language,
+    // Detected from the audio when not set.
+    language: Option<crate::Language>,
+    detector: super::LanguageDetector,
+    // Detected for the chunk in progress, so interims of the same chunk don't detect again.
+    partial_language: Option<crate::Language>,
     static_prompt: String,
     dynamic_prompt: String,
     state: WhisperState,
@@ -85,6 +89,49 @@ impl Whisper {
     }
 
     pub fn transcribe(&mut self, audio: &[f32]) -> Result<Vec<Segment>, super::Error> {
+        let segments = self.decode(audio, true)?;
+
+        self.dynamic_prompt = segments
+            .iter()
+            .map(|s| s.text())
+            .collect::<Vec<&str>>()
+            .join(" ");
+
+        Ok(segments)
+    }
+
+    // For audio that will be transcribed again once more of it arrives. Leaves the prompt as is.
+    pub fn transcribe_partial(&mut self, audio: &[f32]) -> Result<Vec<Segment>, super::Error> {
+        self.decode(audio, false)
+    }
+
+    // The language spoken so far, when it is being detected.
+    pub fn detected_language(&self) -> Option<crate::Language> {
+        self.detector.current()
+    }
+
+    fn decode(&mut self, audio: &[f32], commit: bool) -> Result<Vec<Segment>, super::Error> {
+        let (language, detected) = match self.language {
+            Some(language) => (language, false),
+            // Interims reuse what is known so far, and detect at most once per chunk.
+            None if !commit => {
+                let language = match self.detector.current().or(self.partial_language) {
+                    Some(language) => language,
+                    None => {
+                        let (language, _) = self.detect_language(audio)?;
+                        self.partial_language = Some(language);
+                        language
+                    }
+                };
+                (language, true)
+            }
+            None => {
+                self.partial_language = None;
+                let (language, probability) = self.detect_language(audio)?;
+                (self.detector.update(language, probability), true)
+            }
+        };
+
         let params = {
             let mut p = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
 
@@ -95,7 +142,7 @@ impl Whisper {
             tracing::info!(initial_prompt = ?initial_prompt, "transcribe");
 
             p.set_translate(false);
-            p.set_language(Some(self.language.as_ref()));
+            p.set_language(Some(language.as_ref()));
             p.set_initial_prompt(&initial_prompt);
 
             p.set_n_threads(1);
@@ -129,18 +176,26 @@ impl Whisper {
                 start: start as f32 / 1000.0,
                 end: end as f32 / 1000.0,
                 confidence,
+                language: detected.then_some(language),
             });
         }
 
-        self.dynamic_prompt = segments
-            .iter()
-            .map(|s| s.text())
-            .collect::<Vec<&str>>()
-            .join(" ");
-
         Ok(segments)
     }
 
+    fn detect_language(&mut self, audio: &[f32]) -> Result<(crate::Language, f32), super::Error> {
+        self.state.pcm_to_mel(audio, 1)?;
+        let (id, probs) = self.state.lang_detect(0, 1)?;
+
+        let language = whisper_rs::get_lang_str(id)
+            .and_then(|code| code.parse().ok())
+            .unwrap_or(crate::Language::En);
+        let probability = probs.get(id as usize).copied().unwrap_or(0.0);
+
+        tracing::info!(language = ?language, probability, "detect_language");
+        Ok((language, probability))
+    }
+
     // https://github.com/ggml-org/whisper.cpp/pull/971/files#diff-2d3599a9fad195f2c3c60bd06691bc1815325b3560b5feda41a91fa71194e805R310-R327
     fn calculate_segment_confidence(&self, segment_idx: i32) -> f32 {
         let n_tokens = self.state.full_n_tokens(segment_idx).unwrap_or(0);
@@ -187,6 +242,8 @@ pub struct Segment {
     pub start: f32,
     pub end: f32,
     pub confidence: f32,
+    // Set when the language was detected rather than configured.
+    pub language: Option<crate::Language>,
 }
 
 impl Segment {
@@ -209,17 +266,22 @@ impl Segment {
     pub fn confidence(&self) -> f32 {
         self.confidence
     }
+
+    pub fn language(&self) -> Option<crate::Language> {
+        self.language
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use futures_util::StreamExt;
+    use futures_util::TryStreamExt;
 
     #[test]
     fn test_whisper() {
         let mut whisper = Whisper::builder()
             .model_path(concat!(env!("CARGO_MANIFEST_DIR"), "/model.bin"))
+            .language(crate::Language::En)
             .build();
 
         let audio: Vec<f32> = hypr_data::english_1::AUDIO
@@ -242,6 +304,7 @@ mod tests {
 
         let mut whisper = Whisper::builder()
             .model_path(concat!(env!("CARGO_MANIFEST_DIR"), "/model.bin"))
+            .language(crate::Language::En)
             .build();
 
         let request = hypr_llama::LlamaRequest {
@@ -253,7 +316,13 @@ mod tests {
             ..Default::default()
         };
 
-        let response: String = llama.generate_stream(request).unwrap().collect().await;
+        let response: String = llama
+            .generate_stream(request)
+            .await
+            .unwrap()
+            .try_collect()
+            .await
+            .unwrap();
         assert!(response.len() > 4);
 
         let audio: Vec<f32> = hypr_data::english_1::AUDIO
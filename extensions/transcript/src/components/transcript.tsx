@@ -1,15 +1,24 @@
 import { EarIcon, Loader2Icon } from "lucide-react";
-import { useEffect, useRef } from "react";
+import { useEffect, useRef, useState } from "react";
 
 import { useSessions } from "@hypr/utils/contexts";
 import { useTranscript } from "../hooks/useTranscript";
 
+type TranscriptDisplay = "original" | "translated" | "both";
+
+const DISPLAY_LABELS: Record<TranscriptDisplay, string> = {
+  original: "Original",
+  translated: "Translated",
+  both: "Both",
+};
+
 export default function Transcript({ sessionId }: { sessionId?: string }) {
   const currentSessionId = useSessions((s) => s.currentSessionId);
   const effectiveSessionId = sessionId || currentSessionId;
 
   const ref = useRef<HTMLDivElement>(null);
   const { timeline, isLive, isLoading } = useTranscript(effectiveSessionId);
+  const [display, setDisplay] = useState<TranscriptDisplay>("original");
 
   useEffect(() => {
     const scrollToBottom = () => {
@@ -26,6 +35,7 @@ export default function Transcript({ sessionId }: { sessionId?: string }) {
   }, [timeline?.items, isLive, ref]);
 
   const items = timeline?.items || [];
+  const hasTranslation = items.some((item) => item.translation);
 
   return (
     <div
@@ -40,12 +50,30 @@ export default function Transcript({ sessionId }: { sessionId?: string }) {
         )
         : (
           <>
+            {hasTranslation && (
+              <div className="flex items-center gap-1 self-end text-xs">
+                {(Object.keys(DISPLAY_LABELS) as TranscriptDisplay[]).map((value) => (
+                  <button
+                    key={value}
+                    onClick={() => setDisplay(value)}
+                    className={`px-2 py-0.5 rounded-md ${
+                      display === value ? "bg-neutral-200 text-neutral-900" : "text-neutral-500"
+                    }`}
+                  >
+                    {DISPLAY_LABELS[value]}
+                  </button>
+                ))}
+              </div>
+            )}
+
             {items.length > 0
               && items.map((item, index) => (
                 <div key={index}>
                   <p
                     className={`select-text ${
-                      item.confidence > 0.9
+                      item.interim
+                        ? "font-light italic text-neutral-400"
+                        : item.confidence > 0.9
                         ? "font-normal opacity-100"
                         : item.confidence > 0.8
                         ? "font-normal opacity-80"
@@ -60,8 +88,13 @@ export default function Transcript({ sessionId }: { sessionId?: string }) {
                         : "font-extralight opacity-40"
                     }`}
                   >
-                    {item.text}
+                    {display === "translated" ? (item.translation ?? item.text) : item.text}
                   </p>
+                  {display === "both" && item.translation && (
+                    <p className="select-text text-neutral-500">
+                      {item.translation}
+                    </p>
+                  )}
                 </div>
               ))}
 
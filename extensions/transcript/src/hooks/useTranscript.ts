@@ -2,7 +2,12 @@ import { Channel } from "@tauri-apps/api/core";
 import { useEffect, useMemo, useState } from "react";
 
 import { commands as dbCommands } from "@hypr/plugin-db";
-import { commands as listenerCommands, type SessionEvent, type TimelineView } from "@hypr/plugin-listener";
+import {
+  commands as listenerCommands,
+  type SessionEvent,
+  type TimelineDiff,
+  type TimelineView,
+} from "@hypr/plugin-listener";
 import { useOngoingSession, useSession } from "@hypr/utils/contexts";
 
 export function useTranscript(sessionId: string | null) {
@@ -60,6 +65,10 @@ export function useTranscript(sessionId: string | null) {
       if (e.type === "timelineView") {
         setTimeline(e.timeline);
       }
+
+      if (e.type === "timelineDiff") {
+        setTimeline((timeline) => applyDiffs(timeline ?? { items: [] }, e.diffs));
+      }
     };
 
     return () => {
@@ -79,3 +88,19 @@ export function useTranscript(sessionId: string | null) {
     isLoading,
   };
 }
+
+function applyDiffs(timeline: TimelineView, diffs: TimelineDiff[]): TimelineView {
+  const items = [...timeline.items];
+
+  for (const diff of diffs) {
+    if (diff.type === "itemAppended") {
+      items.push(diff.item);
+    } else if (diff.type === "itemUpdated") {
+      items[diff.index] = diff.item;
+    } else if (diff.type === "truncated") {
+      items.length = diff.len;
+    }
+  }
+
+  return { items };
+}
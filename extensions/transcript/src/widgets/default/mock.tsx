@@ -101,6 +101,8 @@ const handleListenerSubscribe = (channel: Channel<SessionEvent>) => {
             speaker: 1,
             text: "Hey team, thanks for joining. Today we'll discuss the new transcription feature requirements.",
             confidence: 0.9,
+            interim: false,
+            translation: null,
           },
         ],
       },
@@ -120,6 +122,8 @@ const handleListenerSubscribe = (channel: Channel<SessionEvent>) => {
             text:
               "I've been working on some mockups based on user feedback. The main request is for real-time updates and clear speaker identification.",
             confidence: 0.9,
+            interim: false,
+            translation: null,
           },
         ],
       },
@@ -139,6 +143,8 @@ const handleListenerSubscribe = (channel: Channel<SessionEvent>) => {
             text:
               "That aligns with our backend capabilities. We can stream the transcription with about 500ms latency.",
             confidence: 0.9,
+            interim: false,
+            translation: null,
           },
         ],
       },
@@ -157,6 +163,8 @@ const handleListenerSubscribe = (channel: Channel<SessionEvent>) => {
             speaker: 1,
             text: "What's our timeline for implementing this?",
             confidence: 0.9,
+            interim: false,
+            translation: null,
           },
         ],
       },
@@ -175,6 +183,8 @@ const handleListenerSubscribe = (channel: Channel<SessionEvent>) => {
             speaker: 2,
             text: "The UI work should take about two weeks. We already have most of the components ready.",
             confidence: 0.9,
+            interim: false,
+            translation: null,
           },
         ],
       },
@@ -193,6 +203,8 @@ const handleListenerSubscribe = (channel: Channel<SessionEvent>) => {
             speaker: 3,
             text: "Backend integration can be done in parallel. We should be ready for testing in two weeks.",
             confidence: 0.9,
+            interim: false,
+            translation: null,
           },
         ],
       },
@@ -211,6 +223,8 @@ const handleListenerSubscribe = (channel: Channel<SessionEvent>) => {
             speaker: 1,
             text: "Perfect, let's reconvene next week for a progress check. Thanks everyone!",
             confidence: 0.9,
+            interim: false,
+            translation: null,
           },
         ],
       },
@@ -1,10 +1,10 @@
 import { createOpenAICompatible } from "@ai-sdk/openai-compatible";
 import { customProvider, type TextStreamPart, type ToolSet } from "ai";
 
-import { commands as connectorCommands } from "@hypr/plugin-connector";
+import { commands as connectorCommands, type ConnectionLLM } from "@hypr/plugin-connector";
 import { fetch as customFetch } from "@hypr/utils";
 
-export { generateText, type Provider, smoothStream, streamText } from "ai";
+export { embed, embedMany, generateText, type Provider, smoothStream, streamText } from "ai";
 
 import { useChat as useChat$1 } from "@ai-sdk/react";
 
@@ -15,9 +15,7 @@ export const useChat = (options: Parameters<typeof useChat$1>[0]) => {
   });
 };
 
-const getModel = async ({ onboarding }: { onboarding: boolean }) => {
-  const { type, connection: { api_base, api_key } } = await connectorCommands.getLocalLlmConnection();
-
+const getProvider = ({ type, connection: { api_base, api_key } }: ConnectionLLM) => {
   const openai = createOpenAICompatible({
     name: "hypr-llm",
     baseURL: api_base,
@@ -28,12 +26,22 @@ const getModel = async ({ onboarding }: { onboarding: boolean }) => {
     },
   });
 
+  return { type, openai };
+};
+
+const getLocalProvider = async () => getProvider(await connectorCommands.getLocalLlmConnection());
+
+// Onboarding always runs on the local server's mock model. Everything else uses the configured connection.
+const getModel = async ({ onboarding }: { onboarding: boolean }) => {
+  if (onboarding) {
+    const { openai } = await getLocalProvider();
+    return openai("mock-onboarding");
+  }
+
+  const { type, openai } = getProvider(await connectorCommands.getLlmConnection());
+
   const customModel = await connectorCommands.getCustomLlmModel();
-  const model = onboarding
-    ? "mock-onboarding"
-    : (type === "Custom" && customModel)
-    ? customModel
-    : "gpt-4";
+  const model = (type === "Custom" && customModel) ? customModel : "gpt-4";
 
   return openai(model);
 };
@@ -47,6 +55,13 @@ export const modelProvider = async () => {
   });
 };
 
+// The local server embeds with its chat model, so this works offline whichever model writes notes.
+// `id` is the model it has loaded, which the server embeds with whatever is asked for.
+export const embeddingModel = async (id: string) => {
+  const { openai } = await getLocalProvider();
+  return openai.textEmbeddingModel(id);
+};
+
 type TransformState = {
   buffer: string;
   seenMdPrefix: boolean;
@@ -99,6 +99,7 @@ pub async fn sync_events(
                 common: ListEventFilterCommon {
                     user_id: user_id.clone(),
                     limit: Some(200),
+                    cursor: None,
                 },
                 specific: ListEventFilterSpecific::DateRange {
                     start: Utc::now(),
@@ -107,8 +108,7 @@ pub async fn sync_events(
             }))
             .await
             .map_err(|e| crate::Error::DatabaseError(e.into()))?
-            .into_iter()
-            .collect::<Vec<hypr_db_user::Event>>();
+            .items;
 
         tracing::info!("db_existing_events_len: {}", items.len());
         items
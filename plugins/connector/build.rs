@@ -2,6 +2,8 @@ const COMMANDS: &[&str] = &[
     "list_custom_llm_models",
     "get_custom_llm_model",
     "set_custom_llm_model",
+    "get_custom_llm_context_length",
+    "set_custom_llm_context_length",
     "get_custom_llm_enabled",
     "set_custom_llm_enabled",
     "get_local_llm_connection",
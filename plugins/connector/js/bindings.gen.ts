@@ -16,6 +16,12 @@ async getCustomLlmModel() : Promise<string | null> {
 async setCustomLlmModel(model: string) : Promise<null> {
     return await TAURI_INVOKE("plugin:connector|set_custom_llm_model", { model });
 },
+async getCustomLlmContextLength() : Promise<number | null> {
+    return await TAURI_INVOKE("plugin:connector|get_custom_llm_context_length");
+},
+async setCustomLlmContextLength(tokens: number) : Promise<null> {
+    return await TAURI_INVOKE("plugin:connector|set_custom_llm_context_length", { tokens });
+},
 async getCustomLlmEnabled() : Promise<boolean> {
     return await TAURI_INVOKE("plugin:connector|get_custom_llm_enabled");
 },
@@ -27,6 +27,25 @@ pub async fn set_custom_llm_model<R: tauri::Runtime>(
     app.set_custom_llm_model(model).map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+#[specta::specta]
+pub async fn get_custom_llm_context_length<R: tauri::Runtime>(
+    app: tauri::AppHandle<R>,
+) -> Result<Option<u32>, String> {
+    app.get_custom_llm_context_length()
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn set_custom_llm_context_length<R: tauri::Runtime>(
+    app: tauri::AppHandle<R>,
+    tokens: u32,
+) -> Result<(), String> {
+    app.set_custom_llm_context_length(tokens)
+        .map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 #[specta::specta]
 pub async fn get_custom_llm_enabled<R: tauri::Runtime>(
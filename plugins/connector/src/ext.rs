@@ -11,6 +11,9 @@ pub trait ConnectorPluginExt<R: tauri::Runtime> {
     fn get_custom_llm_model(&self) -> Result<Option<String>, crate::Error>;
     fn set_custom_llm_model(&self, model: String) -> Result<(), crate::Error>;
 
+    fn get_custom_llm_context_length(&self) -> Result<Option<u32>, crate::Error>;
+    fn set_custom_llm_context_length(&self, tokens: u32) -> Result<(), crate::Error>;
+
     fn set_custom_llm_enabled(&self, enabled: bool) -> Result<(), crate::Error>;
     fn get_custom_llm_enabled(&self) -> Result<bool, crate::Error>;
 
@@ -54,6 +57,20 @@ impl<R: tauri::Runtime, T: tauri::Manager<R>> ConnectorPluginExt<R> for T {
         Ok(())
     }
 
+    // Custom endpoints don't report their model's context window, so the user sets it.
+    fn get_custom_llm_context_length(&self) -> Result<Option<u32>, crate::Error> {
+        Ok(self
+            .connector_store()
+            .get(StoreKey::CustomContextLength)?
+            .flatten())
+    }
+
+    fn set_custom_llm_context_length(&self, tokens: u32) -> Result<(), crate::Error> {
+        self.connector_store()
+            .set(StoreKey::CustomContextLength, tokens)?;
+        Ok(())
+    }
+
     fn set_custom_llm_enabled(&self, enabled: bool) -> Result<(), crate::Error> {
         self.connector_store()
             .set(StoreKey::CustomEnabled, enabled)?;
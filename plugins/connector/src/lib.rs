@@ -18,6 +18,8 @@ fn make_specta_builder<R: tauri::Runtime>() -> tauri_specta::Builder<R> {
             commands::list_custom_llm_models::<tauri::Wry>,
             commands::get_custom_llm_model::<tauri::Wry>,
             commands::set_custom_llm_model::<tauri::Wry>,
+            commands::get_custom_llm_context_length::<tauri::Wry>,
+            commands::set_custom_llm_context_length::<tauri::Wry>,
             commands::get_custom_llm_enabled::<tauri::Wry>,
             commands::set_custom_llm_enabled::<tauri::Wry>,
             commands::get_custom_llm_connection::<tauri::Wry>,
@@ -6,6 +6,7 @@ pub enum StoreKey {
     CustomApiBase,
     CustomApiKey,
     CustomModel,
+    CustomContextLength,
 }
 
 impl ScopedStoreKey for StoreKey {}
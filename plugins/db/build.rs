@@ -15,9 +15,16 @@ const COMMANDS: &[&str] = &[
     "session_add_participant",
     "session_remove_participant",
     "session_list_participants",
+    "session_resolve_speakers",
+    "session_assign_speaker",
+    "session_merge_speakers",
+    "list_voice_suggestions",
+    "confirm_voice_suggestion",
+    "reject_voice_suggestion",
     "session_get_event",
     "get_timeline_view_onboarding",
     "get_timeline_view",
+    "get_timeline_view_windows",
     // template
     "list_templates",
     "upsert_template",
@@ -38,21 +45,43 @@ const COMMANDS: &[&str] = &[
     "get_organization",
     "get_organization_by_user_id",
     "list_organizations",
+    "list_all_organizations",
     "list_organization_members",
     // chat
     "list_chat_groups",
     "list_chat_messages",
     "create_chat_group",
     "upsert_chat_message",
+    "send_chat_message",
     // tag
     "list_all_tags",
     "list_session_tags",
     "assign_tag_to_session",
     "unassign_tag_from_session",
+    "list_tags_with_usage",
+    "upsert_tag",
+    "delete_tag",
+    "rename_tag",
+    "merge_tags",
+    // vocabulary
+    "list_vocabulary",
+    "upsert_vocabulary_entry",
+    "delete_vocabulary_entry",
+    // passages
+    "replace_session_passages",
+    "search_sessions_by_embedding",
     // extension
     "get_extension_mapping",
     "list_extension_mappings",
     "upsert_extension_mapping",
+    // doctor
+    "run_doctor",
+    // export
+    "export_sessions_markdown",
+    "export_transcript",
+    // sync
+    "sync_now",
+    "list_sync_conflicts",
 ];
 
 fn main() {
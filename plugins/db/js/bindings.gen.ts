@@ -10,7 +10,7 @@ export const commands = {
 async getEvent(id: string) : Promise<Event | null> {
     return await TAURI_INVOKE("plugin:db|get_event", { id });
 },
-async listEvents(filter: ListEventFilter | null) : Promise<Event[]> {
+async listEvents(filter: ListEventFilter | null) : Promise<Page<Event>> {
     return await TAURI_INVOKE("plugin:db|list_events", { filter });
 },
 async getCalendar(calendarId: string) : Promise<Calendar> {
@@ -43,7 +43,7 @@ async deleteTemplate(id: string) : Promise<null> {
 async onboardingSessionId() : Promise<string> {
     return await TAURI_INVOKE("plugin:db|onboarding_session_id");
 },
-async listSessions(filter: ListSessionFilter | null) : Promise<Session[]> {
+async listSessions(filter: ListSessionFilter | null) : Promise<Page<Session>> {
     return await TAURI_INVOKE("plugin:db|list_sessions", { filter });
 },
 async deleteSession(id: string) : Promise<null> {
@@ -64,12 +64,33 @@ async sessionRemoveParticipant(sessionId: string, humanId: string) : Promise<nul
 async sessionListParticipants(sessionId: string) : Promise<Human[]> {
     return await TAURI_INVOKE("plugin:db|session_list_participants", { sessionId });
 },
+async sessionResolveSpeakers(sessionId: string) : Promise<ResolvedSpeaker[]> {
+    return await TAURI_INVOKE("plugin:db|session_resolve_speakers", { sessionId });
+},
+async sessionAssignSpeaker(sessionId: string, speaker: number, humanId: string) : Promise<SessionSpeaker> {
+    return await TAURI_INVOKE("plugin:db|session_assign_speaker", { sessionId, speaker, humanId });
+},
+async sessionMergeSpeakers(sessionId: string, from: number, into: number) : Promise<SessionSpeaker> {
+    return await TAURI_INVOKE("plugin:db|session_merge_speakers", { sessionId, from, into });
+},
+async listVoiceSuggestions(sessionId: string) : Promise<VoiceSuggestion[]> {
+    return await TAURI_INVOKE("plugin:db|list_voice_suggestions", { sessionId });
+},
+async confirmVoiceSuggestion(id: string) : Promise<SessionSpeaker> {
+    return await TAURI_INVOKE("plugin:db|confirm_voice_suggestion", { id });
+},
+async rejectVoiceSuggestion(id: string) : Promise<null> {
+    return await TAURI_INVOKE("plugin:db|reject_voice_suggestion", { id });
+},
 async sessionGetEvent(sessionId: string) : Promise<Event | null> {
     return await TAURI_INVOKE("plugin:db|session_get_event", { sessionId });
 },
 async getTimelineView(sessionId: string) : Promise<TimelineView | null> {
     return await TAURI_INVOKE("plugin:db|get_timeline_view", { sessionId });
 },
+async getTimelineViewWindows(sessionId: string, maxChars: number) : Promise<TimelineView[]> {
+    return await TAURI_INVOKE("plugin:db|get_timeline_view_windows", { sessionId, maxChars });
+},
 async getTimelineViewOnboarding() : Promise<TimelineView> {
     return await TAURI_INVOKE("plugin:db|get_timeline_view_onboarding");
 },
@@ -85,7 +106,7 @@ async getHuman(id: string) : Promise<Human | null> {
 async upsertHuman(human: Human) : Promise<Human> {
     return await TAURI_INVOKE("plugin:db|upsert_human", { human });
 },
-async listHumans(filter: ListHumanFilter | null) : Promise<Human[]> {
+async listHumans(filter: ListHumanFilter | null) : Promise<Page<Human>> {
     return await TAURI_INVOKE("plugin:db|list_humans", { filter });
 },
 async getOrganization(id: string) : Promise<Organization | null> {
@@ -97,9 +118,12 @@ async getOrganizationByUserId(userId: string) : Promise<Organization | null> {
 async upsertOrganization(organization: Organization) : Promise<Organization> {
     return await TAURI_INVOKE("plugin:db|upsert_organization", { organization });
 },
-async listOrganizations(filter: ListOrganizationFilter | null) : Promise<Organization[]> {
+async listOrganizations(filter: ListOrganizationFilter | null) : Promise<Page<Organization>> {
     return await TAURI_INVOKE("plugin:db|list_organizations", { filter });
 },
+async listAllOrganizations() : Promise<Organization[]> {
+    return await TAURI_INVOKE("plugin:db|list_all_organizations");
+},
 async listOrganizationMembers(organizationId: string) : Promise<Human[]> {
     return await TAURI_INVOKE("plugin:db|list_organization_members", { organizationId });
 },
@@ -115,6 +139,9 @@ async createChatGroup(group: ChatGroup) : Promise<ChatGroup> {
 async upsertChatMessage(message: ChatMessage) : Promise<ChatMessage> {
     return await TAURI_INVOKE("plugin:db|upsert_chat_message", { message });
 },
+async sendChatMessage(groupId: string, content: string, onDelta: TAURI_CHANNEL<string>) : Promise<ChatMessage> {
+    return await TAURI_INVOKE("plugin:db|send_chat_message", { groupId, content, onDelta });
+},
 async listAllTags() : Promise<Tag[]> {
     return await TAURI_INVOKE("plugin:db|list_all_tags");
 },
@@ -127,6 +154,36 @@ async assignTagToSession(tagId: string, sessionId: string) : Promise<null> {
 async unassignTagFromSession(tagId: string, sessionId: string) : Promise<null> {
     return await TAURI_INVOKE("plugin:db|unassign_tag_from_session", { tagId, sessionId });
 },
+async listTagsWithUsage() : Promise<TagWithUsage[]> {
+    return await TAURI_INVOKE("plugin:db|list_tags_with_usage");
+},
+async upsertTag(tag: Tag) : Promise<Tag> {
+    return await TAURI_INVOKE("plugin:db|upsert_tag", { tag });
+},
+async deleteTag(tagId: string) : Promise<null> {
+    return await TAURI_INVOKE("plugin:db|delete_tag", { tagId });
+},
+async renameTag(tagId: string, name: string) : Promise<Tag | null> {
+    return await TAURI_INVOKE("plugin:db|rename_tag", { tagId, name });
+},
+async mergeTags(fromId: string, intoId: string) : Promise<Tag | null> {
+    return await TAURI_INVOKE("plugin:db|merge_tags", { fromId, intoId });
+},
+async listVocabulary(sessionId: string | null) : Promise<VocabularyEntry[]> {
+    return await TAURI_INVOKE("plugin:db|list_vocabulary", { sessionId });
+},
+async upsertVocabularyEntry(entry: VocabularyEntry) : Promise<VocabularyEntry> {
+    return await TAURI_INVOKE("plugin:db|upsert_vocabulary_entry", { entry });
+},
+async deleteVocabularyEntry(id: string) : Promise<null> {
+    return await TAURI_INVOKE("plugin:db|delete_vocabulary_entry", { id });
+},
+async replaceSessionPassages(sessionId: string, model: string, passages: SessionPassage[]) : Promise<SessionPassage[]> {
+    return await TAURI_INVOKE("plugin:db|replace_session_passages", { sessionId, model, passages });
+},
+async searchSessionsByEmbedding(model: string, query: number[], limit: number) : Promise<SessionSearchResult[]> {
+    return await TAURI_INVOKE("plugin:db|search_sessions_by_embedding", { model, query, limit });
+},
 async getExtensionMapping(userId: string, extensionId: string) : Promise<ExtensionMapping | null> {
     return await TAURI_INVOKE("plugin:db|get_extension_mapping", { userId, extensionId });
 },
@@ -135,12 +192,32 @@ async listExtensionMappings(userId: string) : Promise<ExtensionMapping[]> {
 },
 async upsertExtensionMapping(mapping: ExtensionMapping) : Promise<ExtensionMapping> {
     return await TAURI_INVOKE("plugin:db|upsert_extension_mapping", { mapping });
+},
+async runDoctor(quarantine: boolean) : Promise<DoctorReport> {
+    return await TAURI_INVOKE("plugin:db|run_doctor", { quarantine });
+},
+async exportSessionsMarkdown(dir: string, sessionIds: string[]) : Promise<string[]> {
+    return await TAURI_INVOKE("plugin:db|export_sessions_markdown", { dir, sessionIds });
+},
+async exportTranscript(sessionId: string, format: TranscriptFormat) : Promise<string | null> {
+    return await TAURI_INVOKE("plugin:db|export_transcript", { sessionId, format });
+},
+async syncNow() : Promise<SyncReport> {
+    return await TAURI_INVOKE("plugin:db|sync_now");
+},
+async listSyncConflicts() : Promise<SyncConflict[]> {
+    return await TAURI_INVOKE("plugin:db|list_sync_conflicts");
 }
 }
 
 /** user-defined events **/
 
 
+export const events = __makeEvents__<{
+syncEvent: SyncEvent
+}>({
+syncEvent: "plugin:db:sync-event"
+})
 
 /** user-defined constants **/
 
@@ -149,15 +226,18 @@ async upsertExtensionMapping(mapping: ExtensionMapping) : Promise<ExtensionMappi
 /** user-defined types **/
 
 export type Calendar = { id: string; tracking_id: string; user_id: string; platform: Platform; name: string; selected: boolean; source: string | null }
-export type ChatGroup = { id: string; user_id: string; name: string | null; created_at: string }
-export type ChatMessage = { id: string; group_id: string; created_at: string; role: ChatMessageRole; content: string }
+export type ChatCitation = { index: number; session_id: string; start_ms: number | null; end_ms: number | null; text: string }
+export type ChatGroup = { id: string; user_id: string; name: string | null; created_at: string; session_id: string | null }
+export type ChatMessage = { id: string; group_id: string; created_at: string; role: ChatMessageRole; content: string; citations: ChatCitation[] }
 export type ChatMessageRole = "User" | "Assistant"
 export type Config = { id: string; user_id: string; general: ConfigGeneral; notification: ConfigNotification; ai: ConfigAI }
 export type ConfigAI = { api_base: string | null; api_key: string | null }
-export type ConfigGeneral = { autostart: boolean; display_language: string; jargons: string[]; telemetry_consent: boolean; save_recordings: boolean | null }
+export type ConfigGeneral = { autostart: boolean; display_language: string; jargons: string[]; telemetry_consent: boolean; save_recordings: boolean | null; detect_language: boolean | null; translation_language: string | null; selected_template_id: string | null }
 export type ConfigNotification = { before: boolean; auto: boolean; ignoredPlatforms: string[] | null }
-export type ConversationChunk = { start: string; end: string; transcripts: TranscriptChunk[]; diarizations: DiarizationChunk[] }
+export type ConversationChunk = { start: string; end: string; transcripts: TranscriptChunk[]; diarizations: DiarizationChunk[]; translations: TranscriptChunk[] }
 export type DiarizationChunk = { start: number; end: number; speaker: number; confidence: number | null }
+export type DoctorIssue = { table: string; row_id: string | null; error: string }
+export type DoctorReport = { scanned: number; issues: DoctorIssue[]; quarantined: number }
 export type Event = { id: string; user_id: string; tracking_id: string; calendar_id: string | null; name: string; note: string; start_date: string; end_date: string; google_event_url: string | null }
 export type ExtensionDefinition = { id: string; title: string; description: string; implemented: boolean; default: boolean; cloud_only: boolean; plugins: string[]; tags: string[] }
 export type ExtensionMapping = { id: string; user_id: string; extension_id: string; config: JsonValue; widgets: ExtensionWidget[] }
@@ -167,19 +247,36 @@ export type ExtensionWidgetPosition = { x: number; y: number }
 export type GetSessionFilter = { id: string } | { calendarEventId: string } | { tagId: string }
 export type Human = { id: string; organization_id: string | null; is_user: boolean; full_name: string | null; email: string | null; job_title: string | null; linkedin_username: string | null }
 export type JsonValue = null | boolean | number | string | JsonValue[] | Partial<{ [key in string]: JsonValue }>
-export type ListEventFilter = ({ user_id: string; limit: number | null }) & ({ type: "simple" } | { type: "search"; query: string } | { type: "dateRange"; start: string; end: string })
-export type ListHumanFilter = { search: [number, string] }
-export type ListOrganizationFilter = { search: [number, string] }
-export type ListSessionFilter = ({ user_id: string; limit: number | null }) & ({ type: "search"; query: string } | { type: "recentlyVisited" } | { type: "dateRange"; start: string; end: string })
+export type ListEventFilter = ({ user_id: string; limit: number | null; cursor: string | null }) & ({ type: "simple" } | { type: "search"; query: string } | { type: "dateRange"; start: string; end: string })
+export type ListHumanFilter = ({ limit: number | null; cursor: string | null }) & ({ type: "simple" } | { type: "search"; query: string })
+export type ListOrganizationFilter = ({ limit: number | null; cursor: string | null }) & ({ type: "simple" } | { type: "search"; query: string })
+export type ListSessionFilter = ({ user_id: string; limit: number | null; cursor: string | null }) & ({ type: "search"; query: string } | { type: "recentlyVisited" } | { type: "dateRange"; start: string; end: string } | { type: "tags"; any_of: string[]; all_of: string[] })
 export type Organization = { id: string; name: string; description: string | null }
+export type Page<T> = { items: T[]; next_cursor: string | null }
+export type PassageMatch = { session_id: string; kind: SessionPassageKind; text: string; start_ms: number | null; end_ms: number | null; score: number }
 export type Platform = "Apple" | "Google" | "Outlook"
+export type ResolvedSpeaker = { speaker: number; human: Human | null }
 export type Session = { id: string; created_at: string; visited_at: string; user_id: string; calendar_event_id: string | null; title: string; raw_memo_html: string; enhanced_memo_html: string | null; conversations: ConversationChunk[] }
-export type Tag = { id: string; name: string }
+export type SessionPassage = { id: string; session_id: string; kind: SessionPassageKind; text: string; start_ms: number | null; end_ms: number | null; embedding: number[]; model: string; created_at: string }
+export type SessionPassageKind = "transcript" | "note"
+export type SessionSearchResult = { session: Session; score: number; passages: PassageMatch[] }
+export type SessionSpeaker = { id: string; session_id: string; speaker: number; human_id: string | null; segments: SpeakerSegment[]; merged: boolean }
+export type SpeakerSegment = { start: number; end: number }
+export type SyncConflict = { id: string; table_name: string; row_id: string; field: string; local_value: string; remote_value: string; local_updated_at: string | null; remote_updated_at: string | null; winner: SyncConflictWinner; created_at: string }
+export type SyncConflictWinner = "local" | "remote"
+export type SyncEvent = { type: "started" } | { type: "finished"; report: SyncReport } | { type: "failed"; error: string }
+export type SyncReport = { pulled: number; pushed: number; conflicts: number }
+export type Tag = { id: string; name: string; parent_id: string | null }
+export type TagWithUsage = ({ id: string; name: string; parent_id: string | null }) & { session_count: number }
 export type Template = { id: string; user_id: string; title: string; description: string; sections: TemplateSection[]; tags: string[] }
 export type TemplateSection = { title: string; description: string }
 export type TimelineView = { items: TimelineViewItem[] }
-export type TimelineViewItem = { start: number; end: number; speaker: number; text: string; confidence: number }
-export type TranscriptChunk = { start: number; end: number; text: string; confidence: number | null }
+export type TimelineViewItem = { start: number; end: number; speaker: number; text: string; confidence: number; interim: boolean; translation: string | null }
+export type TranscriptChunk = { start: number; end: number; text: string; confidence: number | null; is_final: boolean; revision: number | null; language: string | null }
+export type TranscriptFormat = "srt" | "vtt" | "text" | "json"
+export type VocabularyEntry = { id: string; user_id: string; session_id: string | null; term: string; boost: number | null; pronunciations: string[] }
+export type VoiceSuggestion = { id: string; session_id: string; speaker: number; human_id: string; score: number; status: VoiceSuggestionStatus; created_at: string }
+export type VoiceSuggestionStatus = "pending" | "confirmed" | "rejected"
 
 /** tauri-specta globals **/
 
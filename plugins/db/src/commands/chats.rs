@@ -75,3 +75,65 @@ pub async fn upsert_chat_message(
         .await
         .map_err(|e| e.to_string())
 }
+
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip(app, state, on_delta))]
+pub async fn send_chat_message<R: tauri::Runtime>(
+    app: tauri::AppHandle<R>,
+    state: tauri::State<'_, crate::ManagedState>,
+    group_id: String,
+    content: String,
+    on_delta: tauri::ipc::Channel<String>,
+) -> Result<hypr_db_user::ChatMessage, String> {
+    use tauri_plugin_connector::{ConnectionLLM, ConnectorPluginExt};
+    use tauri_plugin_local_llm::LocalLlmPluginExt;
+
+    // Cloned so the state isn't locked while the answer streams.
+    let db = {
+        let guard = state.lock().await;
+        guard
+            .db
+            .clone()
+            .ok_or(crate::Error::NoneDatabase)
+            .map_err(|e| e.to_string())?
+    };
+
+    let conn = app.get_llm_connection().await.map_err(|e| e.to_string())?;
+    let model = match &conn {
+        ConnectionLLM::Custom(_) => app
+            .get_custom_llm_model()
+            .map_err(|e| e.to_string())?
+            .unwrap_or("gpt-4".to_string()),
+        _ => "gpt-4".to_string(),
+    };
+
+    let llm = client(conn.into());
+
+    // Questions are embedded by the local server only when it's already running, so chatting with a
+    // custom model doesn't load the local one as well. Passages are then matched by their words.
+    let embedder = if app.is_server_running().await {
+        app.api_base().await.map(|api_base| {
+            client(tauri_plugin_connector::Connection {
+                api_base,
+                api_key: None,
+            })
+        })
+    } else {
+        None
+    };
+
+    hypr_chat::ChatOrchestrator::new(db, llm, model, embedder)
+        .send(group_id, content, |delta| {
+            let _ = on_delta.send(delta.to_string());
+        })
+        .await
+        .map_err(|e| e.to_string())
+}
+
+fn client(conn: tauri_plugin_connector::Connection) -> hypr_openai::OpenAIClient {
+    hypr_openai::OpenAIClient::builder()
+        .api_base(conn.api_base)
+        .api_key(conn.api_key.unwrap_or_default())
+        .build()
+}
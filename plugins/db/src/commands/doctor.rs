@@ -0,0 +1,17 @@
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip(state))]
+pub async fn run_doctor(
+    state: tauri::State<'_, crate::ManagedState>,
+    quarantine: bool,
+) -> Result<hypr_db_user::DoctorReport, String> {
+    let guard = state.lock().await;
+
+    let db = guard
+        .db
+        .as_ref()
+        .ok_or(crate::Error::NoneDatabase)
+        .map_err(|e| e.to_string())?;
+
+    db.doctor(quarantine).await.map_err(|e| e.to_string())
+}
@@ -22,7 +22,7 @@ pub async fn get_event(
 pub async fn list_events(
     state: tauri::State<'_, crate::ManagedState>,
     filter: Option<hypr_db_user::ListEventFilter>,
-) -> Result<Vec<hypr_db_user::Event>, String> {
+) -> Result<hypr_db_user::Page<hypr_db_user::Event>, String> {
     let guard = state.lock().await;
 
     let db = guard
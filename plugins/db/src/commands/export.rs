@@ -0,0 +1,51 @@
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip(state))]
+pub async fn export_sessions_markdown(
+    state: tauri::State<'_, crate::ManagedState>,
+    dir: String,
+    session_ids: Vec<String>,
+) -> Result<Vec<String>, String> {
+    // Cloned so the state isn't locked while writing files.
+    let db = {
+        let guard = state.lock().await;
+        guard
+            .db
+            .clone()
+            .ok_or(crate::Error::NoneDatabase)
+            .map_err(|e| e.to_string())?
+    };
+
+    let report = hypr_export::export_sessions(&db, dir, session_ids)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(report
+        .written
+        .into_iter()
+        .map(|p| p.to_string_lossy().to_string())
+        .collect())
+}
+
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip(state))]
+pub async fn export_transcript(
+    state: tauri::State<'_, crate::ManagedState>,
+    session_id: String,
+    format: hypr_timeline::TranscriptFormat,
+) -> Result<Option<String>, String> {
+    let guard = state.lock().await;
+
+    let db = guard
+        .db
+        .as_ref()
+        .ok_or(crate::Error::NoneDatabase)
+        .map_err(|e| e.to_string())?;
+
+    let export = hypr_export::SessionExport::load(db, session_id)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(export.and_then(|export| export.transcript(format)))
+}
@@ -58,7 +58,7 @@ pub async fn upsert_human(
 pub async fn list_humans(
     state: tauri::State<'_, crate::ManagedState>,
     filter: Option<hypr_db_user::ListHumanFilter>,
-) -> Result<Vec<hypr_db_user::Human>, String> {
+) -> Result<hypr_db_user::Page<hypr_db_user::Human>, String> {
     let guard = state.lock().await;
 
     let db = guard
@@ -1,10 +1,15 @@
 pub mod calendars;
 pub mod chats;
 pub mod configs;
+pub mod doctor;
 pub mod events;
+pub mod export;
 pub mod extensions;
 pub mod humans;
 pub mod organizations;
+pub mod passages;
 pub mod sessions;
+pub mod sync;
 pub mod tags;
 pub mod templates;
+pub mod vocabulary;
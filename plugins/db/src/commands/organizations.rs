@@ -4,7 +4,7 @@
 pub async fn list_organizations(
     state: tauri::State<'_, crate::ManagedState>,
     filter: Option<hypr_db_user::ListOrganizationFilter>,
-) -> Result<Vec<hypr_db_user::Organization>, String> {
+) -> Result<hypr_db_user::Page<hypr_db_user::Organization>, String> {
     let guard = state.lock().await;
 
     let db = guard
@@ -18,6 +18,23 @@ pub async fn list_organizations(
         .map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip(state))]
+pub async fn list_all_organizations(
+    state: tauri::State<'_, crate::ManagedState>,
+) -> Result<Vec<hypr_db_user::Organization>, String> {
+    let guard = state.lock().await;
+
+    let db = guard
+        .db
+        .as_ref()
+        .ok_or(crate::Error::NoneDatabase)
+        .map_err(|e| e.to_string())?;
+
+    db.list_all_organizations().await.map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 #[specta::specta]
 #[tracing::instrument(skip(state))]
@@ -0,0 +1,49 @@
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip(state, passages))]
+pub async fn replace_session_passages(
+    state: tauri::State<'_, crate::ManagedState>,
+    session_id: String,
+    model: String,
+    passages: Vec<hypr_db_user::SessionPassage>,
+) -> Result<Vec<hypr_db_user::SessionPassage>, String> {
+    let guard = state.lock().await;
+
+    let db = guard
+        .db
+        .as_ref()
+        .ok_or(crate::Error::NoneDatabase)
+        .map_err(|e| e.to_string())?;
+
+    db.replace_session_passages(session_id, model, passages)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip(state, query))]
+pub async fn search_sessions_by_embedding(
+    state: tauri::State<'_, crate::ManagedState>,
+    model: String,
+    query: Vec<f32>,
+    limit: u32,
+) -> Result<Vec<hypr_db_user::SessionSearchResult>, String> {
+    let guard = state.lock().await;
+
+    let db = guard
+        .db
+        .as_ref()
+        .ok_or(crate::Error::NoneDatabase)
+        .map_err(|e| e.to_string())?;
+
+    let user_id = guard
+        .user_id
+        .as_ref()
+        .ok_or(crate::Error::NoneUser)
+        .map_err(|e| e.to_string())?;
+
+    db.search_sessions_by_embedding(user_id, model, query, limit as usize)
+        .await
+        .map_err(|e| e.to_string())
+}
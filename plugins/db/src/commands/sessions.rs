@@ -59,6 +59,30 @@ pub async fn get_timeline_view(
     Ok(v)
 }
 
+// The timeline split into windows of at most `max_chars` characters, for models with a small context.
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip(state))]
+pub async fn get_timeline_view_windows(
+    state: tauri::State<'_, crate::ManagedState>,
+    session_id: String,
+    max_chars: u32,
+) -> Result<Vec<hypr_timeline::TimelineView>, String> {
+    let guard = state.lock().await;
+
+    let db = guard
+        .db
+        .as_ref()
+        .ok_or(crate::Error::NoneDatabase)
+        .map_err(|e| e.to_string())?;
+
+    let v = db
+        .get_timeline_view(session_id)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(v.map(|v| v.windows(max_chars as usize)).unwrap_or_default())
+}
+
 #[tauri::command]
 #[specta::specta]
 #[tracing::instrument(skip(state))]
@@ -83,7 +107,7 @@ pub async fn upsert_session(
 pub async fn list_sessions(
     state: tauri::State<'_, crate::ManagedState>,
     filter: Option<hypr_db_user::ListSessionFilter>,
-) -> Result<Vec<hypr_db_user::Session>, String> {
+) -> Result<hypr_db_user::Page<hypr_db_user::Session>, String> {
     let guard = state.lock().await;
 
     let db = guard
@@ -232,6 +256,130 @@ pub async fn session_list_participants(
         .map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip(state))]
+pub async fn session_resolve_speakers(
+    state: tauri::State<'_, crate::ManagedState>,
+    session_id: String,
+) -> Result<Vec<hypr_db_user::ResolvedSpeaker>, String> {
+    let guard = state.lock().await;
+
+    let db = guard
+        .db
+        .as_ref()
+        .ok_or(crate::Error::NoneDatabase)
+        .map_err(|e| e.to_string())?;
+
+    db.session_resolve_speakers(session_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip(state))]
+pub async fn session_assign_speaker(
+    state: tauri::State<'_, crate::ManagedState>,
+    session_id: String,
+    speaker: i32,
+    human_id: String,
+) -> Result<hypr_db_user::SessionSpeaker, String> {
+    let guard = state.lock().await;
+
+    let db = guard
+        .db
+        .as_ref()
+        .ok_or(crate::Error::NoneDatabase)
+        .map_err(|e| e.to_string())?;
+
+    db.session_assign_speaker(session_id, speaker, human_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip(state))]
+pub async fn session_merge_speakers(
+    state: tauri::State<'_, crate::ManagedState>,
+    session_id: String,
+    from: i32,
+    into: i32,
+) -> Result<hypr_db_user::SessionSpeaker, String> {
+    let guard = state.lock().await;
+
+    let db = guard
+        .db
+        .as_ref()
+        .ok_or(crate::Error::NoneDatabase)
+        .map_err(|e| e.to_string())?;
+
+    db.session_merge_speakers(session_id, from, into)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip(state))]
+pub async fn list_voice_suggestions(
+    state: tauri::State<'_, crate::ManagedState>,
+    session_id: String,
+) -> Result<Vec<hypr_db_user::VoiceSuggestion>, String> {
+    let guard = state.lock().await;
+
+    let db = guard
+        .db
+        .as_ref()
+        .ok_or(crate::Error::NoneDatabase)
+        .map_err(|e| e.to_string())?;
+
+    db.list_voice_suggestions(session_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip(state))]
+pub async fn confirm_voice_suggestion(
+    state: tauri::State<'_, crate::ManagedState>,
+    id: String,
+) -> Result<hypr_db_user::SessionSpeaker, String> {
+    let guard = state.lock().await;
+
+    let db = guard
+        .db
+        .as_ref()
+        .ok_or(crate::Error::NoneDatabase)
+        .map_err(|e| e.to_string())?;
+
+    db.confirm_voice_suggestion(id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip(state))]
+pub async fn reject_voice_suggestion(
+    state: tauri::State<'_, crate::ManagedState>,
+    id: String,
+) -> Result<(), String> {
+    let guard = state.lock().await;
+
+    let db = guard
+        .db
+        .as_ref()
+        .ok_or(crate::Error::NoneDatabase)
+        .map_err(|e| e.to_string())?;
+
+    db.reject_voice_suggestion(id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 #[specta::specta]
 #[tracing::instrument(skip(state))]
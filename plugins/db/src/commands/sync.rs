@@ -0,0 +1,27 @@
+use crate::DatabasePluginExt;
+
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip(app))]
+pub async fn sync_now<R: tauri::Runtime>(
+    app: tauri::AppHandle<R>,
+) -> Result<hypr_db_user::SyncReport, String> {
+    app.db_sync().await.map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip(state))]
+pub async fn list_sync_conflicts(
+    state: tauri::State<'_, crate::ManagedState>,
+) -> Result<Vec<hypr_db_user::SyncConflict>, String> {
+    let guard = state.lock().await;
+
+    let db = guard
+        .db
+        .as_ref()
+        .ok_or(crate::Error::NoneDatabase)
+        .map_err(|e| e.to_string())?;
+
+    db.list_sync_conflicts().await.map_err(|e| e.to_string())
+}
@@ -76,3 +76,96 @@ pub async fn unassign_tag_from_session(
         .await
         .map_err(|e| e.to_string())
 }
+
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip(state))]
+pub async fn list_tags_with_usage(
+    state: tauri::State<'_, crate::ManagedState>,
+) -> Result<Vec<hypr_db_user::TagWithUsage>, String> {
+    let guard = state.lock().await;
+
+    let db = guard
+        .db
+        .as_ref()
+        .ok_or(crate::Error::NoneDatabase)
+        .map_err(|e| e.to_string())?;
+
+    db.list_tags_with_usage().await.map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip(state))]
+pub async fn upsert_tag(
+    state: tauri::State<'_, crate::ManagedState>,
+    tag: hypr_db_user::Tag,
+) -> Result<hypr_db_user::Tag, String> {
+    let guard = state.lock().await;
+
+    let db = guard
+        .db
+        .as_ref()
+        .ok_or(crate::Error::NoneDatabase)
+        .map_err(|e| e.to_string())?;
+
+    db.upsert_tag(tag).await.map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip(state))]
+pub async fn delete_tag(
+    state: tauri::State<'_, crate::ManagedState>,
+    tag_id: String,
+) -> Result<(), String> {
+    let guard = state.lock().await;
+
+    let db = guard
+        .db
+        .as_ref()
+        .ok_or(crate::Error::NoneDatabase)
+        .map_err(|e| e.to_string())?;
+
+    db.delete_tag(tag_id).await.map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip(state))]
+pub async fn rename_tag(
+    state: tauri::State<'_, crate::ManagedState>,
+    tag_id: String,
+    name: String,
+) -> Result<Option<hypr_db_user::Tag>, String> {
+    let guard = state.lock().await;
+
+    let db = guard
+        .db
+        .as_ref()
+        .ok_or(crate::Error::NoneDatabase)
+        .map_err(|e| e.to_string())?;
+
+    db.rename_tag(tag_id, name).await.map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip(state))]
+pub async fn merge_tags(
+    state: tauri::State<'_, crate::ManagedState>,
+    from_id: String,
+    into_id: String,
+) -> Result<Option<hypr_db_user::Tag>, String> {
+    let guard = state.lock().await;
+
+    let db = guard
+        .db
+        .as_ref()
+        .ok_or(crate::Error::NoneDatabase)
+        .map_err(|e| e.to_string())?;
+
+    db.merge_tags(from_id, into_id)
+        .await
+        .map_err(|e| e.to_string())
+}
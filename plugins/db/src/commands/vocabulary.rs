@@ -0,0 +1,74 @@
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip(state))]
+pub async fn list_vocabulary(
+    state: tauri::State<'_, crate::ManagedState>,
+    session_id: Option<String>,
+) -> Result<Vec<hypr_db_user::VocabularyEntry>, String> {
+    let guard = state.lock().await;
+
+    let db = guard
+        .db
+        .as_ref()
+        .ok_or(crate::Error::NoneDatabase)
+        .map_err(|e| e.to_string())?;
+
+    let user_id = guard
+        .user_id
+        .as_ref()
+        .ok_or(crate::Error::NoneUser)
+        .map_err(|e| e.to_string())?;
+
+    db.list_vocabulary(user_id, session_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip(state))]
+pub async fn upsert_vocabulary_entry(
+    state: tauri::State<'_, crate::ManagedState>,
+    entry: hypr_db_user::VocabularyEntry,
+) -> Result<hypr_db_user::VocabularyEntry, String> {
+    let guard = state.lock().await;
+
+    let db = guard
+        .db
+        .as_ref()
+        .ok_or(crate::Error::NoneDatabase)
+        .map_err(|e| e.to_string())?;
+
+    let user_id = guard
+        .user_id
+        .as_ref()
+        .ok_or(crate::Error::NoneUser)
+        .map_err(|e| e.to_string())?;
+
+    db.upsert_vocabulary_entry(hypr_db_user::VocabularyEntry {
+        user_id: user_id.clone(),
+        ..entry
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip(state))]
+pub async fn delete_vocabulary_entry(
+    state: tauri::State<'_, crate::ManagedState>,
+    id: String,
+) -> Result<(), String> {
+    let guard = state.lock().await;
+
+    let db = guard
+        .db
+        .as_ref()
+        .ok_or(crate::Error::NoneDatabase)
+        .map_err(|e| e.to_string())?;
+
+    db.delete_vocabulary_entry(id)
+        .await
+        .map_err(|e| e.to_string())
+}
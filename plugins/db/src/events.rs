@@ -0,0 +1,10 @@
+#[derive(serde::Serialize, Clone, specta::Type, tauri_specta::Event)]
+#[serde(tag = "type")]
+pub enum SyncEvent {
+    #[serde(rename = "started")]
+    Started,
+    #[serde(rename = "finished")]
+    Finished { report: hypr_db_user::SyncReport },
+    #[serde(rename = "failed")]
+    Failed { error: String },
+}
@@ -1,5 +1,8 @@
 use std::future::Future;
 use tauri::Manager;
+use tauri_specta::Event;
+
+use crate::SyncEvent;
 
 pub trait DatabasePluginExt<R: tauri::Runtime> {
     fn db_user_id(&self) -> impl Future<Output = Result<Option<String>, crate::Error>>;
@@ -8,7 +11,17 @@ pub trait DatabasePluginExt<R: tauri::Runtime> {
         &self,
         db: hypr_db_core::Database,
     ) -> impl Future<Output = Result<(), crate::Error>>;
-    fn db_sync(&self) -> impl Future<Output = Result<(), crate::Error>>;
+    fn db_set_remote(
+        &self,
+        url: impl Into<String>,
+        token: impl Into<String>,
+    ) -> impl Future<Output = ()>;
+    fn db_handle(&self) -> impl Future<Output = Result<hypr_db_user::UserDatabase, crate::Error>>;
+    fn db_sync(&self) -> impl Future<Output = Result<hypr_db_user::SyncReport, crate::Error>>;
+    fn db_start_sync(
+        &self,
+        interval: std::time::Duration,
+    ) -> impl Future<Output = Result<(), crate::Error>>;
     fn db_ensure_user(
         &self,
         user_id: impl Into<String>,
@@ -59,12 +72,108 @@ impl<R: tauri::Runtime, T: tauri::Manager<R>> DatabasePluginExt<R> for T {
         Ok(())
     }
 
-    async fn db_sync(&self) -> Result<(), crate::Error> {
+    async fn db_set_remote(&self, url: impl Into<String>, token: impl Into<String>) {
+        let state = self.state::<crate::ManagedState>();
+        let mut s = state.lock().await;
+
+        s.remote_config = Some((url.into(), token.into()));
+        s.remote = None;
+    }
+
+    async fn db_handle(&self) -> Result<hypr_db_user::UserDatabase, crate::Error> {
         let state = self.state::<crate::ManagedState>();
         let guard = state.lock().await;
 
-        let db = guard.db.as_ref().ok_or(crate::Error::NoneDatabase)?;
-        db.sync().await?;
+        guard.db.clone().ok_or(crate::Error::NoneDatabase)
+    }
+
+    async fn db_sync(&self) -> Result<hypr_db_user::SyncReport, crate::Error> {
+        let sync_lock = {
+            let state = self.state::<crate::ManagedState>();
+            let guard = state.lock().await;
+            guard.sync_lock.clone()
+        };
+        let _syncing = sync_lock.lock().await;
+
+        // Cloned out so other commands are not blocked while talking to the remote.
+        let (db, remote, remote_config) = {
+            let state = self.state::<crate::ManagedState>();
+            let guard = state.lock().await;
+
+            let db = guard.db.clone().ok_or(crate::Error::NoneDatabase)?;
+            (db, guard.remote.clone(), guard.remote_config.clone())
+        };
+
+        let Some((url, token)) = remote_config else {
+            return Ok(hypr_db_user::SyncReport::default());
+        };
+
+        let app = self.app_handle();
+        SyncEvent::Started.emit(app)?;
+
+        let result = async {
+            let remote = match remote {
+                Some(remote) => remote,
+                // Connected lazily, so being offline only fails this attempt. The next one retries.
+                None => {
+                    let remote = hypr_db_user::UserDatabase::from(
+                        hypr_db_core::DatabaseBuilder::default()
+                            .remote(url, token)
+                            .build()
+                            .await?,
+                    );
+                    hypr_db_user::migrate(&remote).await?;
+
+                    let state = self.state::<crate::ManagedState>();
+                    state.lock().await.remote = Some(remote.clone());
+                    remote
+                }
+            };
+
+            db.sync_with(&remote).await
+        }
+        .await;
+
+        match result {
+            Ok(report) => {
+                SyncEvent::Finished {
+                    report: report.clone(),
+                }
+                .emit(app)?;
+                Ok(report)
+            }
+            Err(e) => {
+                SyncEvent::Failed {
+                    error: e.to_string(),
+                }
+                .emit(app)?;
+                Err(e.into())
+            }
+        }
+    }
+
+    async fn db_start_sync(&self, interval: std::time::Duration) -> Result<(), crate::Error> {
+        let app = self.app_handle().clone();
+
+        let task = tauri::async_runtime::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+            loop {
+                ticker.tick().await;
+                if let Err(e) = app.db_sync().await {
+                    tracing::error!("db_sync_error: {}", e);
+                }
+            }
+        });
+
+        let state = self.state::<crate::ManagedState>();
+        let mut guard = state.lock().await;
+
+        if let Some(previous) = guard.sync_task.replace(task) {
+            previous.abort();
+        }
+
         Ok(())
     }
 
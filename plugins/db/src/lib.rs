@@ -3,9 +3,11 @@ use tokio::sync::Mutex;
 
 mod commands;
 mod error;
+mod events;
 mod ext;
 
 pub use error::{Error, Result};
+pub use events::*;
 pub use ext::DatabasePluginExt;
 
 pub type ManagedState = Mutex<State>;
@@ -14,6 +16,12 @@ pub type ManagedState = Mutex<State>;
 pub struct State {
     pub user_id: Option<String>,
     pub db: Option<hypr_db_user::UserDatabase>,
+    // Turso URL and token. The remote is connected to on the first sync, so the app starts offline.
+    pub remote_config: Option<(String, String)>,
+    pub remote: Option<hypr_db_user::UserDatabase>,
+    pub sync_task: Option<tauri::async_runtime::JoinHandle<()>>,
+    // Held for a whole sync, so a manual one and the interval one never run at once.
+    pub sync_lock: std::sync::Arc<Mutex<()>>,
 }
 
 const PLUGIN_NAME: &str = "db";
@@ -41,9 +49,16 @@ fn make_specta_builder<R: tauri::Runtime>() -> tauri_specta::Builder<R> {
             commands::sessions::session_add_participant,
             commands::sessions::session_remove_participant,
             commands::sessions::session_list_participants,
+            commands::sessions::session_resolve_speakers,
+            commands::sessions::session_assign_speaker,
+            commands::sessions::session_merge_speakers,
+            commands::sessions::list_voice_suggestions,
+            commands::sessions::confirm_voice_suggestion,
+            commands::sessions::reject_voice_suggestion,
             commands::sessions::session_get_event,
             commands::sessions::get_timeline_view,
             commands::sessions::get_timeline_view_onboarding,
+            commands::sessions::get_timeline_view_windows,
             commands::configs::get_config,
             commands::configs::set_config,
             commands::humans::get_human,
@@ -53,19 +68,37 @@ fn make_specta_builder<R: tauri::Runtime>() -> tauri_specta::Builder<R> {
             commands::organizations::get_organization_by_user_id,
             commands::organizations::upsert_organization,
             commands::organizations::list_organizations,
+            commands::organizations::list_all_organizations,
             commands::organizations::list_organization_members,
             commands::chats::list_chat_groups,
             commands::chats::list_chat_messages,
             commands::chats::create_chat_group,
             commands::chats::upsert_chat_message,
+            commands::chats::send_chat_message::<tauri::Wry>,
             commands::tags::list_all_tags,
             commands::tags::list_session_tags,
             commands::tags::assign_tag_to_session,
             commands::tags::unassign_tag_from_session,
+            commands::tags::list_tags_with_usage,
+            commands::tags::upsert_tag,
+            commands::tags::delete_tag,
+            commands::tags::rename_tag,
+            commands::tags::merge_tags,
+            commands::vocabulary::list_vocabulary,
+            commands::vocabulary::upsert_vocabulary_entry,
+            commands::vocabulary::delete_vocabulary_entry,
+            commands::passages::replace_session_passages,
+            commands::passages::search_sessions_by_embedding,
             commands::extensions::get_extension_mapping,
             commands::extensions::list_extension_mappings,
             commands::extensions::upsert_extension_mapping,
+            commands::doctor::run_doctor,
+            commands::export::export_sessions_markdown,
+            commands::export::export_transcript,
+            commands::sync::sync_now::<tauri::Wry>,
+            commands::sync::list_sync_conflicts,
         ])
+        .events(tauri_specta::collect_events![SyncEvent])
         .typ::<hypr_db_user::ExtensionDefinition>()
         .typ::<hypr_db_user::ExtensionWidgetKind>()
         .error_handling(tauri_specta::ErrorHandlingMode::Throw)
@@ -76,7 +109,8 @@ pub fn init<R: tauri::Runtime>() -> tauri::plugin::TauriPlugin<R> {
 
     tauri::plugin::Builder::new(PLUGIN_NAME)
         .invoke_handler(specta_builder.invoke_handler())
-        .setup(|app, _api| {
+        .setup(move |app, _api| {
+            specta_builder.mount_events(app);
             app.manage(ManagedState::default());
             Ok(())
         })
@@ -21,9 +21,22 @@ common_derives! {
         pub end: u64,
         pub text: String,
         pub confidence: Option<f32>,
+        // Interim chunks are provisional. A later chunk with the same revision replaces them,
+        // and a final one covering their time range removes them.
+        #[serde(default = "default_is_final")]
+        pub is_final: bool,
+        #[serde(default)]
+        pub revision: Option<u64>,
+        // ISO 639-1 code of the language detected for this chunk, if the provider reports one.
+        #[serde(default)]
+        pub language: Option<String>,
     }
 }
 
+fn default_is_final() -> bool {
+    true
+}
+
 common_derives! {
     pub struct DiarizationChunk {
         pub start: u64,
@@ -48,33 +61,83 @@ common_derives! {
     }
 }
 
+common_derives! {
+    pub struct VocabularyTerm {
+        pub term: String,
+        // Relative weight from 0 to 1. `None` uses each engine's default.
+        #[serde(default)]
+        pub boost: Option<f32>,
+        // How the term is said or commonly misheard, e.g. "hyper note" for "Hyprnote".
+        #[serde(default)]
+        pub pronunciations: Vec<String>,
+    }
+}
+
 common_derives! {
     #[derive(Default)]
     pub struct ListenParams {
+        // `None` detects the language from the audio. Sent as "auto".
         #[specta(type = String)]
         #[schemars(with = "String")]
         #[serde(serialize_with = "serialize_language", deserialize_with = "deserialize_language")]
-        pub language: hypr_language::Language,
+        pub language: Option<hypr_language::Language>,
         pub static_prompt: String,
         pub dynamic_prompt: String,
+        // Whether to also send interim transcripts, where the server supports them.
+        #[serde(default)]
+        pub interim_results: bool,
+        // Sent as JSON, since query strings have no lists of structs.
+        #[specta(type = String)]
+        #[schemars(with = "String")]
+        #[serde(
+            default,
+            serialize_with = "serialize_vocabulary",
+            deserialize_with = "deserialize_vocabulary"
+        )]
+        pub vocabulary: Vec<VocabularyTerm>,
     }
 }
 
 use serde::Deserialize;
 use std::str::FromStr;
 
+pub const AUTO_LANGUAGE: &str = "auto";
+
 fn serialize_language<S: serde::Serializer>(
-    lang: &hypr_language::Language,
+    lang: &Option<hypr_language::Language>,
     serializer: S,
 ) -> Result<S::Ok, S::Error> {
-    let code = lang.iso639().code();
+    let code = lang.as_ref().map_or(AUTO_LANGUAGE, |l| l.iso639().code());
     serializer.serialize_str(code)
 }
 
 fn deserialize_language<'de, D: serde::Deserializer<'de>>(
     deserializer: D,
-) -> Result<hypr_language::Language, D::Error> {
+) -> Result<Option<hypr_language::Language>, D::Error> {
     let str = String::deserialize(deserializer)?;
+    if str == AUTO_LANGUAGE {
+        return Ok(None);
+    }
+
     let iso639 = hypr_language::ISO639::from_str(&str).map_err(serde::de::Error::custom)?;
-    Ok(iso639.into())
+    Ok(Some(iso639.into()))
+}
+
+fn serialize_vocabulary<S: serde::Serializer>(
+    vocabulary: &[VocabularyTerm],
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let json = serde_json::to_string(vocabulary).map_err(serde::ser::Error::custom)?;
+    serializer.serialize_str(&json)
+}
+
+fn deserialize_vocabulary<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<VocabularyTerm>, D::Error> {
+    let str = String::deserialize(deserializer)?;
+    if str.is_empty() {
+        return Ok(vec![]);
+    }
+
+    serde_json::from_str(&str).map_err(serde::de::Error::custom)
 }
@@ -16,6 +16,11 @@ const COMMANDS: &[&str] = &[
     "pause_session",
     "resume_session",
     "get_state",
+    "enroll_speaker",
+    "suggest_speakers",
+    "is_voice_model_downloaded",
+    "is_voice_model_downloading",
+    "download_voice_model",
 ];
 
 fn main() {
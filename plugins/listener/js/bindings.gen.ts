@@ -60,6 +60,21 @@ async resumeSession() : Promise<null> {
 },
 async getState() : Promise<string> {
     return await TAURI_INVOKE("plugin:listener|get_state");
+},
+async enrollSpeaker(sessionId: string, speaker: number, humanId: string) : Promise<SessionSpeaker> {
+    return await TAURI_INVOKE("plugin:listener|enroll_speaker", { sessionId, speaker, humanId });
+},
+async suggestSpeakers(sessionId: string) : Promise<VoiceSuggestion[]> {
+    return await TAURI_INVOKE("plugin:listener|suggest_speakers", { sessionId });
+},
+async isVoiceModelDownloaded() : Promise<boolean> {
+    return await TAURI_INVOKE("plugin:listener|is_voice_model_downloaded");
+},
+async isVoiceModelDownloading() : Promise<boolean> {
+    return await TAURI_INVOKE("plugin:listener|is_voice_model_downloading");
+},
+async downloadVoiceModel(channel: TAURI_CHANNEL<number>) : Promise<null> {
+    return await TAURI_INVOKE("plugin:listener|download_voice_model", { channel });
 }
 }
 
@@ -78,13 +93,19 @@ statusEvent: "plugin:listener:status-event"
 
 /** user-defined types **/
 
-export type SessionEvent = ({ type: "started" } & SessionEventStarted) | { type: "stopped" } | { type: "paused" } | { type: "resumed" } | { type: "silence" } | ({ type: "timelineView" } & SessionEventTimelineView) | ({ type: "audioAmplitude" } & SessionEventAudioAmplitude)
+export type SessionEvent = ({ type: "started" } & SessionEventStarted) | { type: "stopped" } | { type: "paused" } | { type: "resumed" } | { type: "silence" } | ({ type: "timelineView" } & SessionEventTimelineView) | ({ type: "timelineDiff" } & SessionEventTimelineDiff) | ({ type: "audioAmplitude" } & SessionEventAudioAmplitude)
 export type SessionEventAudioAmplitude = { mic: number; speaker: number }
 export type SessionEventStarted = { seconds: number }
+export type SessionEventTimelineDiff = { diffs: TimelineDiff[] }
 export type SessionEventTimelineView = { timeline: TimelineView }
+export type SessionSpeaker = { id: string; session_id: string; speaker: number; human_id: string | null; segments: SpeakerSegment[]; merged: boolean }
+export type SpeakerSegment = { start: number; end: number }
 export type StatusEvent = "inactive" | "running_active" | "running_paused"
+export type TimelineDiff = { type: "itemAppended"; item: TimelineViewItem } | { type: "itemUpdated"; index: number; item: TimelineViewItem } | { type: "truncated"; len: number }
 export type TimelineView = { items: TimelineViewItem[] }
-export type TimelineViewItem = { start: number; end: number; speaker: number; text: string; confidence: number }
+export type TimelineViewItem = { start: number; end: number; speaker: number; text: string; confidence: number; interim: boolean; translation: string | null }
+export type VoiceSuggestion = { id: string; session_id: string; speaker: number; human_id: string; score: number; status: VoiceSuggestionStatus; created_at: string }
+export type VoiceSuggestionStatus = "pending" | "confirmed" | "rejected"
 
 /** tauri-specta globals **/
 
@@ -34,13 +34,24 @@ impl ListenClientBuilder {
             let mut url: url::Url = self.api_base.unwrap().parse().unwrap();
 
             let params = self.params.unwrap_or_default();
-            let language = params.language.code();
+            let language = params
+                .language
+                .as_ref()
+                .map_or(hypr_listener_interface::AUTO_LANGUAGE, |l| l.code());
 
             url.set_path("/api/desktop/listen/realtime");
             url.query_pairs_mut()
                 .append_pair("language", &language)
                 .append_pair("static_prompt", &params.static_prompt)
-                .append_pair("dynamic_prompt", &params.dynamic_prompt);
+                .append_pair("dynamic_prompt", &params.dynamic_prompt)
+                .append_pair("interim_results", &params.interim_results.to_string());
+
+            if !params.vocabulary.is_empty() {
+                url.query_pairs_mut().append_pair(
+                    "vocabulary",
+                    &serde_json::to_string(&params.vocabulary).unwrap(),
+                );
+            }
 
             let host = url.host_str().unwrap();
 
@@ -122,7 +133,7 @@ mod tests {
             .api_base("http://127.0.0.1:1234")
             .api_key("".to_string())
             .params(hypr_listener_interface::ListenParams {
-                language: hypr_language::ISO639::En.into(),
+                language: Some(hypr_language::ISO639::En.into()),
                 ..Default::default()
             })
             .build();
@@ -174,3 +174,54 @@ pub async fn get_state<R: tauri::Runtime>(
 ) -> Result<crate::fsm::State, String> {
     Ok(app.get_state().await)
 }
+
+#[tauri::command]
+#[specta::specta]
+pub async fn enroll_speaker<R: tauri::Runtime>(
+    app: tauri::AppHandle<R>,
+    session_id: String,
+    speaker: i32,
+    human_id: String,
+) -> Result<hypr_db_user::SessionSpeaker, String> {
+    app.enroll_speaker(session_id, speaker, human_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn suggest_speakers<R: tauri::Runtime>(
+    app: tauri::AppHandle<R>,
+    session_id: String,
+) -> Result<Vec<hypr_db_user::VoiceSuggestion>, String> {
+    app.suggest_speakers(session_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn is_voice_model_downloaded<R: tauri::Runtime>(
+    app: tauri::AppHandle<R>,
+) -> Result<bool, String> {
+    Ok(app.is_voice_model_downloaded().await)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn is_voice_model_downloading<R: tauri::Runtime>(
+    app: tauri::AppHandle<R>,
+) -> Result<bool, String> {
+    Ok(app.is_voice_model_downloading().await)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn download_voice_model<R: tauri::Runtime>(
+    app: tauri::AppHandle<R>,
+    channel: tauri::ipc::Channel<i8>,
+) -> Result<(), String> {
+    app.download_voice_model(channel)
+        .await
+        .map_err(|e| e.to_string())
+}
@@ -14,6 +14,16 @@ pub enum Error {
     DatabaseError(#[from] tauri_plugin_db::Error),
     #[error(transparent)]
     ConnectorError(#[from] tauri_plugin_connector::Error),
+    #[error(transparent)]
+    UserDatabaseError(#[from] hypr_db_core::Error),
+    #[error(transparent)]
+    VoiceError(#[from] hypr_voice::Error),
+    #[error(transparent)]
+    EncryptionError(#[from] hypr_encryption::EncryptionError),
+    #[error(transparent)]
+    WavError(#[from] hound::Error),
+    #[error(transparent)]
+    JoinError(#[from] tokio::task::JoinError),
     #[error("no session")]
     NoneSession,
     #[error("start session failed")]
@@ -24,6 +34,14 @@ pub enum Error {
     PauseSessionFailed,
     #[error("resume session failed")]
     ResumeSessionFailed,
+    #[error("no recording for this session")]
+    NoneRecording,
+    #[error("speaker embedding model not downloaded")]
+    NoneVoiceModel,
+    #[error("unlock the app to enroll voices")]
+    AppLocked,
+    #[error("translation failed: {0}")]
+    TranslationFailed(String),
 }
 
 impl Serialize for Error {
@@ -13,6 +13,8 @@ pub enum SessionEvent {
     Silence,
     #[serde(rename = "timelineView")]
     TimelineView(SessionEventTimelineView),
+    #[serde(rename = "timelineDiff")]
+    TimelineDiff(SessionEventTimelineDiff),
     #[serde(rename = "audioAmplitude")]
     AudioAmplitude(SessionEventAudioAmplitude),
 }
@@ -27,6 +29,11 @@ pub struct SessionEventTimelineView {
     pub timeline: hypr_timeline::TimelineView,
 }
 
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize, specta::Type)]
+pub struct SessionEventTimelineDiff {
+    pub diffs: Vec<hypr_timeline::TimelineDiff>,
+}
+
 #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize, specta::Type)]
 pub struct SessionEventAudioAmplitude {
     pub mic: u16,
@@ -10,6 +10,8 @@ use {
     objc2_foundation::NSString,
 };
 
+use tauri_plugin_db::DatabasePluginExt;
+
 use crate::SessionEvent;
 
 pub trait ListenerPluginExt<R: tauri::Runtime> {
@@ -34,6 +36,23 @@ pub trait ListenerPluginExt<R: tauri::Runtime> {
     fn start_session(&self, id: impl Into<String>) -> impl Future<Output = ()>;
     fn pause_session(&self) -> impl Future<Output = ()>;
     fn resume_session(&self) -> impl Future<Output = ()>;
+
+    fn enroll_speaker(
+        &self,
+        session_id: impl Into<String>,
+        speaker: i32,
+        human_id: impl Into<String>,
+    ) -> impl Future<Output = Result<hypr_db_user::SessionSpeaker, crate::Error>>;
+    fn suggest_speakers(
+        &self,
+        session_id: impl Into<String>,
+    ) -> impl Future<Output = Result<Vec<hypr_db_user::VoiceSuggestion>, crate::Error>>;
+    fn is_voice_model_downloaded(&self) -> impl Future<Output = bool>;
+    fn is_voice_model_downloading(&self) -> impl Future<Output = bool>;
+    fn download_voice_model(
+        &self,
+        channel: Channel<i8>,
+    ) -> impl Future<Output = Result<(), crate::Error>>;
 }
 
 impl<R: tauri::Runtime, T: tauri::Manager<R>> ListenerPluginExt<R> for T {
@@ -242,4 +261,150 @@ impl<R: tauri::Runtime, T: tauri::Manager<R>> ListenerPluginExt<R> for T {
             guard.fsm.handle(&event).await;
         }
     }
+
+    #[tracing::instrument(skip_all)]
+    async fn enroll_speaker(
+        &self,
+        session_id: impl Into<String>,
+        speaker: i32,
+        human_id: impl Into<String>,
+    ) -> Result<hypr_db_user::SessionSpeaker, crate::Error> {
+        let (session_id, human_id) = (session_id.into(), human_id.into());
+
+        // Checked first, so a locked app never stores a plaintext embedding.
+        let key = crate::voice::sealing_key(self)?;
+
+        let model = crate::voice::model_path(self)?;
+        let recording = crate::voice::recording_path(self, &session_id)?;
+        let db = self.db_handle().await?;
+
+        let (_, embedding) =
+            crate::voice::embed_speakers(&db, &session_id, recording, model, Some(vec![speaker]))
+                .await?
+                .pop()
+                .ok_or(hypr_voice::Error::EmptyAudio)?;
+
+        let (bytes, encrypted) = crate::voice::seal(&embedding, key)?;
+        db.add_voice_enrollment(crate::voice::new_enrollment(
+            human_id.clone(),
+            bytes,
+            encrypted,
+        ))
+        .await?;
+
+        Ok(db
+            .session_assign_speaker(&session_id, speaker, human_id)
+            .await?)
+    }
+
+    #[tracing::instrument(skip_all)]
+    async fn suggest_speakers(
+        &self,
+        session_id: impl Into<String>,
+    ) -> Result<Vec<hypr_db_user::VoiceSuggestion>, crate::Error> {
+        let session_id = session_id.into();
+        let db = self.db_handle().await?;
+
+        let enrolled = crate::voice::enrolled_voices(
+            db.list_voice_enrollments(crate::voice::MODEL_NAME).await?,
+            crate::voice::encryption_key(self),
+        );
+        if enrolled.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let unassigned = db
+            .session_resolve_speakers(&session_id)
+            .await?
+            .into_iter()
+            .filter(|s| s.human.is_none())
+            .map(|s| s.speaker)
+            .collect::<Vec<_>>();
+        if unassigned.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let model = crate::voice::model_path(self)?;
+        let recording = crate::voice::recording_path(self, &session_id)?;
+        let embeddings =
+            crate::voice::embed_speakers(&db, &session_id, recording, model, Some(unassigned))
+                .await?;
+
+        let suggestions = embeddings
+            .into_iter()
+            .filter_map(|(speaker, embedding)| {
+                let m = hypr_voice::best_match(
+                    &embedding,
+                    enrolled.iter().map(|(h, v)| (h.as_str(), v.as_slice())),
+                    hypr_voice::DEFAULT_THRESHOLD,
+                )?;
+                Some(crate::voice::new_suggestion(&session_id, speaker, m))
+            })
+            .collect();
+
+        Ok(db
+            .replace_voice_suggestions(&session_id, suggestions)
+            .await?)
+    }
+
+    #[tracing::instrument(skip_all)]
+    async fn is_voice_model_downloaded(&self) -> bool {
+        crate::voice::model_path(self).is_ok()
+    }
+
+    #[tracing::instrument(skip_all)]
+    async fn is_voice_model_downloading(&self) -> bool {
+        let state = self.state::<crate::SharedState>();
+        let guard = state.lock().await;
+        guard
+            .voice_download
+            .as_ref()
+            .is_some_and(|task| !task.is_finished())
+    }
+
+    #[tracing::instrument(skip_all)]
+    async fn download_voice_model(&self, channel: Channel<i8>) -> Result<(), crate::Error> {
+        let path = crate::voice::model_file(self)?;
+        // Downloaded next to the final path and renamed once complete, so a partial file never counts as the model.
+        let partial = path.with_extension("onnx.part");
+
+        let task = tokio::spawn(async move {
+            let callback = |progress: hypr_file::DownloadProgress| match progress {
+                hypr_file::DownloadProgress::Started => {
+                    let _ = channel.send(0);
+                }
+                hypr_file::DownloadProgress::Progress(downloaded, total_size) => {
+                    let percent = (downloaded as f64 / total_size as f64) * 100.0;
+                    // 100 is sent once the file is in place.
+                    let _ = channel.send((percent as i8).min(99));
+                }
+                hypr_file::DownloadProgress::Finished => {}
+            };
+
+            let result =
+                hypr_file::download_file_with_callback(crate::voice::MODEL_URL, &partial, callback)
+                    .await
+                    .map_err(|e| e.to_string())
+                    .and_then(|_| std::fs::rename(&partial, &path).map_err(|e| e.to_string()));
+
+            match result {
+                Ok(_) => {
+                    let _ = channel.send(100);
+                }
+                Err(e) => {
+                    tracing::error!("voice_model_download_error: {}", e);
+                    let _ = std::fs::remove_file(&partial);
+                    let _ = channel.send(-1);
+                }
+            }
+        });
+
+        let state = self.state::<crate::SharedState>();
+        let mut guard = state.lock().await;
+        if let Some(task) = guard.voice_download.replace(task) {
+            task.abort();
+        }
+
+        Ok(())
+    }
 }
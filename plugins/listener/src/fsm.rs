@@ -1,4 +1,5 @@
 use std::collections::HashMap;
+use std::str::FromStr;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
@@ -11,12 +12,18 @@ use futures_util::StreamExt;
 use tokio::sync::{mpsc, Mutex};
 use tokio::task::JoinSet;
 
-use crate::{SessionEvent, SessionEventStarted, SessionEventTimelineView, StatusEvent};
+use crate::{
+    SessionEvent, SessionEventStarted, SessionEventTimelineDiff, SessionEventTimelineView,
+    StatusEvent,
+};
 use hypr_audio::AsyncSource;
-use hypr_timeline::{Timeline, TimelineFilter};
+use hypr_timeline::Timeline;
 
 const SAMPLE_RATE: u32 = 16000;
 const AUDIO_AMPLITUDE_THROTTLE: Duration = Duration::from_millis(100);
+const TRANSLATION_FLUSH_AFTER: Duration = Duration::from_secs(2);
+// How long stopping waits for the last sentences to be translated and saved.
+const TRANSLATION_DRAIN_TIMEOUT: Duration = Duration::from_secs(10);
 
 pub struct Session {
     app: tauri::AppHandle,
@@ -28,7 +35,9 @@ pub struct Session {
     speaker_muted_rx: Option<tokio::sync::watch::Receiver<bool>>,
     silence_stream_tx: Option<std::sync::mpsc::Sender<()>>,
     session_state_tx: Option<tokio::sync::watch::Sender<State>>,
+    timeline: Option<Arc<Mutex<Timeline>>>,
     tasks: Option<JoinSet<()>>,
+    translator: Option<tauri::async_runtime::JoinHandle<()>>,
 }
 
 impl Session {
@@ -43,7 +52,9 @@ impl Session {
             speaker_muted_rx: None,
             silence_stream_tx: None,
             tasks: None,
+            translator: None,
             session_state_tx: None,
+            timeline: None,
         }
     }
 
@@ -67,7 +78,7 @@ impl Session {
         let session_id = id.into();
         self.session_id = Some(session_id.clone());
 
-        let (record, language, jargons) = {
+        let (record, language, detect_language, translation_language) = {
             let config = self.app.db_get_config(&user_id).await?;
 
             let record = config
@@ -79,11 +90,27 @@ impl Session {
                 |c| c.general.display_language.clone(),
             );
 
-            let jargons = config.map_or_else(Vec::new, |c| c.general.jargons);
+            // The display language still picks sentence boundaries when the spoken one is detected.
+            let detect_language = config
+                .as_ref()
+                .is_some_and(|c| c.general.detect_language.unwrap_or(false));
+
+            let translation_language = config
+                .as_ref()
+                .and_then(|c| c.general.translation_language.as_deref())
+                .and_then(|code| hypr_language::ISO639::from_str(code).ok())
+                .map(hypr_language::Language::from);
 
-            (record, language, jargons)
+            (record, language, detect_language, translation_language)
         };
 
+        let vocabulary = self
+            .app
+            .db_handle()
+            .await?
+            .vocabulary_terms(&user_id, Some(session_id.clone()))
+            .await?;
+
         let session = self
             .app
             .db_get_session(&session_id)
@@ -103,7 +130,17 @@ impl Session {
         self.speaker_muted_rx = Some(speaker_muted_rx_main.clone());
         self.session_state_tx = Some(session_state_tx);
 
-        let listen_client = setup_listen_client(&self.app, language, jargons).await?;
+        let timeline_config = hypr_timeline::TimelineConfig {
+            sentence_boundary: hypr_timeline::SentenceBoundary::for_language(language.code()),
+            ..Default::default()
+        };
+
+        let listen_client = setup_listen_client(
+            &self.app,
+            (!detect_language).then_some(language),
+            vocabulary,
+        )
+        .await?;
 
         let mic_sample_stream = {
             let mut input = hypr_audio::AudioInput::from_mic();
@@ -268,12 +305,43 @@ impl Session {
             });
         }
 
-        let timeline = Arc::new(Mutex::new(initialize_timeline(&session).await));
+        let timeline = Arc::new(Mutex::new(
+            initialize_timeline(&session, timeline_config).await,
+        ));
+        self.timeline = Some(timeline.clone());
         let audio_stream = hypr_audio::ReceiverStreamSource::new(process_rx, SAMPLE_RATE);
 
         let listen_stream = listen_client.from_audio(audio_stream).await?;
         let channels = self.channels.clone();
 
+        let translator = match translation_language {
+            Some(target) => match crate::translate::Translator::new(&self.app, target).await {
+                Ok(translator) => Some(translator),
+                Err(e) => {
+                    tracing::warn!("translation_disabled: {}", e);
+                    None
+                }
+            },
+            None => None,
+        };
+
+        let translate_tx = translator.map(|translator| {
+            let (translate_tx, translate_rx) = mpsc::unbounded_channel();
+
+            // Not part of `tasks`, so stopping the session doesn't abort it. It translates whatever is
+            // still batched once the listen task drops the sender, and teardown waits for that.
+            self.translator = Some(tauri::async_runtime::spawn(translate_transcripts(
+                self.app.clone(),
+                session.id.clone(),
+                translator,
+                translate_rx,
+                timeline.clone(),
+                channels.clone(),
+            )));
+
+            translate_tx
+        });
+
         tasks.spawn({
             let app = self.app.clone();
             let timeline = timeline.clone();
@@ -286,7 +354,16 @@ impl Session {
                     let mut timeline = timeline.lock().await;
 
                     for t in result.transcripts {
-                        update_session(&app, &session.id, t.clone()).await.unwrap();
+                        // Interim transcripts are only shown live, until a final one replaces them.
+                        if t.is_final && !t.text.trim().is_empty() {
+                            update_session(&app, &session.id, vec![t.clone()], vec![])
+                                .await
+                                .unwrap();
+
+                            if let Some(tx) = &translate_tx {
+                                let _ = tx.send(t.clone());
+                            }
+                        }
                         timeline.add_transcription(t);
                     }
 
@@ -294,11 +371,14 @@ impl Session {
                         timeline.add_diarization(d);
                     }
 
+                    let diffs = timeline.take_diffs();
+                    if diffs.is_empty() {
+                        continue;
+                    }
+
                     Session::broadcast(
                         &channels,
-                        SessionEvent::TimelineView(SessionEventTimelineView {
-                            timeline: timeline.view(TimelineFilter::default()),
-                        }),
+                        SessionEvent::TimelineDiff(SessionEventTimelineDiff { diffs }),
                     )
                     .await
                     .unwrap();
@@ -329,6 +409,7 @@ impl Session {
     #[tracing::instrument(skip_all)]
     async fn teardown_resources(&mut self) {
         self.session_id = None;
+        self.timeline = None;
 
         if let Some(tx) = self.silence_stream_tx.take() {
             let _ = tx.send(());
@@ -341,6 +422,18 @@ impl Session {
             }
         }
 
+        // With the listen task gone, the translator only drains what is left. It has to finish before
+        // the session is handed back, since the note is written to the same row right after.
+        if let Some(mut translator) = self.translator.take() {
+            if tokio::time::timeout(TRANSLATION_DRAIN_TIMEOUT, &mut translator)
+                .await
+                .is_err()
+            {
+                tracing::warn!("translation_drain_timeout");
+                translator.abort();
+            }
+        }
+
         let mut channels = self.channels.lock().await;
         channels.clear();
     }
@@ -362,8 +455,8 @@ impl Session {
 
 async fn setup_listen_client<R: tauri::Runtime>(
     app: &tauri::AppHandle<R>,
-    language: hypr_language::Language,
-    jargons: Vec<String>,
+    language: Option<hypr_language::Language>,
+    vocabulary: Vec<hypr_listener_interface::VocabularyTerm>,
 ) -> Result<crate::client::ListenClient, crate::Error> {
     let api_base = {
         use tauri_plugin_connector::{Connection, ConnectorPluginExt};
@@ -380,11 +473,12 @@ async fn setup_listen_client<R: tauri::Runtime>(
 
     tracing::info!(api_base = ?api_base, api_key = ?api_key, language = ?language, "listen_client");
 
+    // Vocabulary is sent on its own, so that each engine can boost it natively.
     let static_prompt = format!(
-        "{} / {}:",
-        jargons.join(", "),
+        "{}:",
         language
-            .text_transcript()
+            .as_ref()
+            .and_then(|l| l.text_transcript().ok())
             .unwrap_or("transcript".to_string())
     );
 
@@ -394,13 +488,18 @@ async fn setup_listen_client<R: tauri::Runtime>(
         .params(hypr_listener_interface::ListenParams {
             language,
             static_prompt,
+            vocabulary,
+            interim_results: true,
             ..Default::default()
         })
         .build())
 }
 
-async fn initialize_timeline(session: &hypr_db_user::Session) -> Timeline {
-    let mut timeline = Timeline::default();
+async fn initialize_timeline(
+    session: &hypr_db_user::Session,
+    config: hypr_timeline::TimelineConfig,
+) -> Timeline {
+    let mut timeline = Timeline::new(config);
 
     for conversation in &session.conversations {
         for t in &conversation.transcripts {
@@ -409,15 +508,21 @@ async fn initialize_timeline(session: &hypr_db_user::Session) -> Timeline {
         for d in &conversation.diarizations {
             timeline.add_diarization(d.clone());
         }
+        for t in &conversation.translations {
+            timeline.add_translation(t.clone());
+        }
     }
 
+    // Brings the snapshot up to date with the stored history.
+    timeline.take_diffs();
     timeline
 }
 
 async fn update_session<R: tauri::Runtime>(
     app: &tauri::AppHandle<R>,
     session_id: impl Into<String>,
-    transcript: hypr_listener_interface::TranscriptChunk,
+    transcripts: Vec<hypr_listener_interface::TranscriptChunk>,
+    translations: Vec<hypr_listener_interface::TranscriptChunk>,
 ) -> Result<(), crate::Error> {
     use tauri_plugin_db::DatabasePluginExt;
 
@@ -429,17 +534,73 @@ async fn update_session<R: tauri::Runtime>(
         .ok_or(crate::Error::NoneSession)?;
 
     session.conversations.push(hypr_db_user::ConversationChunk {
-        transcripts: vec![transcript],
+        transcripts,
         diarizations: vec![],
+        translations,
         start: chrono::Utc::now(),
         end: chrono::Utc::now(),
     });
 
-    app.db_upsert_session(session.clone()).await.unwrap();
+    app.db_upsert_session(session).await?;
 
     Ok(())
 }
 
+// Translates finalized transcripts sentence by sentence, until the listen task drops its sender.
+async fn translate_transcripts<R: tauri::Runtime>(
+    app: tauri::AppHandle<R>,
+    session_id: String,
+    translator: crate::translate::Translator,
+    mut rx: mpsc::UnboundedReceiver<hypr_listener_interface::TranscriptChunk>,
+    timeline: Arc<Mutex<Timeline>>,
+    channels: Arc<Mutex<HashMap<u32, Channel<SessionEvent>>>>,
+) {
+    let mut batch = crate::translate::TranslationBatch::default();
+    let mut context = String::new();
+
+    loop {
+        let ready = match tokio::time::timeout(TRANSLATION_FLUSH_AFTER, rx.recv()).await {
+            Ok(Some(chunk)) => batch.push(chunk),
+            // A pause in speech ends the sentence too.
+            Err(_) => batch.flush(),
+            Ok(None) => match batch.flush() {
+                Some(chunk) => Some(chunk),
+                None => break,
+            },
+        };
+
+        let Some(chunk) = ready.filter(|chunk| translator.needs_translation(chunk)) else {
+            continue;
+        };
+
+        let translation = match translator.translate(&chunk, &context).await {
+            Ok(translation) if !translation.text.is_empty() => translation,
+            Ok(_) => continue,
+            Err(e) => {
+                tracing::warn!("translation_failed: {}", e);
+                continue;
+            }
+        };
+        context = translation.text.clone();
+
+        // Holding the timeline also keeps this upsert from racing the transcript ones.
+        let mut timeline = timeline.lock().await;
+        if let Err(e) = update_session(&app, &session_id, vec![], vec![translation.clone()]).await {
+            tracing::error!("failed_to_save_translation: {}", e);
+        }
+        timeline.add_translation(translation);
+
+        let diffs = timeline.take_diffs();
+        if !diffs.is_empty() {
+            let _ = Session::broadcast(
+                &channels,
+                SessionEvent::TimelineDiff(SessionEventTimelineDiff { diffs }),
+            )
+            .await;
+        }
+    }
+}
+
 pub enum StateEvent {
     Start(String),
     Stop,
@@ -461,6 +622,19 @@ impl Session {
     async fn common(&mut self, event: &StateEvent) -> Response<State> {
         match event {
             StateEvent::Subscribe(channel) => {
+                // Later updates only carry diffs, so new subscribers start from the full view.
+                // The timeline stays locked until the channel is registered, so no diff falls in between.
+                let timeline = match &self.timeline {
+                    Some(timeline) => Some(timeline.lock().await),
+                    None => None,
+                };
+
+                if let Some(timeline) = &timeline {
+                    let _ = channel.send(SessionEvent::TimelineView(SessionEventTimelineView {
+                        timeline: timeline.snapshot(),
+                    }));
+                }
+
                 let mut channels = self.channels.lock().await;
                 channels.insert(channel.id(), channel.clone());
                 Handled
@@ -547,8 +721,22 @@ impl Session {
             let _ = self.app.set_start_disabled(false);
         }
 
+        let session_id = self.session_id.clone();
         self.teardown_resources().await;
 
+        // Match the new speakers against enrolled voices once the recording is complete.
+        if let Some(session_id) = session_id {
+            let app = self.app.clone();
+            tauri::async_runtime::spawn(async move {
+                use crate::ListenerPluginExt;
+
+                match app.suggest_speakers(session_id).await {
+                    Ok(_) | Err(crate::Error::NoneVoiceModel) => {}
+                    Err(e) => tracing::error!("suggest_speakers_error: {}", e),
+                }
+            });
+        }
+
         Session::broadcast(&self.channels, SessionEvent::Stopped)
             .await
             .unwrap();
@@ -8,6 +8,8 @@ mod error;
 mod events;
 mod ext;
 mod fsm;
+mod translate;
+mod voice;
 
 pub use client::*;
 pub use error::*;
@@ -22,6 +24,7 @@ pub type SharedState = Mutex<State>;
 
 pub struct State {
     fsm: statig::awaitable::StateMachine<fsm::Session>,
+    voice_download: Option<tokio::task::JoinHandle<()>>,
 }
 
 fn make_specta_builder<R: tauri::Runtime>() -> tauri_specta::Builder<R> {
@@ -46,6 +49,11 @@ fn make_specta_builder<R: tauri::Runtime>() -> tauri_specta::Builder<R> {
             commands::pause_session::<tauri::Wry>,
             commands::resume_session::<tauri::Wry>,
             commands::get_state::<tauri::Wry>,
+            commands::enroll_speaker::<tauri::Wry>,
+            commands::suggest_speakers::<tauri::Wry>,
+            commands::is_voice_model_downloaded::<tauri::Wry>,
+            commands::is_voice_model_downloading::<tauri::Wry>,
+            commands::download_voice_model::<tauri::Wry>,
         ])
         .events(tauri_specta::collect_events![StatusEvent])
         .error_handling(tauri_specta::ErrorHandlingMode::Throw)
@@ -61,7 +69,10 @@ pub fn init() -> tauri::plugin::TauriPlugin<tauri::Wry> {
 
             let handle = app.app_handle();
             let fsm = fsm::Session::new(handle.clone()).state_machine();
-            let state: SharedState = Mutex::new(State { fsm });
+            let state: SharedState = Mutex::new(State {
+                fsm,
+                voice_download: None,
+            });
             app.manage(state);
             Ok(())
         })
@@ -0,0 +1,187 @@
+use hypr_listener_interface::TranscriptChunk;
+use hypr_openai::{
+    ChatCompletionRequestMessage, ChatCompletionRequestSystemMessage,
+    ChatCompletionRequestSystemMessageContent, ChatCompletionRequestUserMessage,
+    ChatCompletionRequestUserMessageContent, CreateChatCompletionRequest,
+    CreateChatCompletionResponse,
+};
+
+const DEFAULT_MODEL: &str = "gpt-4";
+
+// Batches longer than this are translated even without a sentence end.
+const MAX_BATCH_CHARS: usize = 300;
+const SENTENCE_ENDS: [char; 7] = ['.', '?', '!', '。', '？', '！', '…'];
+
+pub struct Translator {
+    client: hypr_openai::OpenAIClient,
+    model: String,
+    env: hypr_template::minijinja::Environment<'static>,
+    target: hypr_language::Language,
+}
+
+impl Translator {
+    pub async fn new<R: tauri::Runtime>(
+        app: &tauri::AppHandle<R>,
+        target: hypr_language::Language,
+    ) -> Result<Self, crate::Error> {
+        use tauri_plugin_connector::{ConnectionLLM, ConnectorPluginExt};
+
+        let conn = app.get_llm_connection().await?;
+        let model = match &conn {
+            ConnectionLLM::Custom(_) => app
+                .get_custom_llm_model()?
+                .unwrap_or(DEFAULT_MODEL.to_string()),
+            _ => DEFAULT_MODEL.to_string(),
+        };
+
+        let conn: tauri_plugin_connector::Connection = conn.into();
+        let client = hypr_openai::OpenAIClient::builder()
+            .api_base(conn.api_base)
+            .api_key(conn.api_key.unwrap_or_default())
+            .build();
+
+        let mut env = hypr_template::minijinja::Environment::new();
+        hypr_template::init(&mut env);
+
+        Ok(Self {
+            client,
+            model,
+            env,
+            target,
+        })
+    }
+
+    // Chunks already spoken in the target language are left alone.
+    pub fn needs_translation(&self, chunk: &TranscriptChunk) -> bool {
+        chunk.language.as_deref() != Some(self.target.iso639().code())
+            && !chunk.text.trim().is_empty()
+    }
+
+    // `context` is the previously translated text, passed along for names and pronouns.
+    pub async fn translate(
+        &self,
+        chunk: &TranscriptChunk,
+        context: &str,
+    ) -> Result<TranscriptChunk, crate::Error> {
+        let ctx = serde_json::json!({
+            "target_language": self.target.iso639().code(),
+            "text": chunk.text.trim(),
+            "context": context,
+        });
+        let ctx = ctx.as_object().unwrap();
+
+        let system = hypr_template::render(
+            &self.env,
+            hypr_template::PredefinedTemplate::TranslateSystem.into(),
+            ctx,
+        )
+        .map_err(|e| crate::Error::TranslationFailed(e.to_string()))?;
+        let user = hypr_template::render(
+            &self.env,
+            hypr_template::PredefinedTemplate::TranslateUser.into(),
+            ctx,
+        )
+        .map_err(|e| crate::Error::TranslationFailed(e.to_string()))?;
+
+        let request = CreateChatCompletionRequest {
+            model: self.model.clone(),
+            messages: vec![
+                ChatCompletionRequestMessage::System(ChatCompletionRequestSystemMessage {
+                    content: ChatCompletionRequestSystemMessageContent::Text(system),
+                    ..Default::default()
+                }),
+                ChatCompletionRequestMessage::User(ChatCompletionRequestUserMessage {
+                    content: ChatCompletionRequestUserMessageContent::Text(user),
+                    ..Default::default()
+                }),
+            ],
+            stream: Some(false),
+            ..Default::default()
+        };
+
+        let response = self
+            .client
+            .chat_completion(&request)
+            .await
+            .map_err(|e| crate::Error::TranslationFailed(e.to_string()))?
+            .error_for_status()
+            .map_err(|e| crate::Error::TranslationFailed(e.to_string()))?
+            .json::<CreateChatCompletionResponse>()
+            .await
+            .map_err(|e| crate::Error::TranslationFailed(e.to_string()))?;
+
+        let text = response
+            .choices
+            .into_iter()
+            .next()
+            .and_then(|choice| choice.message.content)
+            .unwrap_or_default();
+
+        Ok(TranscriptChunk {
+            text: text.trim().to_string(),
+            confidence: None,
+            language: Some(self.target.iso639().code().to_string()),
+            ..chunk.clone()
+        })
+    }
+}
+
+// Collects finalized chunks into sentences, which translate far better than single words.
+#[derive(Default)]
+pub struct TranslationBatch {
+    pending: Option<TranscriptChunk>,
+}
+
+impl TranslationBatch {
+    // Returns the batch once `chunk` completes a sentence.
+    pub fn push(&mut self, chunk: TranscriptChunk) -> Option<TranscriptChunk> {
+        let pending = match self.pending.take() {
+            Some(mut pending) => {
+                pending.end = chunk.end;
+                pending.text = format!("{} {}", pending.text.trim_end(), chunk.text.trim());
+                pending
+            }
+            None => chunk,
+        };
+
+        let text = pending.text.trim_end();
+        if text.ends_with(SENTENCE_ENDS) || text.chars().count() >= MAX_BATCH_CHARS {
+            return Some(pending);
+        }
+
+        self.pending = Some(pending);
+        None
+    }
+
+    pub fn flush(&mut self) -> Option<TranscriptChunk> {
+        self.pending.take()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_translation_batch() {
+        let chunk = |start, end, text: &str| TranscriptChunk {
+            start,
+            end,
+            text: text.to_string(),
+            confidence: None,
+            is_final: true,
+            revision: None,
+            language: None,
+        };
+
+        let mut batch = TranslationBatch::default();
+        assert!(batch.push(chunk(0, 300, "Hello")).is_none());
+        let sentence = batch.push(chunk(300, 800, " there.")).unwrap();
+        assert_eq!((sentence.start, sentence.end), (0, 800));
+        assert_eq!(sentence.text, "Hello there.");
+
+        assert!(batch.push(chunk(1000, 1500, "오늘 회의는")).is_none());
+        assert_eq!(batch.flush().unwrap().text, "오늘 회의는");
+        assert!(batch.flush().is_none());
+    }
+}
@@ -0,0 +1,199 @@
+use tauri::Manager;
+
+use hypr_db_user::{UserDatabase, VoiceEnrollment, VoiceSuggestion, VoiceSuggestionStatus};
+
+// Enrollments made with a different model are ignored, since their embeddings are not comparable.
+pub const MODEL_NAME: &str = "speaker_embedding";
+pub const MODEL_URL: &str =
+    "https://pub-8987485129c64debb63bff7f35a2e5fd.r2.dev/v0/hyprnote/speaker-embedding/main/speaker_embedding.onnx";
+
+// Where the model lives once downloaded, whether or not it is there yet.
+pub fn model_file<R: tauri::Runtime>(
+    app: &impl Manager<R>,
+) -> Result<std::path::PathBuf, crate::Error> {
+    Ok(app
+        .path()
+        .app_data_dir()
+        .map_err(|_| crate::Error::NoneVoiceModel)?
+        .join(format!("{}.onnx", MODEL_NAME)))
+}
+
+pub fn model_path<R: tauri::Runtime>(
+    app: &impl Manager<R>,
+) -> Result<std::path::PathBuf, crate::Error> {
+    let path = model_file(app)?;
+
+    if path.exists() {
+        Ok(path)
+    } else {
+        Err(crate::Error::NoneVoiceModel)
+    }
+}
+
+pub fn recording_path<R: tauri::Runtime>(
+    app: &impl Manager<R>,
+    session_id: &str,
+) -> Result<std::path::PathBuf, crate::Error> {
+    let path = app
+        .path()
+        .app_data_dir()
+        .map_err(|_| crate::Error::NoneRecording)?
+        .join(session_id)
+        .join("audio.wav");
+
+    if path.exists() {
+        Ok(path)
+    } else {
+        Err(crate::Error::NoneRecording)
+    }
+}
+
+// The key of the unlocked app, if the encryption plugin is loaded and unlocked.
+pub fn encryption_key<R: tauri::Runtime>(app: &impl Manager<R>) -> Option<[u8; 32]> {
+    let state = app.try_state::<tauri_plugin_encryption::EncryptionState>()?;
+    let key_manager = state.key_manager.lock().ok()?;
+    key_manager.get_key().ok()
+}
+
+// Per-speaker embeddings of a recording, keyed by the index shown in the timeline.
+pub async fn embed_speakers(
+    db: &UserDatabase,
+    session_id: &str,
+    recording: std::path::PathBuf,
+    model: std::path::PathBuf,
+    speakers: Option<Vec<i32>>,
+) -> Result<Vec<(i32, Vec<f32>)>, crate::Error> {
+    let remap = db.session_speaker_remap(session_id).await?;
+    let diarizations = db
+        .get_session(hypr_db_user::GetSessionFilter::Id(session_id.to_string()))
+        .await?
+        .ok_or(crate::Error::NoneSession)?
+        .conversations
+        .into_iter()
+        .flat_map(|c| c.diarizations)
+        .map(|d| hypr_listener_interface::DiarizationChunk {
+            speaker: remap.get(&d.speaker).copied().unwrap_or(d.speaker),
+            ..d
+        })
+        .collect::<Vec<_>>();
+
+    let mut speakers = speakers.unwrap_or_else(|| remap.values().copied().collect());
+    speakers.sort();
+    speakers.dedup();
+
+    tokio::task::spawn_blocking(move || {
+        // Both channels of the recording carry the same mix.
+        let samples = hound::WavReader::open(recording)?
+            .into_samples::<f32>()
+            .step_by(2)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let embedder = hypr_voice::SpeakerEmbedder::from_file(model)?;
+
+        let mut embeddings = Vec::new();
+        for speaker in speakers {
+            let audio = hypr_voice::speaker_audio(&samples, &diarizations, speaker);
+            if audio.is_empty() {
+                continue;
+            }
+            embeddings.push((speaker, embedder.embed(&audio)?));
+        }
+
+        Ok(embeddings)
+    })
+    .await?
+}
+
+// Key to seal new enrollments with. Once encryption is set up, nothing is stored until the app is unlocked.
+pub fn sealing_key<R: tauri::Runtime>(
+    app: &impl Manager<R>,
+) -> Result<Option<[u8; 32]>, crate::Error> {
+    if let Some(key) = encryption_key(app) {
+        return Ok(Some(key));
+    }
+
+    if app
+        .try_state::<tauri_plugin_encryption::EncryptionState>()
+        .is_none()
+    {
+        return Ok(None);
+    }
+
+    match tauri_plugin_encryption::EncryptionPluginExt::load_salt(app) {
+        Ok(None) => Ok(None),
+        _ => Err(crate::Error::AppLocked),
+    }
+}
+
+pub fn seal(embedding: &[f32], key: Option<[u8; 32]>) -> Result<(Vec<u8>, bool), crate::Error> {
+    let bytes = hypr_voice::to_bytes(embedding);
+
+    match key {
+        Some(key) => Ok((hypr_encryption::encrypt_bytes(&key, &bytes)?, true)),
+        None => Ok((bytes, false)),
+    }
+}
+
+// Enrolled voices grouped per participant. Encrypted ones are skipped while the app is locked.
+pub fn enrolled_voices(
+    enrollments: Vec<VoiceEnrollment>,
+    key: Option<[u8; 32]>,
+) -> Vec<(String, Vec<f32>)> {
+    let mut grouped: std::collections::BTreeMap<String, Vec<Vec<f32>>> = Default::default();
+
+    for enrollment in enrollments {
+        let bytes = match (enrollment.encrypted, key) {
+            (false, _) => enrollment.embedding,
+            (true, Some(key)) => {
+                match hypr_encryption::decrypt_bytes(&key, &enrollment.embedding) {
+                    Ok(bytes) => bytes,
+                    Err(e) => {
+                        tracing::warn!("voice_enrollment_decrypt_error: {}", e);
+                        continue;
+                    }
+                }
+            }
+            (true, None) => continue,
+        };
+
+        grouped
+            .entry(enrollment.human_id)
+            .or_default()
+            .push(hypr_voice::from_bytes(&bytes));
+    }
+
+    grouped
+        .into_iter()
+        .filter_map(|(human_id, voices)| {
+            let centroid = hypr_voice::centroid(voices.iter().map(Vec::as_slice))?;
+            Some((human_id, centroid))
+        })
+        .collect()
+}
+
+pub fn new_enrollment(human_id: String, embedding: Vec<u8>, encrypted: bool) -> VoiceEnrollment {
+    VoiceEnrollment {
+        id: uuid::Uuid::new_v4().to_string(),
+        human_id,
+        embedding,
+        encrypted,
+        model: MODEL_NAME.to_string(),
+        created_at: chrono::Utc::now(),
+    }
+}
+
+pub fn new_suggestion(
+    session_id: &str,
+    speaker: i32,
+    m: hypr_voice::VoiceMatch,
+) -> VoiceSuggestion {
+    VoiceSuggestion {
+        id: uuid::Uuid::new_v4().to_string(),
+        session_id: session_id.to_string(),
+        speaker,
+        human_id: m.human_id,
+        score: m.score,
+        status: VoiceSuggestionStatus::Pending,
+        created_at: chrono::Utc::now(),
+    }
+}
@@ -5,6 +5,8 @@ const COMMANDS: &[&str] = &[
     "download_model",
     "start_server",
     "stop_server",
+    "get_current_model",
+    "context_length",
     "list_ollama_models",
 ];
 
@@ -25,6 +25,12 @@ async startServer() : Promise<string> {
 async stopServer() : Promise<null> {
     return await TAURI_INVOKE("plugin:local-llm|stop_server");
 },
+async getCurrentModel() : Promise<SupportedModel> {
+    return await TAURI_INVOKE("plugin:local-llm|get_current_model");
+},
+async contextLength() : Promise<number> {
+    return await TAURI_INVOKE("plugin:local-llm|context_length");
+},
 async listOllamaModels() : Promise<string[]> {
     return await TAURI_INVOKE("plugin:local-llm|list_ollama_models");
 }
@@ -40,6 +46,7 @@ async listOllamaModels() : Promise<string[]> {
 
 /** user-defined types **/
 
+export type SupportedModel = "Llama3p2_3bQ4"
 export type TAURI_CHANNEL<TSend> = null
 
 /** tauri-specta globals **/
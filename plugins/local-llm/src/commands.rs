@@ -46,6 +46,20 @@ pub async fn stop_server<R: tauri::Runtime>(app: tauri::AppHandle<R>) -> Result<
     app.stop_server().await.map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+#[specta::specta]
+pub async fn get_current_model<R: tauri::Runtime>(
+    app: tauri::AppHandle<R>,
+) -> Result<crate::SupportedModel, String> {
+    app.current_model().await.map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn context_length<R: tauri::Runtime>(app: tauri::AppHandle<R>) -> Result<u32, String> {
+    app.context_length().await.map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 #[specta::specta]
 pub async fn list_ollama_models<R: tauri::Runtime>(
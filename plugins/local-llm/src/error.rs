@@ -14,6 +14,14 @@ pub enum Error {
     IoError(#[from] std::io::Error),
     #[error(transparent)]
     StoreError(#[from] tauri_plugin_store2::Error),
+    #[error(transparent)]
+    GbnfError(#[from] hypr_gbnf::Error),
+    #[error("unknown grammar: {0}")]
+    UnknownGrammar(String),
+    #[error("unknown tool: {0}")]
+    UnknownTool(String),
+    #[error("seed must fit in a u32: {0}")]
+    InvalidSeed(i64),
     #[error("Model not downloaded")]
     ModelNotDownloaded,
 }
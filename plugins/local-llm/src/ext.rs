@@ -17,6 +17,7 @@ pub trait LocalLlmPluginExt<R: Runtime> {
     ) -> impl Future<Output = Result<(), crate::Error>>;
     fn start_server(&self) -> impl Future<Output = Result<String, crate::Error>>;
     fn stop_server(&self) -> impl Future<Output = Result<(), crate::Error>>;
+    fn context_length(&self) -> impl Future<Output = Result<u32, crate::Error>>;
 }
 
 impl<R: Runtime, T: Manager<R>> LocalLlmPluginExt<R> for T {
@@ -118,9 +119,10 @@ impl<R: Runtime, T: Manager<R>> LocalLlmPluginExt<R> for T {
             return Err(crate::Error::ModelNotDownloaded);
         }
 
+        let model = self.current_model().await?;
         let model_manager = {
             let s = state.lock().await;
-            crate::ModelManager::new(s.model_path.clone())
+            crate::ModelManager::new(s.model_path.clone(), model.as_ref())
         };
 
         let server = crate::server::run_server(model_manager).await?;
@@ -142,4 +144,17 @@ impl<R: Runtime, T: Manager<R>> LocalLlmPluginExt<R> for T {
         }
         Ok(())
     }
+
+    // Read from the model file, so it doesn't have to be loaded.
+    #[tracing::instrument(skip_all)]
+    async fn context_length(&self) -> Result<u32, crate::Error> {
+        let state = self.state::<crate::SharedState>();
+        let model_path = state.lock().await.model_path.clone();
+
+        if !self.is_model_downloaded().await? {
+            return Err(crate::Error::ModelNotDownloaded);
+        }
+
+        Ok(hypr_llama::context_length(model_path)?)
+    }
 }
@@ -50,6 +50,8 @@ fn make_specta_builder<R: tauri::Runtime>() -> tauri_specta::Builder<R> {
             commands::download_model::<Wry>,
             commands::start_server::<Wry>,
             commands::stop_server::<Wry>,
+            commands::get_current_model::<Wry>,
+            commands::context_length::<Wry>,
             commands::list_ollama_models::<Wry>,
         ])
         .error_handling(tauri_specta::ErrorHandlingMode::Throw)
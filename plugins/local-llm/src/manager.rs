@@ -4,6 +4,7 @@ use tokio::sync::{watch, Mutex};
 #[derive(Clone)]
 pub struct ModelManager {
     model_path: std::path::PathBuf,
+    model_id: String,
     model: Arc<Mutex<Option<Arc<hypr_llama::Llama>>>>,
     last_activity: Arc<Mutex<Option<tokio::time::Instant>>>,
     _drop_guard: Arc<DropGuard>,
@@ -20,11 +21,12 @@ impl Drop for DropGuard {
 }
 
 impl ModelManager {
-    pub fn new(model_path: impl Into<std::path::PathBuf>) -> Self {
+    pub fn new(model_path: impl Into<std::path::PathBuf>, model_id: impl Into<String>) -> Self {
         let (shutdown_tx, shutdown_rx) = watch::channel(());
 
         let manager = Self {
             model_path: model_path.into(),
+            model_id: model_id.into(),
             model: Arc::new(tokio::sync::Mutex::new(None)),
             last_activity: Arc::new(tokio::sync::Mutex::new(None)),
             _drop_guard: Arc::new(DropGuard { shutdown_tx }),
@@ -34,6 +36,11 @@ impl ModelManager {
         manager
     }
 
+    // The `SupportedModel` at `model_path`.
+    pub fn model_id(&self) -> &str {
+        &self.model_id
+    }
+
     pub async fn update_activity(&self) {
         *self.last_activity.lock().await = Some(tokio::time::Instant::now());
     }
@@ -1,6 +1,7 @@
 pub static SUPPORTED_MODELS: &[SupportedModel; 1] = &[SupportedModel::Llama3p2_3bQ4];
 
-#[derive(serde::Serialize, serde::Deserialize, specta::Type)]
+// Also the key that embeddings from the model are stored under.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, specta::Type, strum::AsRefStr)]
 pub enum SupportedModel {
     Llama3p2_3bQ4,
 }
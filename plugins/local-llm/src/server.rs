@@ -1,5 +1,6 @@
 use std::net::{Ipv4Addr, SocketAddr};
 use std::pin::Pin;
+use std::time::Duration;
 
 use axum::{
     extract::State as AxumState,
@@ -12,11 +13,38 @@ use futures_util::StreamExt;
 use tower_http::cors::{self, CorsLayer};
 
 use async_openai::types::{
-    ChatChoice, ChatChoiceStream, ChatCompletionResponseMessage, ChatCompletionStreamResponseDelta,
-    CreateChatCompletionRequest, CreateChatCompletionResponse, CreateChatCompletionStreamResponse,
-    Role,
+    ChatChoice, ChatChoiceStream, ChatCompletionMessageToolCall,
+    ChatCompletionMessageToolCallChunk, ChatCompletionResponseMessage,
+    ChatCompletionStreamResponseDelta, ChatCompletionTool, ChatCompletionToolChoiceOption,
+    ChatCompletionToolType, CreateChatCompletionRequest, CreateChatCompletionResponse,
+    CreateChatCompletionStreamResponse, CreateEmbeddingRequest, CreateEmbeddingResponse, Embedding,
+    EmbeddingInput, EmbeddingUsage, FinishReason, FunctionCall, FunctionCallStream, ResponseFormat,
+    Role, Stop,
 };
 
+// Includes time spent waiting for the model behind other requests.
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+// Set when the prompt didn't fit the model's context window, to how many tokens of it were left out.
+const TRUNCATED_TOKENS_HEADER: &str = "x-hypr-truncated-tokens";
+
+type CompletionStream =
+    Pin<Box<dyn futures_util::Stream<Item = Result<String, hypr_llama::Error>> + Send>>;
+
+// OpenAI's request, plus the llama.cpp options it has no field for.
+#[derive(serde::Deserialize)]
+pub struct ChatCompletionRequest {
+    #[serde(flatten)]
+    pub inner: CreateChatCompletionRequest,
+    pub top_k: Option<i32>,
+    pub min_p: Option<f32>,
+    // A name from the `hypr_gbnf::GBNF` registry, or GBNF source.
+    pub grammar: Option<String>,
+    // Section titles of the note template, for the `enhance-template` grammar.
+    pub sections: Option<Vec<String>>,
+    // In seconds.
+    pub timeout: Option<u64>,
+}
+
 #[derive(Clone)]
 pub struct ServerHandle {
     pub addr: SocketAddr,
@@ -33,12 +61,14 @@ pub async fn run_server(model_manager: crate::ModelManager) -> Result<ServerHand
     let app = Router::new()
         .route("/health", get(health))
         .route("/chat/completions", post(chat_completions))
+        .route("/embeddings", post(embeddings))
         .with_state(model_manager)
         .layer(
             CorsLayer::new()
                 .allow_origin(cors::Any)
                 .allow_methods(cors::Any)
-                .allow_headers(cors::Any),
+                .allow_headers(cors::Any)
+                .expose_headers([axum::http::HeaderName::from_static(TRUNCATED_TOKENS_HEADER)]),
         );
 
     let listener =
@@ -75,24 +105,93 @@ async fn health(AxumState(model_manager): AxumState<crate::ModelManager>) -> imp
 
 async fn chat_completions(
     AxumState(model_manager): AxumState<crate::ModelManager>,
-    Json(request): Json<CreateChatCompletionRequest>,
+    Json(request): Json<ChatCompletionRequest>,
 ) -> Result<Response, (StatusCode, String)> {
     let model = model_manager
         .get_model()
         .await
         .map_err(|e| (StatusCode::SERVICE_UNAVAILABLE, e.to_string()))?;
 
+    tracing::info!("local_llm_request queue_len={}", model.queue_len());
+
     let res = inference_with_hypr(&model, &request)
         .await
-        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+        .map_err(|e| match e {
+            crate::Error::GbnfError(_)
+            | crate::Error::UnknownGrammar(_)
+            | crate::Error::UnknownTool(_)
+            | crate::Error::InvalidSeed(_) => (StatusCode::BAD_REQUEST, e.to_string()),
+            crate::Error::HyprLlamaError(hypr_llama::Error::QueueFull) => {
+                (StatusCode::TOO_MANY_REQUESTS, e.to_string())
+            }
+            crate::Error::HyprLlamaError(hypr_llama::Error::Timeout) => {
+                (StatusCode::GATEWAY_TIMEOUT, e.to_string())
+            }
+            crate::Error::HyprLlamaError(
+                hypr_llama::Error::ContextLengthExceeded { .. }
+                | hypr_llama::Error::UnsupportedMessage(_)
+                | hypr_llama::Error::GbnfError(_),
+            ) => (StatusCode::BAD_REQUEST, e.to_string()),
+            _ => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+        })?;
 
     Ok(res.into_response())
 }
 
+// Embeds with the chat model, mean-pooled. Vectors from different models are not comparable, so
+// callers keep track of which model produced what they store: the response names the model that
+// is loaded, whatever the request asked for.
+async fn embeddings(
+    AxumState(model_manager): AxumState<crate::ModelManager>,
+    Json(request): Json<CreateEmbeddingRequest>,
+) -> Result<Json<CreateEmbeddingResponse>, (StatusCode, String)> {
+    let texts = match request.input {
+        EmbeddingInput::String(text) => vec![text],
+        EmbeddingInput::StringArray(texts) => texts,
+        _ => {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                "only text input is supported".to_string(),
+            ))
+        }
+    };
+
+    let model = model_manager
+        .get_model()
+        .await
+        .map_err(|e| (StatusCode::SERVICE_UNAVAILABLE, e.to_string()))?;
+
+    let embeddings = model.embed(texts).await.map_err(|e| match e {
+        hypr_llama::Error::QueueFull => (StatusCode::TOO_MANY_REQUESTS, e.to_string()),
+        _ => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    })?;
+
+    Ok(Json(CreateEmbeddingResponse {
+        object: "list".to_string(),
+        model: model_manager.model_id().to_string(),
+        data: embeddings
+            .into_iter()
+            .enumerate()
+            .map(|(index, embedding)| Embedding {
+                index: index as u32,
+                object: "embedding".to_string(),
+                embedding,
+            })
+            .collect(),
+        // Not counted.
+        usage: EmbeddingUsage {
+            prompt_tokens: 0,
+            total_tokens: 0,
+        },
+    }))
+}
+
 async fn inference_with_hypr(
     model: &hypr_llama::Llama,
-    request: &CreateChatCompletionRequest,
+    request: &ChatCompletionRequest,
 ) -> Result<impl IntoResponse, crate::Error> {
+    let ChatCompletionRequest { inner, .. } = request;
+
     #[allow(deprecated)]
     let empty_message = ChatCompletionResponseMessage {
         content: None,
@@ -117,7 +216,7 @@ async fn inference_with_hypr(
             .duration_since(std::time::UNIX_EPOCH)
             .unwrap()
             .as_secs() as u32,
-        model: request.model.clone(),
+        model: inner.model.clone(),
         service_tier: None,
         system_fingerprint: None,
         object: "chat.completion".to_string(),
@@ -144,76 +243,279 @@ async fn inference_with_hypr(
         refusal: None,
     };
 
-    let is_stream = request.stream.unwrap_or(false);
+    let is_stream = inner.stream.unwrap_or(false);
 
     if !is_stream {
-        let completion =
-            futures_util::StreamExt::collect::<String>(build_response(model, request)?).await;
+        let (stream, truncation) = build_response(model, request).await?;
 
-        let res = CreateChatCompletionResponse {
-            choices: vec![ChatChoice {
+        // Dropped along with this future when the client disconnects, which stops generation.
+        let completion = futures_util::TryStreamExt::try_collect::<String>(stream).await?;
+
+        let choice = match hypr_llama::parse_tool_call(&completion, &callable_tools(inner)) {
+            Some(call) => ChatChoice {
+                message: ChatCompletionResponseMessage {
+                    tool_calls: Some(vec![ChatCompletionMessageToolCall {
+                        id: call.id,
+                        r#type: ChatCompletionToolType::Function,
+                        function: FunctionCall {
+                            name: call.name,
+                            arguments: call.arguments,
+                        },
+                    }]),
+                    ..empty_message
+                },
+                finish_reason: Some(FinishReason::ToolCalls),
+                ..empty_choice
+            },
+            None => ChatChoice {
                 message: ChatCompletionResponseMessage {
                     content: Some(completion),
                     ..empty_message
                 },
                 ..empty_choice
-            }],
+            },
+        };
+
+        let res = CreateChatCompletionResponse {
+            choices: vec![choice],
             ..empty_response
         };
 
-        return Ok(Json(res).into_response());
+        return Ok(with_truncation(Json(res).into_response(), truncation));
     }
 
-    let res = if request.model == "mock-onboarding" {
-        build_mock_response()
+    let (res, truncation) = if inner.model == "mock-onboarding" {
+        (build_mock_response(), None)
     } else {
-        build_response(model, request)?
+        build_response(model, request).await?
     };
 
-    let stream = res
-        .map(move |chunk| CreateChatCompletionStreamResponse {
-            choices: vec![ChatChoiceStream {
-                index: 0,
-                delta: ChatCompletionStreamResponseDelta {
-                    content: Some(chunk),
+    let chunk = move |delta, finish_reason| CreateChatCompletionStreamResponse {
+        choices: vec![ChatChoiceStream {
+            index: 0,
+            delta,
+            finish_reason,
+            logprobs: None,
+        }],
+        ..empty_stream_response.clone()
+    };
+
+    // Errors end the stream, so they are reported in the body, the way OpenAI does once streaming has started.
+    let stream = with_tool_calls(res, callable_tools(inner)).flat_map(move |delta| {
+        let chunks = match delta {
+            Ok(Delta::Content(text)) => vec![serde_json::to_string(&chunk(
+                ChatCompletionStreamResponseDelta {
+                    content: Some(text),
                     ..empty_stream_response_delta.clone()
                 },
-                finish_reason: None,
-                logprobs: None,
-            }],
-            ..empty_stream_response.clone()
-        })
-        .map(|chunk| {
-            Ok::<_, std::convert::Infallible>(
-                sse::Event::default().data(serde_json::to_string(&chunk).unwrap()),
-            )
-        });
+                None,
+            ))],
+            // The whole call in one delta, then the finish reason, as OpenAI sends it last.
+            Ok(Delta::ToolCall(call)) => vec![
+                serde_json::to_string(&chunk(
+                    ChatCompletionStreamResponseDelta {
+                        tool_calls: Some(vec![ChatCompletionMessageToolCallChunk {
+                            index: 0,
+                            id: Some(call.id),
+                            r#type: Some(ChatCompletionToolType::Function),
+                            function: Some(FunctionCallStream {
+                                name: Some(call.name),
+                                arguments: Some(call.arguments),
+                            }),
+                        }]),
+                        role: Some(Role::Assistant),
+                        ..empty_stream_response_delta.clone()
+                    },
+                    None,
+                )),
+                serde_json::to_string(&chunk(
+                    empty_stream_response_delta.clone(),
+                    Some(FinishReason::ToolCalls),
+                )),
+            ],
+            Err(e) => vec![serde_json::to_string(&serde_json::json!({
+                "error": { "message": e.to_string() }
+            }))],
+        };
 
-    Ok(sse::Sse::new(stream).into_response())
+        futures_util::stream::iter(chunks.into_iter().map(|data| {
+            Ok::<_, std::convert::Infallible>(sse::Event::default().data(data.unwrap()))
+        }))
+    });
+
+    Ok(with_truncation(
+        sse::Sse::new(stream).into_response(),
+        truncation,
+    ))
 }
 
-fn build_response(
+fn with_truncation(mut response: Response, truncation: Option<hypr_llama::Truncation>) -> Response {
+    if let Some(truncation) = truncation {
+        let dropped = truncation.prompt_tokens - truncation.kept_tokens;
+        response
+            .headers_mut()
+            .insert(TRUNCATED_TOKENS_HEADER, dropped.into());
+    }
+    response
+}
+
+async fn build_response(
     model: &hypr_llama::Llama,
-    request: &CreateChatCompletionRequest,
-) -> Result<Pin<Box<dyn futures_util::Stream<Item = String> + Send>>, crate::Error> {
-    let messages = request
+    request: &ChatCompletionRequest,
+) -> Result<(CompletionStream, Option<hypr_llama::Truncation>), crate::Error> {
+    let ChatCompletionRequest {
+        inner,
+        top_k,
+        min_p,
+        timeout,
+        ..
+    } = request;
+
+    let messages = inner
         .messages
         .iter()
         .map(hypr_llama::FromOpenAI::from_openai)
-        .collect();
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if let Some(ChatCompletionToolChoiceOption::Named(named)) = &inner.tool_choice {
+        let tools = inner.tools.as_deref().unwrap_or_default();
+        if !tools.iter().any(|t| t.function.name == named.function.name) {
+            return Err(crate::Error::UnknownTool(named.function.name.clone()));
+        }
+    }
+
+    #[allow(deprecated)]
+    let max_tokens = inner.max_completion_tokens.or(inner.max_tokens);
 
     let request = hypr_llama::LlamaRequest {
         messages,
-        // TODO: should not hard-code this
-        grammar: Some(hypr_gbnf::GBNF::Enhance(Some(vec!["".to_string()])).build()),
+        grammar: select_grammar(request)?,
+        temperature: inner.temperature,
+        top_p: inner.top_p,
+        top_k: *top_k,
+        min_p: *min_p,
+        seed: inner
+            .seed
+            .map(|seed| u32::try_from(seed).map_err(|_| crate::Error::InvalidSeed(seed)))
+            .transpose()?,
+        max_tokens,
+        stop: match &inner.stop {
+            Some(Stop::String(stop)) => vec![stop.clone()],
+            Some(Stop::StringArray(stops)) => stops.clone(),
+            None => vec![],
+        },
+        frequency_penalty: inner.frequency_penalty,
+        presence_penalty: inner.presence_penalty,
+        response_format: inner.response_format.clone(),
+        tools: inner.tools.clone().unwrap_or_default(),
+        tool_choice: inner.tool_choice.clone(),
+        timeout: Some(
+            timeout
+                .map(Duration::from_secs)
+                .unwrap_or(DEFAULT_TIMEOUT)
+                .min(DEFAULT_TIMEOUT),
+        ),
     };
 
-    Ok(Box::pin(model.generate_stream(request)?))
+    let stream = model.generate_stream(request).await?;
+    let truncation = stream.truncation.clone();
+    Ok((Box::pin(stream), truncation))
+}
+
+// An explicit `grammar` wins over `response_format`. Requests with neither, like plain chat, are unconstrained.
+fn select_grammar(request: &ChatCompletionRequest) -> Result<Option<String>, crate::Error> {
+    if let Some(grammar) = &request.grammar {
+        return match hypr_gbnf::GBNF::from_name(grammar) {
+            Some(hypr_gbnf::GBNF::Enhance(Some(_))) => Ok(Some(
+                hypr_gbnf::GBNF::Enhance(Some(request.sections.clone().unwrap_or_default()))
+                    .build()?,
+            )),
+            Some(gbnf) => Ok(Some(gbnf.build()?)),
+            None if grammar.contains("::=") => Ok(Some(grammar.clone())),
+            None => Err(crate::Error::UnknownGrammar(grammar.clone())),
+        };
+    }
+
+    match &request.inner.response_format {
+        Some(ResponseFormat::JsonSchema { json_schema }) => match &json_schema.schema {
+            Some(schema) => Ok(Some(hypr_gbnf::json_schema::compile(schema)?)),
+            // Left to `hypr_llama`, which falls back to any JSON object.
+            None => Ok(None),
+        },
+        _ => Ok(None),
+    }
+}
+
+// Tools the model may call. With `tool_choice: none`, it isn't told about any.
+fn callable_tools(request: &CreateChatCompletionRequest) -> Vec<ChatCompletionTool> {
+    match request.tool_choice {
+        Some(ChatCompletionToolChoiceOption::None) => vec![],
+        _ => request.tools.clone().unwrap_or_default(),
+    }
+}
+
+enum Delta {
+    Content(String),
+    ToolCall(hypr_llama::LlamaToolCall),
+}
+
+struct ToolCallState {
+    stream: CompletionStream,
+    tools: Vec<ChatCompletionTool>,
+    // The start of the output, held back while it could still be a tool call.
+    pending: Option<String>,
+    done: bool,
+}
+
+// Passes text through as content, unless the whole output turns out to be a call to one of `tools`.
+fn with_tool_calls(
+    stream: CompletionStream,
+    tools: Vec<ChatCompletionTool>,
+) -> impl futures_util::Stream<Item = Result<Delta, hypr_llama::Error>> {
+    let state = ToolCallState {
+        stream,
+        pending: (!tools.is_empty()).then(String::new),
+        tools,
+        done: false,
+    };
+
+    futures_util::stream::unfold(state, |mut state| async move {
+        if state.done {
+            return None;
+        }
+
+        loop {
+            match state.stream.next().await {
+                Some(Ok(text)) => match state.pending.as_mut() {
+                    Some(pending) => {
+                        pending.push_str(&text);
+                        if !hypr_llama::may_be_tool_call(pending) {
+                            let text = state.pending.take().unwrap();
+                            return Some((Ok(Delta::Content(text)), state));
+                        }
+                    }
+                    None => return Some((Ok(Delta::Content(text)), state)),
+                },
+                Some(Err(e)) => {
+                    state.done = true;
+                    return Some((Err(e), state));
+                }
+                None => {
+                    state.done = true;
+                    let pending = state.pending.take().filter(|p| !p.is_empty())?;
+                    let delta = match hypr_llama::parse_tool_call(&pending, &state.tools) {
+                        Some(call) => Delta::ToolCall(call),
+                        None => Delta::Content(pending),
+                    };
+                    return Some((Ok(delta), state));
+                }
+            }
+        }
+    })
 }
 
-fn build_mock_response() -> Pin<Box<dyn futures_util::Stream<Item = String> + Send>> {
+fn build_mock_response() -> CompletionStream {
     use futures_util::stream::{self, StreamExt};
-    use std::time::Duration;
 
     let content = crate::ONBOARDING_ENHANCED_MD;
     let chunk_size = 30;
@@ -227,6 +529,6 @@ fn build_mock_response() -> Pin<Box<dyn futures_util::Stream<Item = String> + Se
 
     Box::pin(stream::iter(chunks).then(|chunk| async move {
         tokio::time::sleep(Duration::from_millis(200)).await;
-        chunk
+        Ok(chunk)
     }))
 }
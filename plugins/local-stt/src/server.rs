@@ -14,10 +14,10 @@ use axum::{
     Router,
 };
 
-use futures_util::{SinkExt, StreamExt};
+use futures_util::{future, SinkExt, Stream, StreamExt};
 use tower_http::cors::{self, CorsLayer};
 
-use hypr_chunker::ChunkerExt;
+use hypr_chunker::{ChunkStream, ChunkerExt, RMS};
 use hypr_listener_interface::{ListenOutputChunk, ListenParams, TranscriptChunk};
 use hypr_ws_utils::WebSocketAudioSource;
 
@@ -119,51 +119,64 @@ async fn listen(
         .ok_or(StatusCode::TOO_MANY_REQUESTS)?;
 
     let model_path = state.model_type.model_path(&state.model_cache_dir);
-    let language = params.language.try_into().unwrap();
+    let interim_results = params.interim_results;
+    let corrector = hypr_vocabulary::Corrector::new(&params.vocabulary);
 
-    let model = hypr_whisper::local::Whisper::builder()
+    // Terms go first, as the jargons used to.
+    let static_prompt = match hypr_vocabulary::whisper_prompt(&params.vocabulary) {
+        terms if terms.is_empty() => params.static_prompt.clone(),
+        terms => format!("{} / {}", terms, params.static_prompt),
+    };
+
+    let mut builder = hypr_whisper::local::Whisper::builder()
         .model_path(model_path.to_str().unwrap())
-        .language(language)
-        .static_prompt(&params.static_prompt)
-        .dynamic_prompt(&params.dynamic_prompt)
-        .build();
+        .static_prompt(&static_prompt)
+        .dynamic_prompt(&params.dynamic_prompt);
+
+    // Languages Whisper does not know are detected like "auto".
+    match params
+        .language
+        .map(TryInto::<hypr_whisper::Language>::try_into)
+    {
+        Some(Ok(language)) => builder = builder.language(language),
+        Some(Err(e)) => tracing::warn!("{}", e),
+        None => {}
+    }
+
+    let model = builder.build();
 
-    Ok(ws.on_upgrade(move |socket| websocket(socket, model, guard)))
+    Ok(ws.on_upgrade(move |socket| websocket(socket, model, interim_results, corrector, guard)))
 }
 
 #[tracing::instrument(skip_all)]
 async fn websocket(
     socket: WebSocket,
     model: hypr_whisper::local::Whisper,
+    interim_results: bool,
+    corrector: hypr_vocabulary::Corrector,
     _guard: ConnectionGuard,
 ) {
     let (mut ws_sender, ws_receiver) = socket.split();
     let mut stream = {
         let audio_source = WebSocketAudioSource::new(ws_receiver, 16 * 1000);
-        let chunked =
-            audio_source.chunks(hypr_chunker::RMS::new(), std::time::Duration::from_secs(15));
-        hypr_whisper::local::TranscribeChunkedAudioStreamExt::transcribe(chunked, model)
-    };
+        let chunked = audio_source.chunks(RMS::new(), std::time::Duration::from_secs(15));
 
-    while let Some(chunk) = stream.next().await {
-        let text = chunk.text().to_string();
-        let start = chunk.start() as u64;
-        let duration = chunk.duration() as u64;
-        let confidence = chunk.confidence();
+        if interim_results {
+            transcribe_with_interims(chunked, model).boxed()
+        } else {
+            transcribe(chunked, model).boxed()
+        }
+    };
 
-        if confidence < 0.5 {
-            tracing::warn!(confidence, "skipping_transcript: {}", text);
-            continue;
+    while let Some(mut transcripts) = stream.next().await {
+        // Whisper only takes vocabulary as a hint, so near misses are fixed afterwards.
+        for transcript in &mut transcripts {
+            transcript.text = corrector.correct(&transcript.text);
         }
 
         let data = ListenOutputChunk {
             diarizations: vec![],
-            transcripts: vec![TranscriptChunk {
-                text,
-                start,
-                end: start + duration,
-                confidence: Some(confidence),
-            }],
+            transcripts,
         };
 
         let msg = Message::Text(serde_json::to_string(&data).unwrap().into());
@@ -175,3 +188,101 @@ async fn websocket(
 
     let _ = ws_sender.close().await;
 }
+
+fn transcribe(
+    chunked: ChunkStream<WebSocketAudioSource, RMS>,
+    model: hypr_whisper::local::Whisper,
+) -> impl Stream<Item = Vec<TranscriptChunk>> + Send {
+    hypr_whisper::local::TranscribeChunkedAudioStreamExt::transcribe(chunked, model).filter_map(
+        |chunk| {
+            let text = chunk.text().to_string();
+            let start = chunk.start() as u64;
+            let duration = chunk.duration() as u64;
+            let confidence = chunk.confidence();
+            let language = language_code(chunk.language());
+
+            if confidence < 0.5 {
+                tracing::warn!(confidence, "skipping_transcript: {}", text);
+                return future::ready(None);
+            }
+
+            future::ready(Some(vec![TranscriptChunk {
+                text,
+                start,
+                end: start + duration,
+                confidence: Some(confidence),
+                is_final: true,
+                revision: None,
+                language,
+            }]))
+        },
+    )
+}
+
+// Re-decodes the chunk in progress about every second as an interim transcript,
+// and the whole chunk once the chunker completes it.
+fn transcribe_with_interims(
+    chunked: ChunkStream<WebSocketAudioSource, RMS>,
+    mut model: hypr_whisper::local::Whisper,
+) -> impl Stream<Item = Vec<TranscriptChunk>> + Send {
+    chunked
+        .with_partials(std::time::Duration::from_secs(1))
+        .filter_map(move |chunk| {
+            let result = if chunk.is_final {
+                model.transcribe(&chunk.samples)
+            } else {
+                model.transcribe_partial(&chunk.samples)
+            };
+
+            let segments = match result {
+                Ok(segments) => segments,
+                Err(e) => {
+                    tracing::error!("{:?}", e);
+                    return future::ready(None);
+                }
+            };
+
+            let (texts, confidences): (Vec<_>, Vec<_>) = segments
+                .iter()
+                .filter(|s| {
+                    if s.confidence() < 0.5 {
+                        tracing::warn!(
+                            confidence = s.confidence(),
+                            "skipping_transcript: {}",
+                            s.text()
+                        );
+                        return false;
+                    }
+                    true
+                })
+                .map(|s| (s.text().trim(), s.confidence()))
+                .unzip();
+
+            // A final chunk is sent even when empty, so that it still replaces the interim ones.
+            let text = texts.join(" ");
+            if text.is_empty() && !chunk.is_final {
+                return future::ready(None);
+            }
+
+            let start = chunk.start.as_millis() as u64;
+            let duration = chunk.samples.len() as u64 * 1000 / chunk.sample_rate as u64;
+            let language = language_code(segments.iter().find_map(|s| s.language()));
+
+            future::ready(Some(vec![TranscriptChunk {
+                text,
+                start,
+                end: start + duration,
+                confidence: (!confidences.is_empty())
+                    .then(|| confidences.iter().sum::<f32>() / confidences.len() as f32),
+                is_final: chunk.is_final,
+                revision: Some(start),
+                language,
+            }]))
+        })
+}
+
+fn language_code(language: Option<hypr_whisper::Language>) -> Option<String> {
+    language
+        .and_then(|l| hypr_language::Language::from_whisper_code(l.as_ref()))
+        .map(|l| l.code().to_string())
+}
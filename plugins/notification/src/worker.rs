@@ -28,6 +28,7 @@ pub async fn perform_event_notification(_job: Job, ctx: Data<WorkerState>) -> Re
             common: ListEventFilterCommon {
                 user_id: ctx.user_id.clone(),
                 limit: Some(1),
+                cursor: None,
             },
             specific: ListEventFilterSpecific::DateRange {
                 start: Utc::now(),
@@ -37,7 +38,7 @@ pub async fn perform_event_notification(_job: Job, ctx: Data<WorkerState>) -> Re
         .await
         .map_err(|e| crate::Error::Db(e).as_worker_error())?;
 
-    if let Some(event) = latest_event.first() {
+    if let Some(event) = latest_event.items.first() {
         hypr_notification2::show(hypr_notification2::Notification {
             title: "Scheduled meeting in 5 minutes".to_string(),
             message: event.name.clone(),
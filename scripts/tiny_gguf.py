@@ -0,0 +1,177 @@
+import sys
+import struct
+
+# Writes a randomly initialized, single-layer llama model small enough to live in the repo.
+# The output is gibberish, but deterministic for a given seed, which is all the tests need.
+#
+# python3 scripts/tiny_gguf.py crates/llama/assets/tiny.gguf
+
+N_EMBD = 16
+N_HEAD = 2
+N_FF = 32
+N_CTX = 2048
+
+CHAT_TEMPLATE = (
+    "{% for message in messages %}"
+    "{{'<|im_start|>' + message['role'] + '\\n' + message['content'] + '<|im_end|>' + '\\n'}}"
+    "{% endfor %}"
+    "{% if add_generation_prompt %}{{ '<|im_start|>assistant\\n' }}{% endif %}"
+)
+
+TYPE_UINT32 = 4
+TYPE_INT32 = 5
+TYPE_FLOAT32 = 6
+TYPE_STRING = 8
+TYPE_ARRAY = 9
+
+TOKEN_NORMAL = 1
+TOKEN_UNKNOWN = 2
+TOKEN_CONTROL = 3
+TOKEN_BYTE = 6
+
+ALIGNMENT = 32
+
+# <unk>, <s>, </s>, <|im_start|> and <|im_end|>, which come first in the vocabulary.
+N_CONTROL = 5
+
+
+def vocab():
+    tokens = [
+        ("<unk>", TOKEN_UNKNOWN),
+        ("<s>", TOKEN_CONTROL),
+        ("</s>", TOKEN_CONTROL),
+        ("<|im_start|>", TOKEN_CONTROL),
+        ("<|im_end|>", TOKEN_CONTROL),
+    ]
+    tokens += [("<0x%02X>" % b, TOKEN_BYTE) for b in range(256)]
+    letters = "abcdefghijklmnopqrstuvwxyz"
+    tokens += [(c, TOKEN_NORMAL) for c in letters]
+    tokens += [("▁" + c, TOKEN_NORMAL) for c in letters]
+    tokens += [(w, TOKEN_NORMAL) for w in ["▁the", "▁and", ".", ",", "▁"]]
+    return tokens
+
+
+class Random:
+    def __init__(self, seed):
+        self.state = seed
+
+    def next(self):
+        self.state = (self.state * 6364136223846793005 + 1442695040888963407) % (1 << 64)
+        return ((self.state >> 33) / (1 << 31)) * 2.0 - 1.0
+
+
+def string(s):
+    b = s.encode("utf-8")
+    return struct.pack("<Q", len(b)) + b
+
+
+def kv(key, kind, value):
+    out = string(key) + struct.pack("<I", kind)
+    if kind == TYPE_STRING:
+        return out + string(value)
+    if kind == TYPE_UINT32:
+        return out + struct.pack("<I", value)
+    if kind == TYPE_FLOAT32:
+        return out + struct.pack("<f", value)
+    raise ValueError(kind)
+
+
+def kv_array(key, kind, values):
+    out = string(key) + struct.pack("<I", TYPE_ARRAY) + struct.pack("<IQ", kind, len(values))
+    for v in values:
+        if kind == TYPE_STRING:
+            out += string(v)
+        elif kind == TYPE_FLOAT32:
+            out += struct.pack("<f", v)
+        elif kind == TYPE_INT32:
+            out += struct.pack("<i", v)
+        else:
+            raise ValueError(kind)
+    return out
+
+
+def tensors(n_vocab, rng):
+    # Dimensions are innermost first, as ggml orders them.
+    shapes = [
+        ("token_embd.weight", [N_EMBD, n_vocab]),
+        ("output_norm.weight", [N_EMBD]),
+        ("output.weight", [N_EMBD, n_vocab]),
+        ("blk.0.attn_norm.weight", [N_EMBD]),
+        ("blk.0.attn_q.weight", [N_EMBD, N_EMBD]),
+        ("blk.0.attn_k.weight", [N_EMBD, N_EMBD]),
+        ("blk.0.attn_v.weight", [N_EMBD, N_EMBD]),
+        ("blk.0.attn_output.weight", [N_EMBD, N_EMBD]),
+        ("blk.0.ffn_norm.weight", [N_EMBD]),
+        ("blk.0.ffn_gate.weight", [N_EMBD, N_FF]),
+        ("blk.0.ffn_up.weight", [N_EMBD, N_FF]),
+        ("blk.0.ffn_down.weight", [N_FF, N_EMBD]),
+    ]
+
+    out = []
+    for name, dims in shapes:
+        count = 1
+        for d in dims:
+            count *= d
+        if name.endswith("norm.weight"):
+            values = [1.0] * count
+        else:
+            values = [rng.next() * 0.5 for _ in range(count)]
+        if name == "output.weight":
+            # Control tokens, end of generation included, get a logit of zero and so never win greedy sampling.
+            for t in range(N_CONTROL):
+                values[t * N_EMBD : (t + 1) * N_EMBD] = [0.0] * N_EMBD
+        out.append((name, dims, struct.pack("<%df" % count, *values)))
+    return out
+
+
+def main(path):
+    tokens = vocab()
+    rng = Random(1234)
+
+    metadata = [
+        kv("general.architecture", TYPE_STRING, "llama"),
+        kv("general.name", TYPE_STRING, "tiny"),
+        kv("general.alignment", TYPE_UINT32, ALIGNMENT),
+        kv("llama.context_length", TYPE_UINT32, N_CTX),
+        kv("llama.embedding_length", TYPE_UINT32, N_EMBD),
+        kv("llama.block_count", TYPE_UINT32, 1),
+        kv("llama.feed_forward_length", TYPE_UINT32, N_FF),
+        kv("llama.attention.head_count", TYPE_UINT32, N_HEAD),
+        kv("llama.attention.head_count_kv", TYPE_UINT32, N_HEAD),
+        kv("llama.rope.dimension_count", TYPE_UINT32, N_EMBD // N_HEAD),
+        kv("llama.attention.layer_norm_rms_epsilon", TYPE_FLOAT32, 1e-5),
+        kv("tokenizer.ggml.model", TYPE_STRING, "llama"),
+        kv_array("tokenizer.ggml.tokens", TYPE_STRING, [t for t, _ in tokens]),
+        kv_array("tokenizer.ggml.scores", TYPE_FLOAT32, [-float(i) for i in range(len(tokens))]),
+        kv_array("tokenizer.ggml.token_type", TYPE_INT32, [k for _, k in tokens]),
+        kv("tokenizer.ggml.unknown_token_id", TYPE_UINT32, 0),
+        kv("tokenizer.ggml.bos_token_id", TYPE_UINT32, 1),
+        kv("tokenizer.ggml.eos_token_id", TYPE_UINT32, 2),
+        kv("tokenizer.chat_template", TYPE_STRING, CHAT_TEMPLATE),
+    ]
+
+    data = tensors(len(tokens), rng)
+
+    infos = b""
+    offset = 0
+    for name, dims, blob in data:
+        infos += string(name) + struct.pack("<I", len(dims))
+        infos += b"".join(struct.pack("<Q", d) for d in dims)
+        infos += struct.pack("<IQ", 0, offset)
+        offset += len(blob)
+        offset += -offset % ALIGNMENT
+
+    header = b"GGUF" + struct.pack("<IQQ", 3, len(data), len(metadata))
+    out = header + b"".join(metadata) + infos
+    out += b"\0" * (-len(out) % ALIGNMENT)
+
+    for _, _, blob in data:
+        out += blob
+        out += b"\0" * (-len(blob) % ALIGNMENT)
+
+    with open(path, "wb") as f:
+        f.write(out)
+
+
+if __name__ == "__main__":
+    main(sys.argv[1])